@@ -4,7 +4,7 @@ use tokio::sync::Mutex;
 use anyhow::{Context, Result};
 use axum::{
     body::Body,
-    extract::State,
+    extract::{FromRef, State},
     http::{header::CONTENT_TYPE, Response, StatusCode},
     middleware,
     response::IntoResponse,
@@ -15,27 +15,77 @@ use axum_tracing_opentelemetry::middleware::{OtelAxumLayer, OtelInResponseLayer}
 use opentelemetry::trace::TracerProvider as _;
 use prometheus_client::{encoding::text::encode, registry::Registry};
 use scrum_discord_bot::{
-    configuration::{get_configuration, Settings},
-    drivers::http::middlewares::{self},
+    configuration::{get_configuration, normalize_prefix, ConfigReloader, HttpSettings, Settings},
+    domain::{
+        audit::Auditor,
+        cleanup::MessageCleanup,
+        debounce::{EditDebouncer, EDIT_WINDOW},
+        delivery::{DeliveryService, RETRY_FAILED_INTERVAL},
+        feature::FeatureFlags,
+        id::GuildId,
+        job::JobRunner,
+        kickoff::KickoffTracker,
+        nudge::{StandupNudger, NUDGE_INTERVAL},
+        participation::ParticipationTracker,
+        reminder::{ReminderFirer, ReminderService, FIRE_INTERVAL},
+        replay::PromptReplay,
+        retention::{StandupPruner, PRUNE_INTERVAL},
+        scheduler::{Jitter, ScheduledSender},
+        standup::{StandupFeed, StandupPrompt, StandupRules, StandupService},
+    },
+    drivers::{
+        database::{breaker::CircuitBreaker, standup::MongoStandupRepository, WriteRetry},
+        discord::{
+            command::Dispatcher,
+            gateway::ShardMonitor,
+            me::MeCommand,
+            registry::{CommandRegistrar, CommandRegistry},
+            remind::RemindCommand,
+            rest::DiscordRest,
+            shard::Shard,
+            sprint::SprintCommand,
+            standup::StandupCommand,
+            whoami::WhoamiCommand,
+        },
+        grpc,
+        http::{
+            handlers::{
+                self,
+                admin::{AdminCommands, AdminReminders, AdminState},
+                health::Dependency,
+                standup::StandupState,
+            },
+            listener,
+            middlewares::{
+                self,
+                auth::ApiKeys,
+                force_trace::{force_trace, ForceTrace},
+                panic::handle_panic,
+                path::{nest_under_prefix, PathNormalization},
+                redact::{AccessLogSampler, LogRequest, LogResponse, RedactHeaders},
+                telemetry::ExcludePathsLayer,
+                timeout::{route_timeout, with_body_timeouts, RouteTimeouts},
+                MetricsState,
+            },
+            server,
+            state::{AppState, Repositories},
+        },
+    },
     observability::{
+        collector::{self, Collector},
+        export::set_error_handler,
+        fields::FieldMapping,
+        file::LogFile,
         get_subscriber, init_subscriber,
         log::init_log,
-        metrics::{init_metrics, Metrics},
+        meter::{init_meter, shutdown_meter},
+        metrics::{init_metrics, HttpMetrics},
         trace::init_trace,
     },
 };
-use tokio::signal;
+use tokio::{signal, task::JoinHandle};
 use tower::ServiceBuilder;
-use tower_http::{
-    catch_panic::CatchPanicLayer,
-    compression::CompressionLayer,
-    normalize_path::NormalizePathLayer,
-    timeout::{RequestBodyTimeoutLayer, TimeoutLayer},
-    trace::{DefaultOnRequest, DefaultOnResponse, TraceLayer},
-    validate_request::ValidateRequestHeaderLayer,
-    CompressionLevel, LatencyUnit,
-};
-use tracing::Level;
+use tower_http::{catch_panic::CatchPanicLayer, trace::TraceLayer};
 
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
@@ -43,93 +93,479 @@ static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 #[tokio::main]
 #[tracing::instrument]
 async fn main() -> Result<()> {
+    let cli = Cli::parse(&std::env::args().skip(1).collect::<Vec<_>>())?;
     let settings = get_configuration().expect("expected to parse configuration with success");
+    if let Cli::RegisterCommands { guild_id } = cli {
+        return register_commands(&settings, guild_id).await;
+    }
 
     // Tracing, logs and metrics
-    let trace_provider = init_trace(&settings).expect("expected to get trace_provider");
+    let (metrics, registry) = init_metrics(&settings);
+    set_error_handler(metrics.export.clone()).expect("expected to count the export errors");
+    let trace_provider =
+        init_trace(&settings, metrics.export.clone()).expect("expected to get trace_provider");
     let tracer = trace_provider.tracer(settings.application.name.clone());
     let logger_provider = init_log(&settings).expect("expected to create logger provider");
+    let meter_provider = init_meter(&settings).expect("expected to create meter provider");
+    opentelemetry::global::set_meter_provider(meter_provider.clone());
 
     let subscriber = get_subscriber(
-        settings.application.name.clone(),
+        &settings.application,
         "info".into(),
         std::io::stdout,
+        LogFile::from_settings(settings.logging.file.as_ref())
+            .expect("expected to open the log file"),
+        FieldMapping::from_settings(&settings.logging),
         tracer,
         logger_provider.clone(),
     );
     init_subscriber(subscriber);
 
-    let (metrics, registry) = init_metrics(&settings);
+    let probe_settings = settings.clone();
+    tokio::spawn(async move { collector::warn_if_unreachable(&probe_settings).await });
+
+    let features = FeatureFlags::new(settings.features.clone());
+    let reloader = ConfigReloader::new(features.clone()).with_counter(metrics.config.clone());
+    #[cfg(unix)]
+    tokio::spawn(reload_on_hangup(reloader.clone()));
+
     let registry = Arc::new(Mutex::new(registry));
 
-    metrics_server(&settings, registry).await?;
+    let exposure = settings.metrics_exposure();
+    let mut metrics_task = None;
+    if exposure.standalone_server {
+        metrics_task = Some(metrics_server(&settings, registry.clone()).await?);
 
-    tracing::info!(
-        "listening on address for metrics {:?}",
-        settings.prometheus.port
-    );
+        tracing::info!(
+            "listening on address for metrics {:?}",
+            settings.prometheus.port
+        );
+    }
+    let metrics_registry = exposure.main_router.then_some(registry);
 
-    let app = app(&settings, metrics);
+    let client = mongodb::Client::with_options(settings.database.connect_options()?)
+        .context("expected to create mongodb client")?;
+    let database = client.database(settings.mongo_database_name()?);
+
+    if settings.grpc.enabled {
+        grpc::spawn(
+            &settings.grpc,
+            Arc::new(database.clone()),
+            shutdown_signal(),
+        )
+        .await?;
+    }
+
+    let breaker = CircuitBreaker::from_settings(&settings.database.breaker)
+        .with_gauge(metrics.database.clone());
+    let retry =
+        WriteRetry::from_settings(&settings.database.retry).with_counter(metrics.database.clone());
+    let repositories = Repositories::guarded(&database, breaker, retry);
+    // The API still starts without the database, it reports it in /readyz.
+    if let Err(error) = MongoStandupRepository::new(&database)
+        .create_indexes()
+        .await
+    {
+        tracing::warn!(error = ?error, "failed to create the standup indexes");
+    }
+
+    let discord = DiscordRest::connect(&settings.discord).await?;
+    // Warns once about the privileged intents, before the gateway is refused.
+    let intents = settings
+        .discord
+        .gateway_intents()
+        .context("expected valid discord.intents")?;
+    tracing::info!(intents = intents.bits(), "gateway intents");
+    let timezone = settings.application.default_tz();
+    // Dashboards lagging further behind skip entries.
+    let feed = StandupFeed::new(64).with_recorder(metrics.standup.clone());
+    let standups = StandupService::new(repositories.standups.clone(), repositories.sprints.clone())
+        .with_feed(feed.clone())
+        .with_participation(ParticipationTracker::new(
+            repositories.standups.clone(),
+            repositories.guilds.clone(),
+            metrics.standup.clone(),
+            timezone,
+        ))
+        .with_kickoff(KickoffTracker::new(
+            repositories.standups.clone(),
+            repositories.sprints.clone(),
+            repositories.guilds.clone(),
+            metrics.standup.clone(),
+            timezone,
+        ));
+    let reminders = ReminderService::new(repositories.reminders.clone())
+        .with_snoozes(repositories.snoozes.clone())
+        .with_jitter(Jitter::new(settings.scheduler.max_jitter_secs))
+        // Published on every run of the `fire_reminders` job.
+        .with_pending_age(metrics.scheduler.clone());
+    let debouncer = EditDebouncer::new(standups.clone(), EDIT_WINDOW);
+    let commands = commands(
+        &settings,
+        &repositories,
+        standups,
+        debouncer.clone(),
+        reminders.clone(),
+    )?;
+    if let Some(discord) = &discord {
+        let dispatcher = Dispatcher::new(features)
+            .with_concurrency_limit(settings.discord.max_concurrent_interactions)
+            .with_busy_counter(metrics.discord.clone())
+            .with_registry(&commands);
+        let monitor = Arc::new(ShardMonitor::new(metrics.discord.clone()));
+        tokio::spawn(Shard::new(discord.clone(), dispatcher, monitor, intents).run());
+    }
+    // What the bot posts on its own goes through the dead-letter log.
+    let delivery = discord.clone().map(|discord| {
+        DeliveryService::new(Arc::new(discord), repositories.failed_messages.clone())
+            .with_failure_counter(Arc::new(metrics.discord.shard(0)))
+    });
+    // Only logged in dry run, to try out schedules without posting.
+    let scheduled = delivery.clone().map(|delivery| {
+        ScheduledSender::new(Arc::new(delivery), settings.scheduler.dry_run)
+            .with_fire_counter(metrics.scheduler.clone())
+    });
+
+    let mut jobs = JobRunner::new(
+        settings.scheduler.max_concurrent_jobs,
+        Duration::from_secs(settings.scheduler.job_timeout_secs),
+    )
+    .with_recorder(metrics.jobs.clone());
+    if settings.scrum.retention_days > 0 {
+        let pruner =
+            StandupPruner::new(repositories.standups.clone(), settings.scrum.retention_days)
+                .with_counter(metrics.retention.clone());
+        jobs = jobs.with_job("prune_standups", PRUNE_INTERVAL, Arc::new(pruner));
+    }
+    if let Some(delivery) = &delivery {
+        jobs = jobs.with_job(
+            "retry_failed_messages",
+            RETRY_FAILED_INTERVAL,
+            Arc::new(delivery.clone()),
+        );
+    }
+    if let Some(scheduled) = &scheduled {
+        let firer = ReminderFirer::new(reminders.clone(), Arc::new(scheduled.clone()));
+        jobs = jobs.with_job("fire_reminders", FIRE_INTERVAL, Arc::new(firer));
+
+        let nudger = StandupNudger::new(
+            repositories.guilds.clone(),
+            repositories.standups.clone(),
+            Arc::new(scheduled.clone()),
+            timezone,
+        );
+        jobs = jobs.with_job("nudge_standups", NUDGE_INTERVAL, Arc::new(nudger));
+    }
+    jobs.start();
+
+    let mut dependencies = vec![Dependency::critical("mongodb", Arc::new(database.clone()))];
+    if let Some(collector) = Collector::from_settings(&settings) {
+        dependencies.push(Dependency::optional("otlp_collector", Arc::new(collector)));
+    }
+
+    // On demand, so not held back by `scheduler.dry_run`.
+    let prompt = StandupPrompt::new(&settings.templates.standup_prompt)
+        .context("expected a valid templates.standup_prompt")?;
+    let replay = delivery.clone().map(|delivery| {
+        PromptReplay::new(
+            repositories.guilds.clone(),
+            Arc::new(delivery),
+            prompt,
+            timezone,
+            Auditor::new(Some(repositories.audit.clone())),
+        )
+        .with_counter(metrics.discord.clone())
+    });
+
+    let standup_state = StandupState {
+        standups: repositories.standups.clone(),
+        feed,
+        heartbeat: Duration::from_secs(settings.http.sse_heartbeat_secs),
+    };
+
+    let handlers = Handlers {
+        standup_state,
+        admin_state: AdminState {
+            reloader,
+            traces: Arc::new(trace_provider.clone()),
+            reminders: scheduled.clone().map(|scheduled| AdminReminders {
+                service: reminders.clone(),
+                sender: Arc::new(scheduled),
+            }),
+            commands: discord.clone().map(|discord| AdminCommands {
+                specs: commands.specs(),
+                registrar: Arc::new(discord),
+            }),
+            cleanup: discord.clone().map(|discord| {
+                let bot_id = discord.application().bot_id;
+                MessageCleanup::new(Arc::new(discord), bot_id).with_counter(metrics.discord.clone())
+            }),
+            replay,
+        },
+        dependencies,
+        metrics_registry,
+    };
+    let state = AppState {
+        settings: Arc::new(settings.clone()),
+        metrics,
+        db: database,
+        repositories,
+    };
+    let app = app(state, handlers);
 
     let address = format!("{}:{}", settings.http.host, settings.http.port)
         .parse::<SocketAddr>()
         .context("expected to parse address")?;
 
-    let listener = tokio::net::TcpListener::bind(address)
-        .await
-        .context("expected to create listener")?;
+    let listener = listener::bind(address, &settings.http)?;
 
     tracing::info!("listening on address {:?}", address);
 
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await
-        .unwrap();
+    server::serve(listener, app, &settings.http, shutdown_signal()).await?;
 
+    // Edits still in their window are saved before the process exits.
+    debouncer.flush().await;
+
+    // Got the same signal, a scraper holding its connection is cut at the grace.
+    if let Some(metrics_task) = metrics_task {
+        if let Err(error) = metrics_task.await {
+            tracing::warn!(error = ?error, "metrics server task failed");
+        }
+    }
+
+    // The server has stopped recording, whatever it recorded last is exported.
+    if let Err(error) = shutdown_meter(meter_provider).await {
+        tracing::warn!(error = ?error, "failed to flush metrics on shutdown");
+    }
     opentelemetry::global::shutdown_tracer_provider();
     let _ = logger_provider.shutdown();
 
     Ok(())
 }
 
-fn app(settings: &Settings, metrics: Arc<Metrics>) -> Router {
-    let telemetry_middleware = ServiceBuilder::new()
-        .layer(OtelInResponseLayer)
-        .layer(OtelAxumLayer::default());
+/// What the binary was asked to do, serving unless told otherwise.
+#[derive(Debug, PartialEq)]
+enum Cli {
+    Serve,
+    /// `register-commands [--guild <id>]`
+    RegisterCommands {
+        guild_id: Option<GuildId>,
+    },
+}
+
+impl Cli {
+    fn parse(args: &[String]) -> Result<Self> {
+        match args {
+            [] => Ok(Self::Serve),
+            [command] if command == "register-commands" => {
+                Ok(Self::RegisterCommands { guild_id: None })
+            }
+            [command, flag, guild_id] if command == "register-commands" && flag == "--guild" => {
+                let guild_id = guild_id
+                    .parse::<u64>()
+                    .with_context(|| format!("expected a guild id, got {:?}", guild_id))?;
+
+                Ok(Self::RegisterCommands {
+                    guild_id: Some(guild_id.into()),
+                })
+            }
+            _ => anyhow::bail!("usage: http [register-commands [--guild <id>]]"),
+        }
+    }
+}
+
+/// The slash commands of the bot, the list Discord is told about and the
+/// interactions are dispatched with.
+fn commands(
+    settings: &Settings,
+    repositories: &Repositories,
+    standups: StandupService,
+    debouncer: EditDebouncer,
+    reminders: ReminderService,
+) -> Result<CommandRegistry> {
+    let timezone = settings.application.default_tz();
+    let guilds = repositories.guilds.clone();
+    let mut registry = CommandRegistry::new();
+
+    registry.register(
+        StandupCommand::spec(),
+        Arc::new(
+            StandupCommand::new(standups, guilds.clone(), timezone)
+                .with_rules(StandupRules::new(settings.scrum.standup_min_length))
+                .with_debouncer(debouncer),
+        ),
+    )?;
+    registry.register(
+        RemindCommand::spec(),
+        Arc::new(RemindCommand::new(reminders, guilds.clone(), timezone)),
+    )?;
+    registry.register(
+        SprintCommand::spec(),
+        Arc::new(SprintCommand::new(
+            repositories.sprints.clone(),
+            repositories.completions.clone(),
+            repositories.standups.clone(),
+            guilds.clone(),
+            timezone,
+            Auditor::new(Some(repositories.audit.clone())),
+        )),
+    )?;
+    registry.register(
+        MeCommand::spec(),
+        Arc::new(MeCommand::new(repositories.snoozes.clone())),
+    )?;
+    registry.register(
+        WhoamiCommand::spec(),
+        Arc::new(WhoamiCommand::new(guilds.clone(), timezone)),
+    )?;
+
+    Ok(registry.with_help(guilds)?)
+}
+
+/// Replace the slash commands of the bot with the ones of this build, in
+/// `guild_id` only or globally.
+async fn register_commands(settings: &Settings, guild_id: Option<GuildId>) -> Result<()> {
+    let discord = DiscordRest::connect(&settings.discord)
+        .await?
+        .context("expected discord.token to register the commands")?;
+
+    // The handlers are left unused, the client connects lazily.
+    let client = mongodb::Client::with_options(settings.database.connect_options()?)
+        .context("expected to create mongodb client")?;
+    let database = client.database(settings.mongo_database_name()?);
+    let repositories = Repositories::guarded(
+        &database,
+        CircuitBreaker::from_settings(&settings.database.breaker),
+        WriteRetry::from_settings(&settings.database.retry),
+    );
+    let standups = StandupService::new(repositories.standups.clone(), repositories.sprints.clone());
+    let reminders = ReminderService::new(repositories.reminders.clone());
+    let debouncer = EditDebouncer::new(standups.clone(), EDIT_WINDOW);
+    let specs = commands(settings, &repositories, standups, debouncer, reminders)?.specs();
+
+    discord.overwrite(guild_id, &specs).await?;
+    println!(
+        "registered {} commands {}",
+        specs.len(),
+        guild_id.map_or("globally".to_owned(), |guild_id| format!(
+            "in guild {}",
+            guild_id
+        ))
+    );
+
+    Ok(())
+}
+
+/// What the routers of [`app`] are built from, besides the [`AppState`].
+struct Handlers {
+    standup_state: StandupState,
+    admin_state: AdminState,
+    dependencies: Vec<Dependency>,
+    /// Served on `prometheus.path` when the main router exposes the metrics.
+    metrics_registry: Option<Arc<Mutex<Registry>>>,
+}
 
+fn app(state: AppState, handlers: Handlers) -> Router {
+    let Handlers {
+        standup_state,
+        admin_state,
+        dependencies,
+        metrics_registry,
+    } = handlers;
+    let settings = &state.settings;
+    let metrics = Arc::<HttpMetrics>::from_ref(&state);
+    let api_keys = ApiKeys::new(settings.http.api_keys.clone());
+    let mut metrics_state = MetricsState::new(metrics.clone());
+    if settings.prometheus.strip_prefix {
+        metrics_state = metrics_state.with_strip_prefix(&normalize_prefix(&settings.http.prefix));
+    }
+
+    let telemetry_middleware = ExcludePathsLayer::new(
+        ServiceBuilder::new()
+            .layer(OtelInResponseLayer)
+            .layer(OtelAxumLayer::default()),
+        settings.otel.exclude_paths.clone(),
+    );
+
+    let redact_headers = RedactHeaders::new(settings.http.redact_headers.clone());
     let default_middleware = ServiceBuilder::new()
         .layer(
             TraceLayer::new_for_http()
-                .on_request(DefaultOnRequest::new().level(Level::INFO))
+                .on_request(LogRequest(redact_headers.clone()))
                 .on_response(
-                    DefaultOnResponse::new()
-                        .level(Level::INFO)
-                        .include_headers(true)
-                        .latency_unit(LatencyUnit::Micros),
+                    LogResponse::new(redact_headers)
+                        .with_sampler(AccessLogSampler::new(settings.http.access_log_sample_rate)),
                 ),
         )
-        .layer(NormalizePathLayer::trim_trailing_slash())
-        .layer(ValidateRequestHeaderLayer::accept("application/json"))
-        .layer(CompressionLayer::new().quality(CompressionLevel::Fastest))
-        .layer(RequestBodyTimeoutLayer::new(Duration::from_secs(
-            settings.http.timeout,
-        )))
-        .layer(TimeoutLayer::new(Duration::from_secs(
-            settings.http.timeout,
-        )))
-        .layer(CatchPanicLayer::new());
+        // Health checks must see the current state too, so they aren't excluded.
+        .layer(middlewares::cache::layer())
+        .layer(middlewares::compression::layer(
+            settings.http.compression_min_size,
+        ))
+        .layer(CatchPanicLayer::custom(handle_panic));
 
     let real_router = Router::new()
+        .merge(handlers::audit::router(
+            FromRef::from_ref(&state),
+            api_keys.clone(),
+        ))
+        .merge(handlers::snooze::router(
+            FromRef::from_ref(&state),
+            api_keys.clone(),
+        ))
+        .merge(handlers::standup::router(standup_state, api_keys.clone()))
+        .merge(handlers::sprint::router(
+            FromRef::from_ref(&state),
+            api_keys.clone(),
+        ))
+        .merge(handlers::retro::router(
+            FromRef::from_ref(&state),
+            api_keys.clone(),
+        ))
+        .merge(handlers::report::router(
+            FromRef::from_ref(&state),
+            api_keys.clone(),
+        ))
+        .merge(handlers::whoami::router(api_keys.clone()))
+        .merge(handlers::schema::router())
+        .merge(handlers::admin::router(admin_state, api_keys))
+        .merge(handlers::fallback::router(metrics.clone()))
         .route_layer(middleware::from_fn_with_state(
-            metrics.http.clone(),
+            metrics_state,
             middlewares::metrics_middleware,
         ))
-        .layer(telemetry_middleware)
-        // Non telemetry layers that won't contain span shit
+        // Kept out of the request metrics, and out of the traces by
+        // `otel.exclude_paths`.
         .route("/healthz", get(health_handler))
+        .merge(handlers::health::router(dependencies))
+        .layer(telemetry_middleware)
+        // Outside of the telemetry layer, which reads the rewritten traceparent.
+        .layer(middleware::from_fn_with_state(
+            ForceTrace::new(settings.otel.force_trace_from.clone()),
+            force_trace,
+        ))
+        // After routing, the timeout depends on the matched route.
+        .layer(middleware::from_fn_with_state(
+            Arc::new(RouteTimeouts::from_settings(&settings.http)),
+            route_timeout,
+        ))
         .layer(default_middleware);
 
-    Router::new().nest(&settings.http.prefix, real_router)
+    let real_router = with_body_timeouts(
+        real_router,
+        Duration::from_secs(settings.http.timeout),
+        settings.http.response_timeout.map(Duration::from_secs),
+    );
+
+    let mut router = nest_under_prefix(&settings.http.prefix, real_router);
+    if let Some(registry) = metrics_registry {
+        router = router.route(
+            &settings.prometheus.path,
+            get(metrics_handler).with_state(registry),
+        );
+    }
+
+    PathNormalization::from_settings(&settings.http).apply(router)
 }
 
 pub async fn health_handler() -> &'static str {
@@ -151,7 +587,12 @@ async fn metrics_handler(State(state): State<Arc<Mutex<Registry>>>) -> impl Into
         .unwrap()
 }
 
-async fn metrics_server(settings: &Settings, registry: Arc<Mutex<Registry>>) -> Result<()> {
+/// Serve the metrics on `prometheus.port` until the shutdown signal, the task
+/// ends within `prometheus.shutdown_grace_secs` of it.
+async fn metrics_server(
+    settings: &Settings,
+    registry: Arc<Mutex<Registry>>,
+) -> Result<JoinHandle<()>> {
     let router = Router::new()
         .route(&settings.prometheus.path, get(metrics_handler))
         .with_state(registry);
@@ -160,14 +601,32 @@ async fn metrics_server(settings: &Settings, registry: Arc<Mutex<Registry>>) ->
         .await
         .context("expected to create listener")?;
 
-    tokio::spawn(async move {
-        axum::serve(listener, router)
-            .with_graceful_shutdown(shutdown_signal())
-            .await
-            .expect("expected to listen to prometheus handler");
-    });
+    // Plain HTTP/1.1 like the scrapers speak, with its own grace.
+    let http = HttpSettings {
+        http2: false,
+        tls: None,
+        shutdown_grace_secs: settings.prometheus.shutdown_grace_secs,
+        ..settings.http.clone()
+    };
+    Ok(tokio::spawn(async move {
+        if let Err(error) = server::serve(listener, router, &http, shutdown_signal()).await {
+            tracing::error!(error = ?error, "metrics server failed");
+        }
+    }))
+}
 
-    Ok(())
+/// Re-read the configuration on SIGHUP and apply the fields that can change at runtime.
+#[cfg(unix)]
+async fn reload_on_hangup(reloader: ConfigReloader) {
+    let mut hangup = signal::unix::signal(signal::unix::SignalKind::hangup())
+        .expect("failed to install SIGHUP handler");
+
+    while hangup.recv().await.is_some() {
+        match reloader.reload() {
+            Ok(diff) => tracing::info!(changed = diff.features.len(), "configuration reloaded"),
+            Err(error) => tracing::warn!(error = %error, "rejected configuration reload"),
+        }
+    }
 }
 
 async fn shutdown_signal() {
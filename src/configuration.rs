@@ -1,9 +1,32 @@
+use anyhow::Context;
+use chrono_tz::Tz;
+use ipnet::IpNet;
 use mongodb::options::{ClientOptions, Credential, ServerAddress, Tls, TlsOptions};
 use opentelemetry::KeyValue;
 use opentelemetry_sdk::Resource;
 use secrecy::{ExposeSecret, SecretString};
 use serde_aux::field_attributes::deserialize_number_from_string;
-use std::convert::{TryFrom, TryInto};
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::{TryFrom, TryInto},
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
+
+use crate::{
+    domain::{
+        feature::FeatureFlags,
+        id::GuildId,
+        sprint::DuplicateSprints,
+        standup::{StandupPrompt, MAX_ANSWER_LENGTH},
+    },
+    drivers::discord::{
+        gateway::{GatewayIntents, UnknownIntent},
+        message::MESSAGE_CONTENT_LIMIT,
+    },
+    observability::metrics::HISTOGRAMS,
+};
 
 #[derive(serde::Deserialize, Clone)]
 pub struct Settings {
@@ -11,7 +34,16 @@ pub struct Settings {
     pub application: ApplicationSettings,
     pub http: HttpSettings,
     pub otel: OpenTelemetrySettings,
+    pub logging: LoggingSettings,
     pub prometheus: PrometheusSettings,
+    pub discord: DiscordSettings,
+    pub grpc: GrpcSettings,
+    pub templates: TemplateSettings,
+    pub scheduler: SchedulerSettings,
+    pub scrum: ScrumSettings,
+    /// Per command toggles, reloaded on SIGHUP or `POST /admin/config/reload`.
+    /// Missing commands are enabled.
+    pub features: HashMap<String, bool>,
     pub env: Environment,
 }
 
@@ -20,15 +52,124 @@ pub struct HttpSettings {
     #[serde(deserialize_with = "deserialize_number_from_string")]
     pub port: u16,
     pub host: String,
+    /// Path every route is nested under, empty for none. Normalized when
+    /// loaded, so `api`, `/api/` and `/api` all become `/api` and `/` becomes
+    /// empty.
     pub prefix: String,
+    /// Seconds a route may take to respond, and a request body may wait
+    /// between two chunks.
     #[serde(deserialize_with = "deserialize_number_from_string")]
     pub timeout: u64,
+    /// Seconds a response body may wait between two chunks, unbounded when
+    /// unset. Must exceed `sse_heartbeat_secs`, the heartbeats are what keeps
+    /// an idle event stream under it.
+    #[serde(default)]
+    pub response_timeout: Option<u64>,
+    /// Seconds a route may take instead of `timeout`, keyed by its pattern
+    /// relative to `prefix`, e.g. `/sprints/:id/report.html`.
+    pub route_timeouts: HashMap<String, u64>,
+    pub api_keys: Vec<ApiKeySettings>,
+    /// Disable Nagle's algorithm on accepted connections.
+    pub nodelay: bool,
+    /// Enable TCP keep-alive probes on accepted connections.
+    pub keepalive: bool,
+    /// Maximum number of pending connections waiting to be accepted.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub backlog: u32,
+    /// Responses smaller than this many bytes are sent uncompressed.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub compression_min_size: u16,
+    /// Route `/healthz/` as `/healthz`, on when unset.
+    #[serde(default = "default_true")]
+    pub normalize_trailing_slash: bool,
+    /// Route `//api//healthz` as `/api/healthz`.
+    #[serde(default)]
+    pub collapse_slashes: bool,
+    /// Request and response headers logged as `[REDACTED]`, the credential
+    /// headers when unset.
+    #[serde(default = "default_redact_headers")]
+    pub redact_headers: Vec<String>,
+    /// Log one in this many successful responses, 1 logs them all. Other
+    /// responses are always logged.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub access_log_sample_rate: u32,
+    /// Seconds between the heartbeat comments of the Server-Sent Events streams.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub sse_heartbeat_secs: u64,
+    /// Also accept HTTP/2 with prior knowledge (h2c), HTTP/1.1 only otherwise.
+    ///
+    /// The middleware stack runs once per HTTP/2 stream, so the request
+    /// timeout bounds each stream rather than the multiplexed connection,
+    /// and `nodelay`/`keepalive` are shared by every stream of a connection.
+    pub http2: bool,
+    /// Seconds the open connections get to finish on shutdown before being
+    /// dropped.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub shutdown_grace_secs: u64,
+    /// Serve HTTPS with these PEM files, plain HTTP when unset.
+    #[serde(default)]
+    pub tls: Option<TlsSettings>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_redact_headers() -> Vec<String> {
+    [
+        "authorization",
+        "proxy-authorization",
+        "cookie",
+        "set-cookie",
+    ]
+    .map(str::to_owned)
+    .to_vec()
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct TlsSettings {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct ApiKeySettings {
+    pub label: String,
+    pub key: SecretString,
+    pub admin: bool,
+    /// The guilds a non admin key can act on, admin keys act on every guild.
+    #[serde(default)]
+    pub guilds: Vec<GuildId>,
 }
 
 #[derive(serde::Deserialize, Clone)]
 pub struct ApplicationSettings {
     pub name: String,
     pub version: String,
+    /// IANA timezone used for guilds that never configured one.
+    pub default_timezone: String,
+    /// Git commit of the deployed build, `GIT_COMMIT_SHA` at build time
+    /// when unset.
+    #[serde(default)]
+    pub commit_sha: Option<String>,
+}
+
+impl ApplicationSettings {
+    /// The parsed [`ApplicationSettings::default_timezone`], UTC if invalid.
+    ///
+    /// [`Settings::validate`] rejects invalid names at startup.
+    pub fn default_tz(&self) -> Tz {
+        self.default_timezone.parse().unwrap_or(Tz::UTC)
+    }
+
+    /// The configured [`ApplicationSettings::commit_sha`], or the one baked
+    /// in at build time.
+    pub fn commit_sha(&self) -> Option<&str> {
+        self.commit_sha
+            .as_deref()
+            .or(option_env!("GIT_COMMIT_SHA"))
+            .filter(|sha| !sha.is_empty())
+    }
 }
 
 #[derive(serde::Deserialize, Clone)]
@@ -37,12 +178,105 @@ pub struct DatabaseSettings {
     pub password: SecretString,
     #[serde(deserialize_with = "deserialize_number_from_string")]
     pub port: u16,
+    #[serde(deserialize_with = "deserialize_hosts")]
     pub hosts: Vec<String>,
+    /// Most hosts the seed list may hold.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_hosts: usize,
     pub database: String,
     pub ssl: bool,
+    /// How long an operation waits for a reachable server before failing.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub server_selection_timeout_ms: u64,
+    /// How long opening a connection to a server may take.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub connect_timeout_ms: u64,
+    pub breaker: BreakerSettings,
+    #[serde(default)]
+    pub retry: RetrySettings,
+    /// Extra connection string options, e.g. `retryWrites: "false"`. Options
+    /// already covered by the fields above are rejected.
+    #[serde(default)]
+    pub options: HashMap<String, String>,
 }
 
+/// How the writes failing with a transient error are retried.
+#[derive(serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RetrySettings {
+    /// Attempts of a write, the first one included.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub attempts: u32,
+    /// Delay before the second attempt, doubled after every attempt.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub backoff_ms: u64,
+}
+
+impl Default for RetrySettings {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            backoff_ms: 50,
+        }
+    }
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct BreakerSettings {
+    /// Consecutive unreachable errors before operations fail fast.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub failure_threshold: u32,
+    /// How long operations fail fast before the database is tried again.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub cooldown_ms: u64,
+}
+
+/// Characters MongoDB refuses in a database name.
+const INVALID_DATABASE_CHARACTERS: [char; 12] =
+    ['/', '\\', '.', ' ', '"', '$', '*', '<', '>', ':', '|', '?'];
+/// MongoDB database names are shorter than 64 bytes.
+const MAX_DATABASE_NAME_LEN: usize = 63;
+/// Connection string options set by a typed [`DatabaseSettings`] field,
+/// lowercased as option names are case insensitive.
+const TYPED_DATABASE_OPTIONS: [(&str, &str); 8] = [
+    ("tls", "ssl"),
+    ("ssl", "ssl"),
+    ("connecttimeoutms", "connect_timeout_ms"),
+    ("serverselectiontimeoutms", "server_selection_timeout_ms"),
+    ("authsource", "database"),
+    ("username", "username"),
+    ("password", "password"),
+    ("appname", "the application name"),
+];
+
 impl DatabaseSettings {
+    /// The database the repositories live in, if MongoDB accepts its name.
+    pub fn database_name(&self) -> anyhow::Result<&str> {
+        let name = self.database.as_str();
+
+        if name.is_empty() {
+            anyhow::bail!("database.database must not be empty");
+        }
+        if name.len() > MAX_DATABASE_NAME_LEN {
+            anyhow::bail!(
+                "database.database must be at most {} bytes, got {:?}",
+                MAX_DATABASE_NAME_LEN,
+                name
+            );
+        }
+        if let Some(invalid) = name
+            .chars()
+            .find(|c| INVALID_DATABASE_CHARACTERS.contains(c) || *c == '\0')
+        {
+            anyhow::bail!(
+                "database.database must not contain {:?}, got {:?}",
+                invalid,
+                name
+            );
+        }
+
+        Ok(name)
+    }
+
     pub fn connect_options(&self) -> anyhow::Result<ClientOptions> {
         let ssl_mode = if self.ssl {
             Some(Tls::Enabled(TlsOptions::default()))
@@ -55,31 +289,392 @@ impl DatabaseSettings {
             .password(Some(self.password.expose_secret().into()))
             .build();
 
+        if self.hosts.is_empty() {
+            anyhow::bail!("at least one mongodb host required");
+        }
+
         let mut hosts = Vec::with_capacity(self.hosts.len());
         for host in self.hosts.iter() {
-            let parsed_host = ServerAddress::parse(host)?;
+            if host.trim().is_empty() || host.contains(char::is_whitespace) || host.contains('/') {
+                anyhow::bail!("malformed mongodb host {:?}", host);
+            }
+
+            let parsed_host = ServerAddress::parse(host)
+                .with_context(|| format!("malformed mongodb host {:?}", host))?;
             hosts.push(parsed_host);
         }
 
-        Ok(ClientOptions::builder()
+        let mut options = ClientOptions::builder()
             .hosts(hosts)
             .credential(Some(credential))
-            .default_database(self.database.clone())
+            .default_database(self.database_name()?.to_owned())
             .tls(ssl_mode)
             .app_name(Some("scrum-discord-bot".into()))
-            .build())
+            .server_selection_timeout(Some(Duration::from_millis(
+                self.server_selection_timeout_ms,
+            )))
+            .connect_timeout(Some(Duration::from_millis(self.connect_timeout_ms)))
+            .build();
+        self.apply_options(&mut options)?;
+
+        Ok(options)
+    }
+
+    /// Set the passthrough [`DatabaseSettings::options`] on `options`.
+    pub fn apply_options(&self, options: &mut ClientOptions) -> anyhow::Result<()> {
+        // Sorted so the first bad option reported doesn't vary between runs.
+        let mut passthrough: Vec<_> = self.options.iter().collect();
+        passthrough.sort();
+
+        for (name, value) in passthrough {
+            let key = name.to_lowercase();
+            if let Some((_, field)) = TYPED_DATABASE_OPTIONS
+                .iter()
+                .find(|(option, _)| *option == key)
+            {
+                anyhow::bail!(
+                    "database.options.{} conflicts with database.{}",
+                    name,
+                    field
+                );
+            }
+
+            let invalid = || format!("invalid database.options.{} {:?}", name, value);
+            let millis = |value: &str| {
+                value
+                    .parse()
+                    .map(Duration::from_millis)
+                    .with_context(invalid)
+            };
+            match key.as_str() {
+                "retrywrites" => options.retry_writes = Some(value.parse().with_context(invalid)?),
+                "retryreads" => options.retry_reads = Some(value.parse().with_context(invalid)?),
+                "directconnection" => {
+                    options.direct_connection = Some(value.parse().with_context(invalid)?)
+                }
+                "loadbalanced" => {
+                    options.load_balanced = Some(value.parse().with_context(invalid)?)
+                }
+                "maxpoolsize" => options.max_pool_size = Some(value.parse().with_context(invalid)?),
+                "minpoolsize" => options.min_pool_size = Some(value.parse().with_context(invalid)?),
+                "maxconnecting" => {
+                    options.max_connecting = Some(value.parse().with_context(invalid)?)
+                }
+                "maxidletimems" => options.max_idle_time = Some(millis(value)?),
+                "heartbeatfrequencyms" => options.heartbeat_freq = Some(millis(value)?),
+                "localthresholdms" => options.local_threshold = Some(millis(value)?),
+                "replicaset" => options.repl_set_name = Some(value.clone()),
+                _ => anyhow::bail!("unsupported database.options.{}", name),
+            }
+        }
+
+        Ok(())
     }
 }
 
 #[derive(serde::Deserialize, Clone)]
 pub struct OpenTelemetrySettings {
-    pub endpoint: String,
+    /// The OTLP collector, see [`OpenTelemetrySettings::endpoint`] when unset.
+    #[serde(default)]
+    pub endpoint: Option<String>,
     pub enable: bool,
+    /// Request paths, relative to `http.prefix`, that are never traced.
+    pub exclude_paths: Vec<String>,
+    /// Share of the traces started by the bot that are sampled, from 0 to 1.
+    /// Traces continued from a caller follow its decision.
+    pub sample_ratio: f64,
+    /// Networks whose requests may force their trace to be sampled with the
+    /// `x-force-trace: true` header.
+    pub force_trace_from: Vec<IpNet>,
+}
+
+/// The kinds of telemetry exported over OTLP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OtlpSignal {
+    Traces,
+    Logs,
+    Metrics,
+}
+
+impl OtlpSignal {
+    pub const ALL: [OtlpSignal; 3] = [OtlpSignal::Traces, OtlpSignal::Logs, OtlpSignal::Metrics];
+
+    /// The standard variable holding the endpoint of this signal alone.
+    pub fn endpoint_var(self) -> &'static str {
+        match self {
+            OtlpSignal::Traces => "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT",
+            OtlpSignal::Logs => "OTEL_EXPORTER_OTLP_LOGS_ENDPOINT",
+            OtlpSignal::Metrics => "OTEL_EXPORTER_OTLP_METRICS_ENDPOINT",
+        }
+    }
+}
+
+/// The standard variable holding the endpoint of every signal.
+pub const OTLP_ENDPOINT_VAR: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+/// Resource attribute holding [`ApplicationSettings::commit_sha`].
+pub const COMMIT_SHA_ATTRIBUTE: &str = "vcs.repository.ref.revision";
+
+impl OpenTelemetrySettings {
+    /// Where `signal` is exported: `otel.endpoint`, else the variable of the
+    /// signal, else [`OTLP_ENDPOINT_VAR`].
+    pub fn endpoint(&self, signal: OtlpSignal) -> anyhow::Result<String> {
+        self.endpoint_from(signal, |name| std::env::var(name).ok())
+    }
+
+    fn endpoint_from(
+        &self,
+        signal: OtlpSignal,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> anyhow::Result<String> {
+        let set = |value: &Option<String>| value.clone().filter(|value| !value.trim().is_empty());
+
+        set(&self.endpoint)
+            .or_else(|| set(&env(signal.endpoint_var())))
+            .or_else(|| set(&env(OTLP_ENDPOINT_VAR)))
+            .with_context(|| {
+                format!(
+                    "otel.endpoint, {} or {} is required while otel.enable is true",
+                    signal.endpoint_var(),
+                    OTLP_ENDPOINT_VAR
+                )
+            })
+    }
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct LoggingSettings {
+    /// Top level JSON log fields to rename, e.g. `msg: message`.
+    pub rename: HashMap<String, String>,
+    /// Top level JSON log fields to leave out, e.g. `line`.
+    pub drop: Vec<String>,
+    /// Also write the logs to a rotating file, stdout only when unset.
+    #[serde(default)]
+    pub file: Option<LogFileSettings>,
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct LogFileSettings {
+    pub directory: PathBuf,
+    /// Start of the file names, followed by the date of the period.
+    pub prefix: String,
+    #[serde(default)]
+    pub rotation: LogRotation,
 }
 
+/// How often a new log file is started.
+#[derive(serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    Minutely,
+    Hourly,
+    #[default]
+    Daily,
+    Never,
+}
+
+/// Every problem found while validating [`Settings`].
+#[derive(Debug, thiserror::Error)]
+#[error("invalid configuration: {}", .0.join("; "))]
+pub struct ValidationError(pub Vec<String>);
+
 impl Settings {
+    /// Build and validate the settings from the `APP_` environment variables
+    /// over the defaults of `config/base.yaml` compiled in, never reading the
+    /// `config/` directory. See [`environment_source`] for the variable names.
+    pub fn from_env_only() -> Result<Self, config::ConfigError> {
+        let environment = std::env::var("APP_ENVIRONMENT").unwrap_or_else(|_| "local".into());
+        let settings = from_environment(environment_source(), environment)?;
+
+        settings
+            .validate()
+            .map_err(|error| config::ConfigError::Message(error.to_string()))?;
+
+        Ok(settings)
+    }
+
+    /// The MongoDB database of the bot, see [`DatabaseSettings::database_name`].
+    pub fn mongo_database_name(&self) -> anyhow::Result<&str> {
+        self.database.database_name()
+    }
+
+    /// Check invariants that serde alone can't express.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let mut errors = Vec::new();
+
+        if self.application.default_timezone.parse::<Tz>().is_err() {
+            errors.push(format!(
+                "application.default_timezone must be an IANA timezone, got {:?}",
+                self.application.default_timezone
+            ));
+        }
+
+        for pattern in self.http.route_timeouts.keys() {
+            if !pattern.starts_with('/') {
+                errors.push(format!(
+                    "http.route_timeouts keys must start with `/`, got {:?}",
+                    pattern
+                ));
+            }
+        }
+
+        if let Some(response_timeout) = self.http.response_timeout {
+            if response_timeout <= self.http.sse_heartbeat_secs {
+                errors.push(format!(
+                    "http.response_timeout must be longer than http.sse_heartbeat_secs ({}s), got {}s",
+                    self.http.sse_heartbeat_secs, response_timeout
+                ));
+            }
+        }
+
+        if self.http.access_log_sample_rate == 0 {
+            errors.push("http.access_log_sample_rate must be at least 1".to_owned());
+        }
+
+        let metrics_path = &self.prometheus.path;
+        if !metrics_path.starts_with('/') {
+            errors.push(format!(
+                "prometheus.path must start with `/`, got {:?}",
+                metrics_path
+            ));
+        }
+
+        let mut buckets: Vec<_> = self.prometheus.buckets.iter().collect();
+        buckets.sort_by_key(|(name, _)| name.as_str());
+        for (name, bounds) in buckets {
+            if !HISTOGRAMS.contains(&name.as_str()) {
+                errors.push(format!(
+                    "prometheus.buckets.{} is not one of the histograms {:?}",
+                    name, HISTOGRAMS
+                ));
+            } else if bounds.is_empty()
+                || bounds.iter().any(|bound| !bound.is_finite())
+                || bounds.windows(2).any(|pair| pair[0] >= pair[1])
+            {
+                errors.push(format!(
+                    "prometheus.buckets.{} must be finite and strictly increasing, got {:?}",
+                    name, bounds
+                ));
+            }
+        }
+
+        if !self.prometheus.standalone_server && !self.prometheus.main_router {
+            errors.push(
+                "prometheus.standalone_server or prometheus.main_router must be enabled".to_owned(),
+            );
+        }
+
+        // The main router would serve both, so the metrics route must live
+        // outside of the application prefix.
+        let prefix = normalize_prefix(&self.http.prefix);
+        let overlaps = prefix.is_empty()
+            || *metrics_path == prefix
+            || metrics_path.starts_with(&format!("{}/", prefix));
+        if self.metrics_exposure().main_router && overlaps {
+            errors.push(format!(
+                "prometheus.path {:?} overlaps with http.prefix {:?} on the main router",
+                metrics_path, prefix
+            ));
+        }
+
+        if let Some(tls) = &self.http.tls {
+            for (field, path) in [("cert_path", &tls.cert_path), ("key_path", &tls.key_path)] {
+                if !path.is_file() {
+                    errors.push(format!(
+                        "http.tls.{} {:?} is not a readable file",
+                        field, path
+                    ));
+                }
+            }
+        }
+
+        if !(0.0..=1.0).contains(&self.otel.sample_ratio) {
+            errors.push(format!(
+                "otel.sample_ratio must be between 0 and 1, got {}",
+                self.otel.sample_ratio
+            ));
+        }
+
+        if self.otel.enable {
+            if let Some(error) = OtlpSignal::ALL
+                .into_iter()
+                .find_map(|signal| self.otel.endpoint(signal).err())
+            {
+                errors.push(error.to_string());
+            }
+        }
+
+        if let Err(error) = self.mongo_database_name() {
+            errors.push(error.to_string());
+        }
+
+        if let Err(error) = self.database.apply_options(&mut ClientOptions::default()) {
+            errors.push(format!("{:#}", error));
+        }
+
+        if self.database.hosts.len() > self.database.max_hosts {
+            errors.push(format!(
+                "database.hosts must hold at most {} hosts, got {}",
+                self.database.max_hosts,
+                self.database.hosts.len()
+            ));
+        }
+
+        if self.database.retry.attempts == 0 {
+            errors.push("database.retry.attempts must be at least 1".to_owned());
+        }
+        if self.database.breaker.failure_threshold == 0 {
+            errors.push("database.breaker.failure_threshold must be at least 1".to_owned());
+        }
+
+        if self.scheduler.max_jitter_secs >= 60 {
+            errors.push(format!(
+                "scheduler.max_jitter_secs must be below 60, got {}",
+                self.scheduler.max_jitter_secs
+            ));
+        }
+
+        if self.scheduler.max_concurrent_jobs == 0 {
+            errors.push("scheduler.max_concurrent_jobs must be at least 1".to_owned());
+        }
+        if self.scheduler.job_timeout_secs == 0 {
+            errors.push("scheduler.job_timeout_secs must be at least 1".to_owned());
+        }
+
+        if let Err(error) = StandupPrompt::new(&self.templates.standup_prompt) {
+            errors.push(format!("templates.standup_prompt: {}", error));
+        }
+        if let Err(error) = GatewayIntents::from_names(&self.discord.intents) {
+            errors.push(format!("discord.intents: {}", error));
+        }
+
+        if self.scrum.standup_min_length > MAX_ANSWER_LENGTH {
+            errors.push(format!(
+                "scrum.standup_min_length must be at most {}, got {}",
+                MAX_ANSWER_LENGTH, self.scrum.standup_min_length
+            ));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError(errors))
+        }
+    }
+
+    /// A standalone server can't listen on the port of the main application,
+    /// which serves the metrics instead.
+    pub fn metrics_exposure(&self) -> MetricsExposure {
+        let shared_port = self.http.port == self.prometheus.port;
+
+        MetricsExposure {
+            standalone_server: self.prometheus.standalone_server && !shared_port,
+            main_router: self.prometheus.main_router || shared_port,
+        }
+    }
+
     pub fn get_resource(&self) -> Resource {
-        Resource::default().merge(&Resource::new(vec![
+        let mut attributes = vec![
             KeyValue::new(
                 opentelemetry_semantic_conventions::resource::SERVICE_NAME,
                 self.application.name.clone(),
@@ -88,7 +683,12 @@ impl Settings {
                 opentelemetry_semantic_conventions::resource::SERVICE_VERSION,
                 self.application.version.clone(),
             ),
-        ]))
+        ];
+        if let Some(commit_sha) = self.application.commit_sha() {
+            attributes.push(KeyValue::new(COMMIT_SHA_ATTRIBUTE, commit_sha.to_owned()));
+        }
+
+        Resource::default().merge(&Resource::new(attributes))
     }
 }
 
@@ -97,9 +697,147 @@ pub struct PrometheusSettings {
     #[serde(deserialize_with = "deserialize_number_from_string")]
     pub port: u16,
     pub path: String,
+    /// Prefix of every metric name, the application name when unset.
+    #[serde(default)]
+    pub namespace: Option<String>,
+    /// Serve the metrics on their own listener on `port`, on when unset.
+    #[serde(default = "default_standalone_server")]
+    pub standalone_server: bool,
+    /// Serve the metrics on `path` of the main application listener.
+    #[serde(default)]
+    pub main_router: bool,
+    /// Drop `http.prefix` from the `path` label of the http metrics, so the
+    /// series keep their name when the prefix changes.
+    #[serde(default)]
+    pub strip_prefix: bool,
+    /// Histogram buckets by metric name, without the namespace, e.g.
+    /// `latency_success: [0.01, 0.1, 1]`. Unlisted histograms keep theirs.
+    #[serde(default)]
+    pub buckets: HashMap<String, Vec<f64>>,
+    /// Seconds the standalone server waits for open scrapes on shutdown.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub shutdown_grace_secs: u64,
+}
+
+fn default_standalone_server() -> bool {
+    true
+}
+
+/// Where the metrics are served, see [`Settings::metrics_exposure`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetricsExposure {
+    pub standalone_server: bool,
+    pub main_router: bool,
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct GrpcSettings {
+    pub enabled: bool,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub port: u16,
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct SchedulerSettings {
+    /// Log the scheduled messages instead of posting them.
+    pub dry_run: bool,
+    /// Spread the guilds sharing a reminder time over this many seconds of
+    /// the minute, below 60.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_jitter_secs: u32,
+    /// Background jobs running at the same time, at least 1.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_concurrent_jobs: usize,
+    /// A run of a background job taking longer is cancelled.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub job_timeout_secs: u64,
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct ScrumSettings {
+    /// Standup entries older than this many days are deleted, never when 0.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub retention_days: u32,
+    /// Sprints of a team covering the exact dates of another are rejected or only logged.
+    pub duplicate_sprints: DuplicateSprints,
+    /// Shortest yesterday or today answer of a standup, in characters, 0 for any.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub standup_min_length: usize,
+    /// Words left out of the blocker keywords on top of those of the guild
+    /// language, e.g. the team jargon.
+    pub blocker_stopwords: Vec<String>,
+}
+
+/// Wording of the messages the bot posts on its own.
+#[derive(serde::Deserialize, Clone)]
+pub struct TemplateSettings {
+    /// Supports the `{channel}` and `{date}` placeholders.
+    pub standup_prompt: String,
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct DiscordSettings {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_message_length: usize,
+    /// Interactions handled at once, the others are answered busy.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_concurrent_interactions: usize,
+    /// Gateway intents the client identifies with, by snake case name, e.g.
+    /// `guild_messages`. Slash commands need none of them.
+    pub intents: Vec<String>,
+    /// Token of the bot, nothing is sent to Discord without it.
+    #[serde(default)]
+    pub token: Option<SecretString>,
+    /// Versioned base URL of the REST API.
+    pub api_url: String,
+}
+
+impl DiscordSettings {
+    /// The configured message length, never above what Discord accepts.
+    pub fn message_length_limit(&self) -> usize {
+        self.max_message_length.min(MESSAGE_CONTENT_LIMIT)
+    }
+
+    /// The intents to build the gateway client with, warning about the
+    /// privileged ones Discord refuses unless approved for the bot.
+    pub fn gateway_intents(&self) -> Result<GatewayIntents, UnknownIntent> {
+        let intents = GatewayIntents::from_names(&self.intents)?;
+
+        let privileged = intents.privileged();
+        if !privileged.is_empty() {
+            tracing::warn!(
+                intents = ?privileged,
+                "privileged gateway intents requested, the gateway refuses to connect \
+                 unless they are enabled in the Discord developer portal"
+            );
+        }
+
+        Ok(intents)
+    }
 }
 
 pub fn get_configuration() -> Result<Settings, config::ConfigError> {
+    let settings = read_configuration()?;
+
+    settings
+        .validate()
+        .map_err(|error| config::ConfigError::Message(error.to_string()))?;
+
+    Ok(settings)
+}
+
+/// `prefix` with a single leading slash and no trailing one, empty stays empty.
+pub fn normalize_prefix(prefix: &str) -> String {
+    let trimmed = prefix.trim().trim_matches('/');
+    if trimmed.is_empty() {
+        return String::new();
+    }
+
+    format!("/{}", trimmed)
+}
+
+/// Read the configuration files and environment without validating them.
+pub fn read_configuration() -> Result<Settings, config::ConfigError> {
     let base_path = std::env::current_dir().expect("Failed to determine the current directory");
     let configuration_directory = base_path.join("config");
 
@@ -119,20 +857,173 @@ pub fn get_configuration() -> Result<Settings, config::ConfigError> {
         .add_source(config::File::from(
             configuration_directory.join(environment_filename),
         ))
-        .add_source(
-            config::Environment::with_prefix("APP")
-                .prefix_separator("_")
-                .separator("_"),
-        )
+        .add_source(environment_source())
         .build()?;
 
     let mut settings_parsed = settings.try_deserialize::<Settings>()?;
 
     settings_parsed.env = environment;
+    settings_parsed.http.prefix = normalize_prefix(&settings_parsed.http.prefix);
 
     Ok(settings_parsed)
 }
 
+/// The defaults, the settings of `config/base.yaml` as it was built.
+const BASE_CONFIGURATION: &str = include_str!("../config/base.yaml");
+
+/// `source` over [`BASE_CONFIGURATION`], the `environment` being the value of
+/// `APP_ENVIRONMENT`.
+fn from_environment(
+    source: config::Environment,
+    environment: String,
+) -> Result<Settings, config::ConfigError> {
+    let environment: Environment = environment
+        .try_into()
+        .map_err(config::ConfigError::Message)?;
+
+    let mut settings = config::Config::builder()
+        .add_source(config::File::from_str(
+            BASE_CONFIGURATION,
+            config::FileFormat::Yaml,
+        ))
+        .add_source(source)
+        .build()?
+        .try_deserialize::<Settings>()?;
+
+    settings.env = environment;
+    settings.http.prefix = normalize_prefix(&settings.http.prefix);
+
+    Ok(settings)
+}
+
+/// The `APP_` environment variables, e.g. `APP_HTTP_PORT=8080` for `http.port`.
+///
+/// `_` separates the nested keys too, so keys with an underscore of their
+/// own, like `http.sse_heartbeat_secs`, can only be set in the files.
+/// `APP_DATABASE_HOSTS` is a comma separated list, e.g. `mongo-1,mongo-2:27018`.
+fn environment_source() -> config::Environment {
+    // Values stay strings, a password such as `0123` isn't read as a number.
+    config::Environment::with_prefix("APP")
+        .prefix_separator("_")
+        .separator("_")
+}
+
+/// The hosts as a list in the files, or a comma separated string in the
+/// environment.
+fn deserialize_hosts<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<String>, D::Error> {
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Hosts {
+        List(Vec<String>),
+        Joined(String),
+    }
+
+    Ok(
+        match <Hosts as serde::Deserialize>::deserialize(deserializer)? {
+            Hosts::List(hosts) => hosts,
+            Hosts::Joined(hosts) => hosts
+                .split(',')
+                .map(str::trim)
+                .filter(|host| !host.is_empty())
+                .map(str::to_owned)
+                .collect(),
+        },
+    )
+}
+
+type Loader = dyn Fn() -> Result<Settings, config::ConfigError> + Send + Sync;
+
+/// Why a reload was rejected, nothing was applied.
+#[derive(Debug, thiserror::Error)]
+pub enum ReloadError {
+    #[error("failed to read configuration: {0}")]
+    Read(#[from] config::ConfigError),
+    #[error(transparent)]
+    Invalid(#[from] ValidationError),
+}
+
+/// A reloaded field, `None` when unset.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Change<T> {
+    pub before: Option<T>,
+    pub after: Option<T>,
+}
+
+/// The fields changed by a reload.
+#[derive(Debug, Default, PartialEq, Eq, serde::Serialize)]
+pub struct ReloadDiff {
+    pub features: BTreeMap<String, Change<bool>>,
+}
+
+/// Where the outcomes of [`ConfigReloader::reload`] are counted.
+pub trait ReloadCounter: Send + Sync {
+    fn reloaded(&self, applied: bool);
+}
+
+/// Applies the fields that can change at runtime from a fresh configuration,
+/// on SIGHUP or from the admin API.
+#[derive(Clone)]
+pub struct ConfigReloader {
+    load: Arc<Loader>,
+    features: FeatureFlags,
+    counter: Option<Arc<dyn ReloadCounter>>,
+}
+
+impl ConfigReloader {
+    pub fn new(features: FeatureFlags) -> Self {
+        Self::with_loader(features, read_configuration)
+    }
+
+    pub fn with_loader(
+        features: FeatureFlags,
+        load: impl Fn() -> Result<Settings, config::ConfigError> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            load: Arc::new(load),
+            features,
+            counter: None,
+        }
+    }
+
+    pub fn with_counter(mut self, counter: Arc<dyn ReloadCounter>) -> Self {
+        self.counter = Some(counter);
+        self
+    }
+
+    /// Re-read and validate the configuration, then apply it.
+    pub fn reload(&self) -> Result<ReloadDiff, ReloadError> {
+        let result = self.apply();
+        if let Some(counter) = &self.counter {
+            counter.reloaded(result.is_ok());
+        }
+
+        result
+    }
+
+    fn apply(&self) -> Result<ReloadDiff, ReloadError> {
+        let settings = (self.load)()?;
+        settings.validate()?;
+
+        let before = self.features.snapshot();
+        let mut features = BTreeMap::new();
+        for name in before.keys().chain(settings.features.keys()) {
+            let change = Change {
+                before: before.get(name).copied(),
+                after: settings.features.get(name).copied(),
+            };
+            if change.before != change.after {
+                features.insert(name.clone(), change);
+            }
+        }
+
+        self.features.replace(settings.features);
+
+        Ok(ReloadDiff { features })
+    }
+}
+
 /// The possible runtime environment for our application.
 #[derive(Clone, serde::Deserialize)]
 pub enum Environment {
@@ -163,3 +1054,709 @@ impl TryFrom<String> for Environment {
         }
     }
 }
+
+/// The api keys of the HTTP tests: `admin-key` of `ops` acts on every guild,
+/// `key` of `dashboard` on guilds 1 and 3 and `other-key` of `bot` on guild 2.
+#[cfg(test)]
+pub(crate) fn test_keys() -> Vec<ApiKeySettings> {
+    let key = |label: &str, key: &str, admin, guilds: &[u64]| ApiKeySettings {
+        label: label.into(),
+        key: SecretString::from(key),
+        admin,
+        guilds: guilds.iter().copied().map(GuildId).collect(),
+    };
+
+    vec![
+        key("ops", "admin-key", true, &[]),
+        key("dashboard", "key", false, &[1, 3]),
+        key("bot", "other-key", false, &[2]),
+    ]
+}
+
+/// The settings of `config/base.yaml`, without touching the filesystem or the environment.
+#[cfg(test)]
+pub(crate) fn test_settings() -> Settings {
+    config::Config::builder()
+        .add_source(config::File::from_str(
+            BASE_CONFIGURATION,
+            config::FileFormat::Yaml,
+        ))
+        .build()
+        .expect("expected base configuration to build")
+        .try_deserialize()
+        .expect("expected base configuration to deserialize")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base_configuration_is_valid() {
+        test_settings().validate().unwrap();
+    }
+
+    /// `BASE_CONFIGURATION` without the `keys`, and their nested values.
+    fn base_configuration_without(keys: &[&str]) -> Settings {
+        let mut dropped_indent = None;
+        let configuration = BASE_CONFIGURATION
+            .lines()
+            .filter(|line| {
+                let indent = line.len() - line.trim_start().len();
+                if dropped_indent.is_some_and(|dropped| indent > dropped) {
+                    return false;
+                }
+                dropped_indent = keys
+                    .iter()
+                    .any(|key| line.trim_start().starts_with(&format!("{}:", key)))
+                    .then_some(indent);
+                dropped_indent.is_none()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        config::Config::builder()
+            .add_source(config::File::from_str(
+                &configuration,
+                config::FileFormat::Yaml,
+            ))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap()
+    }
+
+    #[test]
+    fn unset_path_and_metrics_options_keep_the_former_behaviour() {
+        let settings = base_configuration_without(&[
+            "normalize_trailing_slash",
+            "collapse_slashes",
+            "redact_headers",
+            "standalone_server",
+            "main_router",
+        ]);
+
+        assert!(settings.http.normalize_trailing_slash);
+        assert!(!settings.http.collapse_slashes);
+        assert_eq!(
+            settings.http.redact_headers,
+            [
+                "authorization",
+                "proxy-authorization",
+                "cookie",
+                "set-cookie"
+            ]
+        );
+        assert!(settings.prometheus.standalone_server);
+        assert!(!settings.prometheus.main_router);
+        settings.validate().unwrap();
+    }
+
+    #[test]
+    fn response_timeout_must_outlast_the_sse_heartbeat() {
+        let mut settings = test_settings();
+        settings.http.sse_heartbeat_secs = 15;
+
+        settings.http.response_timeout = Some(60);
+        settings.validate().unwrap();
+
+        settings.http.response_timeout = Some(15);
+        let error = settings.validate().unwrap_err().to_string();
+        assert!(error.contains("http.response_timeout"), "{error}");
+    }
+
+    #[test]
+    fn resource_carries_the_commit_sha_when_set() {
+        let mut settings = test_settings();
+        settings.application.commit_sha = Some("3f2a9c1".into());
+
+        let resource = settings.get_resource();
+
+        assert_eq!(
+            resource.get(opentelemetry::Key::new(COMMIT_SHA_ATTRIBUTE)),
+            Some("3f2a9c1".into())
+        );
+    }
+
+    #[test]
+    fn environment_splits_database_hosts() {
+        let settings: Settings = config::Config::builder()
+            .add_source(config::File::from_str(
+                BASE_CONFIGURATION,
+                config::FileFormat::Yaml,
+            ))
+            .add_source(environment_source().source(Some(HashMap::from([
+                (
+                    "APP_DATABASE_HOSTS".to_owned(),
+                    "mongo-1,mongo-2:27018".to_owned(),
+                ),
+                ("APP_DATABASE_USERNAME".to_owned(), "scrum".to_owned()),
+                ("APP_HTTP_PORT".to_owned(), "9000".to_owned()),
+            ]))))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap();
+
+        assert_eq!(settings.database.hosts, vec!["mongo-1", "mongo-2:27018"]);
+        assert_eq!(settings.database.username, "scrum");
+        assert_eq!(settings.http.port, 9000);
+    }
+
+    #[test]
+    fn environment_keeps_numeric_looking_strings() {
+        let variables = HashMap::from([
+            ("APP_DATABASE_HOSTS".to_owned(), "mongo-ci".to_owned()),
+            ("APP_DATABASE_PASSWORD".to_owned(), "0123".to_owned()),
+        ]);
+
+        let settings = from_environment(
+            environment_source().source(Some(variables)),
+            "production".into(),
+        )
+        .unwrap();
+
+        assert_eq!(settings.database.password.expose_secret(), "0123");
+    }
+
+    #[test]
+    fn environment_alone_populates_the_settings() {
+        let variables = HashMap::from([
+            ("APP_APPLICATION_NAME".to_owned(), "scrum-ci".to_owned()),
+            ("APP_DATABASE_HOSTS".to_owned(), "mongo-ci".to_owned()),
+            ("APP_HTTP_PORT".to_owned(), "9000".to_owned()),
+            ("APP_HTTP_PREFIX".to_owned(), "api/".to_owned()),
+            ("APP_PROMETHEUS_PORT".to_owned(), "9001".to_owned()),
+        ]);
+
+        let settings = from_environment(
+            environment_source().source(Some(variables)),
+            "production".into(),
+        )
+        .unwrap();
+
+        settings.validate().unwrap();
+        assert_eq!(settings.application.name, "scrum-ci");
+        assert_eq!(settings.database.hosts, vec!["mongo-ci"]);
+        assert_eq!(settings.http.port, 9000);
+        assert_eq!(settings.http.prefix, "/api");
+        assert_eq!(settings.prometheus.port, 9001);
+        assert_eq!(settings.env.as_str(), "production");
+        // Everything else comes from the compiled in defaults.
+        assert_eq!(
+            settings.http.sse_heartbeat_secs,
+            test_settings().http.sse_heartbeat_secs
+        );
+    }
+
+    #[test]
+    fn environment_alone_rejects_unknown_environments() {
+        let Err(error) = from_environment(environment_source(), "staging".into()) else {
+            panic!("expected staging to be rejected");
+        };
+
+        assert!(error.to_string().contains("staging"), "{error}");
+    }
+
+    #[test]
+    fn validate_rejects_unreachable_standup_min_length() {
+        let mut settings = test_settings();
+        settings.scrum.standup_min_length = MAX_ANSWER_LENGTH + 1;
+
+        let error = settings.validate().unwrap_err();
+
+        assert_eq!(
+            error.0,
+            vec!["scrum.standup_min_length must be at most 1000, got 1001"]
+        );
+    }
+
+    #[test]
+    fn validate_rejects_more_database_hosts_than_the_maximum() {
+        let mut settings = test_settings();
+        settings.database.max_hosts = 2;
+        settings.database.hosts = vec!["mongo-1".into(), "mongo-2".into(), "mongo-3".into()];
+
+        let error = settings.validate().unwrap_err();
+
+        assert_eq!(
+            error.0,
+            vec!["database.hosts must hold at most 2 hosts, got 3"]
+        );
+    }
+
+    #[test]
+    fn validate_rejects_unknown_gateway_intents() {
+        let mut settings = test_settings();
+        settings.discord.intents = vec!["guilds".into(), "voice".into()];
+
+        let error = settings.validate().unwrap_err();
+
+        assert_eq!(
+            error.0,
+            vec!["discord.intents: unknown gateway intent \"voice\""]
+        );
+    }
+
+    #[test]
+    fn validate_rejects_unknown_default_timezone() {
+        let mut settings = test_settings();
+        settings.application.default_timezone = "Mars/Olympus_Mons".into();
+
+        let error = settings.validate().unwrap_err();
+
+        assert_eq!(
+            error.0,
+            vec![
+                "application.default_timezone must be an IANA timezone, got \"Mars/Olympus_Mons\""
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_rejects_route_timeout_without_leading_slash() {
+        let mut settings = test_settings();
+        settings.http.route_timeouts = HashMap::from([("export".to_owned(), 60)]);
+
+        let error = settings.validate().unwrap_err();
+
+        assert_eq!(
+            error.0,
+            vec!["http.route_timeouts keys must start with `/`, got \"export\""]
+        );
+    }
+
+    #[test]
+    fn validate_rejects_a_zero_access_log_sample_rate() {
+        let mut settings = test_settings();
+        settings.http.access_log_sample_rate = 0;
+
+        let error = settings.validate().unwrap_err();
+
+        assert_eq!(
+            error.0,
+            vec!["http.access_log_sample_rate must be at least 1"]
+        );
+    }
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn otlp_endpoint_prefers_the_configuration() {
+        let mut otel = test_settings().otel;
+        otel.endpoint = Some("http://collector:4317".into());
+
+        let endpoint = otel
+            .endpoint_from(
+                OtlpSignal::Traces,
+                env(&[(OTLP_ENDPOINT_VAR, "http://other:4317")]),
+            )
+            .unwrap();
+
+        assert_eq!(endpoint, "http://collector:4317");
+    }
+
+    #[test]
+    fn otlp_endpoint_falls_back_to_the_environment() {
+        let mut otel = test_settings().otel;
+        otel.endpoint = None;
+        let env = env(&[
+            (OTLP_ENDPOINT_VAR, "http://shared:4317"),
+            ("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT", "http://traces:4317"),
+        ]);
+
+        assert_eq!(
+            otel.endpoint_from(OtlpSignal::Traces, &env).unwrap(),
+            "http://traces:4317"
+        );
+        assert_eq!(
+            otel.endpoint_from(OtlpSignal::Logs, &env).unwrap(),
+            "http://shared:4317"
+        );
+    }
+
+    #[test]
+    fn otlp_endpoint_is_required_somewhere() {
+        let mut otel = test_settings().otel;
+        otel.endpoint = None;
+
+        let error = otel
+            .endpoint_from(OtlpSignal::Metrics, env(&[(OTLP_ENDPOINT_VAR, "")]))
+            .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "otel.endpoint, OTEL_EXPORTER_OTLP_METRICS_ENDPOINT or OTEL_EXPORTER_OTLP_ENDPOINT is required while otel.enable is true"
+        );
+    }
+
+    #[test]
+    fn validate_rejects_bad_buckets() {
+        let mut settings = test_settings();
+        settings.prometheus.buckets = HashMap::from([
+            ("latency_error".to_owned(), vec![1.0, 0.5]),
+            ("latency_success".to_owned(), vec![]),
+            ("latency".to_owned(), vec![1.0]),
+            (
+                "sprint_time_to_first_standup_seconds".to_owned(),
+                vec![60.0, 3600.0],
+            ),
+        ]);
+
+        let error = settings.validate().unwrap_err();
+
+        assert_eq!(
+            error.0,
+            vec![
+                "prometheus.buckets.latency is not one of the histograms [\"latency_error\", \"latency_success\", \"sprint_time_to_first_standup_seconds\", \"job_duration_seconds\"]",
+                "prometheus.buckets.latency_error must be finite and strictly increasing, got [1.0, 0.5]",
+                "prometheus.buckets.latency_success must be finite and strictly increasing, got []",
+            ]
+        );
+    }
+
+    #[test]
+    fn prefix_is_normalized_to_a_leading_slash_only() {
+        for prefix in ["/api/", "api", "/api", " api// "] {
+            assert_eq!(normalize_prefix(prefix), "/api", "{:?}", prefix);
+        }
+        assert_eq!(normalize_prefix("/v1/api/"), "/v1/api");
+        assert_eq!(normalize_prefix(""), "");
+        assert_eq!(normalize_prefix("/"), "");
+    }
+
+    #[test]
+    fn validate_rejects_missing_tls_files() {
+        let mut settings = test_settings();
+        settings.http.tls = Some(TlsSettings {
+            cert_path: "tests/fixtures/tls/cert.pem".into(),
+            key_path: "tests/fixtures/tls/missing.pem".into(),
+        });
+
+        let error = settings.validate().unwrap_err();
+
+        assert_eq!(
+            error.0,
+            vec!["http.tls.key_path \"tests/fixtures/tls/missing.pem\" is not a readable file"]
+        );
+    }
+
+    #[test]
+    fn validate_rejects_prometheus_path_without_leading_slash() {
+        let mut settings = test_settings();
+        settings.prometheus.path = "metrics".into();
+
+        let error = settings.validate().unwrap_err();
+
+        assert_eq!(
+            error.0,
+            vec!["prometheus.path must start with `/`, got \"metrics\""]
+        );
+    }
+
+    #[test]
+    fn validate_rejects_overlapping_routes_on_shared_port() {
+        let mut settings = test_settings();
+        settings.prometheus.port = settings.http.port;
+
+        settings.http.prefix = "".into();
+        assert!(settings.validate().is_err());
+
+        settings.http.prefix = "/api".into();
+        settings.prometheus.path = "/api/metrics".into();
+        assert!(settings.validate().is_err());
+
+        settings.prometheus.path = "/metrics".into();
+        settings.validate().unwrap();
+    }
+
+    #[test]
+    fn metrics_are_exposed_where_configured() {
+        let mut settings = test_settings();
+        settings.http.prefix = "/api".into();
+
+        for (standalone_server, main_router) in [(true, false), (false, true), (true, true)] {
+            settings.prometheus.standalone_server = standalone_server;
+            settings.prometheus.main_router = main_router;
+
+            settings.validate().unwrap();
+            assert_eq!(
+                settings.metrics_exposure(),
+                MetricsExposure {
+                    standalone_server,
+                    main_router,
+                }
+            );
+        }
+
+        settings.prometheus.standalone_server = false;
+        settings.prometheus.main_router = false;
+        let error = settings.validate().unwrap_err();
+        assert_eq!(
+            error.0,
+            vec!["prometheus.standalone_server or prometheus.main_router must be enabled"]
+        );
+    }
+
+    #[test]
+    fn shared_port_moves_the_metrics_to_the_main_router() {
+        let mut settings = test_settings();
+        settings.http.prefix = "/api".into();
+        settings.prometheus.port = settings.http.port;
+
+        assert_eq!(
+            settings.metrics_exposure(),
+            MetricsExposure {
+                standalone_server: false,
+                main_router: true,
+            }
+        );
+    }
+
+    #[test]
+    fn validate_rejects_metrics_under_the_prefix_of_the_main_router() {
+        let mut settings = test_settings();
+        settings.prometheus.main_router = true;
+        settings.http.prefix = "/api".into();
+        settings.prometheus.path = "/api/metrics".into();
+
+        let error = settings.validate().unwrap_err();
+
+        assert_eq!(
+            error.0,
+            vec!["prometheus.path \"/api/metrics\" overlaps with http.prefix \"/api\" on the main router"]
+        );
+    }
+
+    #[test]
+    fn validate_rejects_unknown_prompt_placeholders() {
+        let mut settings = test_settings();
+        settings.templates.standup_prompt = "Standup in {chanel}".into();
+
+        let error = settings.validate().unwrap_err();
+
+        assert_eq!(
+            error.0,
+            vec!["templates.standup_prompt: unknown placeholder `{chanel}`"]
+        );
+    }
+
+    fn database_settings(hosts: &[&str]) -> DatabaseSettings {
+        DatabaseSettings {
+            username: "root".into(),
+            password: SecretString::from("example"),
+            port: 27017,
+            hosts: hosts.iter().map(|host| host.to_string()).collect(),
+            max_hosts: 7,
+            database: "discord-bot-rustson".into(),
+            ssl: false,
+            server_selection_timeout_ms: 2000,
+            connect_timeout_ms: 1500,
+            breaker: BreakerSettings {
+                failure_threshold: 5,
+                cooldown_ms: 10_000,
+            },
+            retry: RetrySettings::default(),
+            options: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn connect_options_accepts_valid_hosts() {
+        let options = database_settings(&["localhost", "mongo:27018"])
+            .connect_options()
+            .unwrap();
+
+        assert_eq!(options.hosts.len(), 2);
+    }
+
+    #[test]
+    fn database_name_accepts_valid_names() {
+        for name in ["discord-bot-rustson", "scrum_bot", "Bot2024"] {
+            let mut settings = database_settings(&["localhost"]);
+            settings.database = name.into();
+
+            assert_eq!(settings.database_name().unwrap(), name);
+        }
+    }
+
+    #[test]
+    fn database_name_rejects_invalid_names() {
+        let error = |name: &str| {
+            let mut settings = database_settings(&["localhost"]);
+            settings.database = name.into();
+            settings.database_name().unwrap_err().to_string()
+        };
+
+        assert_eq!(error(""), "database.database must not be empty");
+        assert_eq!(
+            error("scrum.bot"),
+            "database.database must not contain '.', got \"scrum.bot\""
+        );
+        for name in ["a/b", "a\\b", "a b", "a\"b", "$bot", "a\0b"] {
+            assert!(error(name).contains("must not contain"), "{:?}", name);
+        }
+        assert!(error(&"a".repeat(64)).contains("at most 63 bytes"));
+        assert!(database_settings(&["localhost"]).connect_options().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_jitter_past_the_minute() {
+        let mut settings = test_settings();
+        settings.scheduler.max_jitter_secs = 60;
+
+        let error = settings.validate().unwrap_err();
+
+        assert_eq!(
+            error.0,
+            vec!["scheduler.max_jitter_secs must be below 60, got 60"]
+        );
+    }
+
+    #[test]
+    fn validate_rejects_an_empty_job_pool() {
+        let mut settings = test_settings();
+        settings.scheduler.max_concurrent_jobs = 0;
+        settings.scheduler.job_timeout_secs = 0;
+
+        let error = settings.validate().unwrap_err();
+
+        assert_eq!(
+            error.0,
+            vec![
+                "scheduler.max_concurrent_jobs must be at least 1",
+                "scheduler.job_timeout_secs must be at least 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_rejects_sample_ratio_out_of_range() {
+        let mut settings = test_settings();
+        settings.otel.sample_ratio = 1.5;
+
+        let error = settings.validate().unwrap_err();
+
+        assert_eq!(
+            error.0,
+            vec!["otel.sample_ratio must be between 0 and 1, got 1.5"]
+        );
+    }
+
+    #[test]
+    fn validate_rejects_invalid_database_name() {
+        let mut settings = test_settings();
+        settings.database.database = "scrum bot".into();
+
+        let error = settings.validate().unwrap_err();
+
+        assert_eq!(
+            error.0,
+            vec!["database.database must not contain ' ', got \"scrum bot\""]
+        );
+    }
+
+    #[test]
+    fn connect_options_applies_the_timeouts() {
+        let options = database_settings(&["localhost"]).connect_options().unwrap();
+
+        assert_eq!(
+            options.server_selection_timeout,
+            Some(Duration::from_millis(2000))
+        );
+        assert_eq!(options.connect_timeout, Some(Duration::from_millis(1500)));
+    }
+
+    #[test]
+    fn connect_options_passes_options_through() {
+        let mut settings = database_settings(&["localhost"]);
+        settings.options = HashMap::from([
+            ("retryWrites".to_owned(), "false".to_owned()),
+            ("maxPoolSize".to_owned(), "20".to_owned()),
+            ("heartbeatFrequencyMS".to_owned(), "2500".to_owned()),
+        ]);
+
+        let options = settings.connect_options().unwrap();
+
+        assert_eq!(options.retry_writes, Some(false));
+        assert_eq!(options.max_pool_size, Some(20));
+        assert_eq!(options.heartbeat_freq, Some(Duration::from_millis(2500)));
+    }
+
+    #[test]
+    fn connect_options_rejects_options_of_typed_fields() {
+        let mut settings = database_settings(&["localhost"]);
+        settings.options = HashMap::from([("connectTimeoutMS".to_owned(), "100".to_owned())]);
+
+        let error = settings.connect_options().unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "database.options.connectTimeoutMS conflicts with database.connect_timeout_ms"
+        );
+    }
+
+    #[test]
+    fn validate_rejects_bad_database_options() {
+        let mut settings = test_settings();
+        settings.database.options = HashMap::from([
+            ("retryWrites".to_owned(), "maybe".to_owned()),
+            ("zlibCompressionLevel".to_owned(), "9".to_owned()),
+        ]);
+
+        let error = settings.validate().unwrap_err();
+
+        assert_eq!(error.0.len(), 1);
+        assert!(
+            error.0[0].starts_with("invalid database.options.retryWrites \"maybe\""),
+            "{:?}",
+            error.0
+        );
+    }
+
+    #[test]
+    fn connect_options_requires_at_least_one_host() {
+        let error = database_settings(&[]).connect_options().unwrap_err();
+
+        assert_eq!(error.to_string(), "at least one mongodb host required");
+    }
+
+    #[test]
+    fn connect_options_rejects_malformed_host_with_its_value() {
+        for host in ["", "mongodb://localhost", "local host", "localhost:port"] {
+            let error = database_settings(&[host]).connect_options().unwrap_err();
+
+            assert!(
+                error.to_string().contains(&format!("{:?}", host)),
+                "unexpected error for {host:?}: {error}"
+            );
+        }
+    }
+
+    #[test]
+    fn message_length_limit_is_capped_at_discord_maximum() {
+        let discord = DiscordSettings {
+            max_message_length: 10_000,
+            max_concurrent_interactions: 1,
+            intents: vec![],
+            token: None,
+            api_url: "https://discord.com/api/v10".into(),
+        };
+        assert_eq!(discord.message_length_limit(), MESSAGE_CONTENT_LIMIT);
+
+        let discord = DiscordSettings {
+            max_message_length: 500,
+            max_concurrent_interactions: 1,
+            intents: vec![],
+            token: None,
+            api_url: "https://discord.com/api/v10".into(),
+        };
+        assert_eq!(discord.message_length_limit(), 500);
+    }
+}
@@ -0,0 +1,250 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use bson::Document;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::id::GuildId;
+
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Argument names whose values never reach the logs or the audit log.
+const SECRET_ARGUMENTS: [&str; 4] = ["token", "password", "secret", "key"];
+
+/// Something an operator did that changes how the bot behaves for a guild.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    GuildConfigUpdated,
+    ManualReminder,
+    Reindex,
+    AdminCommand,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::GuildConfigUpdated => "guild_config_updated",
+            AuditAction::ManualReminder => "manual_reminder",
+            AuditAction::Reindex => "reindex",
+            AuditAction::AdminCommand => "admin_command",
+        }
+    }
+}
+
+/// Who did what, when and on which guild.
+///
+/// The actor is the discord user id for commands and the api key label for
+/// HTTP calls. Global actions, like a config reload, have no guild.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub actor: String,
+    pub guild_id: Option<GuildId>,
+    pub action: AuditAction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub arguments: BTreeMap<String, String>,
+    pub before: Option<Document>,
+    pub after: Option<Document>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub timestamp: DateTime<Utc>,
+}
+
+impl AuditEntry {
+    pub fn new(
+        actor: impl Into<String>,
+        guild_id: impl Into<Option<GuildId>>,
+        action: AuditAction,
+    ) -> Self {
+        Self {
+            actor: actor.into(),
+            guild_id: guild_id.into(),
+            action,
+            command: None,
+            arguments: BTreeMap::new(),
+            before: None,
+            after: None,
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// Attach the state of the changed entity before and after the action.
+    pub fn with_change<T: Serialize>(mut self, before: &T, after: &T) -> Result<Self> {
+        self.before = Some(bson::to_document(before)?);
+        self.after = Some(bson::to_document(after)?);
+        Ok(self)
+    }
+
+    /// Attach the command and its arguments, redacting the secret ones.
+    pub fn with_command(
+        mut self,
+        command: impl Into<String>,
+        arguments: BTreeMap<String, String>,
+    ) -> Self {
+        self.command = Some(command.into());
+        self.arguments = redact_arguments(arguments);
+        self
+    }
+}
+
+pub fn redact_arguments(arguments: BTreeMap<String, String>) -> BTreeMap<String, String> {
+    arguments
+        .into_iter()
+        .map(|(name, value)| {
+            let lowercase = name.to_lowercase();
+            if SECRET_ARGUMENTS
+                .iter()
+                .any(|secret| lowercase.contains(secret))
+            {
+                (name, REDACTED.to_owned())
+            } else {
+                (name, value)
+            }
+        })
+        .collect()
+}
+
+/// Filters used to query the audit log, newest entries first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct AuditQuery {
+    pub guild_id: Option<GuildId>,
+    pub actor: Option<String>,
+    pub action: Option<AuditAction>,
+    pub limit: Option<u32>,
+}
+
+impl AuditQuery {
+    pub const DEFAULT_LIMIT: u32 = 50;
+    pub const MAX_LIMIT: u32 = 500;
+
+    pub fn limit(&self) -> u32 {
+        self.limit
+            .unwrap_or(Self::DEFAULT_LIMIT)
+            .min(Self::MAX_LIMIT)
+    }
+
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        self.guild_id.is_none_or(|id| entry.guild_id == Some(id))
+            && self
+                .actor
+                .as_ref()
+                .is_none_or(|actor| &entry.actor == actor)
+            && self.action.is_none_or(|action| entry.action == action)
+    }
+}
+
+#[async_trait]
+pub trait AuditRepository: Send + Sync {
+    async fn insert(&self, entry: &AuditEntry) -> Result<()>;
+    async fn list(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>>;
+}
+
+/// Emits audit entries as structured logs and optionally persists them.
+#[derive(Clone, Default)]
+pub struct Auditor {
+    repository: Option<Arc<dyn AuditRepository>>,
+}
+
+impl Auditor {
+    pub fn new(repository: Option<Arc<dyn AuditRepository>>) -> Self {
+        Self { repository }
+    }
+
+    #[tracing::instrument(name = "Record audit entry", skip(self, entry))]
+    pub async fn record(&self, entry: AuditEntry) -> Result<()> {
+        tracing::info!(
+            audit = true,
+            actor = %entry.actor,
+            guild_id = ?entry.guild_id,
+            action = entry.action.as_str(),
+            command = ?entry.command,
+            arguments = ?entry.arguments,
+            before = ?entry.before,
+            after = ?entry.after,
+            timestamp = %entry.timestamp,
+            "audit"
+        );
+
+        if let Some(repository) = &self.repository {
+            repository.insert(&entry).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Record that `actor` ran an admin `command` with the given arguments.
+    pub async fn record_command(
+        &self,
+        actor: impl Into<String>,
+        guild_id: impl Into<Option<GuildId>>,
+        command: impl Into<String>,
+        arguments: BTreeMap<String, String>,
+    ) -> Result<()> {
+        let entry = AuditEntry::new(actor, guild_id, AuditAction::AdminCommand)
+            .with_command(command, arguments);
+
+        self.record(entry).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::drivers::database::memory::InMemoryAuditRepository;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn admin_command_is_recorded_with_redacted_secrets() {
+        let repository = Arc::new(InMemoryAuditRepository::default());
+        let auditor = Auditor::new(Some(repository.clone()));
+
+        let arguments = BTreeMap::from([
+            ("reminder_time".to_owned(), "09:30".to_owned()),
+            ("api_key".to_owned(), "super-secret".to_owned()),
+        ]);
+        auditor
+            .record_command("42", GuildId(1), "config set", arguments)
+            .await
+            .unwrap();
+
+        let entries = repository.list(&AuditQuery::default()).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        let entry = &entries[0];
+        assert_eq!(entry.actor, "42");
+        assert_eq!(entry.guild_id, Some(GuildId(1)));
+        assert_eq!(entry.action, AuditAction::AdminCommand);
+        assert_eq!(entry.command.as_deref(), Some("config set"));
+        assert_eq!(entry.arguments["reminder_time"], "09:30");
+        assert_eq!(entry.arguments["api_key"], REDACTED);
+    }
+
+    #[tokio::test]
+    async fn audit_log_is_queried_by_filters_newest_first() {
+        let repository = InMemoryAuditRepository::default();
+        for (actor, guild_id) in [("1", 10), ("2", 10), ("1", 20)] {
+            let entry = AuditEntry::new(actor, GuildId(guild_id), AuditAction::Reindex);
+            repository.insert(&entry).await.unwrap();
+        }
+
+        let query = AuditQuery {
+            actor: Some("1".into()),
+            ..Default::default()
+        };
+        let entries = repository.list(&query).await.unwrap();
+
+        let guilds: Vec<_> = entries.iter().map(|entry| entry.guild_id).collect();
+        assert_eq!(guilds, vec![Some(GuildId(20)), Some(GuildId(10))]);
+
+        let query = AuditQuery {
+            guild_id: Some(GuildId(10)),
+            limit: Some(1),
+            ..Default::default()
+        };
+        let entries = repository.list(&query).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].actor, "2");
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::i18n::Locale;
+
+/// Keywords returned by default.
+pub const DEFAULT_TOP_KEYWORDS: usize = 10;
+/// Keywords returned at most, whatever was asked.
+pub const MAX_TOP_KEYWORDS: usize = 50;
+
+const STOPWORDS_EN: &[&str] = &[
+    "a", "about", "after", "all", "an", "and", "any", "are", "as", "at", "be", "been", "before",
+    "being", "but", "by", "can", "could", "did", "do", "does", "for", "from", "get", "got", "had",
+    "has", "have", "i", "if", "in", "into", "is", "it", "its", "just", "me", "my", "no", "none",
+    "not", "of", "on", "or", "our", "so", "still", "that", "the", "their", "them", "then", "there",
+    "this", "to", "too", "up", "us", "was", "we", "were", "what", "when", "which", "while", "who",
+    "will", "with", "would", "yet", "you",
+];
+
+const STOPWORDS_PT: &[&str] = &[
+    "a", "ao", "aos", "as", "até", "com", "como", "da", "das", "de", "do", "dos", "e", "ela",
+    "ele", "em", "entre", "era", "essa", "esse", "esta", "está", "este", "eu", "foi", "há", "isso",
+    "já", "mais", "mas", "me", "meu", "minha", "na", "nada", "nas", "nem", "no", "nos", "não", "o",
+    "os", "ou", "para", "pela", "pelo", "por", "que", "se", "sem", "ser", "sua", "seu", "só",
+    "também", "tem", "um", "uma", "vou",
+];
+
+/// A word and how many times it shows up in the blockers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct KeywordCount {
+    pub keyword: String,
+    pub count: usize,
+}
+
+/// Counts the words of the standup blockers, leaving out the stopwords of
+/// the guild language and those configured on top of them.
+#[derive(Debug, Clone)]
+pub struct BlockerKeywords {
+    stopwords: HashSet<String>,
+}
+
+impl BlockerKeywords {
+    pub fn new(locale: Locale, extra_stopwords: &[String]) -> Self {
+        let builtin = match locale {
+            Locale::En => STOPWORDS_EN,
+            Locale::Pt => STOPWORDS_PT,
+        };
+        let stopwords = builtin
+            .iter()
+            .map(|word| word.to_string())
+            .chain(extra_stopwords.iter().map(|word| word.to_lowercase()))
+            .collect();
+
+        Self { stopwords }
+    }
+
+    /// The lowercase words of `text`, split on anything but letters and
+    /// digits. Numbers and single letters say nothing about a blocker.
+    pub fn tokenize<'a>(&'a self, text: &'a str) -> impl Iterator<Item = String> + 'a {
+        text.split(|c: char| !c.is_alphanumeric())
+            .map(str::to_lowercase)
+            .filter(|word| word.chars().count() > 1)
+            .filter(|word| !word.chars().all(|c| c.is_ascii_digit()))
+            .filter(|word| !self.stopwords.contains(word))
+    }
+
+    /// The `limit` most frequent keywords of `blockers`, ties alphabetically.
+    pub fn top<S: AsRef<str>>(&self, blockers: &[S], limit: usize) -> Vec<KeywordCount> {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for blocker in blockers {
+            for word in self.tokenize(blocker.as_ref()) {
+                *counts.entry(word).or_default() += 1;
+            }
+        }
+
+        let mut keywords: Vec<_> = counts
+            .into_iter()
+            .map(|(keyword, count)| KeywordCount { keyword, count })
+            .collect();
+        keywords.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| a.keyword.cmp(&b.keyword))
+        });
+        keywords.truncate(limit);
+
+        keywords
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(keyword: &str, count: usize) -> KeywordCount {
+        KeywordCount {
+            keyword: keyword.into(),
+            count,
+        }
+    }
+
+    #[test]
+    fn top_keywords_leave_out_the_stopwords() {
+        let keywords = BlockerKeywords::new(Locale::En, &[]);
+        let blockers = [
+            "Waiting on the review of PR 42",
+            "waiting for staging access",
+            "Staging is down, waiting on ops",
+            "",
+        ];
+
+        assert_eq!(
+            keywords.top(&blockers, 3),
+            vec![count("waiting", 3), count("staging", 2), count("access", 1)]
+        );
+    }
+
+    #[test]
+    fn stopwords_follow_the_language_and_the_settings() {
+        let keywords = BlockerKeywords::new(Locale::Pt, &["Deploy".into()]);
+
+        let words: Vec<_> = keywords
+            .tokenize("Esperando o deploy da revisão de código")
+            .collect();
+
+        assert_eq!(words, vec!["esperando", "revisão", "código"]);
+    }
+
+    #[test]
+    fn no_blockers_have_no_keywords() {
+        let keywords = BlockerKeywords::new(Locale::En, &[]);
+
+        assert!(keywords.top::<&str>(&[], 10).is_empty());
+        assert!(keywords.top(&["none", "-"], 10).is_empty());
+    }
+}
@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use bson::oid::ObjectId;
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use super::{id::UserId, sprint::Sprint};
+
+/// A goal of a sprint was marked as done.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GoalCompletion {
+    #[serde(rename = "_id", default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub sprint_id: ObjectId,
+    /// The [`Goal::title`](super::sprint::Goal::title) of the completed goal.
+    pub goal: String,
+    pub completed_by: UserId,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub completed_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait GoalCompletionRepository: Send + Sync {
+    async fn insert(&self, completion: &GoalCompletion) -> Result<ObjectId>;
+    /// The completions of the sprint, oldest first.
+    async fn list(&self, sprint_id: ObjectId) -> Result<Vec<GoalCompletion>>;
+    /// Delete every completion of the goal, whether there was one.
+    async fn delete(&self, sprint_id: ObjectId, goal: &str) -> Result<bool>;
+}
+
+/// Mark the goal done, or open again when it already was, returning whether
+/// it ends up done.
+pub async fn toggle_completion(
+    completions: &dyn GoalCompletionRepository,
+    sprint_id: ObjectId,
+    goal: &str,
+    user_id: UserId,
+    now: DateTime<Utc>,
+) -> Result<bool> {
+    if completions.delete(sprint_id, goal).await? {
+        return Ok(false);
+    }
+
+    completions
+        .insert(&GoalCompletion {
+            id: None,
+            sprint_id,
+            goal: goal.to_owned(),
+            completed_by: user_id,
+            completed_at: now,
+        })
+        .await?;
+    Ok(true)
+}
+
+/// What was left of the sprint at the end of `date`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BurndownPoint {
+    pub date: NaiveDate,
+    pub remaining_goals: usize,
+    pub remaining_points: u32,
+}
+
+/// One point per day from the sprint start up to `today` or the sprint end,
+/// whichever comes first.
+///
+/// Days are read in `timezone`. Completions of unknown goals are ignored and a
+/// goal completed twice counts from its first completion. A sprint without
+/// goals, or that has not started yet, has an empty series.
+pub fn burndown(
+    sprint: &Sprint,
+    completions: &[GoalCompletion],
+    timezone: Tz,
+    today: NaiveDate,
+) -> Vec<BurndownPoint> {
+    if sprint.goals.is_empty() {
+        return Vec::new();
+    }
+
+    let mut completed_on: HashMap<&str, NaiveDate> = HashMap::new();
+    for completion in completions {
+        let date = completion
+            .completed_at
+            .with_timezone(&timezone)
+            .date_naive();
+        completed_on
+            .entry(completion.goal.as_str())
+            .and_modify(|first| *first = (*first).min(date))
+            .or_insert(date);
+    }
+
+    let last = sprint.end_date.min(today);
+    sprint
+        .start_date
+        .iter_days()
+        .take_while(|date| *date <= last)
+        .map(|date| {
+            let remaining: Vec<_> = sprint
+                .goals
+                .iter()
+                .filter(|goal| {
+                    completed_on
+                        .get(goal.title.as_str())
+                        .is_none_or(|completed| *completed > date)
+                })
+                .collect();
+
+            BurndownPoint {
+                date,
+                remaining_goals: remaining.len(),
+                remaining_points: remaining.iter().map(|goal| goal.points).sum(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use crate::{
+        domain::{id::GuildId, sprint::Goal},
+        drivers::database::memory::InMemoryGoalCompletionRepository,
+    };
+
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 10, day).unwrap()
+    }
+
+    fn sprint(goals: &[(&str, u32)]) -> Sprint {
+        Sprint {
+            id: Some(ObjectId::new()),
+            guild_id: GuildId(1),
+            team: None,
+            name: "Sprint 1".into(),
+            start_date: date(14),
+            end_date: date(18),
+            goals: goals
+                .iter()
+                .map(|&(title, points)| Goal {
+                    title: title.into(),
+                    points,
+                })
+                .collect(),
+        }
+    }
+
+    fn completion(goal: &str, day: u32, hour: u32) -> GoalCompletion {
+        GoalCompletion {
+            id: None,
+            sprint_id: ObjectId::new(),
+            goal: goal.into(),
+            completed_by: UserId(3),
+            completed_at: Utc.with_ymd_and_hms(2024, 10, day, hour, 0, 0).unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn toggling_a_goal_twice_opens_it_again() {
+        let completions = InMemoryGoalCompletionRepository::default();
+        let sprint_id = ObjectId::new();
+        let now = Utc.with_ymd_and_hms(2024, 10, 15, 12, 0, 0).unwrap();
+
+        let done = toggle_completion(&completions, sprint_id, "scheduler", UserId(3), now)
+            .await
+            .unwrap();
+        assert!(done);
+        assert_eq!(completions.list(sprint_id).await.unwrap().len(), 1);
+
+        let done = toggle_completion(&completions, sprint_id, "scheduler", UserId(3), now)
+            .await
+            .unwrap();
+        assert!(!done);
+        assert!(completions.list(sprint_id).await.unwrap().is_empty());
+    }
+
+    fn remaining(series: &[BurndownPoint]) -> Vec<(usize, u32)> {
+        series
+            .iter()
+            .map(|point| (point.remaining_goals, point.remaining_points))
+            .collect()
+    }
+
+    #[test]
+    fn burns_down_as_goals_are_completed() {
+        let sprint = sprint(&[("scheduler", 5), ("dashboard", 3), ("docs", 1)]);
+        let completions = [
+            completion("docs", 15, 10),
+            completion("scheduler", 17, 9),
+            // Reopened and completed again, the first completion counts.
+            completion("docs", 17, 12),
+            completion("unplanned", 16, 9),
+        ];
+
+        let series = burndown(&sprint, &completions, Tz::UTC, date(20));
+
+        assert_eq!(series.first().unwrap().date, date(14));
+        assert_eq!(series.last().unwrap().date, date(18));
+        assert_eq!(
+            remaining(&series),
+            vec![(3, 9), (2, 8), (2, 8), (1, 3), (1, 3)]
+        );
+    }
+
+    #[test]
+    fn series_stops_at_today() {
+        let sprint = sprint(&[("scheduler", 5)]);
+
+        let series = burndown(&sprint, &[], Tz::UTC, date(15));
+
+        assert_eq!(remaining(&series), vec![(1, 5), (1, 5)]);
+        assert!(burndown(&sprint, &[], Tz::UTC, date(13)).is_empty());
+    }
+
+    #[test]
+    fn completions_are_dated_in_the_guild_timezone() {
+        let sprint = sprint(&[("scheduler", 5)]);
+        // 01:00 UTC on the 16th is still the 15th in Sao Paulo.
+        let completions = [completion("scheduler", 16, 1)];
+
+        let series = burndown(
+            &sprint,
+            &completions,
+            chrono_tz::America::Sao_Paulo,
+            date(16),
+        );
+
+        assert_eq!(remaining(&series), vec![(1, 5), (0, 0), (0, 0)]);
+    }
+
+    #[test]
+    fn sprint_without_goals_has_an_empty_series() {
+        let completions = [completion("scheduler", 15, 10)];
+
+        assert!(burndown(&sprint(&[]), &completions, Tz::UTC, date(20)).is_empty());
+    }
+}
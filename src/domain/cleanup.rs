@@ -0,0 +1,297 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::Serialize;
+
+use super::id::{ChannelId, MessageId, UserId};
+
+/// Messages read per page of the channel history, the most Discord returns.
+pub const HISTORY_PAGE_SIZE: usize = 100;
+
+/// Wait between two deletes, Discord rate limits them per channel.
+pub const DELETE_PAUSE: Duration = Duration::from_millis(250);
+
+/// A message of the channel history, without its content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelMessage {
+    pub id: MessageId,
+    pub author_id: UserId,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Reads and deletes the messages of a Discord channel.
+#[async_trait]
+pub trait ChannelHistory: Send + Sync {
+    /// Up to `limit` messages sent before `before`, newest first, from the
+    /// latest one when `None`.
+    async fn messages(
+        &self,
+        channel_id: ChannelId,
+        before: Option<MessageId>,
+        limit: usize,
+    ) -> Result<Vec<ChannelMessage>>;
+    /// One by one, Discord refuses bulk deletes of messages older than two weeks.
+    async fn delete(&self, channel_id: ChannelId, message_id: MessageId) -> Result<()>;
+}
+
+/// Returned by [`ChannelHistory`] when the bot can't read or manage the
+/// messages of the channel.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("missing permissions in the channel")]
+pub struct MissingPermissions;
+
+/// Where the deleted messages are counted.
+pub trait CleanupCounter: Send + Sync {
+    fn deleted(&self, count: u64);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CleanupOutcome {
+    pub deleted: u64,
+    /// The cleanup stopped early, the bot lacks permissions in the channel.
+    pub forbidden: bool,
+}
+
+/// Deletes the old reminders and announcements the bot left in a channel,
+/// never the messages of the members.
+#[derive(Clone)]
+pub struct MessageCleanup {
+    history: Arc<dyn ChannelHistory>,
+    bot_id: UserId,
+    page_size: usize,
+    pause: Duration,
+    counter: Option<Arc<dyn CleanupCounter>>,
+}
+
+impl MessageCleanup {
+    pub fn new(history: Arc<dyn ChannelHistory>, bot_id: UserId) -> Self {
+        Self {
+            history,
+            bot_id,
+            page_size: HISTORY_PAGE_SIZE,
+            pause: DELETE_PAUSE,
+            counter: None,
+        }
+    }
+
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.clamp(1, HISTORY_PAGE_SIZE);
+        self
+    }
+
+    pub fn with_pause(mut self, pause: Duration) -> Self {
+        self.pause = pause;
+        self
+    }
+
+    pub fn with_counter(mut self, counter: Arc<dyn CleanupCounter>) -> Self {
+        self.counter = Some(counter);
+        self
+    }
+
+    /// Whether `message` is the bot's own and was sent before `cutoff`.
+    fn is_target(&self, message: &ChannelMessage, cutoff: DateTime<Utc>) -> bool {
+        message.author_id == self.bot_id && message.created_at < cutoff
+    }
+
+    /// Delete the messages of the bot older than `older_than_days` in the
+    /// channel, walking its whole history page by page.
+    ///
+    /// Missing permissions stop the cleanup with what was deleted so far,
+    /// the other errors are returned.
+    #[tracing::instrument(name = "Clean up channel", skip(self))]
+    pub async fn clean(
+        &self,
+        channel_id: ChannelId,
+        older_than_days: u32,
+        now: DateTime<Utc>,
+    ) -> Result<CleanupOutcome> {
+        let cutoff = now - TimeDelta::days(older_than_days.into());
+        let mut outcome = CleanupOutcome {
+            deleted: 0,
+            forbidden: false,
+        };
+
+        let mut before = None;
+        loop {
+            let page = match self
+                .history
+                .messages(channel_id, before, self.page_size)
+                .await
+            {
+                Ok(page) => page,
+                Err(error) if error.is::<MissingPermissions>() => {
+                    outcome.forbidden = true;
+                    break;
+                }
+                Err(error) => return Err(error),
+            };
+
+            for message in page
+                .iter()
+                .filter(|message| self.is_target(message, cutoff))
+            {
+                if outcome.deleted > 0 {
+                    tokio::time::sleep(self.pause).await;
+                }
+
+                match self.history.delete(channel_id, message.id).await {
+                    Ok(()) => {
+                        outcome.deleted += 1;
+                        if let Some(counter) = &self.counter {
+                            counter.deleted(1);
+                        }
+                    }
+                    Err(error) if error.is::<MissingPermissions>() => {
+                        outcome.forbidden = true;
+                        break;
+                    }
+                    Err(error) => return Err(error),
+                }
+            }
+
+            if outcome.forbidden || page.len() < self.page_size {
+                break;
+            }
+            before = page.last().map(|message| message.id);
+        }
+
+        if outcome.forbidden {
+            tracing::warn!(
+                deleted = outcome.deleted,
+                "missing permissions, stopped the channel cleanup"
+            );
+        } else {
+            tracing::info!(deleted = outcome.deleted, "cleaned up the channel");
+        }
+        Ok(outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    const BOT: UserId = UserId(1);
+    const MEMBER: UserId = UserId(2);
+
+    /// A channel history in memory, newest message first.
+    #[derive(Default)]
+    struct Channel {
+        messages: Vec<ChannelMessage>,
+        deleted: Mutex<Vec<MessageId>>,
+        /// Refuse the deletes after this many.
+        forbid_after: Option<usize>,
+    }
+
+    #[async_trait]
+    impl ChannelHistory for Channel {
+        async fn messages(
+            &self,
+            _channel_id: ChannelId,
+            before: Option<MessageId>,
+            limit: usize,
+        ) -> Result<Vec<ChannelMessage>> {
+            Ok(self
+                .messages
+                .iter()
+                .filter(|message| before.is_none_or(|before| message.id < before))
+                .take(limit)
+                .cloned()
+                .collect())
+        }
+
+        async fn delete(&self, _channel_id: ChannelId, message_id: MessageId) -> Result<()> {
+            let mut deleted = self.deleted.lock().unwrap();
+            if self
+                .forbid_after
+                .is_some_and(|after| deleted.len() >= after)
+            {
+                return Err(MissingPermissions.into());
+            }
+            deleted.push(message_id);
+            Ok(())
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        "2024-10-30T12:00:00Z".parse().unwrap()
+    }
+
+    fn message(id: u64, author_id: UserId, days_ago: i64) -> ChannelMessage {
+        ChannelMessage {
+            id: MessageId(id),
+            author_id,
+            created_at: now() - TimeDelta::days(days_ago),
+        }
+    }
+
+    fn channel() -> Channel {
+        Channel {
+            messages: vec![
+                message(7, BOT, 1),
+                message(6, MEMBER, 10),
+                message(5, BOT, 10),
+                message(4, BOT, 20),
+                message(3, MEMBER, 30),
+                message(2, BOT, 40),
+                message(1, MEMBER, 50),
+            ],
+            ..Channel::default()
+        }
+    }
+
+    fn cleanup(channel: Arc<Channel>) -> MessageCleanup {
+        MessageCleanup::new(channel, BOT)
+            .with_page_size(2)
+            .with_pause(Duration::ZERO)
+    }
+
+    #[tokio::test]
+    async fn only_old_messages_of_the_bot_are_deleted() {
+        let channel = Arc::new(channel());
+
+        let outcome = cleanup(channel.clone())
+            .clean(ChannelId(9), 7, now())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            outcome,
+            CleanupOutcome {
+                deleted: 3,
+                forbidden: false
+            }
+        );
+        assert_eq!(
+            *channel.deleted.lock().unwrap(),
+            vec![MessageId(5), MessageId(4), MessageId(2)]
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_permissions_stop_the_cleanup() {
+        let channel = Arc::new(Channel {
+            forbid_after: Some(1),
+            ..channel()
+        });
+
+        let outcome = cleanup(channel.clone())
+            .clean(ChannelId(9), 7, now())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            outcome,
+            CleanupOutcome {
+                deleted: 1,
+                forbidden: true
+            }
+        );
+        assert_eq!(*channel.deleted.lock().unwrap(), vec![MessageId(5)]);
+    }
+}
@@ -0,0 +1,5 @@
+/// A write was refused because it would duplicate an existing record, such as
+/// a second standup for the same user, channel and day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("conflicts with an existing record")]
+pub struct Conflict;
@@ -0,0 +1,198 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use chrono::NaiveDate;
+
+use super::{
+    id::{ChannelId, GuildId, UserId},
+    standup::{StandupEntry, StandupService},
+};
+
+/// How long the edits of a standup are coalesced for.
+pub const EDIT_WINDOW: Duration = Duration::from_secs(3);
+
+type EditKey = (GuildId, UserId, ChannelId, NaiveDate);
+
+/// The latest edit of a standup, waiting for its window to close.
+struct Pending {
+    entry: StandupEntry,
+    /// Unique to every edit, only the timer of the latest one saves.
+    generation: u64,
+}
+
+/// Coalesces quick successive edits of a standup, e.g. saves while typing in
+/// a modal, into a single [`StandupService::edit`] with the last answers.
+///
+/// An entry is saved once no edit of the same guild, member, channel and date
+/// came in for `window`.
+#[derive(Clone)]
+pub struct EditDebouncer {
+    service: StandupService,
+    window: Duration,
+    pending: Arc<Mutex<HashMap<EditKey, Pending>>>,
+    /// Never reused, a timer outliving a flush can't save a later edit early.
+    generations: Arc<AtomicU64>,
+}
+
+impl EditDebouncer {
+    pub fn new(service: StandupService, window: Duration) -> Self {
+        Self {
+            service,
+            window,
+            pending: Arc::default(),
+            generations: Arc::default(),
+        }
+    }
+
+    /// Queue `entry`, replacing the pending edit of the same standup.
+    pub fn edit(&self, entry: StandupEntry) {
+        let key = (entry.guild_id, entry.user_id, entry.channel_id, entry.date);
+        let generation = self.generations.fetch_add(1, Ordering::Relaxed);
+        self.pending
+            .lock()
+            .unwrap()
+            .insert(key, Pending { entry, generation });
+
+        let debouncer = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(debouncer.window).await;
+
+            let entry = {
+                let mut pending = debouncer.pending.lock().unwrap();
+                match pending.get(&key) {
+                    Some(edit) if edit.generation == generation => pending.remove(&key),
+                    _ => None,
+                }
+            };
+            if let Some(edit) = entry {
+                debouncer.save(edit.entry).await;
+            }
+        });
+    }
+
+    /// Save every pending edit now, e.g. on shutdown.
+    pub async fn flush(&self) {
+        let pending: Vec<_> = self.pending.lock().unwrap().drain().collect();
+
+        for (_, edit) in pending {
+            self.save(edit.entry).await;
+        }
+    }
+
+    /// How many standups have an edit waiting.
+    pub fn pending(&self) -> usize {
+        self.pending.lock().unwrap().len()
+    }
+
+    /// Failures are logged, the member already got their reply.
+    #[tracing::instrument(name = "Save debounced edit", skip(self, entry), fields(guild_id = %entry.guild_id, user_id = %entry.user_id))]
+    async fn save(&self, entry: StandupEntry) {
+        if let Err(error) = self.service.edit(entry).await {
+            tracing::warn!(error = ?error, "failed to save debounced standup edit");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use tokio::sync::broadcast::error::TryRecvError;
+
+    use crate::{
+        domain::standup::StandupFeed,
+        drivers::database::memory::{InMemorySprintRepository, InMemoryStandupRepository},
+    };
+
+    use super::*;
+
+    fn entry(today: &str) -> StandupEntry {
+        StandupEntry {
+            id: None,
+            guild_id: GuildId(1),
+            channel_id: ChannelId(2),
+            user_id: UserId(3),
+            team: None,
+            date: NaiveDate::from_ymd_opt(2024, 10, 15).unwrap(),
+            yesterday: "reviewed PRs".into(),
+            today: today.into(),
+            blockers: String::new(),
+            sprint_id: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// The feed sees every save of the service.
+    fn debouncer(window: Duration) -> (EditDebouncer, StandupFeed) {
+        let feed = StandupFeed::new(16);
+        let service = StandupService::new(
+            Arc::new(InMemoryStandupRepository::default()),
+            Arc::new(InMemorySprintRepository::default()),
+        )
+        .with_feed(feed.clone());
+
+        (EditDebouncer::new(service, window), feed)
+    }
+
+    #[tokio::test]
+    async fn edits_within_the_window_are_saved_once() {
+        let (debouncer, feed) = debouncer(Duration::from_millis(50));
+        let mut saved = feed.subscribe();
+
+        for today in ["sch", "schedu", "scheduler"] {
+            debouncer.edit(entry(today));
+        }
+        assert_eq!(saved.try_recv().unwrap_err(), TryRecvError::Empty);
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        assert_eq!(saved.try_recv().unwrap().today, "scheduler");
+        assert_eq!(saved.try_recv().unwrap_err(), TryRecvError::Empty);
+        assert_eq!(debouncer.pending(), 0);
+    }
+
+    #[tokio::test]
+    async fn flush_saves_pending_edits_right_away() {
+        let (debouncer, feed) = debouncer(Duration::from_secs(60));
+        let mut saved = feed.subscribe();
+
+        debouncer.edit(entry("scheduler"));
+        let mut other = entry("dashboard");
+        other.user_id = UserId(4);
+        debouncer.edit(other);
+        debouncer.flush().await;
+
+        let mut today = vec![
+            saved.try_recv().unwrap().today,
+            saved.try_recv().unwrap().today,
+        ];
+        today.sort();
+        assert_eq!(today, vec!["dashboard", "scheduler"]);
+        assert_eq!(debouncer.pending(), 0);
+    }
+
+    #[tokio::test]
+    async fn timer_of_a_flushed_edit_leaves_the_next_one_pending() {
+        let (debouncer, feed) = debouncer(Duration::from_millis(100));
+        let mut saved = feed.subscribe();
+
+        debouncer.edit(entry("sch"));
+        debouncer.flush().await;
+        assert_eq!(saved.try_recv().unwrap().today, "sch");
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        debouncer.edit(entry("scheduler"));
+
+        // The timer of the flushed edit fires first.
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert_eq!(saved.try_recv().unwrap_err(), TryRecvError::Empty);
+        assert_eq!(debouncer.pending(), 1);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(saved.try_recv().unwrap().today, "scheduler");
+    }
+}
@@ -0,0 +1,344 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    id::{ChannelId, UserId},
+    job::Job,
+};
+
+/// A failed message is retried at most this many times before it is left for
+/// an operator to look at.
+pub const MAX_SEND_ATTEMPTS: u32 = 5;
+
+/// How often the failed messages are retried.
+pub const RETRY_FAILED_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Where a message is posted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Destination {
+    Channel(ChannelId),
+    Direct(UserId),
+}
+
+/// A message the bot posts on its own, outside of a command reply.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutgoingMessage {
+    pub destination: Destination,
+    pub content: String,
+}
+
+/// Posts messages to Discord.
+#[async_trait]
+pub trait MessageSender: Send + Sync {
+    async fn send(&self, message: &OutgoingMessage) -> Result<()>;
+}
+
+/// Where send failures are counted.
+pub trait SendFailureCounter: Send + Sync {
+    fn increment(&self);
+}
+
+/// A message Discord refused, e.g. missing permissions or a deleted channel.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailedMessage {
+    #[serde(rename = "_id", default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub message: OutgoingMessage,
+    /// The error of the last attempt.
+    pub reason: String,
+    pub attempts: u32,
+    pub resolved: bool,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub failed_at: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub last_attempt_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait FailedMessageRepository: Send + Sync {
+    async fn insert(&self, failed: &FailedMessage) -> Result<ObjectId>;
+    /// The unresolved messages tried less than `max_attempts` times, oldest first.
+    async fn pending(&self, max_attempts: u32) -> Result<Vec<FailedMessage>>;
+    async fn record_attempt(&self, id: ObjectId, reason: &str, at: DateTime<Utc>) -> Result<()>;
+    async fn mark_resolved(&self, id: ObjectId, at: DateTime<Utc>) -> Result<()>;
+}
+
+/// Sends messages, keeping the ones that fail in a dead-letter log.
+///
+/// As a [`MessageSender`], it is what the scheduled messages are posted with:
+/// a refused message is left to [`DeliveryService::retry_failed`] rather than
+/// to its caller.
+#[derive(Clone)]
+pub struct DeliveryService {
+    sender: Arc<dyn MessageSender>,
+    failed: Arc<dyn FailedMessageRepository>,
+    failures: Option<Arc<dyn SendFailureCounter>>,
+}
+
+impl DeliveryService {
+    pub fn new(sender: Arc<dyn MessageSender>, failed: Arc<dyn FailedMessageRepository>) -> Self {
+        Self {
+            sender,
+            failed,
+            failures: None,
+        }
+    }
+
+    pub fn with_failure_counter(mut self, failures: Arc<dyn SendFailureCounter>) -> Self {
+        self.failures = Some(failures);
+        self
+    }
+
+    /// Send `message`, returning whether it was delivered.
+    ///
+    /// A failed send is recorded for [`Self::retry_failed`] instead of being
+    /// returned, only failing to record it is an error.
+    #[tracing::instrument(name = "Deliver message", skip(self, message), fields(destination = ?message.destination))]
+    pub async fn send(&self, message: OutgoingMessage, now: DateTime<Utc>) -> Result<bool> {
+        let Err(error) = self.sender.send(&message).await else {
+            return Ok(true);
+        };
+
+        tracing::warn!(error = ?error, "failed to send message");
+        self.count_failure();
+        self.failed
+            .insert(&FailedMessage {
+                id: None,
+                message,
+                reason: format!("{error:#}"),
+                attempts: 1,
+                resolved: false,
+                failed_at: now,
+                last_attempt_at: now,
+            })
+            .await?;
+
+        Ok(false)
+    }
+
+    /// Resend the pending failed messages, returning how many went through.
+    #[tracing::instrument(name = "Retry failed messages", skip(self))]
+    pub async fn retry_failed(&self, now: DateTime<Utc>) -> Result<usize> {
+        let mut resolved = 0;
+
+        for failed in self.failed.pending(MAX_SEND_ATTEMPTS).await? {
+            let Some(id) = failed.id else {
+                continue;
+            };
+
+            match self.sender.send(&failed.message).await {
+                Ok(()) => {
+                    self.failed.mark_resolved(id, now).await?;
+                    resolved += 1;
+                }
+                Err(error) => {
+                    tracing::warn!(error = ?error, message_id = %id, "failed to resend message");
+                    self.count_failure();
+                    self.failed
+                        .record_attempt(id, &format!("{error:#}"), now)
+                        .await?;
+                }
+            }
+        }
+
+        Ok(resolved)
+    }
+
+    fn count_failure(&self) {
+        if let Some(failures) = &self.failures {
+            failures.increment();
+        }
+    }
+}
+
+/// Only failing to record a refused message is returned.
+#[async_trait]
+impl MessageSender for DeliveryService {
+    async fn send(&self, message: &OutgoingMessage) -> Result<()> {
+        DeliveryService::send(self, message.clone(), Utc::now())
+            .await
+            .map(|_| ())
+    }
+}
+
+/// Run by the [`JobRunner`](super::job::JobRunner) every [`RETRY_FAILED_INTERVAL`].
+#[async_trait]
+impl Job for DeliveryService {
+    async fn run(&self) -> Result<()> {
+        self.retry_failed(Utc::now()).await.map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex,
+    };
+
+    use anyhow::bail;
+
+    use crate::{
+        domain::{
+            reminder::{Reminder, ReminderService},
+            scheduler::ScheduledSender,
+        },
+        drivers::database::memory::{InMemoryFailedMessageRepository, InMemoryReminderRepository},
+    };
+
+    use super::*;
+
+    /// Refuses every message while `down` is set.
+    #[derive(Default)]
+    struct Discord {
+        down: AtomicBool,
+        sent: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl MessageSender for Discord {
+        async fn send(&self, message: &OutgoingMessage) -> Result<()> {
+            if self.down.load(Ordering::SeqCst) {
+                bail!("Missing Access");
+            }
+            self.sent.lock().unwrap().push(message.content.clone());
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct Failures(AtomicUsize);
+
+    impl SendFailureCounter for Failures {
+        fn increment(&self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn message(content: &str) -> OutgoingMessage {
+        OutgoingMessage {
+            destination: Destination::Channel(ChannelId(2)),
+            content: content.into(),
+        }
+    }
+
+    fn service(
+        discord: Arc<Discord>,
+        failed: Arc<InMemoryFailedMessageRepository>,
+        failures: Arc<Failures>,
+    ) -> DeliveryService {
+        DeliveryService::new(discord, failed).with_failure_counter(failures)
+    }
+
+    #[tokio::test]
+    async fn failed_send_is_recorded_with_its_reason() {
+        let discord = Arc::new(Discord::default());
+        discord.down.store(true, Ordering::SeqCst);
+        let failed = Arc::new(InMemoryFailedMessageRepository::default());
+        let failures = Arc::new(Failures::default());
+        let service = service(discord, failed.clone(), failures.clone());
+
+        assert!(!service
+            .send(message("standup time"), Utc::now())
+            .await
+            .unwrap());
+
+        let pending = failed.pending(MAX_SEND_ATTEMPTS).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].message, message("standup time"));
+        assert_eq!(pending[0].reason, "Missing Access");
+        assert_eq!(pending[0].attempts, 1);
+        assert_eq!(failures.0.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn retry_resolves_messages_once_discord_accepts_them() {
+        let discord = Arc::new(Discord::default());
+        discord.down.store(true, Ordering::SeqCst);
+        let failed = Arc::new(InMemoryFailedMessageRepository::default());
+        let failures = Arc::new(Failures::default());
+        let service = service(discord.clone(), failed.clone(), failures.clone());
+        service
+            .send(message("standup time"), Utc::now())
+            .await
+            .unwrap();
+
+        assert_eq!(service.retry_failed(Utc::now()).await.unwrap(), 0);
+        assert_eq!(
+            failed.pending(MAX_SEND_ATTEMPTS).await.unwrap()[0].attempts,
+            2
+        );
+
+        discord.down.store(false, Ordering::SeqCst);
+        assert_eq!(service.retry_failed(Utc::now()).await.unwrap(), 1);
+        assert_eq!(service.retry_failed(Utc::now()).await.unwrap(), 0);
+
+        assert!(failed.pending(MAX_SEND_ATTEMPTS).await.unwrap().is_empty());
+        assert_eq!(
+            *discord.sent.lock().unwrap(),
+            vec!["standup time".to_owned()]
+        );
+        assert_eq!(failures.0.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_the_max_attempts() {
+        let discord = Arc::new(Discord::default());
+        discord.down.store(true, Ordering::SeqCst);
+        let failed = Arc::new(InMemoryFailedMessageRepository::default());
+        let service = service(discord, failed.clone(), Arc::default());
+        service
+            .send(message("standup time"), Utc::now())
+            .await
+            .unwrap();
+
+        for _ in 1..MAX_SEND_ATTEMPTS {
+            service.retry_failed(Utc::now()).await.unwrap();
+        }
+
+        assert!(failed.pending(MAX_SEND_ATTEMPTS).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn refused_reminders_are_left_to_the_dead_letter_retry() {
+        let discord = Arc::new(Discord::default());
+        discord.down.store(true, Ordering::SeqCst);
+        let failed = Arc::new(InMemoryFailedMessageRepository::default());
+        let failures = Arc::new(Failures::default());
+        let delivery = service(discord.clone(), failed.clone(), failures.clone());
+        let sender = ScheduledSender::new(Arc::new(delivery.clone()), false);
+        let reminders = ReminderService::new(Arc::new(InMemoryReminderRepository::default()));
+        let now = Utc::now();
+        reminders
+            .schedule(Reminder {
+                id: None,
+                guild_id: None,
+                channel_id: Some(ChannelId(2)),
+                user_id: UserId(3),
+                message: "review PR".into(),
+                due_at: now,
+                delivered: false,
+                created_at: now,
+            })
+            .await
+            .unwrap();
+
+        // Handed over once, the dead-letter log owns it from there.
+        assert_eq!(reminders.fire_due(now, &sender).await.unwrap(), 1);
+        assert_eq!(reminders.fire_due(now, &sender).await.unwrap(), 0);
+        let pending = failed.pending(MAX_SEND_ATTEMPTS).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].message.content, "review PR");
+        assert_eq!(failures.0.load(Ordering::SeqCst), 1);
+
+        discord.down.store(false, Ordering::SeqCst);
+        Job::run(&delivery).await.unwrap();
+        assert_eq!(*discord.sent.lock().unwrap(), vec!["review PR".to_owned()]);
+    }
+}
@@ -0,0 +1,35 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+/// Runtime toggles for individual commands, keyed by command name.
+///
+/// Commands missing from the map are enabled. The flags are shared, so a
+/// [`FeatureFlags::replace`] is seen by every clone right away.
+#[derive(Clone, Debug, Default)]
+pub struct FeatureFlags(Arc<RwLock<HashMap<String, bool>>>);
+
+impl FeatureFlags {
+    pub fn new(flags: HashMap<String, bool>) -> Self {
+        Self(Arc::new(RwLock::new(flags)))
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.0
+            .read()
+            .expect("feature flags lock poisoned")
+            .get(name)
+            .copied()
+            .unwrap_or(true)
+    }
+
+    /// The flags as of now.
+    pub fn snapshot(&self) -> HashMap<String, bool> {
+        self.0.read().expect("feature flags lock poisoned").clone()
+    }
+
+    pub fn replace(&self, flags: HashMap<String, bool>) {
+        *self.0.write().expect("feature flags lock poisoned") = flags;
+    }
+}
@@ -0,0 +1,475 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::Locale;
+
+use super::{
+    audit::{AuditAction, AuditEntry, Auditor},
+    id::{ChannelId, GuildId, RoleId, UserId},
+    nudge::NudgeConfig,
+    participation::ParticipationTracker,
+};
+
+/// Discord permission bit of the members allowed to do everything.
+const ADMINISTRATOR: u64 = 1 << 3;
+
+/// What Discord tells about a member acting in a guild.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Member {
+    pub roles: Vec<RoleId>,
+    /// The permission bit set of the member in the channel.
+    pub permissions: u64,
+    /// Whether the member owns the guild.
+    pub owner: bool,
+}
+
+/// Per guild settings managed by the guild admins.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GuildConfig {
+    pub guild_id: GuildId,
+    /// The guild name, as last seen from Discord.
+    #[serde(default)]
+    pub name: Option<String>,
+    pub standup_channel_id: Option<ChannelId>,
+    pub reminder_time: Option<NaiveTime>,
+    pub timezone: Option<String>,
+    /// The language tag of the bot responses, e.g. `pt-BR`.
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// Teams running their own sprints and standups inside the guild.
+    #[serde(default)]
+    pub teams: Vec<Team>,
+    /// The members expected to answer the daily standup.
+    #[serde(default)]
+    pub roster: Vec<UserId>,
+    /// Roles whose members run the admin commands, on top of the guild owner
+    /// and administrators.
+    #[serde(default)]
+    pub admin_roles: Vec<RoleId>,
+    /// Who sees the standups the members submit.
+    #[serde(default)]
+    pub standup_visibility: StandupVisibility,
+    /// Whether and when the members missing from the standup are pinged.
+    #[serde(default)]
+    pub nudge: NudgeConfig,
+    /// Slash commands turned off in this guild only, by name.
+    #[serde(default)]
+    pub disabled_commands: Vec<String>,
+    /// Standups are expected this many minutes before and after
+    /// `reminder_time`, at any time when either is unset.
+    #[serde(default)]
+    pub standup_window_minutes: Option<u32>,
+    /// Turn away the standups sent outside the window instead of only
+    /// warning their authors.
+    #[serde(default)]
+    pub reject_outside_window: bool,
+}
+
+/// Who sees a submitted standup.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StandupVisibility {
+    /// Posted in the channel for everyone.
+    Public,
+    /// Only shown to its author, the team reads the daily summary.
+    #[default]
+    Ephemeral,
+}
+
+/// A team of a guild, whose members are told apart by a role or a channel.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Team {
+    pub name: String,
+    pub role_id: Option<RoleId>,
+    pub channel_id: Option<ChannelId>,
+}
+
+impl GuildConfig {
+    pub fn new(guild_id: GuildId) -> Self {
+        Self {
+            guild_id,
+            name: None,
+            standup_channel_id: None,
+            reminder_time: None,
+            timezone: None,
+            locale: None,
+            teams: Vec::new(),
+            roster: Vec::new(),
+            admin_roles: Vec::new(),
+            standup_visibility: StandupVisibility::default(),
+            nudge: NudgeConfig::default(),
+            disabled_commands: Vec::new(),
+            standup_window_minutes: None,
+            reject_outside_window: false,
+        }
+    }
+
+    pub fn is_command_enabled(&self, name: &str) -> bool {
+        !self
+            .disabled_commands
+            .iter()
+            .any(|disabled| disabled == name)
+    }
+
+    /// Whether `member` may run the admin commands of the guild.
+    pub fn is_admin(&self, member: &Member) -> bool {
+        member.owner
+            || member.permissions & ADMINISTRATOR != 0
+            || member
+                .roles
+                .iter()
+                .any(|role| self.admin_roles.contains(role))
+    }
+
+    /// The team a member acting in `channel_id` belongs to, `None` for the
+    /// guild wide team.
+    ///
+    /// A team channel wins over the member roles, so someone in two teams
+    /// picks one by posting in its channel.
+    pub fn team_for(&self, channel_id: ChannelId, roles: &[RoleId]) -> Option<&Team> {
+        self.teams
+            .iter()
+            .find(|team| team.channel_id == Some(channel_id))
+            .or_else(|| {
+                self.teams
+                    .iter()
+                    .find(|team| team.role_id.is_some_and(|role| roles.contains(&role)))
+            })
+    }
+
+    /// The IANA timezone of the guild, `fallback` when unset or unknown.
+    ///
+    /// Legacy guilds were created before the timezone was asked for.
+    pub fn tz(&self, fallback: Tz) -> Tz {
+        self.timezone
+            .as_deref()
+            .and_then(|timezone| timezone.parse().ok())
+            .unwrap_or(fallback)
+    }
+
+    /// The locale of the bot responses, English when unset or unsupported.
+    pub fn locale(&self) -> Locale {
+        self.locale
+            .as_deref()
+            .and_then(Locale::parse)
+            .unwrap_or_default()
+    }
+
+    /// The calendar date at `now` for the guild members.
+    pub fn local_date(&self, now: DateTime<Utc>, fallback: Tz) -> NaiveDate {
+        now.with_timezone(&self.tz(fallback)).date_naive()
+    }
+
+    /// When the standup window opens and closes in the guild timezone, `None`
+    /// without a window.
+    pub fn standup_window(&self) -> Option<(NaiveTime, NaiveTime)> {
+        let minutes = self.standup_window_minutes.filter(|minutes| *minutes > 0)?;
+        let scheduled = self.reminder_time?;
+        let half = TimeDelta::minutes(minutes.into());
+
+        Some((scheduled - half, scheduled + half))
+    }
+
+    /// Whether `now` falls in the standup window, always true without one.
+    pub fn is_within_standup_window(&self, now: DateTime<Utc>, fallback: Tz) -> bool {
+        let (Some(minutes), Some(scheduled)) = (
+            self.standup_window_minutes.filter(|minutes| *minutes > 0),
+            self.reminder_time,
+        ) else {
+            return true;
+        };
+
+        let tz = self.tz(fallback);
+        let local = now.with_timezone(&tz);
+        let half = TimeDelta::minutes(minutes.into());
+
+        // A window around midnight opens or closes on another day than `now`.
+        [-1, 0, 1]
+            .into_iter()
+            .filter_map(|days| {
+                let date = local.date_naive() + TimeDelta::days(days);
+                let scheduled = date.and_time(scheduled);
+                // Skipped by a DST change, the clocks read an hour later.
+                tz.from_local_datetime(&scheduled).earliest().or_else(|| {
+                    tz.from_local_datetime(&(scheduled + TimeDelta::hours(1)))
+                        .earliest()
+                })
+            })
+            .any(|scheduled| scheduled - half <= local && local <= scheduled + half)
+    }
+}
+
+#[async_trait]
+pub trait GuildConfigRepository: Send + Sync {
+    async fn find(&self, guild_id: GuildId) -> Result<Option<GuildConfig>>;
+    async fn upsert(&self, config: &GuildConfig) -> Result<()>;
+    /// The guilds that opted in to nudges.
+    async fn list_nudged(&self) -> Result<Vec<GuildConfig>>;
+
+    /// The timezone of the guild, `fallback` outside of a guild or when unset.
+    async fn timezone(&self, guild_id: Option<GuildId>, fallback: Tz) -> Result<Tz> {
+        let Some(guild_id) = guild_id else {
+            return Ok(fallback);
+        };
+
+        Ok(self
+            .find(guild_id)
+            .await?
+            .map_or(fallback, |config| config.tz(fallback)))
+    }
+}
+
+#[derive(Clone)]
+pub struct GuildConfigService {
+    repository: Arc<dyn GuildConfigRepository>,
+    auditor: Auditor,
+    participation: Option<ParticipationTracker>,
+}
+
+impl GuildConfigService {
+    pub fn new(repository: Arc<dyn GuildConfigRepository>, auditor: Auditor) -> Self {
+        Self {
+            repository,
+            auditor,
+            participation: None,
+        }
+    }
+
+    /// Refresh the participation of the day whenever the config changes.
+    pub fn with_participation(mut self, participation: ParticipationTracker) -> Self {
+        self.participation = Some(participation);
+        self
+    }
+
+    /// Apply `change` to the guild config, creating it if needed, and audit the mutation.
+    #[tracing::instrument(name = "Update guild config", skip(self, change))]
+    pub async fn update<F>(&self, actor: &str, guild_id: GuildId, change: F) -> Result<GuildConfig>
+    where
+        F: FnOnce(&mut GuildConfig) + Send,
+    {
+        let before = self
+            .repository
+            .find(guild_id)
+            .await?
+            .unwrap_or_else(|| GuildConfig::new(guild_id));
+
+        let mut after = before.clone();
+        change(&mut after);
+
+        self.repository.upsert(&after).await?;
+
+        let entry = AuditEntry::new(actor, guild_id, AuditAction::GuildConfigUpdated)
+            .with_change(&before, &after)?;
+        self.auditor.record(entry).await?;
+
+        if let Some(participation) = &self.participation {
+            if before.roster != after.roster {
+                participation.refresh_today(guild_id, Utc::now()).await;
+            }
+        }
+
+        Ok(after)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::audit::{AuditQuery, AuditRepository};
+    use crate::drivers::database::memory::{
+        InMemoryAuditRepository, InMemoryGuildConfigRepository,
+    };
+
+    use super::*;
+
+    #[test]
+    fn timezone_falls_back_to_the_default() {
+        let mut config = GuildConfig::new(GuildId(1));
+        let fallback = Tz::America__Sao_Paulo;
+
+        assert_eq!(config.tz(fallback), fallback);
+
+        config.timezone = Some("Not/AZone".into());
+        assert_eq!(config.tz(fallback), fallback);
+
+        config.timezone = Some("Europe/Lisbon".into());
+        assert_eq!(config.tz(fallback), Tz::Europe__Lisbon);
+    }
+
+    #[test]
+    fn local_date_follows_the_guild_timezone() {
+        let now = DateTime::parse_from_rfc3339("2024-10-01T02:00:00Z")
+            .unwrap()
+            .to_utc();
+        let mut config = GuildConfig::new(GuildId(1));
+
+        assert_eq!(
+            config.local_date(now, Tz::UTC),
+            NaiveDate::from_ymd_opt(2024, 10, 1).unwrap()
+        );
+
+        config.timezone = Some("America/Fortaleza".into());
+        assert_eq!(
+            config.local_date(now, Tz::UTC),
+            NaiveDate::from_ymd_opt(2024, 9, 30).unwrap()
+        );
+    }
+
+    #[test]
+    fn team_is_resolved_by_channel_then_role() {
+        let mut config = GuildConfig::new(GuildId(1));
+        config.teams = vec![
+            Team {
+                name: "backend".into(),
+                role_id: Some(RoleId(10)),
+                channel_id: Some(ChannelId(100)),
+            },
+            Team {
+                name: "frontend".into(),
+                role_id: Some(RoleId(20)),
+                channel_id: Some(ChannelId(200)),
+            },
+        ];
+        let team = |channel_id, roles: &[u64]| {
+            let roles: Vec<_> = roles.iter().copied().map(RoleId).collect();
+            config
+                .team_for(ChannelId(channel_id), &roles)
+                .map(|team| team.name.clone())
+        };
+
+        assert_eq!(team(200, &[10]).as_deref(), Some("frontend"));
+        assert_eq!(team(7, &[10, 20]).as_deref(), Some("backend"));
+        assert_eq!(team(7, &[20]).as_deref(), Some("frontend"));
+        assert_eq!(team(7, &[30]), None);
+    }
+
+    #[test]
+    fn admins_are_the_owner_administrators_and_admin_roles() {
+        let mut config = GuildConfig::new(GuildId(1));
+        config.admin_roles = vec![RoleId(10)];
+        let member = |roles: &[u64]| Member {
+            roles: roles.iter().copied().map(RoleId).collect(),
+            ..Member::default()
+        };
+
+        assert!(config.is_admin(&member(&[20, 10])));
+        assert!(!config.is_admin(&member(&[20])));
+        assert!(config.is_admin(&Member {
+            owner: true,
+            ..member(&[])
+        }));
+        assert!(config.is_admin(&Member {
+            permissions: ADMINISTRATOR,
+            ..member(&[])
+        }));
+        // Managing the guild alone doesn't make an admin of the bot.
+        assert!(!config.is_admin(&Member {
+            permissions: 1 << 5,
+            ..member(&[])
+        }));
+    }
+
+    fn windowed(timezone: &str, hour: u32, minute: u32, minutes: u32) -> GuildConfig {
+        let mut config = GuildConfig::new(GuildId(1));
+        config.timezone = Some(timezone.into());
+        config.reminder_time = NaiveTime::from_hms_opt(hour, minute, 0);
+        config.standup_window_minutes = Some(minutes);
+        config
+    }
+
+    fn utc(at: &str) -> DateTime<Utc> {
+        at.parse().unwrap()
+    }
+
+    #[test]
+    fn standup_window_follows_the_guild_timezone() {
+        let sao_paulo = windowed("America/Sao_Paulo", 9, 0, 30);
+        assert!(sao_paulo.is_within_standup_window(utc("2024-10-14T12:10:00Z"), Tz::UTC));
+        assert!(sao_paulo.is_within_standup_window(utc("2024-10-14T11:30:00Z"), Tz::UTC));
+        assert!(!sao_paulo.is_within_standup_window(utc("2024-10-14T09:10:00Z"), Tz::UTC));
+        assert!(!sao_paulo.is_within_standup_window(utc("2024-10-14T12:31:00Z"), Tz::UTC));
+
+        let tokyo = windowed("Asia/Tokyo", 9, 0, 30);
+        assert!(tokyo.is_within_standup_window(utc("2024-10-14T00:10:00Z"), Tz::UTC));
+        assert!(!tokyo.is_within_standup_window(utc("2024-10-14T12:10:00Z"), Tz::UTC));
+    }
+
+    #[test]
+    fn standup_window_can_span_midnight() {
+        let berlin = windowed("Europe/Berlin", 23, 50, 20);
+
+        // 00:05 and 23:35 in Berlin, on either side of midnight.
+        assert!(berlin.is_within_standup_window(utc("2024-10-14T22:05:00Z"), Tz::UTC));
+        assert!(berlin.is_within_standup_window(utc("2024-10-14T21:35:00Z"), Tz::UTC));
+        assert!(!berlin.is_within_standup_window(utc("2024-10-14T22:15:00Z"), Tz::UTC));
+        assert_eq!(
+            berlin.standup_window(),
+            Some((
+                NaiveTime::from_hms_opt(23, 30, 0).unwrap(),
+                NaiveTime::from_hms_opt(0, 10, 0).unwrap()
+            ))
+        );
+    }
+
+    #[test]
+    fn without_a_window_standups_are_always_in_it() {
+        let mut config = windowed("UTC", 9, 0, 0);
+        assert!(config.is_within_standup_window(utc("2024-10-14T18:00:00Z"), Tz::UTC));
+        assert_eq!(config.standup_window(), None);
+
+        config.standup_window_minutes = Some(30);
+        config.reminder_time = None;
+        assert!(config.is_within_standup_window(utc("2024-10-14T18:00:00Z"), Tz::UTC));
+    }
+
+    #[test]
+    fn legacy_config_has_no_teams() {
+        let config: GuildConfig = bson::from_document(bson::doc! {
+            "guild_id": 1_i64,
+            "standup_channel_id": null,
+            "reminder_time": null,
+            "timezone": null,
+        })
+        .unwrap();
+
+        assert!(config.teams.is_empty());
+        assert!(config.admin_roles.is_empty());
+        assert_eq!(config.standup_visibility, StandupVisibility::Ephemeral);
+    }
+
+    #[tokio::test]
+    async fn config_change_is_audited_with_actor_and_before_after() {
+        let configs = Arc::new(InMemoryGuildConfigRepository::default());
+        let audit_log = Arc::new(InMemoryAuditRepository::default());
+        let service =
+            GuildConfigService::new(configs.clone(), Auditor::new(Some(audit_log.clone())));
+
+        let mut original = GuildConfig::new(GuildId(1));
+        original.reminder_time = NaiveTime::from_hms_opt(9, 0, 0);
+        configs.upsert(&original).await.unwrap();
+
+        let updated = service
+            .update("42", GuildId(1), |config| {
+                config.reminder_time = NaiveTime::from_hms_opt(10, 30, 0)
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(updated.reminder_time, NaiveTime::from_hms_opt(10, 30, 0));
+
+        let entries = audit_log.list(&AuditQuery::default()).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        let entry = &entries[0];
+        assert_eq!(entry.actor, "42");
+        assert_eq!(entry.guild_id, Some(GuildId(1)));
+        assert_eq!(entry.action, AuditAction::GuildConfigUpdated);
+        let before = entry.before.as_ref().unwrap();
+        let after = entry.after.as_ref().unwrap();
+        assert_eq!(before.get_str("reminder_time").unwrap(), "09:00:00");
+        assert_eq!(after.get_str("reminder_time").unwrap(), "10:30:00");
+    }
+}
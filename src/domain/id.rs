@@ -0,0 +1,137 @@
+//! Typed Discord snowflakes, so a channel id can't be passed where a guild id is expected.
+//!
+//! They serialize exactly like the bare `u64`. Snowflakes use 63 bits, so they
+//! always fit the `i64` MongoDB stores.
+
+use std::fmt;
+
+use bson::Bson;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+macro_rules! snowflake {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(
+            Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+        )]
+        #[serde(transparent)]
+        pub struct $name(pub u64);
+
+        impl $name {
+            pub fn get(self) -> u64 {
+                self.0
+            }
+        }
+
+        impl From<u64> for $name {
+            fn from(id: u64) -> Self {
+                Self(id)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl From<$name> for Bson {
+            fn from(id: $name) -> Self {
+                Bson::Int64(id.0 as i64)
+            }
+        }
+    };
+}
+
+snowflake!(
+    /// Identifies a Discord server.
+    GuildId
+);
+snowflake!(
+    /// Identifies a text channel of a guild.
+    ChannelId
+);
+snowflake!(
+    /// Identifies a Discord user.
+    UserId
+);
+snowflake!(
+    /// Identifies a role of a guild.
+    RoleId
+);
+snowflake!(
+    /// Identifies a message of a channel.
+    MessageId
+);
+snowflake!(
+    /// Identifies the Discord application of the bot.
+    ApplicationId
+);
+
+#[cfg(test)]
+mod tests {
+    use bson::doc;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Ids {
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        user_id: UserId,
+    }
+
+    #[derive(Serialize)]
+    struct Bare {
+        guild_id: u64,
+        channel_id: u64,
+        user_id: u64,
+    }
+
+    const SNOWFLAKE: u64 = 1_098_765_432_109_876_543;
+
+    fn ids() -> Ids {
+        Ids {
+            guild_id: GuildId(SNOWFLAKE),
+            channel_id: ChannelId(SNOWFLAKE + 1),
+            user_id: UserId(SNOWFLAKE + 2),
+        }
+    }
+
+    fn bare() -> Bare {
+        Bare {
+            guild_id: SNOWFLAKE,
+            channel_id: SNOWFLAKE + 1,
+            user_id: SNOWFLAKE + 2,
+        }
+    }
+
+    #[test]
+    fn ids_serialize_like_a_bare_u64() {
+        assert_eq!(
+            serde_json::to_string(&ids()).unwrap(),
+            serde_json::to_string(&bare()).unwrap()
+        );
+        assert_eq!(
+            bson::to_document(&ids()).unwrap(),
+            bson::to_document(&bare()).unwrap()
+        );
+    }
+
+    #[test]
+    fn ids_round_trip_through_bson() {
+        let document = bson::to_document(&ids()).unwrap();
+
+        assert_eq!(document.get_i64("guild_id").unwrap(), SNOWFLAKE as i64);
+        assert_eq!(bson::from_document::<Ids>(document).unwrap(), ids());
+    }
+
+    #[test]
+    fn ids_convert_into_bson_filters() {
+        assert_eq!(
+            doc! { "guild_id": GuildId(SNOWFLAKE) },
+            doc! { "guild_id": SNOWFLAKE as i64 }
+        );
+    }
+}
@@ -0,0 +1,249 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tokio::{
+    sync::Semaphore,
+    task::JoinHandle,
+    time::{Instant, MissedTickBehavior},
+};
+
+/// Work run in the background on a schedule, such as pruning old records.
+#[async_trait]
+pub trait Job: Send + Sync {
+    async fn run(&self) -> Result<()>;
+}
+
+/// How a run of a [`Job`] ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobOutcome {
+    Succeeded,
+    Failed,
+    TimedOut,
+    Panicked,
+}
+
+impl JobOutcome {
+    pub fn is_failure(&self) -> bool {
+        *self != JobOutcome::Succeeded
+    }
+}
+
+/// Where the runs of the jobs are recorded.
+pub trait JobRecorder: Send + Sync {
+    fn finished(&self, job: &str, outcome: JobOutcome, duration: Duration);
+}
+
+struct ScheduledJob {
+    name: String,
+    every: Duration,
+    timeout: Duration,
+    job: Arc<dyn Job>,
+}
+
+/// Runs the registered jobs every period, at most `max_concurrent` at once.
+///
+/// A run of a job never overlaps the previous one, missed periods are
+/// skipped. Each run is its own task, so a panicking job only fails that run.
+pub struct JobRunner {
+    jobs: Vec<ScheduledJob>,
+    pool: Arc<Semaphore>,
+    default_timeout: Duration,
+    recorder: Option<Arc<dyn JobRecorder>>,
+}
+
+impl JobRunner {
+    pub fn new(max_concurrent: usize, default_timeout: Duration) -> Self {
+        Self {
+            jobs: Vec::new(),
+            pool: Arc::new(Semaphore::new(max_concurrent.max(1))),
+            default_timeout,
+            recorder: None,
+        }
+    }
+
+    pub fn with_recorder(mut self, recorder: Arc<dyn JobRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Run `job` every `every`, the first time right away.
+    pub fn with_job(self, name: impl Into<String>, every: Duration, job: Arc<dyn Job>) -> Self {
+        let timeout = self.default_timeout;
+        self.with_job_timeout(name, every, timeout, job)
+    }
+
+    /// Same as [`JobRunner::with_job`], for a job needing another timeout.
+    pub fn with_job_timeout(
+        mut self,
+        name: impl Into<String>,
+        every: Duration,
+        timeout: Duration,
+        job: Arc<dyn Job>,
+    ) -> Self {
+        self.jobs.push(ScheduledJob {
+            name: name.into(),
+            every,
+            timeout,
+            job,
+        });
+        self
+    }
+
+    /// Start every job, they run until their handle is aborted.
+    pub fn start(self) -> Vec<JoinHandle<()>> {
+        self.jobs
+            .into_iter()
+            .map(|job| {
+                let pool = self.pool.clone();
+                let recorder = self.recorder.clone();
+                tokio::spawn(schedule(job, pool, recorder))
+            })
+            .collect()
+    }
+}
+
+async fn schedule(job: ScheduledJob, pool: Arc<Semaphore>, recorder: Option<Arc<dyn JobRecorder>>) {
+    let mut interval = tokio::time::interval(job.every);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    loop {
+        interval.tick().await;
+        let Ok(_permit) = pool.acquire().await else {
+            return;
+        };
+
+        let started = Instant::now();
+        let outcome = run_once(&job).await;
+        let duration = started.elapsed();
+
+        match outcome {
+            JobOutcome::Succeeded => tracing::debug!(job = %job.name, ?duration, "job finished"),
+            outcome => tracing::warn!(job = %job.name, ?outcome, ?duration, "job failed"),
+        }
+        if let Some(recorder) = &recorder {
+            recorder.finished(&job.name, outcome, duration);
+        }
+    }
+}
+
+async fn run_once(job: &ScheduledJob) -> JobOutcome {
+    let runnable = job.job.clone();
+    let timeout = job.timeout;
+    let mut run = tokio::spawn(async move { tokio::time::timeout(timeout, runnable.run()).await });
+
+    match (&mut run).await {
+        Ok(Ok(Ok(()))) => JobOutcome::Succeeded,
+        Ok(Ok(Err(error))) => {
+            tracing::warn!(job = %job.name, error = ?error, "job returned an error");
+            JobOutcome::Failed
+        }
+        Ok(Err(_)) => JobOutcome::TimedOut,
+        Err(error) if error.is_panic() => JobOutcome::Panicked,
+        Err(_) => JobOutcome::Failed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    };
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Ticks(AtomicUsize);
+
+    #[async_trait]
+    impl Job for Ticks {
+        async fn run(&self) -> Result<()> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    struct Panics;
+
+    #[async_trait]
+    impl Job for Panics {
+        async fn run(&self) -> Result<()> {
+            panic!("job bug");
+        }
+    }
+
+    struct Hangs;
+
+    #[async_trait]
+    impl Job for Hangs {
+        async fn run(&self) -> Result<()> {
+            std::future::pending().await
+        }
+    }
+
+    #[derive(Default)]
+    struct Recorded(Mutex<Vec<(String, JobOutcome)>>);
+
+    impl JobRecorder for Recorded {
+        fn finished(&self, job: &str, outcome: JobOutcome, _: Duration) {
+            self.0.lock().unwrap().push((job.to_owned(), outcome));
+        }
+    }
+
+    impl Recorded {
+        fn outcomes(&self, job: &str) -> Vec<JobOutcome> {
+            self.0
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(name, _)| name == job)
+                .map(|(_, outcome)| *outcome)
+                .collect()
+        }
+    }
+
+    #[tokio::test]
+    async fn registered_job_runs_every_period() {
+        let ticks = Arc::new(Ticks::default());
+        let recorded = Arc::new(Recorded::default());
+        let handles = JobRunner::new(2, Duration::from_secs(1))
+            .with_recorder(recorded.clone())
+            .with_job("tick", Duration::from_millis(20), ticks.clone())
+            .start();
+
+        tokio::time::sleep(Duration::from_millis(110)).await;
+        handles.iter().for_each(JoinHandle::abort);
+
+        let runs = ticks.0.load(Ordering::SeqCst);
+        assert!((3..=7).contains(&runs), "ran {runs} times");
+        assert!(recorded
+            .outcomes("tick")
+            .iter()
+            .all(|outcome| *outcome == JobOutcome::Succeeded));
+    }
+
+    // On a paused clock, so a busy machine can't starve the runs.
+    #[tokio::test(start_paused = true)]
+    async fn panicking_job_is_isolated_and_counted() {
+        let ticks = Arc::new(Ticks::default());
+        let recorded = Arc::new(Recorded::default());
+        let handles = JobRunner::new(1, Duration::from_millis(30))
+            .with_recorder(recorded.clone())
+            .with_job("panics", Duration::from_millis(20), Arc::new(Panics))
+            .with_job("hangs", Duration::from_millis(20), Arc::new(Hangs))
+            .with_job("tick", Duration::from_millis(20), ticks.clone())
+            .start();
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        handles.iter().for_each(JoinHandle::abort);
+
+        let panics = recorded.outcomes("panics");
+        assert!(panics.len() >= 2, "{panics:?}");
+        assert!(panics
+            .iter()
+            .all(|outcome| *outcome == JobOutcome::Panicked));
+        assert!(recorded.outcomes("hangs").contains(&JobOutcome::TimedOut));
+        assert!(ticks.0.load(Ordering::SeqCst) >= 2);
+    }
+}
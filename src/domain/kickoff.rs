@@ -0,0 +1,206 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use super::{
+    guild::GuildConfigRepository,
+    sprint::SprintRepository,
+    standup::{StandupEntry, StandupRepository},
+};
+
+/// Where the time to the first standup of a sprint is published.
+pub trait KickoffHistogram: Send + Sync {
+    fn observe(&self, delay: TimeDelta);
+}
+
+/// The delay from the start of the sprint, midnight of `start_date` in
+/// `timezone`, to its first standup at `first_at`.
+///
+/// Standups can't be sent before the sprint starts, a negative delay only
+/// comes from clock skew and is reported as zero.
+pub fn time_to_first_standup(
+    start_date: NaiveDate,
+    timezone: Tz,
+    first_at: DateTime<Utc>,
+) -> TimeDelta {
+    // Midnight skipped by a DST change doesn't exist in the guild timezone.
+    let start = timezone
+        .from_local_datetime(&start_date.and_time(NaiveTime::MIN))
+        .earliest()
+        .map_or_else(
+            || start_date.and_time(NaiveTime::MIN).and_utc(),
+            |start| start.with_timezone(&Utc),
+        );
+
+    (first_at - start).max(TimeDelta::zero())
+}
+
+/// Observes how long sprints take to get their first standup.
+#[derive(Clone)]
+pub struct KickoffTracker {
+    standups: Arc<dyn StandupRepository>,
+    sprints: Arc<dyn SprintRepository>,
+    guilds: Arc<dyn GuildConfigRepository>,
+    histogram: Arc<dyn KickoffHistogram>,
+    default_timezone: Tz,
+}
+
+impl KickoffTracker {
+    pub fn new(
+        standups: Arc<dyn StandupRepository>,
+        sprints: Arc<dyn SprintRepository>,
+        guilds: Arc<dyn GuildConfigRepository>,
+        histogram: Arc<dyn KickoffHistogram>,
+        default_timezone: Tz,
+    ) -> Self {
+        Self {
+            standups,
+            sprints,
+            guilds,
+            histogram,
+            default_timezone,
+        }
+    }
+
+    /// Observe the delay when `entry` is the first standup of its sprint.
+    /// Failures are logged, metrics never fail a command.
+    #[tracing::instrument(name = "Record sprint kickoff", skip(self, entry), fields(sprint_id = ?entry.sprint_id))]
+    pub async fn record(&self, entry: &StandupEntry) {
+        match self.delay(entry).await {
+            Ok(Some(delay)) => self.histogram.observe(delay),
+            Ok(None) => {}
+            Err(error) => tracing::warn!(error = ?error, "failed to record sprint kickoff"),
+        }
+    }
+
+    async fn delay(&self, entry: &StandupEntry) -> Result<Option<TimeDelta>> {
+        let Some(sprint_id) = entry.sprint_id else {
+            return Ok(None);
+        };
+        let Some(first) = self.standups.first_for_sprint(sprint_id).await? else {
+            return Ok(None);
+        };
+        if first.id != entry.id {
+            return Ok(None);
+        }
+        let Some(sprint) = self.sprints.find(sprint_id).await? else {
+            return Ok(None);
+        };
+
+        let timezone = self
+            .guilds
+            .timezone(Some(sprint.guild_id), self.default_timezone)
+            .await?;
+
+        Ok(Some(time_to_first_standup(
+            sprint.start_date,
+            timezone,
+            first.created_at,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use crate::{
+        domain::{
+            id::{ChannelId, GuildId, UserId},
+            sprint::Sprint,
+            standup::StandupService,
+        },
+        drivers::database::memory::{
+            InMemoryGuildConfigRepository, InMemorySprintRepository, InMemoryStandupRepository,
+        },
+    };
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Histogram(Mutex<Vec<TimeDelta>>);
+
+    impl KickoffHistogram for Histogram {
+        fn observe(&self, delay: TimeDelta) {
+            self.0.lock().unwrap().push(delay);
+        }
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 10, day).unwrap()
+    }
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 10, day, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn delay_runs_from_midnight_of_the_start_date() {
+        assert_eq!(
+            time_to_first_standup(date(14), Tz::UTC, at(15, 9)),
+            TimeDelta::hours(33)
+        );
+        // Midnight in Sao Paulo is 03:00 UTC.
+        assert_eq!(
+            time_to_first_standup(date(14), chrono_tz::America::Sao_Paulo, at(14, 12)),
+            TimeDelta::hours(9)
+        );
+        assert_eq!(
+            time_to_first_standup(date(14), Tz::UTC, at(13, 23)),
+            TimeDelta::zero()
+        );
+    }
+
+    #[tokio::test]
+    async fn only_the_first_standup_of_a_sprint_is_observed() {
+        let standups = Arc::new(InMemoryStandupRepository::default());
+        let sprints = Arc::new(InMemorySprintRepository::default());
+        let histogram = Arc::new(Histogram::default());
+        let today = Utc::now().date_naive();
+        sprints
+            .insert(&Sprint {
+                id: None,
+                guild_id: GuildId(1),
+                team: None,
+                name: "Sprint 1".into(),
+                start_date: today,
+                end_date: today,
+                goals: vec![],
+            })
+            .await
+            .unwrap();
+        let tracker = KickoffTracker::new(
+            standups.clone(),
+            sprints.clone(),
+            Arc::new(InMemoryGuildConfigRepository::default()),
+            histogram.clone(),
+            Tz::UTC,
+        );
+        let service = StandupService::new(standups, sprints).with_kickoff(tracker);
+
+        for user_id in [3, 4] {
+            service
+                .submit(StandupEntry {
+                    id: None,
+                    guild_id: GuildId(1),
+                    channel_id: ChannelId(2),
+                    user_id: UserId(user_id),
+                    team: None,
+                    date: today,
+                    yesterday: String::new(),
+                    today: String::new(),
+                    blockers: String::new(),
+                    sprint_id: None,
+                    created_at: Utc::now(),
+                })
+                .await
+                .unwrap();
+        }
+
+        let observed = histogram.0.lock().unwrap();
+        assert_eq!(observed.len(), 1);
+        assert!(observed[0] <= TimeDelta::days(1));
+    }
+}
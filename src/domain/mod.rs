@@ -0,0 +1,24 @@
+pub mod audit;
+pub mod blockers;
+pub mod burndown;
+pub mod cleanup;
+pub mod conflict;
+pub mod debounce;
+pub mod delivery;
+pub mod feature;
+pub mod guild;
+pub mod id;
+pub mod job;
+pub mod kickoff;
+pub mod nudge;
+pub mod participation;
+pub mod reminder;
+pub mod replay;
+pub mod report;
+pub mod retention;
+pub mod retro;
+pub mod scheduler;
+pub mod snooze;
+pub mod sprint;
+pub mod standup;
+pub mod template;
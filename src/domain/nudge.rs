@@ -0,0 +1,295 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::t;
+
+use super::{
+    delivery::{Destination, MessageSender, OutgoingMessage},
+    guild::{GuildConfig, GuildConfigRepository},
+    id::{GuildId, UserId},
+    job::Job,
+    standup::StandupRepository,
+};
+
+/// How often the guilds are checked for a closed standup window.
+pub const NUDGE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Members mentioned by a single nudge, to stay well within the message limit.
+pub const MAX_NUDGE_MENTIONS: usize = 50;
+
+/// Opt-in ping of the roster members who didn't answer the standup of the day.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NudgeConfig {
+    pub enabled: bool,
+    /// When the standup window closes, in the guild timezone. The close of
+    /// the guild standup window when unset.
+    pub after: Option<NaiveTime>,
+    /// Members who asked never to be mentioned.
+    #[serde(default)]
+    pub opt_out: Vec<UserId>,
+}
+
+/// The members who didn't submit, minus those who opted out, in member order.
+pub fn missing_members(
+    members: &[UserId],
+    submitted: &HashSet<UserId>,
+    opt_out: &[UserId],
+) -> Vec<UserId> {
+    let mut seen = HashSet::new();
+
+    members
+        .iter()
+        .copied()
+        .filter(|user_id| !submitted.contains(user_id) && !opt_out.contains(user_id))
+        .filter(|user_id| seen.insert(*user_id))
+        .collect()
+}
+
+/// When the standup window of the guild closes, the end of the day for a
+/// window closing past midnight, so the nudge stays on the day it is about.
+fn window_close(config: &GuildConfig) -> Option<NaiveTime> {
+    let (_, close) = config.standup_window()?;
+    let scheduled = config.reminder_time?;
+
+    Some(if close < scheduled {
+        NaiveTime::from_hms_opt(23, 59, 0).expect("expected a valid time")
+    } else {
+        close
+    })
+}
+
+/// Mentions the members missing from the standup in the standup channel, once
+/// the window closed.
+///
+/// A guild is nudged at most once a day. The days already nudged are kept in
+/// memory, a restart after the window may nudge a guild a second time.
+pub struct StandupNudger {
+    guilds: Arc<dyn GuildConfigRepository>,
+    standups: Arc<dyn StandupRepository>,
+    sender: Arc<dyn MessageSender>,
+    default_timezone: Tz,
+    nudged: Mutex<HashMap<GuildId, NaiveDate>>,
+}
+
+impl StandupNudger {
+    pub fn new(
+        guilds: Arc<dyn GuildConfigRepository>,
+        standups: Arc<dyn StandupRepository>,
+        sender: Arc<dyn MessageSender>,
+        default_timezone: Tz,
+    ) -> Self {
+        Self {
+            guilds,
+            standups,
+            sender,
+            default_timezone,
+            nudged: Mutex::default(),
+        }
+    }
+
+    /// Nudge the guilds whose window closed by `now`, returning how many were.
+    #[tracing::instrument(name = "Nudge missing standups", skip(self))]
+    pub async fn nudge_due(&self, now: DateTime<Utc>) -> Result<usize> {
+        let mut nudged = 0;
+
+        for config in self.guilds.list_nudged().await? {
+            let Some(date) = self.due_date(&config, now) else {
+                continue;
+            };
+
+            match self.nudge(&config, date).await {
+                Ok(true) => nudged += 1,
+                Ok(false) => {}
+                Err(error) => {
+                    tracing::warn!(error = ?error, guild_id = %config.guild_id, "failed to nudge guild");
+                }
+            }
+        }
+
+        Ok(nudged)
+    }
+
+    /// The local date to nudge the guild for, `None` before its window closed
+    /// or once nudged that day.
+    fn due_date(&self, config: &GuildConfig, now: DateTime<Utc>) -> Option<NaiveDate> {
+        let after = config
+            .nudge
+            .after
+            .or_else(|| window_close(config))
+            .filter(|_| config.nudge.enabled)?;
+        let local = now.with_timezone(&config.tz(self.default_timezone));
+        if local.time() < after {
+            return None;
+        }
+
+        let date = local.date_naive();
+        let nudged = self.nudged.lock().unwrap();
+        (nudged.get(&config.guild_id) != Some(&date)).then_some(date)
+    }
+
+    async fn nudge(&self, config: &GuildConfig, date: NaiveDate) -> Result<bool> {
+        let Some(channel_id) = config.standup_channel_id else {
+            return Ok(false);
+        };
+
+        // Taken before sending, a failing channel isn't retried every minute.
+        self.nudged.lock().unwrap().insert(config.guild_id, date);
+
+        let submitted = self.standups.participants(config.guild_id, date).await?;
+        let mut missing = missing_members(&config.roster, &submitted, &config.nudge.opt_out);
+        if missing.is_empty() {
+            return Ok(false);
+        }
+        if missing.len() > MAX_NUDGE_MENTIONS {
+            tracing::info!(
+                left_out = missing.len() - MAX_NUDGE_MENTIONS,
+                "too many missing members to mention"
+            );
+            missing.truncate(MAX_NUDGE_MENTIONS);
+        }
+
+        let mentions = missing
+            .iter()
+            .map(|user_id| format!("<@{}>", user_id))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let message = OutgoingMessage {
+            destination: Destination::Channel(channel_id),
+            content: t(config.locale(), "standup.nudge", &[("mentions", &mentions)]),
+        };
+        self.sender.send(&message).await?;
+
+        Ok(true)
+    }
+}
+
+/// Run by the [`JobRunner`](super::job::JobRunner) every [`NUDGE_INTERVAL`].
+#[async_trait]
+impl Job for StandupNudger {
+    async fn run(&self) -> Result<()> {
+        self.nudge_due(Utc::now()).await.map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use crate::{
+        domain::{id::ChannelId, standup::StandupEntry},
+        drivers::database::memory::{InMemoryGuildConfigRepository, InMemoryStandupRepository},
+    };
+
+    use super::*;
+
+    fn users(ids: &[u64]) -> Vec<UserId> {
+        ids.iter().copied().map(UserId).collect()
+    }
+
+    #[test]
+    fn missing_members_skip_submissions_and_opt_outs() {
+        let submitted = users(&[2, 9]).into_iter().collect();
+
+        assert_eq!(
+            missing_members(&users(&[1, 2, 3, 4, 1]), &submitted, &users(&[4])),
+            users(&[1, 3])
+        );
+        assert!(missing_members(&users(&[2]), &submitted, &[]).is_empty());
+        assert!(missing_members(&[], &submitted, &[]).is_empty());
+    }
+
+    #[derive(Default)]
+    struct Sent(Mutex<Vec<OutgoingMessage>>);
+
+    #[async_trait]
+    impl MessageSender for Sent {
+        async fn send(&self, message: &OutgoingMessage) -> Result<()> {
+            self.0.lock().unwrap().push(message.clone());
+            Ok(())
+        }
+    }
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 10, 14, hour, minute, 0).unwrap()
+    }
+
+    #[tokio::test]
+    async fn guilds_are_nudged_once_after_the_window() {
+        let guilds = Arc::new(InMemoryGuildConfigRepository::default());
+        let mut config = GuildConfig::new(GuildId(1));
+        config.standup_channel_id = Some(ChannelId(2));
+        config.roster = users(&[3, 4, 5]);
+        config.nudge = NudgeConfig {
+            enabled: true,
+            after: NaiveTime::from_hms_opt(11, 0, 0),
+            opt_out: users(&[5]),
+        };
+        guilds.upsert(&config).await.unwrap();
+        let mut silent = GuildConfig::new(GuildId(6));
+        silent.standup_channel_id = Some(ChannelId(7));
+        silent.roster = users(&[3]);
+        guilds.upsert(&silent).await.unwrap();
+
+        let standups = Arc::new(InMemoryStandupRepository::default());
+        standups
+            .insert(&StandupEntry {
+                id: None,
+                guild_id: GuildId(1),
+                channel_id: ChannelId(2),
+                user_id: UserId(4),
+                team: None,
+                date: at(9, 0).date_naive(),
+                yesterday: String::new(),
+                today: String::new(),
+                blockers: String::new(),
+                sprint_id: None,
+                created_at: at(9, 0),
+            })
+            .await
+            .unwrap();
+        let sent = Arc::new(Sent::default());
+        let nudger = StandupNudger::new(guilds, standups, sent.clone(), Tz::UTC);
+
+        assert_eq!(nudger.nudge_due(at(10, 59)).await.unwrap(), 0);
+        assert_eq!(nudger.nudge_due(at(11, 0)).await.unwrap(), 1);
+        assert_eq!(nudger.nudge_due(at(11, 1)).await.unwrap(), 0);
+
+        let sent = sent.0.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].destination, Destination::Channel(ChannelId(2)));
+        assert!(sent[0].content.starts_with("<@3> "), "{}", sent[0].content);
+    }
+
+    #[tokio::test]
+    async fn without_a_nudge_time_the_window_close_is_used() {
+        let guilds = Arc::new(InMemoryGuildConfigRepository::default());
+        let mut config = GuildConfig::new(GuildId(1));
+        config.standup_channel_id = Some(ChannelId(2));
+        config.roster = users(&[3]);
+        config.timezone = Some("America/Sao_Paulo".into());
+        config.reminder_time = NaiveTime::from_hms_opt(9, 0, 0);
+        config.standup_window_minutes = Some(45);
+        config.nudge.enabled = true;
+        guilds.upsert(&config).await.unwrap();
+        let sent = Arc::new(Sent::default());
+        let nudger = StandupNudger::new(
+            guilds,
+            Arc::new(InMemoryStandupRepository::default()),
+            sent.clone(),
+            Tz::UTC,
+        );
+
+        // 09:44 and 09:45 in Sao Paulo.
+        assert_eq!(nudger.nudge_due(at(12, 44)).await.unwrap(), 0);
+        assert_eq!(nudger.nudge_due(at(12, 45)).await.unwrap(), 1);
+    }
+}
@@ -0,0 +1,181 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+
+use super::{
+    guild::{GuildConfig, GuildConfigRepository},
+    id::GuildId,
+    standup::StandupRepository,
+};
+
+/// Where the participation ratio of a guild is published.
+pub trait ParticipationGauge: Send + Sync {
+    fn record(&self, guild_id: GuildId, ratio: f64);
+}
+
+/// The share of the roster that answered, `0.0` for an empty roster.
+pub fn participation_ratio(submitted: usize, roster: usize) -> f64 {
+    if roster == 0 {
+        return 0.0;
+    }
+
+    submitted as f64 / roster as f64
+}
+
+/// Recomputes the standup participation of a guild day.
+#[derive(Clone)]
+pub struct ParticipationTracker {
+    standups: Arc<dyn StandupRepository>,
+    guilds: Arc<dyn GuildConfigRepository>,
+    gauge: Arc<dyn ParticipationGauge>,
+    default_timezone: Tz,
+}
+
+impl ParticipationTracker {
+    pub fn new(
+        standups: Arc<dyn StandupRepository>,
+        guilds: Arc<dyn GuildConfigRepository>,
+        gauge: Arc<dyn ParticipationGauge>,
+        default_timezone: Tz,
+    ) -> Self {
+        Self {
+            standups,
+            guilds,
+            gauge,
+            default_timezone,
+        }
+    }
+
+    /// Submissions from members outside of the roster are not counted.
+    pub async fn ratio(&self, config: &GuildConfig, date: NaiveDate) -> Result<f64> {
+        let participants = self.standups.participants(config.guild_id, date).await?;
+        let submitted = config
+            .roster
+            .iter()
+            .filter(|user_id| participants.contains(user_id))
+            .count();
+
+        Ok(participation_ratio(submitted, config.roster.len()))
+    }
+
+    /// Publish the ratio of `date`. Failures are logged, metrics never fail a command.
+    #[tracing::instrument(name = "Refresh participation", skip(self))]
+    pub async fn refresh(&self, guild_id: GuildId, date: NaiveDate) {
+        match self.compute(guild_id, Some(date), Utc::now()).await {
+            Ok(ratio) => self.gauge.record(guild_id, ratio),
+            Err(error) => tracing::warn!(error = ?error, "failed to refresh participation"),
+        }
+    }
+
+    /// Publish the ratio of the current day in the guild timezone.
+    #[tracing::instrument(name = "Refresh participation of today", skip(self))]
+    pub async fn refresh_today(&self, guild_id: GuildId, now: DateTime<Utc>) {
+        match self.compute(guild_id, None, now).await {
+            Ok(ratio) => self.gauge.record(guild_id, ratio),
+            Err(error) => tracing::warn!(error = ?error, "failed to refresh participation"),
+        }
+    }
+
+    async fn compute(
+        &self,
+        guild_id: GuildId,
+        date: Option<NaiveDate>,
+        now: DateTime<Utc>,
+    ) -> Result<f64> {
+        let config = self
+            .guilds
+            .find(guild_id)
+            .await?
+            .unwrap_or_else(|| GuildConfig::new(guild_id));
+        let date = date.unwrap_or_else(|| config.local_date(now, self.default_timezone));
+
+        self.ratio(&config, date).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Mutex};
+
+    use crate::{
+        domain::{
+            audit::Auditor,
+            guild::GuildConfigService,
+            id::{ChannelId, UserId},
+            sprint::SprintRepository,
+            standup::{StandupEntry, StandupService},
+        },
+        drivers::database::memory::{
+            InMemoryGuildConfigRepository, InMemorySprintRepository, InMemoryStandupRepository,
+        },
+    };
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Gauge(Mutex<HashMap<GuildId, f64>>);
+
+    impl ParticipationGauge for Gauge {
+        fn record(&self, guild_id: GuildId, ratio: f64) {
+            self.0.lock().unwrap().insert(guild_id, ratio);
+        }
+    }
+
+    fn today() -> NaiveDate {
+        Utc::now().date_naive()
+    }
+
+    fn entry(user_id: u64) -> StandupEntry {
+        StandupEntry {
+            id: None,
+            guild_id: GuildId(1),
+            channel_id: ChannelId(2),
+            user_id: UserId(user_id),
+            team: None,
+            date: today(),
+            yesterday: String::new(),
+            today: String::new(),
+            blockers: String::new(),
+            sprint_id: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn ratio_of_an_empty_roster_is_zero() {
+        assert_eq!(participation_ratio(0, 0), 0.0);
+        assert_eq!(participation_ratio(3, 0), 0.0);
+        assert_eq!(participation_ratio(3, 5), 0.6);
+    }
+
+    #[tokio::test]
+    async fn three_of_five_members_is_sixty_percent() {
+        let guilds = Arc::new(InMemoryGuildConfigRepository::default());
+        let standups = Arc::new(InMemoryStandupRepository::default());
+        let sprints: Arc<dyn SprintRepository> = Arc::new(InMemorySprintRepository::default());
+        let gauge = Arc::new(Gauge::default());
+        let tracker =
+            ParticipationTracker::new(standups.clone(), guilds.clone(), gauge.clone(), Tz::UTC);
+
+        let configs =
+            GuildConfigService::new(guilds, Auditor::new(None)).with_participation(tracker.clone());
+        configs
+            .update("admin", GuildId(1), |config| {
+                config.roster = (10..15).map(UserId).collect()
+            })
+            .await
+            .unwrap();
+        assert_eq!(gauge.0.lock().unwrap()[&GuildId(1)], 0.0);
+
+        let service = StandupService::new(standups, sprints).with_participation(tracker);
+        // A guest outside of the roster and a corrected answer don't count.
+        for user_id in [10, 11, 12, 99] {
+            service.submit(entry(user_id)).await.unwrap();
+        }
+        service.edit(entry(12)).await.unwrap();
+
+        assert_eq!(gauge.0.lock().unwrap()[&GuildId(1)], 0.6);
+    }
+}
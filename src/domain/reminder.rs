@@ -0,0 +1,571 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use bson::oid::ObjectId;
+use chrono::{DateTime, Days, NaiveTime, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    id::{ChannelId, GuildId, UserId},
+    job::Job,
+    scheduler::Jitter,
+    snooze::SnoozeRepository,
+};
+
+/// How often the due reminders are fired.
+pub const FIRE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// How far ahead a reminder can be scheduled.
+pub const MAX_REMINDER_DAYS: i64 = 365;
+
+/// A one-off reminder requested with `/remind`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reminder {
+    #[serde(rename = "_id", default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub guild_id: Option<GuildId>,
+    /// Where to post the reminder, a direct message to `user_id` when unset.
+    pub channel_id: Option<ChannelId>,
+    pub user_id: UserId,
+    pub message: String,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub due_at: DateTime<Utc>,
+    pub delivered: bool,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait ReminderRepository: Send + Sync {
+    async fn insert(&self, reminder: &Reminder) -> Result<ObjectId>;
+    /// The undelivered reminders due at or before `now`, oldest first.
+    async fn due(&self, now: DateTime<Utc>) -> Result<Vec<Reminder>>;
+    async fn mark_delivered(&self, id: ObjectId) -> Result<()>;
+}
+
+/// Delivers a due reminder to its channel or member.
+#[async_trait]
+pub trait ReminderSender: Send + Sync {
+    async fn send(&self, reminder: &Reminder) -> Result<()>;
+}
+
+/// Where the age of the oldest due reminder is published, to spot a stalled
+/// scheduler.
+pub trait PendingAgeGauge: Send + Sync {
+    fn oldest_pending(&self, age: Duration);
+}
+
+#[derive(Clone)]
+pub struct ReminderService {
+    repository: Arc<dyn ReminderRepository>,
+    snoozes: Option<Arc<dyn SnoozeRepository>>,
+    pending_age: Option<Arc<dyn PendingAgeGauge>>,
+    jitter: Option<Jitter>,
+}
+
+impl ReminderService {
+    pub fn new(repository: Arc<dyn ReminderRepository>) -> Self {
+        Self {
+            repository,
+            snoozes: None,
+            pending_age: None,
+            jitter: None,
+        }
+    }
+
+    pub fn with_pending_age(mut self, pending_age: Arc<dyn PendingAgeGauge>) -> Self {
+        self.pending_age = Some(pending_age);
+        self
+    }
+
+    /// Fire the reminders of each guild a few seconds late, so the guilds
+    /// sharing a due time don't all post at once.
+    pub fn with_jitter(mut self, jitter: Jitter) -> Self {
+        self.jitter = Some(jitter);
+        self
+    }
+
+    /// Hold back the direct messages of snoozed members until their snooze expires.
+    pub fn with_snoozes(mut self, snoozes: Arc<dyn SnoozeRepository>) -> Self {
+        self.snoozes = Some(snoozes);
+        self
+    }
+
+    #[tracing::instrument(name = "Schedule reminder", skip(self, reminder), fields(user_id = %reminder.user_id, due_at = %reminder.due_at))]
+    pub async fn schedule(&self, mut reminder: Reminder) -> Result<Reminder> {
+        let id = self.repository.insert(&reminder).await?;
+        reminder.id = Some(id);
+
+        Ok(reminder)
+    }
+
+    /// Send every reminder due at `now`, returning how many were delivered.
+    ///
+    /// A reminder that fails to send stays pending and is retried on the next tick,
+    /// as do the direct messages of snoozed members and of members whose
+    /// snooze couldn't be looked up. With a [`Jitter`], the reminders of a
+    /// guild wait for its offset past their due time.
+    ///
+    /// The age of the oldest due reminder, snoozed ones aside, is published
+    /// before sending, it stays within the tick period unless sends fail.
+    #[tracing::instrument(name = "Fire due reminders", skip(self, sender))]
+    pub async fn fire_due(&self, now: DateTime<Utc>, sender: &dyn ReminderSender) -> Result<usize> {
+        let mut ready = Vec::new();
+        for reminder in self.repository.due(now).await? {
+            let Some(id) = reminder.id else {
+                continue;
+            };
+            if self.fires_at(&reminder) > now {
+                continue;
+            }
+
+            match self.is_snoozed(&reminder, now).await {
+                Ok(false) => {}
+                Ok(true) => {
+                    tracing::debug!(reminder_id = %id, "member is snoozed, holding reminder");
+                    continue;
+                }
+                Err(error) => {
+                    tracing::warn!(error = ?error, reminder_id = %id, "failed to look up snooze, holding reminder");
+                    continue;
+                }
+            }
+            ready.push((id, reminder));
+        }
+
+        if let Some(pending_age) = &self.pending_age {
+            // Due reminders come oldest first.
+            let age = ready
+                .first()
+                .map_or(TimeDelta::zero(), |(_, reminder)| now - reminder.due_at);
+            pending_age.oldest_pending(age.to_std().unwrap_or_default());
+        }
+
+        let mut delivered = 0;
+        for (id, reminder) in ready {
+            if let Err(error) = sender.send(&reminder).await {
+                tracing::warn!(error = ?error, reminder_id = %id, "failed to send reminder");
+                continue;
+            }
+
+            self.repository.mark_delivered(id).await?;
+            delivered += 1;
+        }
+
+        Ok(delivered)
+    }
+
+    fn fires_at(&self, reminder: &Reminder) -> DateTime<Utc> {
+        match (self.jitter, reminder.guild_id) {
+            (Some(jitter), Some(guild_id)) => reminder.due_at + jitter.offset(guild_id),
+            _ => reminder.due_at,
+        }
+    }
+
+    async fn is_snoozed(&self, reminder: &Reminder, now: DateTime<Utc>) -> Result<bool> {
+        match (&self.snoozes, reminder.channel_id) {
+            (Some(snoozes), None) => snoozes.is_snoozed(reminder.user_id, now).await,
+            _ => Ok(false),
+        }
+    }
+}
+
+/// Fires the due reminders of a [`ReminderService`] through `sender`.
+#[derive(Clone)]
+pub struct ReminderFirer {
+    service: ReminderService,
+    sender: Arc<dyn ReminderSender>,
+}
+
+impl ReminderFirer {
+    pub fn new(service: ReminderService, sender: Arc<dyn ReminderSender>) -> Self {
+        Self { service, sender }
+    }
+}
+
+/// Run by the [`JobRunner`](super::job::JobRunner) every [`FIRE_INTERVAL`].
+#[async_trait]
+impl Job for ReminderFirer {
+    async fn run(&self) -> Result<()> {
+        let delivered = self
+            .service
+            .fire_due(Utc::now(), self.sender.as_ref())
+            .await?;
+        if delivered > 0 {
+            tracing::info!(delivered, "fired due reminders");
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum WhenError {
+    #[error("expected a time like `30m`, `2h` or `tomorrow 9am`")]
+    Unrecognized,
+    #[error("the reminder time must be in the future")]
+    InThePast,
+    #[error("reminders can't be scheduled more than {MAX_REMINDER_DAYS} days ahead")]
+    TooFar,
+}
+
+/// Parse the `when` of `/remind` relative to `now`.
+///
+/// Accepts a delay such as `30m`, `2h` or `1d`, or a day and a clock time such
+/// as `tomorrow 9am`, `today 5:30pm` or `tomorrow 14:00` in the timezone of `now`.
+pub fn parse_when(input: &str, now: DateTime<Tz>) -> Result<DateTime<Utc>, WhenError> {
+    let input = input.trim().to_ascii_lowercase();
+
+    let due = match parse_delay(&input) {
+        Some(delay) => now
+            .checked_add_signed(delay)
+            .ok_or(WhenError::TooFar)?
+            .with_timezone(&Utc),
+        None => parse_day_time(&input, now)?,
+    };
+
+    let ahead = due - now.with_timezone(&Utc);
+    if ahead <= TimeDelta::zero() {
+        return Err(WhenError::InThePast);
+    }
+    if ahead > TimeDelta::days(MAX_REMINDER_DAYS) {
+        return Err(WhenError::TooFar);
+    }
+
+    Ok(due)
+}
+
+fn parse_delay(input: &str) -> Option<TimeDelta> {
+    let split = input.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = input.split_at(split);
+    let amount: i64 = amount.parse().ok()?;
+
+    match unit.trim() {
+        "m" | "min" | "mins" | "minute" | "minutes" => TimeDelta::try_minutes(amount),
+        "h" | "hr" | "hrs" | "hour" | "hours" => TimeDelta::try_hours(amount),
+        "d" | "day" | "days" => TimeDelta::try_days(amount),
+        _ => None,
+    }
+}
+
+fn parse_day_time(input: &str, now: DateTime<Tz>) -> Result<DateTime<Utc>, WhenError> {
+    let (day, time) = input.split_once(' ').ok_or(WhenError::Unrecognized)?;
+    let time = parse_clock(time.trim()).ok_or(WhenError::Unrecognized)?;
+
+    let today = now.date_naive();
+    let date = match day {
+        "today" => today,
+        "tomorrow" => today + Days::new(1),
+        _ => return Err(WhenError::Unrecognized),
+    };
+
+    // Clock times skipped by a DST change don't exist in the guild timezone.
+    now.timezone()
+        .from_local_datetime(&date.and_time(time))
+        .earliest()
+        .map(|due| due.with_timezone(&Utc))
+        .ok_or(WhenError::Unrecognized)
+}
+
+fn parse_clock(input: &str) -> Option<NaiveTime> {
+    let (clock, afternoon) = if let Some(clock) = input.strip_suffix("am") {
+        (clock, Some(false))
+    } else if let Some(clock) = input.strip_suffix("pm") {
+        (clock, Some(true))
+    } else {
+        (input, None)
+    };
+
+    let (hour, minute) = match clock.trim().split_once(':') {
+        Some((hour, minute)) if minute.len() == 2 => (hour.parse().ok()?, minute.parse().ok()?),
+        Some(_) => return None,
+        None => (clock.trim().parse().ok()?, 0),
+    };
+
+    let hour = match afternoon {
+        // A bare number is ambiguous without am/pm, `tomorrow 14:00` is not.
+        None if !clock.contains(':') => return None,
+        None => hour,
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(false) => hour % 12,
+        Some(true) => hour % 12 + 12,
+    };
+
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use crate::{
+        domain::snooze::Snooze,
+        drivers::database::memory::{InMemoryReminderRepository, InMemorySnoozeRepository},
+    };
+
+    use super::*;
+
+    fn now() -> DateTime<Tz> {
+        Tz::UTC.with_ymd_and_hms(2024, 10, 15, 13, 0, 0).unwrap()
+    }
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 10, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn parses_relative_delays() {
+        assert_eq!(parse_when("30m", now()), Ok(at(15, 13, 30)));
+        assert_eq!(parse_when("2h", now()), Ok(at(15, 15, 0)));
+        assert_eq!(parse_when("1d", now()), Ok(at(16, 13, 0)));
+        assert_eq!(parse_when(" 45 minutes ", now()), Ok(at(15, 13, 45)));
+        assert_eq!(parse_when("3 Hours", now()), Ok(at(15, 16, 0)));
+    }
+
+    #[test]
+    fn parses_day_and_clock_time() {
+        assert_eq!(parse_when("tomorrow 9am", now()), Ok(at(16, 9, 0)));
+        assert_eq!(parse_when("Tomorrow 12am", now()), Ok(at(16, 0, 0)));
+        assert_eq!(parse_when("today 5:30pm", now()), Ok(at(15, 17, 30)));
+        assert_eq!(parse_when("today 12pm", now()), Err(WhenError::InThePast));
+        assert_eq!(parse_when("tomorrow 14:00", now()), Ok(at(16, 14, 0)));
+    }
+
+    #[test]
+    fn clock_times_are_read_in_the_timezone_of_now() {
+        let now = now().with_timezone(&chrono_tz::America::Sao_Paulo);
+
+        assert_eq!(parse_when("tomorrow 9am", now), Ok(at(16, 12, 0)));
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        for input in [
+            "",
+            "soon",
+            "m",
+            "30",
+            "30 fortnights",
+            "-5m",
+            "tomorrow",
+            "tomorrow 9",
+            "tomorrow 13pm",
+            "tomorrow 0am",
+            "tomorrow 25:00",
+            "tomorrow 9:5am",
+            "yesterday 9am",
+            "next week 9am",
+        ] {
+            assert_eq!(
+                parse_when(input, now()),
+                Err(WhenError::Unrecognized),
+                "{input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_times_outside_the_window() {
+        assert_eq!(parse_when("0m", now()), Err(WhenError::InThePast));
+        assert_eq!(parse_when("today 9am", now()), Err(WhenError::InThePast));
+        assert_eq!(parse_when("366d", now()), Err(WhenError::TooFar));
+        assert_eq!(
+            parse_when(&format!("{}m", i64::MAX), now()),
+            Err(WhenError::Unrecognized)
+        );
+    }
+
+    #[derive(Default)]
+    struct Outbox(Mutex<Vec<String>>);
+
+    #[async_trait]
+    impl ReminderSender for Outbox {
+        async fn send(&self, reminder: &Reminder) -> Result<()> {
+            self.0.lock().unwrap().push(reminder.message.clone());
+            Ok(())
+        }
+    }
+
+    fn reminder(message: &str, due_at: DateTime<Utc>) -> Reminder {
+        Reminder {
+            id: None,
+            guild_id: Some(GuildId(1)),
+            channel_id: None,
+            user_id: UserId(3),
+            message: message.into(),
+            due_at,
+            delivered: false,
+            created_at: at(15, 13, 0),
+        }
+    }
+
+    #[tokio::test]
+    async fn fires_only_due_reminders_once() {
+        let service = ReminderService::new(Arc::new(InMemoryReminderRepository::default()));
+        service
+            .schedule(reminder("review PR", at(15, 13, 30)))
+            .await
+            .unwrap();
+        service
+            .schedule(reminder("demo", at(16, 9, 0)))
+            .await
+            .unwrap();
+        let outbox = Outbox::default();
+
+        assert_eq!(service.fire_due(at(15, 14, 0), &outbox).await.unwrap(), 1);
+        assert_eq!(service.fire_due(at(15, 14, 1), &outbox).await.unwrap(), 0);
+        assert_eq!(*outbox.0.lock().unwrap(), vec!["review PR".to_owned()]);
+    }
+
+    #[tokio::test]
+    async fn firer_sends_the_reminders_due_now_and_publishes_their_age() {
+        let age = Arc::new(Age::default());
+        let service = ReminderService::new(Arc::new(InMemoryReminderRepository::default()))
+            .with_pending_age(age.clone());
+        service
+            .schedule(reminder("review PR", Utc::now() - TimeDelta::minutes(1)))
+            .await
+            .unwrap();
+        service
+            .schedule(reminder("demo", Utc::now() + TimeDelta::hours(1)))
+            .await
+            .unwrap();
+        let outbox = Arc::new(Outbox::default());
+
+        ReminderFirer::new(service, outbox.clone())
+            .run()
+            .await
+            .unwrap();
+
+        assert_eq!(*outbox.0.lock().unwrap(), vec!["review PR".to_owned()]);
+        let published = age.0.lock().unwrap().unwrap();
+        assert!(published >= Duration::from_secs(60), "{published:?}");
+    }
+
+    #[tokio::test]
+    async fn jittered_reminders_wait_for_the_offset_of_their_guild() {
+        let jitter = Jitter::new(30);
+        let offset = jitter.offset(GuildId(1));
+        assert!(offset > TimeDelta::zero());
+        let service = ReminderService::new(Arc::new(InMemoryReminderRepository::default()))
+            .with_jitter(jitter);
+        service
+            .schedule(reminder("review PR", at(15, 14, 0)))
+            .await
+            .unwrap();
+        let outbox = Outbox::default();
+
+        let early = at(15, 14, 0) + offset - TimeDelta::seconds(1);
+        assert_eq!(service.fire_due(early, &outbox).await.unwrap(), 0);
+        assert_eq!(
+            service
+                .fire_due(at(15, 14, 0) + offset, &outbox)
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(*outbox.0.lock().unwrap(), vec!["review PR".to_owned()]);
+    }
+
+    #[derive(Default)]
+    struct Age(Mutex<Option<Duration>>);
+
+    impl PendingAgeGauge for Age {
+        fn oldest_pending(&self, age: Duration) {
+            *self.0.lock().unwrap() = Some(age);
+        }
+    }
+
+    #[tokio::test]
+    async fn oldest_pending_age_is_published_on_each_tick() {
+        let age = Arc::new(Age::default());
+        let service = ReminderService::new(Arc::new(InMemoryReminderRepository::default()))
+            .with_pending_age(age.clone());
+        service
+            .schedule(reminder("review PR", at(15, 13, 59)))
+            .await
+            .unwrap();
+        service
+            .schedule(reminder("demo", at(15, 13, 59) + TimeDelta::seconds(30)))
+            .await
+            .unwrap();
+        let outbox = Outbox::default();
+
+        service.fire_due(at(15, 14, 0), &outbox).await.unwrap();
+        assert_eq!(*age.0.lock().unwrap(), Some(Duration::from_secs(60)));
+
+        service.fire_due(at(15, 14, 1), &outbox).await.unwrap();
+        assert_eq!(*age.0.lock().unwrap(), Some(Duration::ZERO));
+    }
+
+    #[tokio::test]
+    async fn snoozed_member_is_skipped_until_the_snooze_expires() {
+        let snoozes = Arc::new(InMemorySnoozeRepository::default());
+        snoozes
+            .upsert(&Snooze::for_days(UserId(3), 1, at(15, 12, 0)).unwrap())
+            .await
+            .unwrap();
+        let service = ReminderService::new(Arc::new(InMemoryReminderRepository::default()))
+            .with_snoozes(snoozes);
+        service
+            .schedule(reminder("review PR", at(15, 13, 30)))
+            .await
+            .unwrap();
+        let mut in_channel = reminder("demo", at(15, 13, 30));
+        in_channel.channel_id = Some(ChannelId(2));
+        service.schedule(in_channel).await.unwrap();
+        let outbox = Outbox::default();
+
+        assert_eq!(service.fire_due(at(15, 14, 0), &outbox).await.unwrap(), 1);
+        assert_eq!(*outbox.0.lock().unwrap(), vec!["demo".to_owned()]);
+
+        assert_eq!(service.fire_due(at(16, 12, 0), &outbox).await.unwrap(), 1);
+        assert_eq!(
+            *outbox.0.lock().unwrap(),
+            vec!["demo".to_owned(), "review PR".to_owned()]
+        );
+    }
+
+    struct BrokenSnoozes;
+
+    #[async_trait]
+    impl SnoozeRepository for BrokenSnoozes {
+        async fn upsert(&self, _: &Snooze) -> Result<()> {
+            anyhow::bail!("database unavailable")
+        }
+
+        async fn find(&self, _: UserId) -> Result<Option<Snooze>> {
+            anyhow::bail!("database unavailable")
+        }
+
+        async fn list_active(&self, _: DateTime<Utc>) -> Result<Vec<Snooze>> {
+            anyhow::bail!("database unavailable")
+        }
+
+        async fn delete(&self, _: UserId) -> Result<bool> {
+            anyhow::bail!("database unavailable")
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_snooze_lookup_holds_only_that_reminder() {
+        let repository = Arc::new(InMemoryReminderRepository::default());
+        let service =
+            ReminderService::new(repository.clone()).with_snoozes(Arc::new(BrokenSnoozes));
+        service
+            .schedule(reminder("review PR", at(15, 13, 30)))
+            .await
+            .unwrap();
+        let mut in_channel = reminder("demo", at(15, 13, 30));
+        in_channel.channel_id = Some(ChannelId(2));
+        service.schedule(in_channel).await.unwrap();
+        let outbox = Outbox::default();
+
+        assert_eq!(service.fire_due(at(15, 14, 0), &outbox).await.unwrap(), 1);
+        assert_eq!(*outbox.0.lock().unwrap(), vec!["demo".to_owned()]);
+        assert_eq!(repository.due(at(15, 14, 0)).await.unwrap().len(), 1);
+    }
+}
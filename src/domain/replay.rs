@@ -0,0 +1,212 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+
+use super::{
+    audit::{AuditAction, AuditEntry, Auditor},
+    delivery::{Destination, MessageSender, OutgoingMessage},
+    guild::GuildConfigRepository,
+    id::{ChannelId, GuildId},
+    standup::StandupPrompt,
+};
+
+/// The command recorded in the audit log for a replay.
+pub const REPLAY_COMMAND: &str = "reminders/replay";
+
+/// Where the replayed prompts are counted.
+pub trait ReplayCounter: Send + Sync {
+    fn replayed(&self);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ReplayOutcome {
+    pub guild_id: GuildId,
+    pub channel_id: ChannelId,
+    /// The standup day of the prompt, in the guild timezone.
+    pub date: NaiveDate,
+}
+
+/// Posts the standup prompt of today again, when the daily one was missed or
+/// the guild moved its standup channel.
+///
+/// Replays are on demand, nothing keeps them to one a day. Each one is
+/// recorded as a manual reminder in the audit log.
+#[derive(Clone)]
+pub struct PromptReplay {
+    guilds: Arc<dyn GuildConfigRepository>,
+    sender: Arc<dyn MessageSender>,
+    prompt: StandupPrompt,
+    default_timezone: Tz,
+    auditor: Auditor,
+    counter: Option<Arc<dyn ReplayCounter>>,
+}
+
+impl PromptReplay {
+    pub fn new(
+        guilds: Arc<dyn GuildConfigRepository>,
+        sender: Arc<dyn MessageSender>,
+        prompt: StandupPrompt,
+        default_timezone: Tz,
+        auditor: Auditor,
+    ) -> Self {
+        Self {
+            guilds,
+            sender,
+            prompt,
+            default_timezone,
+            auditor,
+            counter: None,
+        }
+    }
+
+    pub fn with_counter(mut self, counter: Arc<dyn ReplayCounter>) -> Self {
+        self.counter = Some(counter);
+        self
+    }
+
+    /// Post the prompt of the guild day at `now` to its standup channel on
+    /// behalf of `actor`.
+    ///
+    /// `None` when the guild has no standup channel. A prompt the sender
+    /// refused is returned as an error and left out of the audit log, a
+    /// [`DeliveryService`](super::delivery::DeliveryService) only refuses the
+    /// ones it can't keep for a retry.
+    #[tracing::instrument(name = "Replay standup prompt", skip(self))]
+    pub async fn replay(
+        &self,
+        actor: &str,
+        guild_id: GuildId,
+        now: DateTime<Utc>,
+    ) -> Result<Option<ReplayOutcome>> {
+        let Some(config) = self.guilds.find(guild_id).await? else {
+            return Ok(None);
+        };
+        let Some(channel_id) = config.standup_channel_id else {
+            return Ok(None);
+        };
+        let date = config.local_date(now, self.default_timezone);
+
+        self.sender
+            .send(&OutgoingMessage {
+                destination: Destination::Channel(channel_id),
+                content: self.prompt.render(channel_id, date),
+            })
+            .await?;
+        if let Some(counter) = &self.counter {
+            counter.replayed();
+        }
+
+        let arguments = BTreeMap::from([
+            ("channel_id".to_owned(), channel_id.to_string()),
+            ("date".to_owned(), date.to_string()),
+        ]);
+        let entry = AuditEntry::new(actor, guild_id, AuditAction::ManualReminder)
+            .with_command(REPLAY_COMMAND, arguments);
+        self.auditor.record(entry).await?;
+        tracing::info!(%channel_id, %date, "replayed the standup prompt");
+
+        Ok(Some(ReplayOutcome {
+            guild_id,
+            channel_id,
+            date,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use crate::{
+        domain::{
+            audit::{AuditQuery, AuditRepository},
+            guild::GuildConfig,
+        },
+        drivers::database::memory::{InMemoryAuditRepository, InMemoryGuildConfigRepository},
+    };
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Discord(Mutex<Vec<OutgoingMessage>>);
+
+    #[async_trait]
+    impl MessageSender for Discord {
+        async fn send(&self, message: &OutgoingMessage) -> Result<()> {
+            self.0.lock().unwrap().push(message.clone());
+            Ok(())
+        }
+    }
+
+    async fn replay(
+        config: GuildConfig,
+    ) -> (PromptReplay, Arc<Discord>, Arc<InMemoryAuditRepository>) {
+        let guilds = Arc::new(InMemoryGuildConfigRepository::default());
+        guilds.upsert(&config).await.unwrap();
+        let discord = Arc::new(Discord::default());
+        let audit_log = Arc::new(InMemoryAuditRepository::default());
+        let replay = PromptReplay::new(
+            guilds,
+            discord.clone(),
+            StandupPrompt::new("Standup time in {channel} for {date}!").unwrap(),
+            Tz::UTC,
+            Auditor::new(Some(audit_log.clone())),
+        );
+
+        (replay, discord, audit_log)
+    }
+
+    #[tokio::test]
+    async fn prompt_is_replayed_in_the_guild_day_and_audited() {
+        let mut config = GuildConfig::new(GuildId(1));
+        config.standup_channel_id = Some(ChannelId(2));
+        config.timezone = Some("America/Sao_Paulo".into());
+        let (replay, discord, audit_log) = replay(config).await;
+
+        let outcome = replay
+            .replay("ops", GuildId(1), "2024-10-15T01:00:00Z".parse().unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let date = NaiveDate::from_ymd_opt(2024, 10, 14).unwrap();
+        assert_eq!(outcome.date, date);
+        assert_eq!(
+            *discord.0.lock().unwrap(),
+            vec![OutgoingMessage {
+                destination: Destination::Channel(ChannelId(2)),
+                content: "Standup time in <#2> for 2024-10-14!".into(),
+            }]
+        );
+
+        let entries = audit_log.list(&AuditQuery::default()).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].actor, "ops");
+        assert_eq!(entries[0].guild_id, Some(GuildId(1)));
+        assert_eq!(entries[0].action, AuditAction::ManualReminder);
+        assert_eq!(entries[0].command.as_deref(), Some(REPLAY_COMMAND));
+        assert_eq!(entries[0].arguments["date"], "2024-10-14");
+    }
+
+    #[tokio::test]
+    async fn nothing_is_replayed_without_a_standup_channel() {
+        let (replay, discord, audit_log) = replay(GuildConfig::new(GuildId(1))).await;
+
+        let outcome = replay.replay("ops", GuildId(1), Utc::now()).await.unwrap();
+        let unknown = replay.replay("ops", GuildId(9), Utc::now()).await.unwrap();
+
+        assert_eq!(outcome, None);
+        assert_eq!(unknown, None);
+        assert!(discord.0.lock().unwrap().is_empty());
+        assert!(audit_log
+            .list(&AuditQuery::default())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+
+use super::{
+    burndown::{burndown, BurndownPoint, GoalCompletion},
+    guild::GuildConfig,
+    id::UserId,
+    sprint::Sprint,
+    standup::StandupEntry,
+};
+
+/// Standups answered on a day of the sprint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayParticipation {
+    pub date: NaiveDate,
+    pub participants: usize,
+    /// The size of the guild roster, 0 when it has none.
+    pub roster: usize,
+}
+
+/// A blocker reported in a standup of the sprint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Blocker {
+    pub date: NaiveDate,
+    pub user_id: UserId,
+    pub text: String,
+}
+
+/// Everything known about a sprint, for the printable report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SprintReport {
+    pub sprint: Sprint,
+    /// The guild name, its id when the name was never seen.
+    pub guild_name: String,
+    pub total_goals: usize,
+    pub total_points: u32,
+    pub completed_goals: usize,
+    pub completed_points: u32,
+    pub standups: usize,
+    pub participants: usize,
+    pub participation: Vec<DayParticipation>,
+    pub burndown: Vec<BurndownPoint>,
+    pub blockers: Vec<Blocker>,
+}
+
+impl SprintReport {
+    /// Sum up the sprint as of `today` in `timezone`.
+    ///
+    /// Only days with at least one standup show up in the participation.
+    pub fn new(
+        sprint: Sprint,
+        config: &GuildConfig,
+        standups: &[StandupEntry],
+        completions: &[GoalCompletion],
+        timezone: Tz,
+        today: NaiveDate,
+    ) -> Self {
+        let burndown = burndown(&sprint, completions, timezone, today);
+        let remaining = burndown.last();
+        let total_goals = sprint.goals.len();
+        let total_points = sprint.goals.iter().map(|goal| goal.points).sum();
+        let completed_goals = remaining.map_or(0, |point| total_goals - point.remaining_goals);
+        let completed_points = remaining.map_or(0, |point| total_points - point.remaining_points);
+
+        let mut by_day: BTreeMap<NaiveDate, BTreeSet<UserId>> = BTreeMap::new();
+        for entry in standups {
+            by_day.entry(entry.date).or_default().insert(entry.user_id);
+        }
+        let participation = by_day
+            .iter()
+            .map(|(date, users)| DayParticipation {
+                date: *date,
+                participants: users.len(),
+                roster: config.roster.len(),
+            })
+            .collect();
+        let participants = by_day.values().flatten().collect::<BTreeSet<_>>().len();
+
+        let blockers = standups
+            .iter()
+            .filter(|entry| !entry.blockers.trim().is_empty())
+            .map(|entry| Blocker {
+                date: entry.date,
+                user_id: entry.user_id,
+                text: entry.blockers.trim().to_owned(),
+            })
+            .collect();
+
+        Self {
+            guild_name: config
+                .name
+                .clone()
+                .unwrap_or_else(|| sprint.guild_id.to_string()),
+            sprint,
+            total_goals,
+            total_points,
+            completed_goals,
+            completed_points,
+            standups: standups.len(),
+            participants,
+            participation,
+            burndown,
+            blockers,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use crate::domain::{
+        id::{ChannelId, GuildId},
+        sprint::Goal,
+    };
+
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 10, day).unwrap()
+    }
+
+    fn standup(user_id: u64, day: u32, blockers: &str) -> StandupEntry {
+        StandupEntry {
+            id: None,
+            guild_id: GuildId(1),
+            channel_id: ChannelId(2),
+            user_id: UserId(user_id),
+            team: None,
+            date: date(day),
+            yesterday: String::new(),
+            today: String::new(),
+            blockers: blockers.into(),
+            sprint_id: None,
+            created_at: Utc.with_ymd_and_hms(2024, 10, day, 12, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn sums_up_goals_participation_and_blockers() {
+        let sprint = Sprint {
+            id: None,
+            guild_id: GuildId(1),
+            team: None,
+            name: "Sprint 1".into(),
+            start_date: date(14),
+            end_date: date(25),
+            goals: vec![
+                Goal {
+                    title: "scheduler".into(),
+                    points: 5,
+                },
+                Goal {
+                    title: "dashboard".into(),
+                    points: 3,
+                },
+            ],
+        };
+        let mut config = GuildConfig::new(GuildId(1));
+        config.roster = vec![UserId(3), UserId(4), UserId(5)];
+        let completions = [GoalCompletion {
+            id: None,
+            sprint_id: bson::oid::ObjectId::new(),
+            goal: "dashboard".into(),
+            completed_by: UserId(3),
+            completed_at: Utc.with_ymd_and_hms(2024, 10, 15, 18, 0, 0).unwrap(),
+        }];
+        let standups = [
+            standup(3, 14, ""),
+            standup(4, 14, "  waiting on review "),
+            standup(3, 15, "none"),
+        ];
+
+        let report = SprintReport::new(sprint, &config, &standups, &completions, Tz::UTC, date(16));
+
+        assert_eq!(report.guild_name, "1");
+        assert_eq!((report.total_goals, report.total_points), (2, 8));
+        assert_eq!((report.completed_goals, report.completed_points), (1, 3));
+        assert_eq!((report.standups, report.participants), (3, 2));
+        assert_eq!(
+            report.participation,
+            vec![
+                DayParticipation {
+                    date: date(14),
+                    participants: 2,
+                    roster: 3,
+                },
+                DayParticipation {
+                    date: date(15),
+                    participants: 1,
+                    roster: 3,
+                },
+            ]
+        );
+        assert_eq!(report.blockers.len(), 2);
+        assert_eq!(report.blockers[0].text, "waiting on review");
+    }
+}
@@ -0,0 +1,187 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, TimeDelta, Utc};
+
+use super::{job::Job, standup::StandupRepository};
+
+/// How often the retention policy is applied.
+pub const PRUNE_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Entries deleted per query, to keep each delete short.
+pub const PRUNE_BATCH_SIZE: usize = 500;
+
+/// Where the records deleted by the retention policy are counted.
+pub trait PruneCounter: Send + Sync {
+    fn pruned(&self, count: u64);
+}
+
+/// Deletes the standup entries older than the retention period.
+#[derive(Clone)]
+pub struct StandupPruner {
+    standups: Arc<dyn StandupRepository>,
+    retention_days: u32,
+    batch_size: usize,
+    counter: Option<Arc<dyn PruneCounter>>,
+}
+
+impl StandupPruner {
+    /// Keep entries for `retention_days`, forever when 0.
+    pub fn new(standups: Arc<dyn StandupRepository>, retention_days: u32) -> Self {
+        Self {
+            standups,
+            retention_days,
+            batch_size: PRUNE_BATCH_SIZE,
+            counter: None,
+        }
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    pub fn with_counter(mut self, counter: Arc<dyn PruneCounter>) -> Self {
+        self.counter = Some(counter);
+        self
+    }
+
+    /// Entries created before this are deleted, `None` when nothing is.
+    pub fn cutoff(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if self.retention_days == 0 {
+            return None;
+        }
+
+        Some(now - TimeDelta::days(self.retention_days.into()))
+    }
+
+    /// Delete every entry past the cutoff, batch by batch.
+    #[tracing::instrument(name = "Prune old standups", skip(self))]
+    pub async fn prune(&self, now: DateTime<Utc>) -> Result<u64> {
+        let Some(cutoff) = self.cutoff(now) else {
+            return Ok(0);
+        };
+
+        let mut pruned = 0;
+        loop {
+            let deleted = self
+                .standups
+                .delete_created_before(cutoff, self.batch_size)
+                .await?;
+            if let Some(counter) = &self.counter {
+                counter.pruned(deleted);
+            }
+            pruned += deleted;
+
+            if deleted < self.batch_size as u64 {
+                break;
+            }
+        }
+
+        tracing::info!(pruned, %cutoff, "pruned old standup entries");
+        Ok(pruned)
+    }
+}
+
+/// Run by the [`JobRunner`](super::job::JobRunner) every [`PRUNE_INTERVAL`].
+#[async_trait]
+impl Job for StandupPruner {
+    async fn run(&self) -> Result<()> {
+        self.prune(Utc::now()).await.map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use chrono::NaiveDate;
+
+    use crate::{
+        domain::{
+            id::{ChannelId, GuildId, UserId},
+            standup::StandupEntry,
+        },
+        drivers::database::memory::InMemoryStandupRepository,
+    };
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Counter(AtomicU64);
+
+    impl PruneCounter for Counter {
+        fn pruned(&self, count: u64) {
+            self.0.fetch_add(count, Ordering::SeqCst);
+        }
+    }
+
+    fn entry(user_id: u64, created_at: DateTime<Utc>) -> StandupEntry {
+        StandupEntry {
+            id: None,
+            guild_id: GuildId(1),
+            channel_id: ChannelId(2),
+            user_id: UserId(user_id),
+            team: None,
+            date: created_at.date_naive(),
+            yesterday: String::new(),
+            today: String::new(),
+            blockers: String::new(),
+            sprint_id: None,
+            created_at,
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        NaiveDate::from_ymd_opt(2024, 10, 15)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+            .and_utc()
+    }
+
+    #[tokio::test]
+    async fn deletes_entries_past_the_cutoff_and_keeps_fresher_ones() {
+        let standups = Arc::new(InMemoryStandupRepository::default());
+        for (user_id, age) in [(1, 100), (2, 31), (3, 30), (4, 29), (5, 0)] {
+            standups
+                .insert(&entry(user_id, now() - TimeDelta::days(age)))
+                .await
+                .unwrap();
+        }
+        let counter = Arc::new(Counter::default());
+        let pruner = StandupPruner::new(standups.clone(), 30)
+            .with_batch_size(1)
+            .with_counter(counter.clone());
+
+        assert_eq!(pruner.prune(now()).await.unwrap(), 2);
+        assert_eq!(counter.0.load(Ordering::SeqCst), 2);
+
+        let kept = standups
+            .delete_created_before(now() + TimeDelta::days(1), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(kept, 3);
+    }
+
+    #[tokio::test]
+    async fn zero_retention_never_prunes() {
+        let standups = Arc::new(InMemoryStandupRepository::default());
+        standups
+            .insert(&entry(1, now() - TimeDelta::days(3650)))
+            .await
+            .unwrap();
+        let pruner = StandupPruner::new(standups.clone(), 0);
+
+        assert_eq!(pruner.cutoff(now()), None);
+        assert_eq!(pruner.prune(now()).await.unwrap(), 0);
+        assert_eq!(
+            standups
+                .delete_created_before(now(), usize::MAX)
+                .await
+                .unwrap(),
+            1
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use bson::oid::ObjectId;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::id::{GuildId, UserId};
+
+/// A retrospective held by a guild team.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Retro {
+    #[serde(rename = "_id", default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub guild_id: GuildId,
+    /// The team holding the retro, `None` for the guild wide team.
+    pub team: Option<String>,
+    pub held_on: NaiveDate,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Something the team agreed on during a retro, for a member to do.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActionItem {
+    #[serde(rename = "_id", default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub retro_id: ObjectId,
+    /// The guild of the retro, checked against the api key scopes.
+    pub guild_id: GuildId,
+    pub text: String,
+    pub owner: UserId,
+    pub due_date: NaiveDate,
+    /// The day it was done in the guild timezone, `None` while open.
+    pub completed_on: Option<NaiveDate>,
+}
+
+impl ActionItem {
+    /// Whether the item was still open when `next`, the retro following
+    /// its own, was held by `today`. Items done on the day of `next` made it.
+    pub fn is_carried_over(&self, next: Option<&Retro>, today: NaiveDate) -> bool {
+        let Some(next) = next.filter(|next| next.held_on <= today) else {
+            return false;
+        };
+
+        self.completed_on
+            .is_none_or(|completed_on| completed_on > next.held_on)
+    }
+}
+
+#[async_trait]
+pub trait RetroRepository: Send + Sync {
+    async fn insert(&self, retro: &Retro) -> Result<ObjectId>;
+    async fn find(&self, id: ObjectId) -> Result<Option<Retro>>;
+    /// The first retro of the guild team held after `retro`.
+    async fn next_after(&self, retro: &Retro) -> Result<Option<Retro>>;
+}
+
+#[async_trait]
+pub trait ActionItemRepository: Send + Sync {
+    async fn insert(&self, item: &ActionItem) -> Result<ObjectId>;
+    async fn find(&self, id: ObjectId) -> Result<Option<ActionItem>>;
+    /// The items of the retro, soonest due first.
+    async fn list_for_retro(&self, retro_id: ObjectId) -> Result<Vec<ActionItem>>;
+    /// Mark the item done on `completed_on`, or open again with `None`,
+    /// returning it updated. `None` when there is no such item.
+    async fn set_completed(
+        &self,
+        id: ObjectId,
+        completed_on: Option<NaiveDate>,
+    ) -> Result<Option<ActionItem>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 10, day).unwrap()
+    }
+
+    fn retro(day: u32) -> Retro {
+        Retro {
+            id: Some(ObjectId::new()),
+            guild_id: GuildId(1),
+            team: None,
+            held_on: date(day),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn item(completed_on: Option<u32>) -> ActionItem {
+        ActionItem {
+            id: None,
+            retro_id: ObjectId::new(),
+            guild_id: GuildId(1),
+            text: "pair on reviews".into(),
+            owner: UserId(3),
+            due_date: date(18),
+            completed_on: completed_on.map(date),
+        }
+    }
+
+    #[test]
+    fn items_open_at_the_next_retro_are_carried_over() {
+        let next = retro(25);
+        let today = date(30);
+
+        assert!(item(None).is_carried_over(Some(&next), today));
+        assert!(item(Some(28)).is_carried_over(Some(&next), today));
+        assert!(!item(Some(25)).is_carried_over(Some(&next), today));
+        assert!(!item(Some(17)).is_carried_over(Some(&next), today));
+    }
+
+    #[test]
+    fn nothing_is_carried_over_before_the_next_retro() {
+        assert!(!item(None).is_carried_over(None, date(30)));
+        assert!(!item(None).is_carried_over(Some(&retro(25)), date(24)));
+    }
+}
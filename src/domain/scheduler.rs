@@ -0,0 +1,243 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeDelta, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+
+use super::{
+    delivery::{Destination, MessageSender, OutgoingMessage},
+    id::GuildId,
+    reminder::{Reminder, ReminderSender},
+};
+
+/// Spreads the guilds sharing a reminder time over the first seconds of the
+/// minute, so they don't all post at once.
+///
+/// The offset of a guild only depends on its id, it fires at the same second
+/// every day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Jitter {
+    max_secs: u32,
+}
+
+impl Jitter {
+    /// At most `max_secs` late, capped to stay within the minute.
+    pub fn new(max_secs: u32) -> Self {
+        Self {
+            max_secs: max_secs.min(59),
+        }
+    }
+
+    /// How late the reminders of `guild_id` fire.
+    pub fn offset(&self, guild_id: GuildId) -> TimeDelta {
+        let spread = u64::from(self.max_secs) + 1;
+
+        TimeDelta::seconds((mix(guild_id.get()) % spread) as i64)
+    }
+
+    /// When the reminder at `time` of `date` in `timezone` fires for
+    /// `guild_id`, `None` for a time skipped by a DST change.
+    pub fn fire_at(
+        &self,
+        guild_id: GuildId,
+        date: NaiveDate,
+        time: NaiveTime,
+        timezone: Tz,
+    ) -> Option<DateTime<Utc>> {
+        let minute = time.with_second(0)?.with_nanosecond(0)?;
+        let start = timezone
+            .from_local_datetime(&date.and_time(minute))
+            .earliest()?;
+
+        Some(start.with_timezone(&Utc) + self.offset(guild_id))
+    }
+}
+
+/// SplitMix64 finalizer, spreads close snowflakes over the whole range.
+fn mix(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Where the messages fired by the scheduler are counted.
+pub trait FireCounter: Send + Sync {
+    fn fired(&self, dry_run: bool);
+}
+
+/// Sends the messages fired by the scheduler, or only logs them in dry run to
+/// try out schedules without posting anything.
+#[derive(Clone)]
+pub struct ScheduledSender {
+    sender: Arc<dyn MessageSender>,
+    dry_run: bool,
+    fires: Option<Arc<dyn FireCounter>>,
+}
+
+impl ScheduledSender {
+    pub fn new(sender: Arc<dyn MessageSender>, dry_run: bool) -> Self {
+        Self {
+            sender,
+            dry_run,
+            fires: None,
+        }
+    }
+
+    pub fn with_fire_counter(mut self, fires: Arc<dyn FireCounter>) -> Self {
+        self.fires = Some(fires);
+        self
+    }
+}
+
+#[async_trait]
+impl MessageSender for ScheduledSender {
+    #[tracing::instrument(name = "Discord send", skip_all, fields(destination = ?message.destination, dry_run = self.dry_run))]
+    async fn send(&self, message: &OutgoingMessage) -> Result<()> {
+        if let Some(fires) = &self.fires {
+            fires.fired(self.dry_run);
+        }
+
+        if self.dry_run {
+            tracing::info!(
+                destination = ?message.destination,
+                content = %message.content,
+                "dry run, scheduled message not sent"
+            );
+            return Ok(());
+        }
+
+        self.sender.send(message).await
+    }
+}
+
+#[async_trait]
+impl ReminderSender for ScheduledSender {
+    async fn send(&self, reminder: &Reminder) -> Result<()> {
+        let destination = reminder
+            .channel_id
+            .map_or(Destination::Direct(reminder.user_id), Destination::Channel);
+        let message = OutgoingMessage {
+            destination,
+            content: reminder.message.clone(),
+        };
+
+        MessageSender::send(self, &message).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    };
+
+    use crate::domain::id::{ChannelId, UserId};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Discord(Mutex<Vec<OutgoingMessage>>);
+
+    #[async_trait]
+    impl MessageSender for Discord {
+        async fn send(&self, message: &OutgoingMessage) -> Result<()> {
+            self.0.lock().unwrap().push(message.clone());
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct Fires {
+        live: AtomicUsize,
+        dry_run: AtomicUsize,
+    }
+
+    impl FireCounter for Fires {
+        fn fired(&self, dry_run: bool) {
+            let counter = if dry_run { &self.dry_run } else { &self.live };
+            counter.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn message() -> OutgoingMessage {
+        OutgoingMessage {
+            destination: Destination::Channel(ChannelId(2)),
+            content: "Standup time!".into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn dry_run_counts_without_sending() {
+        let discord = Arc::new(Discord::default());
+        let fires = Arc::new(Fires::default());
+        let sender = ScheduledSender::new(discord.clone(), true).with_fire_counter(fires.clone());
+
+        MessageSender::send(&sender, &message()).await.unwrap();
+
+        assert!(discord.0.lock().unwrap().is_empty());
+        assert_eq!(fires.dry_run.load(Ordering::SeqCst), 1);
+        assert_eq!(fires.live.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn live_run_sends_reminders_to_their_destination() {
+        let discord = Arc::new(Discord::default());
+        let fires = Arc::new(Fires::default());
+        let sender = ScheduledSender::new(discord.clone(), false).with_fire_counter(fires.clone());
+        let reminder = Reminder {
+            id: None,
+            guild_id: None,
+            channel_id: None,
+            user_id: UserId(3),
+            message: "review the PR".into(),
+            due_at: chrono::Utc::now(),
+            delivered: false,
+            created_at: chrono::Utc::now(),
+        };
+
+        ReminderSender::send(&sender, &reminder).await.unwrap();
+
+        assert_eq!(
+            *discord.0.lock().unwrap(),
+            vec![OutgoingMessage {
+                destination: Destination::Direct(UserId(3)),
+                content: "review the PR".into(),
+            }]
+        );
+        assert_eq!(fires.live.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn guilds_sharing_a_time_fire_apart_within_the_minute() {
+        let jitter = Jitter::new(30);
+        let date = NaiveDate::from_ymd_opt(2024, 10, 15).unwrap();
+        let time = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+        let minute = Utc.with_ymd_and_hms(2024, 10, 15, 12, 0, 0).unwrap();
+        let timezone = chrono_tz::America::Sao_Paulo;
+
+        let first = jitter.fire_at(GuildId(1), date, time, timezone).unwrap();
+        let second = jitter.fire_at(GuildId(2), date, time, timezone).unwrap();
+
+        assert_ne!(first, second);
+        for fire_at in [first, second] {
+            assert!(fire_at >= minute && fire_at <= minute + TimeDelta::seconds(30));
+        }
+        assert_eq!(
+            jitter.fire_at(GuildId(1), date, time, timezone),
+            Some(first)
+        );
+    }
+
+    #[test]
+    fn jitter_never_leaves_the_minute() {
+        assert_eq!(Jitter::new(0).offset(GuildId(1)), TimeDelta::zero());
+
+        let jitter = Jitter::new(600);
+        for guild_id in 0..1000 {
+            assert!(jitter.offset(GuildId(guild_id)) < TimeDelta::minutes(1));
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::id::UserId;
+
+/// The longest a member can snooze their reminders for.
+pub const MAX_SNOOZE_DAYS: i64 = 60;
+
+/// Reminders are not delivered to `user_id` until `until`, e.g. during vacations.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snooze {
+    pub user_id: UserId,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub until: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("snooze must be between 1 and {MAX_SNOOZE_DAYS} days")]
+pub struct InvalidSnooze;
+
+impl Snooze {
+    pub fn for_days(user_id: UserId, days: i64, now: DateTime<Utc>) -> Result<Self, InvalidSnooze> {
+        if !(1..=MAX_SNOOZE_DAYS).contains(&days) {
+            return Err(InvalidSnooze);
+        }
+
+        Ok(Self {
+            user_id,
+            until: now + TimeDelta::days(days),
+            created_at: now,
+        })
+    }
+
+    /// Expired snoozes are ignored, so nothing has to clean them up on time.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        now < self.until
+    }
+}
+
+#[async_trait]
+pub trait SnoozeRepository: Send + Sync {
+    /// Replace the snooze of the member, if any.
+    async fn upsert(&self, snooze: &Snooze) -> Result<()>;
+    /// The snooze of the member, expired or not.
+    async fn find(&self, user_id: UserId) -> Result<Option<Snooze>>;
+    /// The snoozes still active at `now`.
+    async fn list_active(&self, now: DateTime<Utc>) -> Result<Vec<Snooze>>;
+    /// Lift the snooze of the member, returning whether there was one.
+    async fn delete(&self, user_id: UserId) -> Result<bool>;
+
+    async fn is_snoozed(&self, user_id: UserId, now: DateTime<Utc>) -> Result<bool> {
+        Ok(self
+            .find(user_id)
+            .await?
+            .is_some_and(|snooze| snooze.is_active(now)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snooze_length_is_bounded() {
+        let now = Utc::now();
+
+        assert_eq!(Snooze::for_days(UserId(1), 0, now), Err(InvalidSnooze));
+        assert_eq!(
+            Snooze::for_days(UserId(1), MAX_SNOOZE_DAYS + 1, now),
+            Err(InvalidSnooze)
+        );
+        assert_eq!(
+            Snooze::for_days(UserId(1), 7, now).unwrap().until,
+            now + TimeDelta::days(7)
+        );
+    }
+
+    #[test]
+    fn snooze_expires_at_until() {
+        let now = Utc::now();
+        let snooze = Snooze::for_days(UserId(1), 1, now).unwrap();
+
+        assert!(snooze.is_active(now));
+        assert!(!snooze.is_active(snooze.until));
+    }
+}
@@ -0,0 +1,251 @@
+use std::collections::HashSet;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use bson::oid::ObjectId;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    id::{GuildId, UserId},
+    participation::participation_ratio,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sprint {
+    #[serde(rename = "_id", default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub guild_id: GuildId,
+    /// The team running the sprint, `None` for the guild wide team.
+    pub team: Option<String>,
+    pub name: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    #[serde(default)]
+    pub goals: Vec<Goal>,
+}
+
+/// Something the team commits to deliver during the sprint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Goal {
+    /// Unique within the sprint, completion events refer to goals by title.
+    pub title: String,
+    pub points: u32,
+}
+
+/// Why a sprint can't be saved.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum InvalidSprint {
+    #[error("sprint ends on {end_date}, before it starts on {start_date}")]
+    EndsBeforeStart {
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    },
+    #[error("goal {0:?} is listed twice")]
+    DuplicateGoal(String),
+}
+
+/// What to do with a sprint covering the same dates as another of its team.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateSprints {
+    /// Save it and log a warning.
+    Warn,
+    #[default]
+    Reject,
+}
+
+impl Sprint {
+    /// Whether `date` falls within `[start_date, end_date]`.
+    pub fn is_active_on(&self, date: NaiveDate) -> bool {
+        self.start_date <= date && date <= self.end_date
+    }
+
+    /// The goal numbered `number` in `/sprint goal list`, from 1.
+    pub fn goal(&self, number: usize) -> Option<&Goal> {
+        number
+            .checked_sub(1)
+            .and_then(|index| self.goals.get(index))
+    }
+
+    /// Append `goal`, titles being unique within the sprint.
+    pub fn add_goal(&mut self, goal: Goal) -> Result<(), InvalidSprint> {
+        if self.goals.iter().any(|other| other.title == goal.title) {
+            return Err(InvalidSprint::DuplicateGoal(goal.title));
+        }
+
+        self.goals.push(goal);
+        Ok(())
+    }
+
+    /// Check what the types don't, points can't be negative already.
+    pub fn validate(&self) -> Result<(), InvalidSprint> {
+        if self.end_date < self.start_date {
+            return Err(InvalidSprint::EndsBeforeStart {
+                start_date: self.start_date,
+                end_date: self.end_date,
+            });
+        }
+
+        let mut titles = HashSet::new();
+        if let Some(goal) = self.goals.iter().find(|goal| !titles.insert(&goal.title)) {
+            return Err(InvalidSprint::DuplicateGoal(goal.title.clone()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Where a running sprint stands on a day, for `/sprint status`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SprintStatus {
+    /// Days left after `today`, 0 on the last day of the sprint.
+    pub days_remaining: u64,
+    pub goals_done: usize,
+    pub goals_total: usize,
+    /// Members of the roster who answered the standup of `today`.
+    pub answered: usize,
+    pub roster: usize,
+}
+
+impl SprintStatus {
+    /// `done` are the titles of the completed goals and `participants` who
+    /// answered the standup, only the roster members among them count.
+    pub fn new(
+        sprint: &Sprint,
+        today: NaiveDate,
+        done: &HashSet<String>,
+        roster: &[UserId],
+        participants: &HashSet<UserId>,
+    ) -> Self {
+        Self {
+            days_remaining: (sprint.end_date - today).num_days().max(0).unsigned_abs(),
+            goals_done: sprint
+                .goals
+                .iter()
+                .filter(|goal| done.contains(&goal.title))
+                .count(),
+            goals_total: sprint.goals.len(),
+            answered: roster
+                .iter()
+                .filter(|user_id| participants.contains(user_id))
+                .count(),
+            roster: roster.len(),
+        }
+    }
+
+    /// The share of the roster that answered, see [`participation_ratio`].
+    pub fn participation(&self) -> f64 {
+        participation_ratio(self.answered, self.roster)
+    }
+}
+
+#[async_trait]
+pub trait SprintRepository: Send + Sync {
+    async fn insert(&self, sprint: &Sprint) -> Result<ObjectId>;
+    /// Create or replace the sprint with the id of `sprint`, which must have one.
+    async fn upsert(&self, sprint: &Sprint) -> Result<()>;
+    async fn find(&self, id: ObjectId) -> Result<Option<Sprint>>;
+    /// Append `goal` to the sprint in a single write, `false` when the sprint
+    /// already has a goal with its title or is gone.
+    async fn add_goal(&self, id: ObjectId, goal: &Goal) -> Result<bool>;
+    /// Another sprint of the guild team with the same dates as `sprint`.
+    async fn find_same_dates(&self, sprint: &Sprint) -> Result<Option<Sprint>>;
+    /// The sprint of the guild team running on `date`, preferring the latest
+    /// start when sprints overlap.
+    async fn find_active(
+        &self,
+        guild_id: GuildId,
+        team: Option<&str>,
+        date: NaiveDate,
+    ) -> Result<Option<Sprint>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sprint() -> Sprint {
+        Sprint {
+            id: None,
+            guild_id: GuildId(1),
+            team: None,
+            name: "Sprint 1".into(),
+            start_date: NaiveDate::from_ymd_opt(2024, 10, 14).unwrap(),
+            end_date: NaiveDate::from_ymd_opt(2024, 10, 25).unwrap(),
+            goals: vec![],
+        }
+    }
+
+    fn goal(title: &str) -> Goal {
+        Goal {
+            title: title.into(),
+            points: 3,
+        }
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 10, day).unwrap()
+    }
+
+    #[test]
+    fn status_sums_up_goals_and_participation() {
+        let mut sprint = sprint();
+        for title in ["scheduler", "dashboard", "metrics"] {
+            sprint.add_goal(goal(title)).unwrap();
+        }
+        let done = HashSet::from(["dashboard".to_owned(), "dropped".to_owned()]);
+        let roster = [UserId(3), UserId(4), UserId(5), UserId(6)];
+        // UserId(9) answered but is not on the roster.
+        let participants = HashSet::from([UserId(3), UserId(5), UserId(9)]);
+
+        let status = SprintStatus::new(&sprint, date(21), &done, &roster, &participants);
+
+        assert_eq!(
+            status,
+            SprintStatus {
+                days_remaining: 4,
+                goals_done: 1,
+                goals_total: 3,
+                answered: 2,
+                roster: 4,
+            }
+        );
+        assert_eq!(status.participation(), 0.5);
+    }
+
+    #[test]
+    fn no_days_remain_from_the_last_day_on() {
+        let nobody = HashSet::new();
+
+        for day in [25, 28] {
+            let status = SprintStatus::new(&sprint(), date(day), &HashSet::new(), &[], &nobody);
+            assert_eq!(status.days_remaining, 0);
+            assert_eq!(status.participation(), 0.0);
+        }
+    }
+
+    #[test]
+    fn added_goals_are_numbered_from_one() {
+        let mut sprint = sprint();
+        sprint.add_goal(goal("scheduler")).unwrap();
+        sprint.add_goal(goal("dashboard")).unwrap();
+
+        assert_eq!(sprint.goal(1), Some(&goal("scheduler")));
+        assert_eq!(sprint.goal(2), Some(&goal("dashboard")));
+        assert_eq!(sprint.goal(0), None);
+        assert_eq!(sprint.goal(3), None);
+    }
+
+    #[test]
+    fn goal_titles_are_unique() {
+        let mut sprint = sprint();
+        sprint.add_goal(goal("scheduler")).unwrap();
+
+        assert_eq!(
+            sprint.add_goal(goal("scheduler")),
+            Err(InvalidSprint::DuplicateGoal("scheduler".into()))
+        );
+        assert_eq!(sprint.goals.len(), 1);
+    }
+}
@@ -0,0 +1,775 @@
+use std::{collections::HashSet, sync::Arc};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use bson::oid::ObjectId;
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use super::{
+    id::{ChannelId, GuildId, UserId},
+    kickoff::KickoffTracker,
+    participation::ParticipationTracker,
+    sprint::SprintRepository,
+    template::{self, TemplateError},
+};
+
+/// The placeholders a standup prompt can use.
+pub const STANDUP_PROMPT_PLACEHOLDERS: [&str; 2] = ["channel", "date"];
+
+/// The message that opens the standup of a channel, e.g.
+/// `Standup time in {channel} for {date}!`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StandupPrompt(String);
+
+impl StandupPrompt {
+    pub fn new(template: impl Into<String>) -> Result<Self, TemplateError> {
+        let template = template.into();
+        template::check(&template, &STANDUP_PROMPT_PLACEHOLDERS)?;
+
+        Ok(Self(template))
+    }
+
+    pub fn render(&self, channel_id: ChannelId, date: NaiveDate) -> String {
+        let channel = format!("<#{}>", channel_id);
+        let date = date.to_string();
+
+        template::render(&self.0, &[("channel", &channel), ("date", &date)])
+            .expect("placeholders are checked by StandupPrompt::new")
+    }
+}
+
+/// The answers of a member to the daily standup.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StandupEntry {
+    #[serde(rename = "_id", default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub guild_id: GuildId,
+    pub channel_id: ChannelId,
+    pub user_id: UserId,
+    /// The team the member answered for, `None` for the guild wide team.
+    pub team: Option<String>,
+    pub date: NaiveDate,
+    pub yesterday: String,
+    pub today: String,
+    pub blockers: String,
+    /// The sprint running on `date` when the entry was saved, if any.
+    pub sprint_id: Option<ObjectId>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Longest answer of a standup, in characters, so it fits an embed field.
+pub const MAX_ANSWER_LENGTH: usize = 1000;
+
+/// Why a standup was turned away before being saved.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum InvalidStandup {
+    #[error("neither yesterday nor today was answered")]
+    Empty,
+    /// `field` is the name of the [`StandupEntry`] answer.
+    #[error("{field} is shorter than {min} characters")]
+    TooShort { field: &'static str, min: usize },
+    #[error("{field} is longer than {max} characters")]
+    TooLong { field: &'static str, max: usize },
+}
+
+/// What a standup must look like to be saved.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StandupRules {
+    /// Shortest `yesterday` or `today` answer, when answered.
+    pub min_length: usize,
+}
+
+impl StandupRules {
+    pub fn new(min_length: usize) -> Self {
+        Self { min_length }
+    }
+
+    /// Trim the answers of `entry` and check them.
+    ///
+    /// At least one of `yesterday` and `today` must be answered, blockers are
+    /// optional and only bounded by [`MAX_ANSWER_LENGTH`].
+    pub fn apply(&self, entry: &mut StandupEntry) -> Result<(), InvalidStandup> {
+        for answer in [&mut entry.yesterday, &mut entry.today, &mut entry.blockers] {
+            *answer = answer.trim().to_owned();
+        }
+
+        if entry.yesterday.is_empty() && entry.today.is_empty() {
+            return Err(InvalidStandup::Empty);
+        }
+
+        let answers = [
+            ("yesterday", &entry.yesterday, true),
+            ("today", &entry.today, true),
+            ("blockers", &entry.blockers, false),
+        ];
+        for (field, answer, bounded_below) in answers {
+            let length = answer.chars().count();
+            if length > MAX_ANSWER_LENGTH {
+                return Err(InvalidStandup::TooLong {
+                    field,
+                    max: MAX_ANSWER_LENGTH,
+                });
+            }
+            if bounded_below && length > 0 && length < self.min_length {
+                return Err(InvalidStandup::TooShort {
+                    field,
+                    min: self.min_length,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Longest range `/standup history` looks back on.
+pub const MAX_HISTORY_DAYS: u32 = 30;
+/// Entries shown per page of `/standup history`.
+pub const HISTORY_PAGE_SIZE: usize = 5;
+
+/// A page of the standups of a member in a guild, newest first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryQuery {
+    pub guild_id: GuildId,
+    pub user_id: UserId,
+    /// The first date of the range, inclusive.
+    pub from: NaiveDate,
+    /// The last date of the range, inclusive.
+    pub to: NaiveDate,
+    pub skip: usize,
+    pub limit: usize,
+}
+
+impl HistoryQuery {
+    /// The `page`, from 1, of the last `days` up to `today`, at most
+    /// [`MAX_HISTORY_DAYS`] of them.
+    pub fn last_days(
+        guild_id: GuildId,
+        user_id: UserId,
+        today: NaiveDate,
+        days: u32,
+        page: usize,
+    ) -> Self {
+        let days = days.clamp(1, MAX_HISTORY_DAYS);
+
+        Self {
+            guild_id,
+            user_id,
+            from: today - Days::new(u64::from(days - 1)),
+            to: today,
+            skip: page.saturating_sub(1) * HISTORY_PAGE_SIZE,
+            limit: HISTORY_PAGE_SIZE,
+        }
+    }
+}
+
+/// The entries of a [`HistoryQuery`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryPage {
+    pub entries: Vec<StandupEntry>,
+    /// Whether later pages have entries.
+    pub has_more: bool,
+}
+
+/// The entry stored by [`StandupRepository::upsert`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Upserted {
+    pub entry: StandupEntry,
+    /// Whether no entry existed for the member, channel and date.
+    pub created: bool,
+}
+
+#[async_trait]
+pub trait StandupRepository: Send + Sync {
+    /// Fails with a [`Conflict`](super::conflict::Conflict) when the member
+    /// already has an entry for the channel and date.
+    async fn insert(&self, entry: &StandupEntry) -> Result<ObjectId>;
+    async fn find(&self, id: ObjectId) -> Result<Option<StandupEntry>>;
+    /// Replace the answers of the entry with the same guild, user, channel and date,
+    /// inserting it when there is none. `created_at` is kept on updates.
+    async fn upsert(&self, entry: &StandupEntry) -> Result<Upserted>;
+    /// The entries associated with the sprint, oldest first.
+    async fn list_for_sprint(&self, sprint_id: ObjectId) -> Result<Vec<StandupEntry>>;
+    /// The earliest created entry associated with the sprint.
+    async fn first_for_sprint(&self, sprint_id: ObjectId) -> Result<Option<StandupEntry>>;
+    /// The members of the guild who answered the standup of `date`.
+    async fn participants(&self, guild_id: GuildId, date: NaiveDate) -> Result<HashSet<UserId>>;
+    /// Delete at most `limit` entries created before `cutoff`, returning how
+    /// many were deleted.
+    async fn delete_created_before(&self, cutoff: DateTime<Utc>, limit: usize) -> Result<u64>;
+    /// The entries of the query, newest date first.
+    async fn history(&self, query: &HistoryQuery) -> Result<Vec<StandupEntry>>;
+    /// The non empty blockers of the guild standups from `from` to `to`,
+    /// both inclusive.
+    async fn blockers_between(
+        &self,
+        guild_id: GuildId,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<String>>;
+}
+
+/// Where the live listeners of the [`StandupFeed`] are measured.
+pub trait FeedRecorder: Send + Sync {
+    /// A listener fell behind and skipped `skipped` entries.
+    fn lagged(&self, skipped: u64);
+    fn listener_opened(&self);
+    fn listener_closed(&self);
+}
+
+/// Entries saved by the [`StandupService`], for live listeners such as a
+/// dashboard.
+///
+/// Listeners that fall more than the capacity behind skip the oldest entries.
+#[derive(Clone)]
+pub struct StandupFeed {
+    sender: broadcast::Sender<StandupEntry>,
+    recorder: Option<Arc<dyn FeedRecorder>>,
+}
+
+impl StandupFeed {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            sender: broadcast::channel(capacity).0,
+            recorder: None,
+        }
+    }
+
+    /// Count the skipped entries and the live [`listen`](Self::listen)
+    /// subscriptions.
+    pub fn with_recorder(mut self, recorder: Arc<dyn FeedRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    pub fn publish(&self, entry: &StandupEntry) {
+        // Nobody listening is fine.
+        let _ = self.sender.send(entry.clone());
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<StandupEntry> {
+        self.sender.subscribe()
+    }
+
+    /// A subscription that skips past the entries it lagged behind on,
+    /// counting them.
+    pub fn listen(&self) -> FeedListener {
+        if let Some(recorder) = &self.recorder {
+            recorder.listener_opened();
+        }
+
+        FeedListener {
+            receiver: self.subscribe(),
+            recorder: self.recorder.clone(),
+        }
+    }
+
+    pub fn listeners(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+/// See [`StandupFeed::listen`].
+pub struct FeedListener {
+    receiver: broadcast::Receiver<StandupEntry>,
+    recorder: Option<Arc<dyn FeedRecorder>>,
+}
+
+impl FeedListener {
+    /// The next entry, `None` once the feed is gone.
+    pub async fn recv(&mut self) -> Option<StandupEntry> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(entry) => return Some(entry),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "standup feed listener lagged behind");
+                    if let Some(recorder) = &self.recorder {
+                        recorder.lagged(skipped);
+                    }
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+impl Drop for FeedListener {
+    fn drop(&mut self) {
+        if let Some(recorder) = &self.recorder {
+            recorder.listener_closed();
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct StandupService {
+    standups: Arc<dyn StandupRepository>,
+    sprints: Arc<dyn SprintRepository>,
+    participation: Option<ParticipationTracker>,
+    kickoff: Option<KickoffTracker>,
+    feed: Option<StandupFeed>,
+}
+
+impl StandupService {
+    pub fn new(standups: Arc<dyn StandupRepository>, sprints: Arc<dyn SprintRepository>) -> Self {
+        Self {
+            standups,
+            sprints,
+            participation: None,
+            kickoff: None,
+            feed: None,
+        }
+    }
+
+    /// Refresh the participation of the day after each submission.
+    pub fn with_participation(mut self, participation: ParticipationTracker) -> Self {
+        self.participation = Some(participation);
+        self
+    }
+
+    /// Observe how long the sprint took to get its first standup.
+    pub fn with_kickoff(mut self, kickoff: KickoffTracker) -> Self {
+        self.kickoff = Some(kickoff);
+        self
+    }
+
+    /// Publish every submitted or edited entry.
+    pub fn with_feed(mut self, feed: StandupFeed) -> Self {
+        self.feed = Some(feed);
+        self
+    }
+
+    /// Save the entry, associating it with the team sprint active on its date.
+    #[tracing::instrument(name = "Submit standup", skip(self, entry), fields(guild_id = %entry.guild_id, user_id = %entry.user_id))]
+    pub async fn submit(&self, mut entry: StandupEntry) -> Result<StandupEntry> {
+        entry.sprint_id = self.active_sprint(&entry).await?;
+
+        let id = self.standups.insert(&entry).await?;
+        entry.id = Some(id);
+
+        if let Some(participation) = &self.participation {
+            participation.refresh(entry.guild_id, entry.date).await;
+        }
+        if let Some(kickoff) = &self.kickoff {
+            kickoff.record(&entry).await;
+        }
+        if let Some(feed) = &self.feed {
+            feed.publish(&entry);
+        }
+
+        Ok(entry)
+    }
+
+    /// Correct the entry of the member for the channel and date, or create it.
+    #[tracing::instrument(name = "Edit standup", skip(self, entry), fields(guild_id = %entry.guild_id, user_id = %entry.user_id))]
+    pub async fn edit(&self, mut entry: StandupEntry) -> Result<Upserted> {
+        entry.sprint_id = self.active_sprint(&entry).await?;
+
+        let upserted = self.standups.upsert(&entry).await?;
+
+        if upserted.created {
+            if let Some(participation) = &self.participation {
+                participation.refresh(entry.guild_id, entry.date).await;
+            }
+            if let Some(kickoff) = &self.kickoff {
+                kickoff.record(&upserted.entry).await;
+            }
+        }
+        if let Some(feed) = &self.feed {
+            feed.publish(&upserted.entry);
+        }
+
+        Ok(upserted)
+    }
+
+    /// Whether the member already has an entry in the channel on `date`, the
+    /// one an [`edit`](Self::edit) would replace.
+    pub async fn has_submitted(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        channel_id: ChannelId,
+        date: NaiveDate,
+    ) -> Result<bool> {
+        let mut query = HistoryQuery {
+            guild_id,
+            user_id,
+            from: date,
+            to: date,
+            skip: 0,
+            limit: HISTORY_PAGE_SIZE,
+        };
+        loop {
+            let page = self.history(&query).await?;
+            if page
+                .entries
+                .iter()
+                .any(|entry| entry.channel_id == channel_id)
+            {
+                return Ok(true);
+            }
+            if !page.has_more {
+                return Ok(false);
+            }
+            query.skip += query.limit;
+        }
+    }
+
+    /// The entries of the member in the range of `query`.
+    #[tracing::instrument(name = "Standup history", skip(self))]
+    pub async fn history(&self, query: &HistoryQuery) -> Result<HistoryPage> {
+        // One more entry tells whether there is a next page.
+        let mut entries = self
+            .standups
+            .history(&HistoryQuery {
+                limit: query.limit + 1,
+                ..query.clone()
+            })
+            .await?;
+        let has_more = entries.len() > query.limit;
+        entries.truncate(query.limit);
+
+        Ok(HistoryPage { entries, has_more })
+    }
+
+    async fn active_sprint(&self, entry: &StandupEntry) -> Result<Option<ObjectId>> {
+        let sprint = self
+            .sprints
+            .find_active(entry.guild_id, entry.team.as_deref(), entry.date)
+            .await?;
+
+        Ok(sprint.and_then(|sprint| sprint.id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+
+    use crate::{
+        domain::sprint::Sprint,
+        drivers::database::memory::{InMemorySprintRepository, InMemoryStandupRepository},
+    };
+
+    use super::*;
+
+    fn answers(yesterday: &str, today: &str) -> StandupEntry {
+        StandupEntry {
+            yesterday: yesterday.into(),
+            today: today.into(),
+            blockers: "  waiting on review \n".into(),
+            ..entry(None, 15)
+        }
+    }
+
+    #[test]
+    fn valid_answers_are_trimmed() {
+        let mut entry = answers("  reviewed PRs ", "");
+
+        StandupRules::new(5).apply(&mut entry).unwrap();
+
+        assert_eq!(entry.yesterday, "reviewed PRs");
+        assert_eq!(entry.today, "");
+        assert_eq!(entry.blockers, "waiting on review");
+    }
+
+    #[test]
+    fn blank_answers_are_empty() {
+        let mut entry = answers(" ", "\n\t");
+
+        assert_eq!(
+            StandupRules::default().apply(&mut entry),
+            Err(InvalidStandup::Empty)
+        );
+    }
+
+    #[test]
+    fn short_answers_are_refused() {
+        let mut entry = answers("ok", "scheduler");
+
+        assert_eq!(
+            StandupRules::new(5).apply(&mut entry),
+            Err(InvalidStandup::TooShort {
+                field: "yesterday",
+                min: 5
+            })
+        );
+    }
+
+    #[test]
+    fn long_answers_are_refused() {
+        let mut entry = answers("reviewed PRs", &"é".repeat(MAX_ANSWER_LENGTH + 1));
+
+        assert_eq!(
+            StandupRules::default().apply(&mut entry),
+            Err(InvalidStandup::TooLong {
+                field: "today",
+                max: MAX_ANSWER_LENGTH
+            })
+        );
+
+        let mut entry = answers("reviewed PRs", &"é".repeat(MAX_ANSWER_LENGTH));
+        assert!(StandupRules::default().apply(&mut entry).is_ok());
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 10, day).unwrap()
+    }
+
+    fn entry(team: Option<&str>, day: u32) -> StandupEntry {
+        StandupEntry {
+            id: None,
+            guild_id: GuildId(1),
+            channel_id: ChannelId(2),
+            user_id: UserId(3),
+            team: team.map(Into::into),
+            date: date(day),
+            yesterday: "reviewed PRs".into(),
+            today: "write the scheduler".into(),
+            blockers: String::new(),
+            sprint_id: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn sprint(team: Option<&str>, start: u32, end: u32) -> Sprint {
+        Sprint {
+            id: None,
+            guild_id: GuildId(1),
+            team: team.map(Into::into),
+            name: "Sprint 1".into(),
+            start_date: date(start),
+            end_date: date(end),
+            goals: vec![],
+        }
+    }
+
+    async fn service(sprints: &[Sprint]) -> (StandupService, Vec<ObjectId>) {
+        let repository = Arc::new(InMemorySprintRepository::default());
+        let mut ids = Vec::new();
+        for sprint in sprints {
+            ids.push(repository.insert(sprint).await.unwrap());
+        }
+
+        let service =
+            StandupService::new(Arc::new(InMemoryStandupRepository::default()), repository);
+
+        (service, ids)
+    }
+
+    #[tokio::test]
+    async fn standup_is_associated_with_the_active_sprint() {
+        let (service, ids) = service(&[sprint(None, 1, 14)]).await;
+
+        let saved = service.submit(entry(None, 7)).await.unwrap();
+
+        assert!(saved.id.is_some());
+        assert_eq!(saved.sprint_id, Some(ids[0]));
+    }
+
+    #[tokio::test]
+    async fn standup_without_active_sprint_has_no_sprint() {
+        let (service, _) = service(&[sprint(None, 1, 14)]).await;
+
+        let saved = service.submit(entry(None, 20)).await.unwrap();
+
+        assert!(saved.id.is_some());
+        assert_eq!(saved.sprint_id, None);
+    }
+
+    #[tokio::test]
+    async fn standups_only_see_the_sprint_of_their_team() {
+        let (service, ids) = service(&[
+            sprint(Some("backend"), 1, 14),
+            sprint(Some("frontend"), 5, 18),
+        ])
+        .await;
+
+        // Each team answers in its own channel.
+        let in_channel = |team, day, channel_id| StandupEntry {
+            channel_id: ChannelId(channel_id),
+            ..entry(team, day)
+        };
+        let backend = service.submit(entry(Some("backend"), 7)).await.unwrap();
+        let frontend = service
+            .submit(in_channel(Some("frontend"), 7, 4))
+            .await
+            .unwrap();
+        let late_backend = service.submit(entry(Some("backend"), 16)).await.unwrap();
+        let guild_wide = service.submit(in_channel(None, 7, 5)).await.unwrap();
+
+        assert_eq!(backend.sprint_id, Some(ids[0]));
+        assert_eq!(frontend.sprint_id, Some(ids[1]));
+        assert_eq!(late_backend.sprint_id, None);
+        assert_eq!(guild_wide.sprint_id, None);
+    }
+
+    #[tokio::test]
+    async fn edit_replaces_the_existing_entry_of_the_day() {
+        let (service, ids) = service(&[sprint(None, 1, 14)]).await;
+        let original = service.submit(entry(None, 7)).await.unwrap();
+
+        let mut correction = entry(None, 7);
+        correction.today = "write the scheduler tests".into();
+        correction.created_at = original.created_at + chrono::TimeDelta::hours(1);
+        let edited = service.edit(correction).await.unwrap();
+
+        assert!(!edited.created);
+        assert_eq!(edited.entry.id, original.id);
+        assert_eq!(edited.entry.today, "write the scheduler tests");
+        assert_eq!(edited.entry.created_at, original.created_at);
+        assert_eq!(edited.entry.sprint_id, Some(ids[0]));
+    }
+
+    #[tokio::test]
+    async fn edit_without_an_entry_creates_one() {
+        let (service, _) = service(&[]).await;
+        let original = service.submit(entry(None, 7)).await.unwrap();
+
+        let mut other_channel = entry(None, 7);
+        other_channel.channel_id = ChannelId(20);
+        let created = service.edit(other_channel).await.unwrap();
+        let next_day = service.edit(entry(None, 8)).await.unwrap();
+
+        assert!(created.created);
+        assert!(next_day.created);
+        assert!(created.entry.id.is_some());
+        assert_ne!(created.entry.id, original.id);
+        assert_ne!(next_day.entry.id, original.id);
+    }
+
+    #[test]
+    fn history_covers_the_last_days_up_to_today() {
+        let query = HistoryQuery::last_days(GuildId(1), UserId(3), date(15), 7, 2);
+
+        assert_eq!((query.from, query.to), (date(9), date(15)));
+        assert_eq!(
+            (query.skip, query.limit),
+            (HISTORY_PAGE_SIZE, HISTORY_PAGE_SIZE)
+        );
+    }
+
+    #[test]
+    fn history_range_is_capped() {
+        let today = date(31);
+
+        let longest = HistoryQuery::last_days(GuildId(1), UserId(3), today, 365, 0);
+        assert_eq!(longest.from, date(2));
+        assert_eq!(longest.skip, 0);
+
+        let shortest = HistoryQuery::last_days(GuildId(1), UserId(3), today, 0, 1);
+        assert_eq!(shortest.from, today);
+    }
+
+    #[tokio::test]
+    async fn history_pages_through_the_entries_of_the_member() {
+        let standups = Arc::new(InMemoryStandupRepository::default());
+        for day in 1..=7 {
+            standups.insert(&entry(None, day)).await.unwrap();
+        }
+        let mut other = entry(None, 7);
+        other.user_id = UserId(4);
+        standups.insert(&other).await.unwrap();
+        let service = StandupService::new(standups, Arc::new(InMemorySprintRepository::default()));
+
+        let first = service
+            .history(&HistoryQuery::last_days(
+                GuildId(1),
+                UserId(3),
+                date(7),
+                7,
+                1,
+            ))
+            .await
+            .unwrap();
+        let second = service
+            .history(&HistoryQuery::last_days(
+                GuildId(1),
+                UserId(3),
+                date(7),
+                7,
+                2,
+            ))
+            .await
+            .unwrap();
+
+        let dates = |page: &HistoryPage| page.entries.iter().map(|e| e.date).collect::<Vec<_>>();
+        assert_eq!(dates(&first), (3..=7).rev().map(date).collect::<Vec<_>>());
+        assert!(first.has_more);
+        assert_eq!(dates(&second), vec![date(2), date(1)]);
+        assert!(!second.has_more);
+    }
+
+    #[derive(Default)]
+    struct Lag {
+        skipped: AtomicU64,
+        listeners: AtomicI64,
+    }
+
+    impl FeedRecorder for Lag {
+        fn lagged(&self, skipped: u64) {
+            self.skipped.fetch_add(skipped, Ordering::Relaxed);
+        }
+
+        fn listener_opened(&self) {
+            self.listeners.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn listener_closed(&self) {
+            self.listeners.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    #[tokio::test]
+    async fn lagging_listeners_skip_and_count_the_overflow() {
+        let lag = Arc::new(Lag::default());
+        let feed = StandupFeed::new(2).with_recorder(lag.clone());
+        let mut listener = feed.listen();
+        assert_eq!(lag.listeners.load(Ordering::Relaxed), 1);
+
+        for day in 1..=5 {
+            feed.publish(&entry(None, day));
+        }
+
+        assert_eq!(listener.recv().await.unwrap().date, date(4));
+        assert_eq!(listener.recv().await.unwrap().date, date(5));
+        assert_eq!(lag.skipped.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn dropped_listeners_leave_the_count() {
+        let lag = Arc::new(Lag::default());
+        let feed = StandupFeed::new(2).with_recorder(lag.clone());
+
+        let first = feed.listen();
+        let second = feed.listen();
+        assert_eq!(lag.listeners.load(Ordering::Relaxed), 2);
+
+        drop(first);
+        assert_eq!(lag.listeners.load(Ordering::Relaxed), 1);
+        drop(second);
+        assert_eq!(lag.listeners.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn prompt_renders_channel_and_date() {
+        let prompt =
+            StandupPrompt::new("Hora da daily em {channel}, {date}! {{sem pressa}}").unwrap();
+
+        assert_eq!(
+            prompt.render(ChannelId(2), date(15)),
+            "Hora da daily em <#2>, 2024-10-15! {sem pressa}"
+        );
+    }
+
+    #[test]
+    fn prompt_rejects_unknown_placeholders() {
+        assert_eq!(
+            StandupPrompt::new("Standup for {team}"),
+            Err(TemplateError::UnknownPlaceholder("team".into()))
+        );
+    }
+}
@@ -0,0 +1,101 @@
+/// A `{name}` placeholder with no value or an unterminated `{`.
+///
+/// Literal braces are written `{{` and `}}`.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TemplateError {
+    #[error("unknown placeholder `{{{0}}}`")]
+    UnknownPlaceholder(String),
+    #[error("unterminated `{{` at byte {0}")]
+    Unterminated(usize),
+}
+
+/// Substitute every `{name}` of `template` with its value in `values`.
+pub fn render(template: &str, values: &[(&str, &str)]) -> Result<String, TemplateError> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find(['{', '}']) {
+        rendered.push_str(&rest[..start]);
+        let brace = &rest[start..];
+
+        if brace.starts_with("{{") || brace.starts_with("}}") {
+            rendered.push_str(&brace[..1]);
+            rest = &brace[2..];
+            continue;
+        }
+        if let Some(after) = brace.strip_prefix('}') {
+            rendered.push('}');
+            rest = after;
+            continue;
+        }
+
+        let Some(end) = brace.find('}') else {
+            return Err(TemplateError::Unterminated(template.len() - brace.len()));
+        };
+        let name = &brace[1..end];
+        let value = values
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| *value)
+            .ok_or_else(|| TemplateError::UnknownPlaceholder(name.to_owned()))?;
+        rendered.push_str(value);
+        rest = &brace[end + 1..];
+    }
+
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+/// Check that `template` only uses `placeholders`, before anything is rendered.
+pub fn check(template: &str, placeholders: &[&str]) -> Result<(), TemplateError> {
+    let values: Vec<_> = placeholders.iter().map(|name| (*name, "")).collect();
+
+    render(template, &values).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn substitutes_every_placeholder() {
+        let rendered = render(
+            "{greeting} {channel}, standup of {date} in {channel}",
+            &[
+                ("channel", "#backend"),
+                ("date", "2024-10-15"),
+                ("greeting", "Bom dia"),
+            ],
+        );
+
+        assert_eq!(
+            rendered.unwrap(),
+            "Bom dia #backend, standup of 2024-10-15 in #backend"
+        );
+    }
+
+    #[test]
+    fn doubled_braces_are_literal() {
+        assert_eq!(
+            render("{{date}} is {date} }}", &[("date", "today")]).unwrap(),
+            "{date} is today }"
+        );
+        assert_eq!(render("no placeholders", &[]).unwrap(), "no placeholders");
+    }
+
+    #[test]
+    fn reports_placeholders_without_a_value() {
+        assert_eq!(
+            render("standup in {chanel}", &[("channel", "#backend")]),
+            Err(TemplateError::UnknownPlaceholder("chanel".into()))
+        );
+        assert_eq!(
+            render("standup in {channel", &[("channel", "#backend")]),
+            Err(TemplateError::Unterminated(11))
+        );
+        assert_eq!(
+            check("{channel} on {date}", &["channel"]),
+            Err(TemplateError::UnknownPlaceholder("date".into()))
+        );
+    }
+}
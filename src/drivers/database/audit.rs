@@ -0,0 +1,72 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use bson::{doc, Document};
+use futures::TryStreamExt;
+use mongodb::{Collection, Database};
+
+use crate::domain::audit::{AuditEntry, AuditQuery, AuditRepository};
+
+use super::WriteRetry;
+
+pub const AUDIT_LOG_COLLECTION: &str = "audit_log";
+
+#[derive(Clone)]
+pub struct MongoAuditRepository {
+    collection: Collection<AuditEntry>,
+    retry: WriteRetry,
+}
+
+impl MongoAuditRepository {
+    pub fn new(database: &Database) -> Self {
+        Self {
+            collection: database.collection(AUDIT_LOG_COLLECTION),
+            retry: WriteRetry::default(),
+        }
+    }
+
+    pub fn with_retry(mut self, retry: WriteRetry) -> Self {
+        self.retry = retry;
+        self
+    }
+}
+
+fn query_filter(query: &AuditQuery) -> Result<Document> {
+    let mut filter = Document::new();
+
+    if let Some(guild_id) = query.guild_id {
+        filter.insert("guild_id", guild_id);
+    }
+    if let Some(actor) = &query.actor {
+        filter.insert("actor", actor);
+    }
+    if let Some(action) = query.action {
+        filter.insert("action", action.as_str());
+    }
+
+    Ok(filter)
+}
+
+#[async_trait]
+impl AuditRepository for MongoAuditRepository {
+    #[tracing::instrument(name = "Insert audit entry", skip(self, entry))]
+    async fn insert(&self, entry: &AuditEntry) -> Result<()> {
+        self.retry
+            .insert("expected to insert audit entry", &self.collection, entry)
+            .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "List audit entries", skip(self))]
+    async fn list(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>> {
+        self.collection
+            .find(query_filter(query)?)
+            .sort(doc! { "timestamp": -1 })
+            .limit(i64::from(query.limit()))
+            .await
+            .context("expected to query audit entries")?
+            .try_collect()
+            .await
+            .context("expected to collect audit entries")
+    }
+}
@@ -0,0 +1,347 @@
+use std::{
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+
+use crate::configuration::BreakerSettings;
+
+use super::is_unavailable;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    /// Operations reach the database.
+    Closed,
+    /// Operations fail fast until the cooldown is over.
+    Open,
+    /// The cooldown is over, a single trial operation decides whether to
+    /// close or open again, the others fail fast meanwhile.
+    HalfOpen,
+}
+
+impl BreakerState {
+    /// The value published by the state gauge.
+    pub fn as_i64(&self) -> i64 {
+        match self {
+            BreakerState::Closed => 0,
+            BreakerState::HalfOpen => 1,
+            BreakerState::Open => 2,
+        }
+    }
+}
+
+/// Where the state of the breaker is published.
+pub trait BreakerGauge: Send + Sync {
+    fn record(&self, state: BreakerState);
+}
+
+/// Returned instead of running an operation while the breaker is open.
+#[derive(Debug, thiserror::Error)]
+#[error("database circuit breaker is open")]
+pub struct BreakerOpen;
+
+#[derive(Debug)]
+struct Circuit {
+    state: BreakerState,
+    failures: u32,
+    opened_at: Instant,
+    /// Whether the trial of the half-open state hasn't settled yet.
+    in_flight_trial: bool,
+}
+
+/// Held by the trial operation, frees the half-open state for another trial
+/// when dropped, even if the operation was cancelled.
+struct Trial(Arc<Mutex<Circuit>>);
+
+impl Drop for Trial {
+    fn drop(&mut self) {
+        self.0.lock().unwrap().in_flight_trial = false;
+    }
+}
+
+/// Stops sending operations to an unreachable database for a while.
+///
+/// Only errors telling the database is unreachable count as failures, a query
+/// the database answered with an error is proof that it is up.
+#[derive(Clone)]
+pub struct CircuitBreaker {
+    circuit: Arc<Mutex<Circuit>>,
+    failure_threshold: u32,
+    cooldown: Duration,
+    gauge: Option<Arc<dyn BreakerGauge>>,
+}
+
+impl CircuitBreaker {
+    /// Open after `failure_threshold` consecutive failures, for `cooldown`.
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            circuit: Arc::new(Mutex::new(Circuit {
+                state: BreakerState::Closed,
+                failures: 0,
+                opened_at: Instant::now(),
+                in_flight_trial: false,
+            })),
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            gauge: None,
+        }
+    }
+
+    pub fn from_settings(settings: &BreakerSettings) -> Self {
+        Self::new(
+            settings.failure_threshold,
+            Duration::from_millis(settings.cooldown_ms),
+        )
+    }
+
+    pub fn with_gauge(mut self, gauge: Arc<dyn BreakerGauge>) -> Self {
+        gauge.record(self.state());
+        self.gauge = Some(gauge);
+        self
+    }
+
+    pub fn state(&self) -> BreakerState {
+        self.circuit.lock().unwrap().state
+    }
+
+    /// Run `operation` unless the breaker is open.
+    pub async fn call<T, F>(&self, operation: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        let trial = self.acquire(Instant::now())?;
+
+        let result = operation.await;
+        let healthy = result
+            .as_ref()
+            .map_or_else(|error| !is_unavailable(error), |_| true);
+        self.settle(healthy, Instant::now());
+        drop(trial);
+
+        result
+    }
+
+    /// Admit an operation, the [`Trial`] when it is the one of the half-open
+    /// state.
+    fn acquire(&self, now: Instant) -> Result<Option<Trial>, BreakerOpen> {
+        let mut circuit = self.circuit.lock().unwrap();
+
+        let trial = match circuit.state {
+            BreakerState::Closed => false,
+            BreakerState::HalfOpen if !circuit.in_flight_trial => true,
+            BreakerState::Open if now.duration_since(circuit.opened_at) >= self.cooldown => {
+                self.transition(&mut circuit, BreakerState::HalfOpen);
+                true
+            }
+            BreakerState::HalfOpen | BreakerState::Open => return Err(BreakerOpen),
+        };
+        circuit.in_flight_trial |= trial;
+
+        Ok(trial.then(|| Trial(self.circuit.clone())))
+    }
+
+    fn settle(&self, healthy: bool, now: Instant) {
+        let mut circuit = self.circuit.lock().unwrap();
+
+        if healthy {
+            circuit.failures = 0;
+            self.transition(&mut circuit, BreakerState::Closed);
+            return;
+        }
+
+        circuit.failures += 1;
+        let trips = match circuit.state {
+            BreakerState::Closed => circuit.failures >= self.failure_threshold,
+            BreakerState::HalfOpen => true,
+            // An operation started before the breaker opened.
+            BreakerState::Open => false,
+        };
+        if trips {
+            circuit.opened_at = now;
+            self.transition(&mut circuit, BreakerState::Open);
+        }
+    }
+
+    fn transition(&self, circuit: &mut Circuit, state: BreakerState) {
+        if circuit.state == state {
+            return;
+        }
+
+        tracing::warn!(from = ?circuit.state, to = ?state, "database circuit breaker changed state");
+        circuit.state = state;
+        if let Some(gauge) = &self.gauge {
+            gauge.record(state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use tokio::sync::{oneshot, Notify};
+
+    use super::*;
+
+    const COOLDOWN: Duration = Duration::from_secs(10);
+
+    #[derive(Default)]
+    struct Gauge(Mutex<Vec<BreakerState>>);
+
+    impl BreakerGauge for Gauge {
+        fn record(&self, state: BreakerState) {
+            self.0.lock().unwrap().push(state);
+        }
+    }
+
+    fn unreachable() -> anyhow::Error {
+        mongodb::error::Error::from(io::ErrorKind::TimedOut).into()
+    }
+
+    #[test]
+    fn goes_through_closed_open_half_open_closed() {
+        let gauge = Arc::new(Gauge::default());
+        let breaker = CircuitBreaker::new(3, COOLDOWN).with_gauge(gauge.clone());
+        let start = Instant::now();
+
+        for _ in 0..2 {
+            breaker.acquire(start).unwrap();
+            breaker.settle(false, start);
+        }
+        assert_eq!(breaker.state(), BreakerState::Closed);
+
+        breaker.acquire(start).unwrap();
+        breaker.settle(false, start);
+        assert_eq!(breaker.state(), BreakerState::Open);
+        assert!(breaker.acquire(start + COOLDOWN / 2).is_err());
+
+        breaker.acquire(start + COOLDOWN).unwrap();
+        assert_eq!(breaker.state(), BreakerState::HalfOpen);
+
+        breaker.settle(true, start + COOLDOWN);
+        assert_eq!(breaker.state(), BreakerState::Closed);
+
+        assert_eq!(
+            *gauge.0.lock().unwrap(),
+            vec![
+                BreakerState::Closed,
+                BreakerState::Open,
+                BreakerState::HalfOpen,
+                BreakerState::Closed,
+            ]
+        );
+    }
+
+    #[test]
+    fn failed_trial_opens_again_for_a_full_cooldown() {
+        let breaker = CircuitBreaker::new(1, COOLDOWN);
+        let start = Instant::now();
+        breaker.settle(false, start);
+
+        let trial = start + COOLDOWN;
+        breaker.acquire(trial).unwrap();
+        breaker.settle(false, trial);
+
+        assert_eq!(breaker.state(), BreakerState::Open);
+        assert!(breaker.acquire(trial + COOLDOWN / 2).is_err());
+        assert!(breaker.acquire(trial + COOLDOWN).is_ok());
+    }
+
+    #[tokio::test]
+    async fn half_open_lets_a_single_trial_through() {
+        let breaker = CircuitBreaker::new(1, Duration::ZERO);
+        breaker.settle(false, Instant::now());
+        assert_eq!(breaker.state(), BreakerState::Open);
+
+        let started = Arc::new(Notify::new());
+        let (finish, finished) = oneshot::channel::<()>();
+        let trial = tokio::spawn({
+            let breaker = breaker.clone();
+            let started = started.clone();
+            async move {
+                breaker
+                    .call(async {
+                        started.notify_one();
+                        finished.await.unwrap();
+                        Ok(())
+                    })
+                    .await
+            }
+        });
+        started.notified().await;
+        assert_eq!(breaker.state(), BreakerState::HalfOpen);
+
+        let others = (0..8).map(|_| {
+            let breaker = breaker.clone();
+            tokio::spawn(async move { breaker.call(async { Ok(()) }).await })
+        });
+        for other in others {
+            assert!(other.await.unwrap().unwrap_err().is::<BreakerOpen>());
+        }
+
+        finish.send(()).unwrap();
+        trial.await.unwrap().unwrap();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+        breaker.call(async { Ok(()) }).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn cancelled_trial_frees_the_half_open_state() {
+        let breaker = CircuitBreaker::new(1, Duration::ZERO);
+        breaker.settle(false, Instant::now());
+
+        let started = Arc::new(Notify::new());
+        let trial = tokio::spawn({
+            let breaker = breaker.clone();
+            let started = started.clone();
+            async move {
+                breaker
+                    .call(async {
+                        started.notify_one();
+                        std::future::pending::<Result<()>>().await
+                    })
+                    .await
+            }
+        });
+        started.notified().await;
+        trial.abort();
+        assert!(trial.await.unwrap_err().is_cancelled());
+
+        assert_eq!(breaker.state(), BreakerState::HalfOpen);
+        breaker.call(async { Ok(()) }).await.unwrap();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+    }
+
+    #[test]
+    fn success_resets_the_consecutive_failures() {
+        let breaker = CircuitBreaker::new(2, COOLDOWN);
+        let now = Instant::now();
+
+        breaker.settle(false, now);
+        breaker.settle(true, now);
+        breaker.settle(false, now);
+
+        assert_eq!(breaker.state(), BreakerState::Closed);
+    }
+
+    #[tokio::test]
+    async fn only_unreachable_errors_trip_the_breaker() {
+        let breaker = CircuitBreaker::new(1, COOLDOWN);
+
+        let result: Result<()> = breaker
+            .call(async { Err(anyhow::anyhow!("duplicate key")) })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(breaker.state(), BreakerState::Closed);
+
+        let result: Result<()> = breaker.call(async { Err(unreachable()) }).await;
+        assert!(result.is_err());
+        assert_eq!(breaker.state(), BreakerState::Open);
+
+        let error = breaker.call(async { Ok(()) }).await.unwrap_err();
+        assert!(error.is::<BreakerOpen>());
+        assert!(is_unavailable(&error));
+    }
+}
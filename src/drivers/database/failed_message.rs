@@ -0,0 +1,103 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use bson::{doc, oid::ObjectId};
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use mongodb::{Collection, Database};
+
+use crate::domain::delivery::{FailedMessage, FailedMessageRepository};
+
+use super::WriteRetry;
+
+pub const FAILED_MESSAGE_COLLECTION: &str = "failed_messages";
+
+#[derive(Clone)]
+pub struct MongoFailedMessageRepository {
+    collection: Collection<FailedMessage>,
+    retry: WriteRetry,
+}
+
+impl MongoFailedMessageRepository {
+    pub fn new(database: &Database) -> Self {
+        Self {
+            collection: database.collection(FAILED_MESSAGE_COLLECTION),
+            retry: WriteRetry::default(),
+        }
+    }
+
+    pub fn with_retry(mut self, retry: WriteRetry) -> Self {
+        self.retry = retry;
+        self
+    }
+}
+
+#[async_trait]
+impl FailedMessageRepository for MongoFailedMessageRepository {
+    #[tracing::instrument(name = "Insert failed message", skip(self, failed))]
+    async fn insert(&self, failed: &FailedMessage) -> Result<ObjectId> {
+        self.retry
+            .insert(
+                "expected to insert failed message",
+                &self.collection,
+                failed,
+            )
+            .await
+    }
+
+    #[tracing::instrument(name = "Find pending failed messages", skip(self))]
+    async fn pending(&self, max_attempts: u32) -> Result<Vec<FailedMessage>> {
+        self.collection
+            .find(doc! {
+                "resolved": false,
+                "attempts": { "$lt": max_attempts },
+            })
+            .sort(doc! { "failed_at": 1 })
+            .await
+            .context("expected to find pending failed messages")?
+            .try_collect()
+            .await
+            .context("expected to read pending failed messages")
+    }
+
+    #[tracing::instrument(name = "Record failed message attempt", skip(self))]
+    async fn record_attempt(&self, id: ObjectId, reason: &str, at: DateTime<Utc>) -> Result<()> {
+        // Counted once, a retry could count the attempt twice.
+        self.retry
+            .write_once("expected to record failed message attempt", || {
+                self.collection.update_one(
+                    doc! { "_id": id },
+                    doc! {
+                        "$inc": { "attempts": 1 },
+                        "$set": {
+                            "reason": reason,
+                            "last_attempt_at": bson::DateTime::from_chrono(at),
+                        },
+                    },
+                )
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Mark failed message resolved", skip(self))]
+    async fn mark_resolved(&self, id: ObjectId, at: DateTime<Utc>) -> Result<()> {
+        // Counted once, a retry could count the attempt twice.
+        self.retry
+            .write_once("expected to mark failed message resolved", || {
+                self.collection.update_one(
+                    doc! { "_id": id },
+                    doc! {
+                        "$inc": { "attempts": 1 },
+                        "$set": {
+                            "resolved": true,
+                            "last_attempt_at": bson::DateTime::from_chrono(at),
+                        },
+                    },
+                )
+            })
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use bson::{doc, oid::ObjectId};
+use futures::TryStreamExt;
+use mongodb::{Collection, Database};
+
+use crate::domain::burndown::{GoalCompletion, GoalCompletionRepository};
+
+use super::WriteRetry;
+
+pub const GOAL_COMPLETION_COLLECTION: &str = "goal_completions";
+
+#[derive(Clone)]
+pub struct MongoGoalCompletionRepository {
+    collection: Collection<GoalCompletion>,
+    retry: WriteRetry,
+}
+
+impl MongoGoalCompletionRepository {
+    pub fn new(database: &Database) -> Self {
+        Self {
+            collection: database.collection(GOAL_COMPLETION_COLLECTION),
+            retry: WriteRetry::default(),
+        }
+    }
+
+    pub fn with_retry(mut self, retry: WriteRetry) -> Self {
+        self.retry = retry;
+        self
+    }
+}
+
+#[async_trait]
+impl GoalCompletionRepository for MongoGoalCompletionRepository {
+    #[tracing::instrument(name = "Insert goal completion", skip(self, completion))]
+    async fn insert(&self, completion: &GoalCompletion) -> Result<ObjectId> {
+        self.retry
+            .insert(
+                "expected to insert goal completion",
+                &self.collection,
+                completion,
+            )
+            .await
+    }
+
+    #[tracing::instrument(name = "List goal completions", skip(self))]
+    async fn list(&self, sprint_id: ObjectId) -> Result<Vec<GoalCompletion>> {
+        self.collection
+            .find(doc! { "sprint_id": sprint_id })
+            .sort(doc! { "completed_at": 1 })
+            .await
+            .context("expected to find goal completions")?
+            .try_collect()
+            .await
+            .context("expected to read goal completions")
+    }
+
+    #[tracing::instrument(name = "Delete goal completions", skip(self))]
+    async fn delete(&self, sprint_id: ObjectId, goal: &str) -> Result<bool> {
+        let result = self
+            .retry
+            .write("expected to delete goal completions", || {
+                self.collection
+                    .delete_many(doc! { "sprint_id": sprint_id, "goal": goal })
+            })
+            .await?;
+
+        Ok(result.deleted_count > 0)
+    }
+}
@@ -0,0 +1,259 @@
+//! Repositories whose operations go through a [`CircuitBreaker`].
+
+use std::collections::HashSet;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use bson::oid::ObjectId;
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::domain::{
+    audit::{AuditEntry, AuditQuery, AuditRepository},
+    burndown::{GoalCompletion, GoalCompletionRepository},
+    delivery::{FailedMessage, FailedMessageRepository},
+    guild::{GuildConfig, GuildConfigRepository},
+    id::{GuildId, UserId},
+    reminder::{Reminder, ReminderRepository},
+    retro::{ActionItem, ActionItemRepository, Retro, RetroRepository},
+    snooze::{Snooze, SnoozeRepository},
+    sprint::{Goal, Sprint, SprintRepository},
+    standup::{HistoryQuery, StandupEntry, StandupRepository, Upserted},
+};
+
+use super::breaker::CircuitBreaker;
+
+/// Wraps a repository so a failing database is not hammered by every request.
+pub struct Guarded<R> {
+    inner: R,
+    breaker: CircuitBreaker,
+}
+
+impl<R> Guarded<R> {
+    pub fn new(inner: R, breaker: CircuitBreaker) -> Self {
+        Self { inner, breaker }
+    }
+}
+
+#[async_trait]
+impl<R: AuditRepository> AuditRepository for Guarded<R> {
+    async fn insert(&self, entry: &AuditEntry) -> Result<()> {
+        self.breaker.call(self.inner.insert(entry)).await
+    }
+
+    async fn list(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>> {
+        self.breaker.call(self.inner.list(query)).await
+    }
+}
+
+#[async_trait]
+impl<R: GoalCompletionRepository> GoalCompletionRepository for Guarded<R> {
+    async fn insert(&self, completion: &GoalCompletion) -> Result<ObjectId> {
+        self.breaker.call(self.inner.insert(completion)).await
+    }
+
+    async fn list(&self, sprint_id: ObjectId) -> Result<Vec<GoalCompletion>> {
+        self.breaker.call(self.inner.list(sprint_id)).await
+    }
+
+    async fn delete(&self, sprint_id: ObjectId, goal: &str) -> Result<bool> {
+        self.breaker.call(self.inner.delete(sprint_id, goal)).await
+    }
+}
+
+#[async_trait]
+impl<R: RetroRepository> RetroRepository for Guarded<R> {
+    async fn insert(&self, retro: &Retro) -> Result<ObjectId> {
+        self.breaker.call(self.inner.insert(retro)).await
+    }
+
+    async fn find(&self, id: ObjectId) -> Result<Option<Retro>> {
+        self.breaker.call(self.inner.find(id)).await
+    }
+
+    async fn next_after(&self, retro: &Retro) -> Result<Option<Retro>> {
+        self.breaker.call(self.inner.next_after(retro)).await
+    }
+}
+
+#[async_trait]
+impl<R: ActionItemRepository> ActionItemRepository for Guarded<R> {
+    async fn insert(&self, item: &ActionItem) -> Result<ObjectId> {
+        self.breaker.call(self.inner.insert(item)).await
+    }
+
+    async fn find(&self, id: ObjectId) -> Result<Option<ActionItem>> {
+        self.breaker.call(self.inner.find(id)).await
+    }
+
+    async fn list_for_retro(&self, retro_id: ObjectId) -> Result<Vec<ActionItem>> {
+        self.breaker.call(self.inner.list_for_retro(retro_id)).await
+    }
+
+    async fn set_completed(
+        &self,
+        id: ObjectId,
+        completed_on: Option<NaiveDate>,
+    ) -> Result<Option<ActionItem>> {
+        self.breaker
+            .call(self.inner.set_completed(id, completed_on))
+            .await
+    }
+}
+
+#[async_trait]
+impl<R: GuildConfigRepository> GuildConfigRepository for Guarded<R> {
+    async fn find(&self, guild_id: GuildId) -> Result<Option<GuildConfig>> {
+        self.breaker.call(self.inner.find(guild_id)).await
+    }
+
+    async fn upsert(&self, config: &GuildConfig) -> Result<()> {
+        self.breaker.call(self.inner.upsert(config)).await
+    }
+
+    async fn list_nudged(&self) -> Result<Vec<GuildConfig>> {
+        self.breaker.call(self.inner.list_nudged()).await
+    }
+}
+
+#[async_trait]
+impl<R: ReminderRepository> ReminderRepository for Guarded<R> {
+    async fn insert(&self, reminder: &Reminder) -> Result<ObjectId> {
+        self.breaker.call(self.inner.insert(reminder)).await
+    }
+
+    async fn due(&self, now: DateTime<Utc>) -> Result<Vec<Reminder>> {
+        self.breaker.call(self.inner.due(now)).await
+    }
+
+    async fn mark_delivered(&self, id: ObjectId) -> Result<()> {
+        self.breaker.call(self.inner.mark_delivered(id)).await
+    }
+}
+
+#[async_trait]
+impl<R: FailedMessageRepository> FailedMessageRepository for Guarded<R> {
+    async fn insert(&self, failed: &FailedMessage) -> Result<ObjectId> {
+        self.breaker.call(self.inner.insert(failed)).await
+    }
+
+    async fn pending(&self, max_attempts: u32) -> Result<Vec<FailedMessage>> {
+        self.breaker.call(self.inner.pending(max_attempts)).await
+    }
+
+    async fn record_attempt(&self, id: ObjectId, reason: &str, at: DateTime<Utc>) -> Result<()> {
+        self.breaker
+            .call(self.inner.record_attempt(id, reason, at))
+            .await
+    }
+
+    async fn mark_resolved(&self, id: ObjectId, at: DateTime<Utc>) -> Result<()> {
+        self.breaker.call(self.inner.mark_resolved(id, at)).await
+    }
+}
+
+#[async_trait]
+impl<R: SnoozeRepository> SnoozeRepository for Guarded<R> {
+    async fn upsert(&self, snooze: &Snooze) -> Result<()> {
+        self.breaker.call(self.inner.upsert(snooze)).await
+    }
+
+    async fn find(&self, user_id: UserId) -> Result<Option<Snooze>> {
+        self.breaker.call(self.inner.find(user_id)).await
+    }
+
+    async fn list_active(&self, now: DateTime<Utc>) -> Result<Vec<Snooze>> {
+        self.breaker.call(self.inner.list_active(now)).await
+    }
+
+    async fn delete(&self, user_id: UserId) -> Result<bool> {
+        self.breaker.call(self.inner.delete(user_id)).await
+    }
+}
+
+#[async_trait]
+impl<R: SprintRepository> SprintRepository for Guarded<R> {
+    async fn insert(&self, sprint: &Sprint) -> Result<ObjectId> {
+        self.breaker.call(self.inner.insert(sprint)).await
+    }
+
+    async fn upsert(&self, sprint: &Sprint) -> Result<()> {
+        self.breaker.call(self.inner.upsert(sprint)).await
+    }
+
+    async fn find(&self, id: ObjectId) -> Result<Option<Sprint>> {
+        self.breaker.call(self.inner.find(id)).await
+    }
+
+    async fn add_goal(&self, id: ObjectId, goal: &Goal) -> Result<bool> {
+        self.breaker.call(self.inner.add_goal(id, goal)).await
+    }
+
+    async fn find_same_dates(&self, sprint: &Sprint) -> Result<Option<Sprint>> {
+        self.breaker.call(self.inner.find_same_dates(sprint)).await
+    }
+
+    async fn find_active(
+        &self,
+        guild_id: GuildId,
+        team: Option<&str>,
+        date: NaiveDate,
+    ) -> Result<Option<Sprint>> {
+        self.breaker
+            .call(self.inner.find_active(guild_id, team, date))
+            .await
+    }
+}
+
+#[async_trait]
+impl<R: StandupRepository> StandupRepository for Guarded<R> {
+    async fn insert(&self, entry: &StandupEntry) -> Result<ObjectId> {
+        self.breaker.call(self.inner.insert(entry)).await
+    }
+
+    async fn find(&self, id: ObjectId) -> Result<Option<StandupEntry>> {
+        self.breaker.call(self.inner.find(id)).await
+    }
+
+    async fn upsert(&self, entry: &StandupEntry) -> Result<Upserted> {
+        self.breaker.call(self.inner.upsert(entry)).await
+    }
+
+    async fn list_for_sprint(&self, sprint_id: ObjectId) -> Result<Vec<StandupEntry>> {
+        self.breaker
+            .call(self.inner.list_for_sprint(sprint_id))
+            .await
+    }
+
+    async fn first_for_sprint(&self, sprint_id: ObjectId) -> Result<Option<StandupEntry>> {
+        self.breaker
+            .call(self.inner.first_for_sprint(sprint_id))
+            .await
+    }
+
+    async fn participants(&self, guild_id: GuildId, date: NaiveDate) -> Result<HashSet<UserId>> {
+        self.breaker
+            .call(self.inner.participants(guild_id, date))
+            .await
+    }
+
+    async fn delete_created_before(&self, cutoff: DateTime<Utc>, limit: usize) -> Result<u64> {
+        self.breaker
+            .call(self.inner.delete_created_before(cutoff, limit))
+            .await
+    }
+
+    async fn history(&self, query: &HistoryQuery) -> Result<Vec<StandupEntry>> {
+        self.breaker.call(self.inner.history(query)).await
+    }
+
+    async fn blockers_between(
+        &self,
+        guild_id: GuildId,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<String>> {
+        self.breaker
+            .call(self.inner.blockers_between(guild_id, from, to))
+            .await
+    }
+}
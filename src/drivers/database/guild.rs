@@ -0,0 +1,69 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use bson::doc;
+use futures::TryStreamExt;
+use mongodb::{Collection, Database};
+
+use crate::domain::{
+    guild::{GuildConfig, GuildConfigRepository},
+    id::GuildId,
+};
+
+use super::WriteRetry;
+
+pub const GUILD_CONFIG_COLLECTION: &str = "guild_configs";
+
+#[derive(Clone)]
+pub struct MongoGuildConfigRepository {
+    collection: Collection<GuildConfig>,
+    retry: WriteRetry,
+}
+
+impl MongoGuildConfigRepository {
+    pub fn new(database: &Database) -> Self {
+        Self {
+            collection: database.collection(GUILD_CONFIG_COLLECTION),
+            retry: WriteRetry::default(),
+        }
+    }
+
+    pub fn with_retry(mut self, retry: WriteRetry) -> Self {
+        self.retry = retry;
+        self
+    }
+}
+
+#[async_trait]
+impl GuildConfigRepository for MongoGuildConfigRepository {
+    #[tracing::instrument(name = "Find guild config", skip(self))]
+    async fn find(&self, guild_id: GuildId) -> Result<Option<GuildConfig>> {
+        self.collection
+            .find_one(doc! { "guild_id": guild_id })
+            .await
+            .context("expected to find guild config")
+    }
+
+    #[tracing::instrument(name = "Upsert guild config", skip(self, config))]
+    async fn upsert(&self, config: &GuildConfig) -> Result<()> {
+        self.retry
+            .write("expected to upsert guild config", || {
+                self.collection
+                    .replace_one(doc! { "guild_id": config.guild_id }, config)
+                    .upsert(true)
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "List nudged guild configs", skip(self))]
+    async fn list_nudged(&self) -> Result<Vec<GuildConfig>> {
+        self.collection
+            .find(doc! { "nudge.enabled": true })
+            .await
+            .context("expected to list nudged guild configs")?
+            .try_collect()
+            .await
+            .context("expected to read guild configs")
+    }
+}
@@ -0,0 +1,554 @@
+//! In memory repositories used to exercise the domain without a MongoDB server.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use bson::oid::ObjectId;
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::domain::{
+    audit::{AuditEntry, AuditQuery, AuditRepository},
+    burndown::{GoalCompletion, GoalCompletionRepository},
+    conflict::Conflict,
+    delivery::{FailedMessage, FailedMessageRepository},
+    guild::{GuildConfig, GuildConfigRepository},
+    id::{GuildId, UserId},
+    reminder::{Reminder, ReminderRepository},
+    retro::{ActionItem, ActionItemRepository, Retro, RetroRepository},
+    snooze::{Snooze, SnoozeRepository},
+    sprint::{Goal, Sprint, SprintRepository},
+    standup::{HistoryQuery, StandupEntry, StandupRepository, Upserted},
+};
+
+#[derive(Default)]
+pub struct InMemoryAuditRepository(Mutex<Vec<AuditEntry>>);
+
+#[async_trait]
+impl AuditRepository for InMemoryAuditRepository {
+    async fn insert(&self, entry: &AuditEntry) -> Result<()> {
+        self.0.lock().unwrap().push(entry.clone());
+        Ok(())
+    }
+
+    async fn list(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>> {
+        Ok(self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .filter(|entry| query.matches(entry))
+            .take(query.limit() as usize)
+            .cloned()
+            .collect())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryGuildConfigRepository(Mutex<HashMap<GuildId, GuildConfig>>);
+
+#[async_trait]
+impl GuildConfigRepository for InMemoryGuildConfigRepository {
+    async fn find(&self, guild_id: GuildId) -> Result<Option<GuildConfig>> {
+        Ok(self.0.lock().unwrap().get(&guild_id).cloned())
+    }
+
+    async fn upsert(&self, config: &GuildConfig) -> Result<()> {
+        self.0
+            .lock()
+            .unwrap()
+            .insert(config.guild_id, config.clone());
+        Ok(())
+    }
+
+    async fn list_nudged(&self) -> Result<Vec<GuildConfig>> {
+        Ok(self
+            .0
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|config| config.nudge.enabled)
+            .cloned()
+            .collect())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemorySprintRepository(Mutex<Vec<Sprint>>);
+
+#[async_trait]
+impl SprintRepository for InMemorySprintRepository {
+    async fn insert(&self, sprint: &Sprint) -> Result<ObjectId> {
+        let id = ObjectId::new();
+        let mut sprint = sprint.clone();
+        sprint.id = Some(id);
+        self.0.lock().unwrap().push(sprint);
+        Ok(id)
+    }
+
+    async fn upsert(&self, sprint: &Sprint) -> Result<()> {
+        let mut sprints = self.0.lock().unwrap();
+        sprints.retain(|stored| stored.id != sprint.id);
+        sprints.push(sprint.clone());
+        Ok(())
+    }
+
+    async fn find(&self, id: ObjectId) -> Result<Option<Sprint>> {
+        Ok(self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|sprint| sprint.id == Some(id))
+            .cloned())
+    }
+
+    async fn add_goal(&self, id: ObjectId, goal: &Goal) -> Result<bool> {
+        let mut sprints = self.0.lock().unwrap();
+        let Some(sprint) = sprints.iter_mut().find(|sprint| sprint.id == Some(id)) else {
+            return Ok(false);
+        };
+
+        Ok(sprint.add_goal(goal.clone()).is_ok())
+    }
+
+    async fn find_same_dates(&self, sprint: &Sprint) -> Result<Option<Sprint>> {
+        Ok(self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|stored| {
+                stored.id != sprint.id
+                    && stored.guild_id == sprint.guild_id
+                    && stored.team == sprint.team
+                    && stored.start_date == sprint.start_date
+                    && stored.end_date == sprint.end_date
+            })
+            .cloned())
+    }
+
+    async fn find_active(
+        &self,
+        guild_id: GuildId,
+        team: Option<&str>,
+        date: NaiveDate,
+    ) -> Result<Option<Sprint>> {
+        Ok(self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|sprint| {
+                sprint.guild_id == guild_id
+                    && sprint.team.as_deref() == team
+                    && sprint.is_active_on(date)
+            })
+            .max_by_key(|sprint| sprint.start_date)
+            .cloned())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryGoalCompletionRepository(Mutex<Vec<GoalCompletion>>);
+
+#[async_trait]
+impl GoalCompletionRepository for InMemoryGoalCompletionRepository {
+    async fn insert(&self, completion: &GoalCompletion) -> Result<ObjectId> {
+        let id = ObjectId::new();
+        let mut completion = completion.clone();
+        completion.id = Some(id);
+        self.0.lock().unwrap().push(completion);
+        Ok(id)
+    }
+
+    async fn list(&self, sprint_id: ObjectId) -> Result<Vec<GoalCompletion>> {
+        let mut completions: Vec<_> = self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|completion| completion.sprint_id == sprint_id)
+            .cloned()
+            .collect();
+        completions.sort_by_key(|completion| completion.completed_at);
+        Ok(completions)
+    }
+
+    async fn delete(&self, sprint_id: ObjectId, goal: &str) -> Result<bool> {
+        let mut completions = self.0.lock().unwrap();
+        let before = completions.len();
+        completions
+            .retain(|completion| completion.sprint_id != sprint_id || completion.goal != goal);
+        Ok(completions.len() < before)
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryRetroRepository(Mutex<Vec<Retro>>);
+
+#[async_trait]
+impl RetroRepository for InMemoryRetroRepository {
+    async fn insert(&self, retro: &Retro) -> Result<ObjectId> {
+        let id = ObjectId::new();
+        let mut retro = retro.clone();
+        retro.id = Some(id);
+        self.0.lock().unwrap().push(retro);
+        Ok(id)
+    }
+
+    async fn find(&self, id: ObjectId) -> Result<Option<Retro>> {
+        Ok(self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|retro| retro.id == Some(id))
+            .cloned())
+    }
+
+    async fn next_after(&self, retro: &Retro) -> Result<Option<Retro>> {
+        Ok(self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|next| {
+                next.guild_id == retro.guild_id
+                    && next.team == retro.team
+                    && next.held_on > retro.held_on
+            })
+            .min_by_key(|next| next.held_on)
+            .cloned())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryActionItemRepository(Mutex<Vec<ActionItem>>);
+
+#[async_trait]
+impl ActionItemRepository for InMemoryActionItemRepository {
+    async fn insert(&self, item: &ActionItem) -> Result<ObjectId> {
+        let id = ObjectId::new();
+        let mut item = item.clone();
+        item.id = Some(id);
+        self.0.lock().unwrap().push(item);
+        Ok(id)
+    }
+
+    async fn find(&self, id: ObjectId) -> Result<Option<ActionItem>> {
+        Ok(self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|item| item.id == Some(id))
+            .cloned())
+    }
+
+    async fn list_for_retro(&self, retro_id: ObjectId) -> Result<Vec<ActionItem>> {
+        let mut items: Vec<_> = self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|item| item.retro_id == retro_id)
+            .cloned()
+            .collect();
+        items.sort_by_key(|item| (item.due_date, item.id));
+        Ok(items)
+    }
+
+    async fn set_completed(
+        &self,
+        id: ObjectId,
+        completed_on: Option<NaiveDate>,
+    ) -> Result<Option<ActionItem>> {
+        let mut items = self.0.lock().unwrap();
+        let Some(item) = items.iter_mut().find(|item| item.id == Some(id)) else {
+            return Ok(None);
+        };
+
+        item.completed_on = completed_on;
+        Ok(Some(item.clone()))
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryStandupRepository(Mutex<Vec<StandupEntry>>);
+
+/// The unique index of the standups collection.
+fn same_day(a: &StandupEntry, b: &StandupEntry) -> bool {
+    a.guild_id == b.guild_id
+        && a.user_id == b.user_id
+        && a.channel_id == b.channel_id
+        && a.date == b.date
+}
+
+#[async_trait]
+impl StandupRepository for InMemoryStandupRepository {
+    async fn insert(&self, entry: &StandupEntry) -> Result<ObjectId> {
+        let mut entries = self.0.lock().unwrap();
+        if entries.iter().any(|existing| same_day(existing, entry)) {
+            return Err(Conflict.into());
+        }
+
+        let id = ObjectId::new();
+        let mut entry = entry.clone();
+        entry.id = Some(id);
+        entries.push(entry);
+        Ok(id)
+    }
+
+    async fn find(&self, id: ObjectId) -> Result<Option<StandupEntry>> {
+        Ok(self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|entry| entry.id == Some(id))
+            .cloned())
+    }
+
+    async fn upsert(&self, entry: &StandupEntry) -> Result<Upserted> {
+        let mut entries = self.0.lock().unwrap();
+        let existing = entries
+            .iter_mut()
+            .find(|existing| same_day(existing, entry));
+
+        if let Some(existing) = existing {
+            *existing = StandupEntry {
+                id: existing.id,
+                created_at: existing.created_at,
+                ..entry.clone()
+            };
+            return Ok(Upserted {
+                entry: existing.clone(),
+                created: false,
+            });
+        }
+
+        let mut entry = entry.clone();
+        entry.id = Some(ObjectId::new());
+        entries.push(entry.clone());
+        Ok(Upserted {
+            entry,
+            created: true,
+        })
+    }
+
+    async fn list_for_sprint(&self, sprint_id: ObjectId) -> Result<Vec<StandupEntry>> {
+        let mut entries: Vec<_> = self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|entry| entry.sprint_id == Some(sprint_id))
+            .cloned()
+            .collect();
+        entries.sort_by_key(|entry| entry.created_at);
+        Ok(entries)
+    }
+
+    async fn first_for_sprint(&self, sprint_id: ObjectId) -> Result<Option<StandupEntry>> {
+        Ok(self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|entry| entry.sprint_id == Some(sprint_id))
+            .min_by_key(|entry| entry.created_at)
+            .cloned())
+    }
+
+    async fn participants(&self, guild_id: GuildId, date: NaiveDate) -> Result<HashSet<UserId>> {
+        Ok(self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|entry| entry.guild_id == guild_id && entry.date == date)
+            .map(|entry| entry.user_id)
+            .collect())
+    }
+
+    async fn delete_created_before(&self, cutoff: DateTime<Utc>, limit: usize) -> Result<u64> {
+        let mut entries = self.0.lock().unwrap();
+        let mut old: Vec<_> = entries
+            .iter()
+            .filter(|entry| entry.created_at < cutoff)
+            .map(|entry| (entry.created_at, entry.id))
+            .collect();
+        old.sort();
+        old.truncate(limit);
+
+        entries.retain(|entry| !old.contains(&(entry.created_at, entry.id)));
+        Ok(old.len() as u64)
+    }
+
+    async fn history(&self, query: &HistoryQuery) -> Result<Vec<StandupEntry>> {
+        let mut entries: Vec<_> = self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|entry| {
+                entry.guild_id == query.guild_id
+                    && entry.user_id == query.user_id
+                    && (query.from..=query.to).contains(&entry.date)
+            })
+            .cloned()
+            .collect();
+        entries.sort_by(|a, b| b.date.cmp(&a.date).then(b.created_at.cmp(&a.created_at)));
+
+        Ok(entries
+            .into_iter()
+            .skip(query.skip)
+            .take(query.limit)
+            .collect())
+    }
+
+    async fn blockers_between(
+        &self,
+        guild_id: GuildId,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<String>> {
+        Ok(self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|entry| {
+                entry.guild_id == guild_id
+                    && (from..=to).contains(&entry.date)
+                    && !entry.blockers.is_empty()
+            })
+            .map(|entry| entry.blockers.clone())
+            .collect())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryReminderRepository(Mutex<Vec<Reminder>>);
+
+#[async_trait]
+impl ReminderRepository for InMemoryReminderRepository {
+    async fn insert(&self, reminder: &Reminder) -> Result<ObjectId> {
+        let id = ObjectId::new();
+        let mut reminder = reminder.clone();
+        reminder.id = Some(id);
+        self.0.lock().unwrap().push(reminder);
+        Ok(id)
+    }
+
+    async fn due(&self, now: DateTime<Utc>) -> Result<Vec<Reminder>> {
+        let mut due: Vec<_> = self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|reminder| !reminder.delivered && reminder.due_at <= now)
+            .cloned()
+            .collect();
+        due.sort_by_key(|reminder| reminder.due_at);
+        Ok(due)
+    }
+
+    async fn mark_delivered(&self, id: ObjectId) -> Result<()> {
+        for reminder in self.0.lock().unwrap().iter_mut() {
+            if reminder.id == Some(id) {
+                reminder.delivered = true;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryFailedMessageRepository(Mutex<Vec<FailedMessage>>);
+
+#[async_trait]
+impl FailedMessageRepository for InMemoryFailedMessageRepository {
+    async fn insert(&self, failed: &FailedMessage) -> Result<ObjectId> {
+        let id = ObjectId::new();
+        let mut failed = failed.clone();
+        failed.id = Some(id);
+        self.0.lock().unwrap().push(failed);
+        Ok(id)
+    }
+
+    async fn pending(&self, max_attempts: u32) -> Result<Vec<FailedMessage>> {
+        let mut pending: Vec<_> = self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|failed| !failed.resolved && failed.attempts < max_attempts)
+            .cloned()
+            .collect();
+        pending.sort_by_key(|failed| failed.failed_at);
+        Ok(pending)
+    }
+
+    async fn record_attempt(&self, id: ObjectId, reason: &str, at: DateTime<Utc>) -> Result<()> {
+        for failed in self.0.lock().unwrap().iter_mut() {
+            if failed.id == Some(id) {
+                failed.attempts += 1;
+                failed.reason = reason.to_owned();
+                failed.last_attempt_at = at;
+            }
+        }
+        Ok(())
+    }
+
+    async fn mark_resolved(&self, id: ObjectId, at: DateTime<Utc>) -> Result<()> {
+        for failed in self.0.lock().unwrap().iter_mut() {
+            if failed.id == Some(id) {
+                failed.attempts += 1;
+                failed.resolved = true;
+                failed.last_attempt_at = at;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemorySnoozeRepository(Mutex<HashMap<UserId, Snooze>>);
+
+#[async_trait]
+impl SnoozeRepository for InMemorySnoozeRepository {
+    async fn upsert(&self, snooze: &Snooze) -> Result<()> {
+        self.0
+            .lock()
+            .unwrap()
+            .insert(snooze.user_id, snooze.clone());
+        Ok(())
+    }
+
+    async fn find(&self, user_id: UserId) -> Result<Option<Snooze>> {
+        Ok(self.0.lock().unwrap().get(&user_id).cloned())
+    }
+
+    async fn list_active(&self, now: DateTime<Utc>) -> Result<Vec<Snooze>> {
+        let mut snoozes: Vec<_> = self
+            .0
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|snooze| snooze.is_active(now))
+            .cloned()
+            .collect();
+        snoozes.sort_by_key(|snooze| snooze.until);
+        Ok(snoozes)
+    }
+
+    async fn delete(&self, user_id: UserId) -> Result<bool> {
+        Ok(self.0.lock().unwrap().remove(&user_id).is_some())
+    }
+}
@@ -0,0 +1,475 @@
+pub mod audit;
+pub mod breaker;
+pub mod failed_message;
+pub mod goal_completion;
+pub mod guarded;
+pub mod guild;
+#[cfg(test)]
+pub mod memory;
+pub mod reminder;
+pub mod retro;
+pub mod snooze;
+pub mod sprint;
+pub mod standup;
+
+use std::{future::IntoFuture, sync::Arc, time::Duration};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use bson::{doc, oid::ObjectId, Document};
+use mongodb::{
+    error::{ErrorKind, WriteFailure, RETRYABLE_WRITE_ERROR},
+    Collection, Database,
+};
+use serde::Serialize;
+
+use crate::{configuration::RetrySettings, domain::conflict::Conflict};
+
+use self::breaker::BreakerOpen;
+
+/// Attempts of a write before a transient error is returned.
+pub const WRITE_ATTEMPTS: u32 = 3;
+/// Delay before the second attempt of a write, doubled after every attempt.
+pub const WRITE_BACKOFF: Duration = Duration::from_millis(50);
+
+/// Code of a write rejected by a unique index.
+const DUPLICATE_KEY: i32 = 11000;
+/// Codes of a primary stepping down or shutting down, another one is elected
+/// within seconds.
+const STEPDOWN_CODES: [i32; 7] = [91, 189, 10107, 11600, 11602, 13435, 13436];
+
+/// A dependency that can tell whether it is reachable.
+#[async_trait]
+pub trait Ping: Send + Sync {
+    async fn ping(&self) -> Result<()>;
+}
+
+#[async_trait]
+impl Ping for Database {
+    #[tracing::instrument(name = "Ping mongodb", skip(self))]
+    async fn ping(&self) -> Result<()> {
+        self.run_command(doc! { "ping": 1 })
+            .await
+            .context("expected mongodb to answer ping")?;
+
+        Ok(())
+    }
+}
+
+/// Whether `error` comes from the database being unreachable, rather than from
+/// the operation itself.
+pub fn is_unavailable(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause.is::<BreakerOpen>()
+            || cause
+                .downcast_ref::<mongodb::error::Error>()
+                .is_some_and(|error| {
+                    matches!(
+                        *error.kind,
+                        ErrorKind::ServerSelection { .. } | ErrorKind::Io(_)
+                    )
+                })
+    })
+}
+
+/// Where the retried writes are counted.
+pub trait RetryCounter: Send + Sync {
+    fn retried(&self);
+}
+
+/// How the repositories retry their writes on transient errors.
+#[derive(Clone)]
+pub struct WriteRetry {
+    attempts: u32,
+    backoff: Duration,
+    counter: Option<Arc<dyn RetryCounter>>,
+}
+
+impl Default for WriteRetry {
+    fn default() -> Self {
+        Self::new(WRITE_ATTEMPTS, WRITE_BACKOFF)
+    }
+}
+
+impl WriteRetry {
+    /// Try a write `attempts` times, waiting `backoff` before the second
+    /// attempt and twice as long before each next one.
+    pub fn new(attempts: u32, backoff: Duration) -> Self {
+        Self {
+            attempts: attempts.max(1),
+            backoff,
+            counter: None,
+        }
+    }
+
+    pub fn from_settings(settings: &RetrySettings) -> Self {
+        Self::new(
+            settings.attempts,
+            Duration::from_millis(settings.backoff_ms),
+        )
+    }
+
+    pub fn with_counter(mut self, counter: Arc<dyn RetryCounter>) -> Self {
+        self.counter = Some(counter);
+        self
+    }
+
+    /// Run the write built by `write`, retrying transient errors with backoff.
+    ///
+    /// A network blip or a primary stepdown is retried up to the configured
+    /// attempts, a duplicate key is reported as a [`Conflict`] and any other
+    /// error is returned right away. Errors carry `context`.
+    ///
+    /// A write that failed in transit may have been applied, so only
+    /// idempotent writes go through here. Inserts go through
+    /// [`WriteRetry::insert`], the others through [`WriteRetry::write_once`].
+    pub async fn write<T, F, W>(&self, context: &'static str, write: F) -> Result<T>
+    where
+        F: FnMut() -> W,
+        W: IntoFuture<Output = mongodb::error::Result<T>>,
+    {
+        self.run(context, self.attempts, None, write).await
+    }
+
+    /// Run the write built by `write` once, for writes applying twice would
+    /// corrupt, such as an `$inc`.
+    pub async fn write_once<T, F, W>(&self, context: &'static str, write: F) -> Result<T>
+    where
+        F: FnMut() -> W,
+        W: IntoFuture<Output = mongodb::error::Result<T>>,
+    {
+        self.run(context, 1, None, write).await
+    }
+
+    /// Insert `value` into `collection`, returning its id.
+    ///
+    /// The id is assigned before the first attempt, a retry that finds it
+    /// taken knows an earlier attempt went through.
+    pub async fn insert<T>(
+        &self,
+        context: &'static str,
+        collection: &Collection<T>,
+        value: &T,
+    ) -> Result<ObjectId>
+    where
+        T: Serialize + Send + Sync,
+    {
+        let mut document = bson::to_document(value).context(context)?;
+        let id = match document.get_object_id("_id") {
+            Ok(id) => id,
+            Err(_) => {
+                let id = ObjectId::new();
+                document.insert("_id", id);
+                id
+            }
+        };
+
+        let collection = collection.clone_with_type::<Document>();
+        self.insert_with(context, || {
+            let insert = collection.insert_one(&document).into_future();
+            async move { insert.await.map(drop) }
+        })
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Run the insert built by `insert`, its document keeping the same id
+    /// across attempts.
+    async fn insert_with<F, W>(&self, context: &'static str, insert: F) -> Result<()>
+    where
+        F: FnMut() -> W,
+        W: IntoFuture<Output = mongodb::error::Result<()>>,
+    {
+        self.run(context, self.attempts, Some(()), insert).await
+    }
+
+    /// Attempt `write` up to `attempts` times. When an earlier attempt may have
+    /// been applied, a duplicate id is that attempt and `already_written` is
+    /// returned for it, if given.
+    async fn run<T, F, W>(
+        &self,
+        context: &'static str,
+        attempts: u32,
+        mut already_written: Option<T>,
+        mut write: F,
+    ) -> Result<T>
+    where
+        F: FnMut() -> W,
+        W: IntoFuture<Output = mongodb::error::Result<T>>,
+    {
+        let mut backoff = self.backoff;
+        let mut attempt = 1;
+
+        loop {
+            let error = match write().await {
+                Ok(written) => return Ok(written),
+                Err(error) => error,
+            };
+
+            if attempt > 1 && is_duplicate_id(&error) {
+                if let Some(written) = already_written.take() {
+                    tracing::info!("an earlier attempt of the retried write was applied");
+                    return Ok(written);
+                }
+            }
+            if is_duplicate_key(&error) {
+                return Err(anyhow::Error::new(error).context(Conflict).context(context));
+            }
+            if attempt >= attempts || !is_transient(&error) {
+                return Err(anyhow::Error::new(error).context(context));
+            }
+
+            tracing::warn!(error = ?error, attempt, "retrying transient write error");
+            if let Some(counter) = &self.counter {
+                counter.retried();
+            }
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+            attempt += 1;
+        }
+    }
+}
+
+fn is_duplicate_key(error: &mongodb::error::Error) -> bool {
+    match error.kind.as_ref() {
+        ErrorKind::Write(WriteFailure::WriteError(error)) => error.code == DUPLICATE_KEY,
+        // Upserts through findAndModify report it as a command error.
+        ErrorKind::Command(error) => error.code == DUPLICATE_KEY,
+        _ => false,
+    }
+}
+
+/// A duplicate key on the `_id` index, rather than on an index of the collection.
+fn is_duplicate_id(error: &mongodb::error::Error) -> bool {
+    match error.kind.as_ref() {
+        ErrorKind::Write(WriteFailure::WriteError(error)) => {
+            error.code == DUPLICATE_KEY && error.message.contains("index: _id_ ")
+        }
+        _ => false,
+    }
+}
+
+fn is_transient(error: &mongodb::error::Error) -> bool {
+    if error.contains_label(RETRYABLE_WRITE_ERROR) {
+        return true;
+    }
+
+    match error.kind.as_ref() {
+        ErrorKind::Io(_) | ErrorKind::ConnectionPoolCleared { .. } => true,
+        ErrorKind::Command(error) => STEPDOWN_CODES.contains(&error.code),
+        ErrorKind::Write(WriteFailure::WriteConcernError(error)) => {
+            STEPDOWN_CODES.contains(&error.code)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io,
+        sync::atomic::{AtomicU32, Ordering},
+    };
+
+    use mongodb::error::{CommandError, WriteError};
+
+    use super::*;
+
+    fn duplicate_key() -> mongodb::error::Error {
+        let error: WriteError = bson::from_document(doc! {
+            "code": DUPLICATE_KEY,
+            "errmsg": "E11000 duplicate key error",
+        })
+        .unwrap();
+
+        ErrorKind::Write(WriteFailure::WriteError(error)).into()
+    }
+
+    fn duplicate_id() -> mongodb::error::Error {
+        let error: WriteError = bson::from_document(doc! {
+            "code": DUPLICATE_KEY,
+            "errmsg": "E11000 duplicate key error collection: scrum.standups index: _id_ dup key: { _id: ObjectId('66f1a1a1a1a1a1a1a1a1a1a1') }",
+        })
+        .unwrap();
+
+        ErrorKind::Write(WriteFailure::WriteError(error)).into()
+    }
+
+    fn stepdown() -> mongodb::error::Error {
+        let error: CommandError = bson::from_document(doc! {
+            "code": 189,
+            "codeName": "PrimarySteppedDown",
+        })
+        .unwrap();
+
+        ErrorKind::Command(error).into()
+    }
+
+    #[derive(Default)]
+    struct Retries(AtomicU32);
+
+    impl RetryCounter for Retries {
+        fn retried(&self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Write with `retry` until `errors` run out, returning the result with
+    /// the attempts and the retries counted.
+    async fn write_with<T>(
+        retry: WriteRetry,
+        errors: Vec<mongodb::error::Error>,
+        written: T,
+    ) -> (Result<T>, u32, u32)
+    where
+        T: Clone,
+    {
+        let attempts = AtomicU32::new(0);
+        let retries = Arc::new(Retries::default());
+        let errors = std::sync::Mutex::new(errors.into_iter());
+
+        let result = retry
+            .with_counter(retries.clone())
+            .write("expected to write", || {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                let next = errors.lock().unwrap().next();
+                let written = written.clone();
+                async move { next.map_or(Ok(written), Err) }
+            })
+            .await;
+
+        (
+            result,
+            attempts.load(Ordering::SeqCst),
+            retries.0.load(Ordering::SeqCst),
+        )
+    }
+
+    async fn attempts_until<T>(errors: Vec<mongodb::error::Error>, written: T) -> (Result<T>, u32)
+    where
+        T: Clone,
+    {
+        let retry = WriteRetry::new(WRITE_ATTEMPTS, Duration::ZERO);
+        let (result, attempts, _) = write_with(retry, errors, written).await;
+
+        (result, attempts)
+    }
+
+    #[tokio::test]
+    async fn transient_errors_are_retried() {
+        let errors = vec![io::ErrorKind::ConnectionReset.into(), stepdown()];
+        let retry = WriteRetry::new(WRITE_ATTEMPTS, Duration::ZERO);
+
+        let (result, attempts, retries) = write_with(retry, errors, 7).await;
+
+        assert_eq!(result.unwrap(), 7);
+        assert_eq!(attempts, 3);
+        assert_eq!(retries, 2);
+    }
+
+    #[tokio::test]
+    async fn permanent_errors_are_not_retried() {
+        let error: CommandError = bson::from_document(doc! {
+            "code": 2,
+            "codeName": "BadValue",
+        })
+        .unwrap();
+        let retry = WriteRetry::new(WRITE_ATTEMPTS, Duration::ZERO);
+
+        let (result, attempts, retries) =
+            write_with(retry, vec![ErrorKind::Command(error).into()], ()).await;
+
+        assert!(result.is_err());
+        assert_eq!((attempts, retries), (1, 0));
+    }
+
+    #[tokio::test]
+    async fn configured_attempts_bound_the_retries() {
+        let errors = vec![stepdown(), stepdown(), stepdown()];
+        let retry = WriteRetry::from_settings(&RetrySettings {
+            attempts: 2,
+            backoff_ms: 0,
+        });
+
+        let (result, attempts, retries) = write_with(retry, errors, ()).await;
+
+        assert!(result.is_err());
+        assert_eq!((attempts, retries), (2, 1));
+    }
+
+    #[tokio::test]
+    async fn transient_errors_give_up_after_the_last_attempt() {
+        let errors = (0..WRITE_ATTEMPTS)
+            .map(|_| io::ErrorKind::ConnectionReset.into())
+            .collect();
+
+        let (result, attempts) = attempts_until(errors, ()).await;
+
+        let error = result.unwrap_err();
+        assert_eq!(attempts, WRITE_ATTEMPTS);
+        assert_eq!(error.to_string(), "expected to write");
+        assert!(is_unavailable(&error));
+    }
+
+    #[tokio::test]
+    async fn duplicate_key_is_a_conflict() {
+        let (result, attempts) = attempts_until(vec![duplicate_key()], ()).await;
+
+        let error = result.unwrap_err();
+        assert_eq!(attempts, 1);
+        assert_eq!(error.downcast_ref::<Conflict>(), Some(&Conflict));
+        assert!(!is_unavailable(&error));
+    }
+
+    async fn insert_until(errors: Vec<mongodb::error::Error>) -> (Result<()>, u32) {
+        let attempts = AtomicU32::new(0);
+        let errors = std::sync::Mutex::new(errors.into_iter());
+
+        let result = WriteRetry::new(WRITE_ATTEMPTS, Duration::ZERO)
+            .insert_with("expected to insert", || {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                let next = errors.lock().unwrap().next();
+                async move { next.map_or(Ok(()), Err) }
+            })
+            .await;
+
+        (result, attempts.load(Ordering::SeqCst))
+    }
+
+    #[tokio::test]
+    async fn retried_insert_finding_its_own_id_succeeds() {
+        let errors = vec![io::ErrorKind::ConnectionReset.into(), duplicate_id()];
+
+        let (result, attempts) = insert_until(errors).await;
+
+        result.unwrap();
+        assert_eq!(attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn first_insert_finding_its_id_or_another_key_is_a_conflict() {
+        let (result, _) = insert_until(vec![duplicate_id()]).await;
+        assert!(result.unwrap_err().is::<Conflict>());
+
+        let errors = vec![io::ErrorKind::ConnectionReset.into(), duplicate_key()];
+        let (result, attempts) = insert_until(errors).await;
+        assert!(result.unwrap_err().is::<Conflict>());
+        assert_eq!(attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn write_once_is_never_retried() {
+        let attempts = AtomicU32::new(0);
+
+        let result: Result<()> = WriteRetry::new(WRITE_ATTEMPTS, Duration::ZERO)
+            .write_once("expected to increment", || {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                async { Err(io::ErrorKind::ConnectionReset.into()) }
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,69 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use bson::{doc, oid::ObjectId};
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use mongodb::{Collection, Database};
+
+use crate::domain::reminder::{Reminder, ReminderRepository};
+
+use super::WriteRetry;
+
+pub const REMINDER_COLLECTION: &str = "reminders";
+
+#[derive(Clone)]
+pub struct MongoReminderRepository {
+    collection: Collection<Reminder>,
+    retry: WriteRetry,
+}
+
+impl MongoReminderRepository {
+    pub fn new(database: &Database) -> Self {
+        Self {
+            collection: database.collection(REMINDER_COLLECTION),
+            retry: WriteRetry::default(),
+        }
+    }
+
+    pub fn with_retry(mut self, retry: WriteRetry) -> Self {
+        self.retry = retry;
+        self
+    }
+}
+
+#[async_trait]
+impl ReminderRepository for MongoReminderRepository {
+    #[tracing::instrument(name = "Insert reminder", skip(self, reminder))]
+    async fn insert(&self, reminder: &Reminder) -> Result<ObjectId> {
+        self.retry
+            .insert("expected to insert reminder", &self.collection, reminder)
+            .await
+    }
+
+    #[tracing::instrument(name = "Find due reminders", skip(self))]
+    async fn due(&self, now: DateTime<Utc>) -> Result<Vec<Reminder>> {
+        self.collection
+            .find(doc! {
+                "delivered": false,
+                "due_at": { "$lte": bson::DateTime::from_chrono(now) },
+            })
+            .sort(doc! { "due_at": 1 })
+            .await
+            .context("expected to find due reminders")?
+            .try_collect()
+            .await
+            .context("expected to read due reminders")
+    }
+
+    #[tracing::instrument(name = "Mark reminder delivered", skip(self))]
+    async fn mark_delivered(&self, id: ObjectId) -> Result<()> {
+        self.retry
+            .write("expected to mark reminder delivered", || {
+                self.collection
+                    .update_one(doc! { "_id": id }, doc! { "$set": { "delivered": true } })
+            })
+            .await?;
+
+        Ok(())
+    }
+}
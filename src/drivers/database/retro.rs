@@ -0,0 +1,134 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use bson::{doc, oid::ObjectId};
+use chrono::NaiveDate;
+use futures::TryStreamExt;
+use mongodb::{options::ReturnDocument, Collection, Database};
+
+use crate::domain::retro::{ActionItem, ActionItemRepository, Retro, RetroRepository};
+
+use super::WriteRetry;
+
+pub const RETRO_COLLECTION: &str = "retros";
+pub const ACTION_ITEM_COLLECTION: &str = "action_items";
+
+#[derive(Clone)]
+pub struct MongoRetroRepository {
+    collection: Collection<Retro>,
+    retry: WriteRetry,
+}
+
+impl MongoRetroRepository {
+    pub fn new(database: &Database) -> Self {
+        Self {
+            collection: database.collection(RETRO_COLLECTION),
+            retry: WriteRetry::default(),
+        }
+    }
+
+    pub fn with_retry(mut self, retry: WriteRetry) -> Self {
+        self.retry = retry;
+        self
+    }
+}
+
+#[async_trait]
+impl RetroRepository for MongoRetroRepository {
+    #[tracing::instrument(name = "Insert retro", skip(self, retro))]
+    async fn insert(&self, retro: &Retro) -> Result<ObjectId> {
+        self.retry
+            .insert("expected to insert retro", &self.collection, retro)
+            .await
+    }
+
+    #[tracing::instrument(name = "Find retro", skip(self))]
+    async fn find(&self, id: ObjectId) -> Result<Option<Retro>> {
+        self.collection
+            .find_one(doc! { "_id": id })
+            .await
+            .context("expected to find retro")
+    }
+
+    #[tracing::instrument(name = "Find next retro", skip(self, retro), fields(retro_id = ?retro.id))]
+    async fn next_after(&self, retro: &Retro) -> Result<Option<Retro>> {
+        // Dates are stored as ISO 8601 strings, which sort chronologically.
+        self.collection
+            .find_one(doc! {
+                "guild_id": retro.guild_id,
+                "team": &retro.team,
+                "held_on": { "$gt": retro.held_on.to_string() },
+            })
+            .sort(doc! { "held_on": 1 })
+            .await
+            .context("expected to find next retro")
+    }
+}
+
+#[derive(Clone)]
+pub struct MongoActionItemRepository {
+    collection: Collection<ActionItem>,
+    retry: WriteRetry,
+}
+
+impl MongoActionItemRepository {
+    pub fn new(database: &Database) -> Self {
+        Self {
+            collection: database.collection(ACTION_ITEM_COLLECTION),
+            retry: WriteRetry::default(),
+        }
+    }
+
+    pub fn with_retry(mut self, retry: WriteRetry) -> Self {
+        self.retry = retry;
+        self
+    }
+}
+
+#[async_trait]
+impl ActionItemRepository for MongoActionItemRepository {
+    #[tracing::instrument(name = "Insert action item", skip(self, item))]
+    async fn insert(&self, item: &ActionItem) -> Result<ObjectId> {
+        self.retry
+            .insert("expected to insert action item", &self.collection, item)
+            .await
+    }
+
+    #[tracing::instrument(name = "Find action item", skip(self))]
+    async fn find(&self, id: ObjectId) -> Result<Option<ActionItem>> {
+        self.collection
+            .find_one(doc! { "_id": id })
+            .await
+            .context("expected to find action item")
+    }
+
+    #[tracing::instrument(name = "List action items", skip(self))]
+    async fn list_for_retro(&self, retro_id: ObjectId) -> Result<Vec<ActionItem>> {
+        self.collection
+            .find(doc! { "retro_id": retro_id })
+            .sort(doc! { "due_date": 1, "_id": 1 })
+            .await
+            .context("expected to list action items")?
+            .try_collect()
+            .await
+            .context("expected to read action items")
+    }
+
+    #[tracing::instrument(name = "Complete action item", skip(self))]
+    async fn set_completed(
+        &self,
+        id: ObjectId,
+        completed_on: Option<NaiveDate>,
+    ) -> Result<Option<ActionItem>> {
+        let completed_on = completed_on.map(|date| date.to_string());
+        self.retry
+            .write("expected to complete action item", || {
+                self.collection
+                    .find_one_and_update(
+                        doc! { "_id": id },
+                        doc! { "$set": { "completed_on": &completed_on } },
+                    )
+                    .return_document(ReturnDocument::After)
+            })
+            .await
+    }
+}
@@ -0,0 +1,83 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use bson::doc;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use mongodb::{Collection, Database};
+
+use crate::domain::{
+    id::UserId,
+    snooze::{Snooze, SnoozeRepository},
+};
+
+use super::WriteRetry;
+
+pub const SNOOZE_COLLECTION: &str = "snoozes";
+
+#[derive(Clone)]
+pub struct MongoSnoozeRepository {
+    collection: Collection<Snooze>,
+    retry: WriteRetry,
+}
+
+impl MongoSnoozeRepository {
+    pub fn new(database: &Database) -> Self {
+        Self {
+            collection: database.collection(SNOOZE_COLLECTION),
+            retry: WriteRetry::default(),
+        }
+    }
+
+    pub fn with_retry(mut self, retry: WriteRetry) -> Self {
+        self.retry = retry;
+        self
+    }
+}
+
+#[async_trait]
+impl SnoozeRepository for MongoSnoozeRepository {
+    #[tracing::instrument(name = "Upsert snooze", skip(self, snooze))]
+    async fn upsert(&self, snooze: &Snooze) -> Result<()> {
+        self.retry
+            .write("expected to upsert snooze", || {
+                self.collection
+                    .replace_one(doc! { "user_id": snooze.user_id }, snooze)
+                    .upsert(true)
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Find snooze", skip(self))]
+    async fn find(&self, user_id: UserId) -> Result<Option<Snooze>> {
+        self.collection
+            .find_one(doc! { "user_id": user_id })
+            .await
+            .context("expected to find snooze")
+    }
+
+    #[tracing::instrument(name = "List active snoozes", skip(self))]
+    async fn list_active(&self, now: DateTime<Utc>) -> Result<Vec<Snooze>> {
+        self.collection
+            .find(doc! { "until": { "$gt": bson::DateTime::from_chrono(now) } })
+            .sort(doc! { "until": 1 })
+            .await
+            .context("expected to list snoozes")?
+            .try_collect()
+            .await
+            .context("expected to read snoozes")
+    }
+
+    #[tracing::instrument(name = "Delete snooze", skip(self))]
+    async fn delete(&self, user_id: UserId) -> Result<bool> {
+        let result = self
+            .retry
+            .write("expected to delete snooze", || {
+                self.collection.delete_one(doc! { "user_id": user_id })
+            })
+            .await?;
+
+        Ok(result.deleted_count > 0)
+    }
+}
@@ -0,0 +1,125 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use bson::{doc, oid::ObjectId, Document};
+use chrono::NaiveDate;
+use mongodb::{Collection, Database};
+
+use crate::domain::{
+    id::GuildId,
+    sprint::{Goal, Sprint, SprintRepository},
+};
+
+use super::WriteRetry;
+
+pub const SPRINT_COLLECTION: &str = "sprints";
+
+#[derive(Clone)]
+pub struct MongoSprintRepository {
+    collection: Collection<Sprint>,
+    retry: WriteRetry,
+}
+
+impl MongoSprintRepository {
+    pub fn new(database: &Database) -> Self {
+        Self {
+            collection: database.collection(SPRINT_COLLECTION),
+            retry: WriteRetry::default(),
+        }
+    }
+
+    pub fn with_retry(mut self, retry: WriteRetry) -> Self {
+        self.retry = retry;
+        self
+    }
+}
+
+#[async_trait]
+impl SprintRepository for MongoSprintRepository {
+    #[tracing::instrument(name = "Insert sprint", skip(self, sprint))]
+    async fn insert(&self, sprint: &Sprint) -> Result<ObjectId> {
+        self.retry
+            .insert("expected to insert sprint", &self.collection, sprint)
+            .await
+    }
+
+    #[tracing::instrument(name = "Upsert sprint", skip(self, sprint), fields(sprint_id = ?sprint.id))]
+    async fn upsert(&self, sprint: &Sprint) -> Result<()> {
+        let id = sprint.id.context("expected the sprint to have an id")?;
+        self.retry
+            .write("expected to upsert sprint", || {
+                self.collection
+                    .replace_one(doc! { "_id": id }, sprint)
+                    .upsert(true)
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Find sprint", skip(self))]
+    async fn find(&self, id: ObjectId) -> Result<Option<Sprint>> {
+        self.collection
+            .find_one(doc! { "_id": id })
+            .await
+            .context("expected to find sprint")
+    }
+
+    #[tracing::instrument(name = "Add sprint goal", skip(self, goal), fields(title = %goal.title))]
+    async fn add_goal(&self, id: ObjectId, goal: &Goal) -> Result<bool> {
+        let title = goal.title.as_str();
+        let goal = bson::to_bson(goal).context("expected sprint goal to serialize")?;
+        // The title guard keeps a retried push from adding the goal twice.
+        let updated = self
+            .retry
+            .write("expected to add sprint goal", || {
+                self.collection.update_one(
+                    doc! { "_id": id, "goals.title": { "$ne": title } },
+                    doc! { "$push": { "goals": &goal } },
+                )
+            })
+            .await?;
+
+        Ok(updated.modified_count == 1)
+    }
+
+    #[tracing::instrument(name = "Find sprint with the same dates", skip(self, sprint))]
+    async fn find_same_dates(&self, sprint: &Sprint) -> Result<Option<Sprint>> {
+        self.collection
+            .find_one(same_dates_filter(sprint))
+            .await
+            .context("expected to find sprint with the same dates")
+    }
+
+    #[tracing::instrument(name = "Find active sprint", skip(self))]
+    async fn find_active(
+        &self,
+        guild_id: GuildId,
+        team: Option<&str>,
+        date: NaiveDate,
+    ) -> Result<Option<Sprint>> {
+        // Dates are stored as ISO 8601 strings, which sort chronologically.
+        let date = date.to_string();
+
+        self.collection
+            .find_one(doc! {
+                "guild_id": guild_id,
+                // `null` also matches sprints created before teams existed.
+                "team": team,
+                "start_date": { "$lte": &date },
+                "end_date": { "$gte": &date },
+            })
+            .sort(doc! { "start_date": -1 })
+            .await
+            .context("expected to find active sprint")
+    }
+}
+
+fn same_dates_filter(sprint: &Sprint) -> Document {
+    doc! {
+        "_id": { "$ne": sprint.id },
+        "guild_id": sprint.guild_id,
+        "team": &sprint.team,
+        "start_date": sprint.start_date.to_string(),
+        "end_date": sprint.end_date.to_string(),
+    }
+}
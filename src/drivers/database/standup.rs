@@ -0,0 +1,271 @@
+use std::collections::HashSet;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use bson::{doc, oid::ObjectId, Bson, Document};
+use chrono::{DateTime, NaiveDate, Utc};
+use futures::TryStreamExt;
+use mongodb::{options::ReturnDocument, Collection, Database, IndexModel};
+
+use crate::domain::{
+    id::{GuildId, UserId},
+    standup::{HistoryQuery, StandupEntry, StandupRepository, Upserted},
+};
+
+use super::WriteRetry;
+
+pub const STANDUP_COLLECTION: &str = "standups";
+
+/// Keeps a single entry per member, channel and day.
+pub const STANDUP_DAY_INDEX: &str = "guild_user_channel_date";
+
+#[derive(Clone)]
+pub struct MongoStandupRepository {
+    collection: Collection<StandupEntry>,
+    retry: WriteRetry,
+}
+
+impl MongoStandupRepository {
+    pub fn new(database: &Database) -> Self {
+        Self {
+            collection: database.collection(STANDUP_COLLECTION),
+            retry: WriteRetry::default(),
+        }
+    }
+
+    pub fn with_retry(mut self, retry: WriteRetry) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Create the unique index [`StandupRepository::upsert`] relies on, a
+    /// no-op when it already exists.
+    #[tracing::instrument(name = "Create standup indexes", skip(self))]
+    pub async fn create_indexes(&self) -> Result<()> {
+        let index = IndexModel::builder()
+            .keys(doc! { "guild_id": 1, "user_id": 1, "channel_id": 1, "date": 1 })
+            .options(
+                mongodb::options::IndexOptions::builder()
+                    .name(STANDUP_DAY_INDEX.to_owned())
+                    .unique(true)
+                    .build(),
+            )
+            .build();
+
+        self.collection
+            .create_index(index)
+            .await
+            .context("expected to create standup indexes")?;
+
+        Ok(())
+    }
+}
+
+/// The entries of the member in the date range of `query`, dates being
+/// stored as `YYYY-MM-DD` strings which sort like the dates.
+fn history_filter(query: &HistoryQuery) -> Document {
+    doc! {
+        "guild_id": query.guild_id,
+        "user_id": query.user_id,
+        "date": { "$gte": query.from.to_string(), "$lte": query.to.to_string() },
+    }
+}
+
+#[async_trait]
+impl StandupRepository for MongoStandupRepository {
+    #[tracing::instrument(name = "Insert standup entry", skip(self, entry))]
+    async fn insert(&self, entry: &StandupEntry) -> Result<ObjectId> {
+        self.retry
+            .insert("expected to insert standup entry", &self.collection, entry)
+            .await
+    }
+
+    #[tracing::instrument(name = "Find standup entry", skip(self))]
+    async fn find(&self, id: ObjectId) -> Result<Option<StandupEntry>> {
+        self.collection
+            .find_one(doc! { "_id": id })
+            .await
+            .context("expected to find standup entry")
+    }
+
+    #[tracing::instrument(name = "Upsert standup entry", skip(self, entry))]
+    async fn upsert(&self, entry: &StandupEntry) -> Result<Upserted> {
+        let mut document =
+            bson::to_document(entry).context("expected standup entry to serialize")?;
+        document.remove("_id");
+        let created_at = document
+            .remove("created_at")
+            .context("expected standup entry to have created_at")?;
+
+        let filter = doc! {
+            "guild_id": entry.guild_id,
+            "user_id": entry.user_id,
+            "channel_id": entry.channel_id,
+            "date": entry.date.to_string(),
+        };
+
+        // The id is only stored on insert, it tells a creation from an edit.
+        let id = ObjectId::new();
+        let update = doc! {
+            "$set": document,
+            "$setOnInsert": { "_id": id, "created_at": created_at },
+        };
+        let stored = self
+            .retry
+            .write("expected to upsert standup entry", || {
+                self.collection
+                    .find_one_and_update(filter.clone(), update.clone())
+                    .upsert(true)
+                    .return_document(ReturnDocument::After)
+            })
+            .await?
+            .context("expected upserted standup entry to be returned")?;
+
+        Ok(Upserted {
+            created: stored.id == Some(id),
+            entry: stored,
+        })
+    }
+
+    #[tracing::instrument(name = "List standups of sprint", skip(self))]
+    async fn list_for_sprint(&self, sprint_id: ObjectId) -> Result<Vec<StandupEntry>> {
+        self.collection
+            .find(doc! { "sprint_id": sprint_id })
+            .sort(doc! { "created_at": 1 })
+            .await
+            .context("expected to list standups of sprint")?
+            .try_collect()
+            .await
+            .context("expected to read standups of sprint")
+    }
+
+    #[tracing::instrument(name = "Find first standup of sprint", skip(self))]
+    async fn first_for_sprint(&self, sprint_id: ObjectId) -> Result<Option<StandupEntry>> {
+        self.collection
+            .find_one(doc! { "sprint_id": sprint_id })
+            .sort(doc! { "created_at": 1 })
+            .await
+            .context("expected to find first standup of sprint")
+    }
+
+    #[tracing::instrument(name = "Find standup participants", skip(self))]
+    async fn participants(&self, guild_id: GuildId, date: NaiveDate) -> Result<HashSet<UserId>> {
+        let user_ids = self
+            .collection
+            .distinct(
+                "user_id",
+                doc! { "guild_id": guild_id, "date": date.to_string() },
+            )
+            .await
+            .context("expected to find standup participants")?;
+
+        user_ids
+            .into_iter()
+            .map(|user_id| match user_id {
+                Bson::Int64(user_id) => Ok(UserId(user_id as u64)),
+                other => anyhow::bail!("expected user id to be an int64, got {:?}", other),
+            })
+            .collect()
+    }
+
+    #[tracing::instrument(name = "Delete old standup entries", skip(self))]
+    async fn delete_created_before(&self, cutoff: DateTime<Utc>, limit: usize) -> Result<u64> {
+        // `delete_many` has no limit, the batch is picked by id first.
+        let ids = self
+            .collection
+            .clone_with_type::<Document>()
+            .find(doc! { "created_at": { "$lt": bson::DateTime::from_chrono(cutoff) } })
+            .projection(doc! { "_id": 1 })
+            .sort(doc! { "created_at": 1 })
+            .limit(limit as i64)
+            .await
+            .context("expected to find old standup entries")?
+            .try_collect::<Vec<_>>()
+            .await
+            .context("expected to read old standup entries")?
+            .into_iter()
+            .filter_map(|document| document.get_object_id("_id").ok())
+            .collect::<Vec<_>>();
+
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let result = self
+            .retry
+            .write("expected to delete old standup entries", || {
+                self.collection
+                    .delete_many(doc! { "_id": { "$in": ids.clone() } })
+            })
+            .await?;
+
+        Ok(result.deleted_count)
+    }
+
+    #[tracing::instrument(name = "Find standup history", skip(self))]
+    async fn history(&self, query: &HistoryQuery) -> Result<Vec<StandupEntry>> {
+        self.collection
+            .find(history_filter(query))
+            .sort(doc! { "date": -1, "created_at": -1 })
+            .skip(query.skip as u64)
+            .limit(query.limit as i64)
+            .await
+            .context("expected to find standup history")?
+            .try_collect()
+            .await
+            .context("expected to read standup history")
+    }
+
+    #[tracing::instrument(name = "Find standup blockers", skip(self))]
+    async fn blockers_between(
+        &self,
+        guild_id: GuildId,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<String>> {
+        let documents: Vec<Document> = self
+            .collection
+            .clone_with_type::<Document>()
+            .find(doc! {
+                "guild_id": guild_id,
+                "date": { "$gte": from.to_string(), "$lte": to.to_string() },
+                "blockers": { "$ne": "" },
+            })
+            .projection(doc! { "_id": 0, "blockers": 1 })
+            .await
+            .context("expected to find standup blockers")?
+            .try_collect()
+            .await
+            .context("expected to read standup blockers")?;
+
+        documents
+            .iter()
+            .map(|document| {
+                document
+                    .get_str("blockers")
+                    .map(str::to_owned)
+                    .context("expected blockers to be a string")
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_filter_bounds_the_dates_of_the_member() {
+        let today = NaiveDate::from_ymd_opt(2024, 10, 15).unwrap();
+        let query = HistoryQuery::last_days(GuildId(1), UserId(3), today, 7, 1);
+
+        assert_eq!(
+            history_filter(&query),
+            doc! {
+                "guild_id": 1_i64,
+                "user_id": 3_i64,
+                "date": { "$gte": "2024-10-09", "$lte": "2024-10-15" },
+            }
+        );
+    }
+}
@@ -0,0 +1,506 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tokio::sync::Semaphore;
+
+use crate::domain::{
+    audit::Auditor,
+    feature::FeatureFlags,
+    guild::{GuildConfig, GuildConfigRepository, Member},
+    id::{ChannelId, GuildId, RoleId, UserId},
+};
+
+use super::registry::CommandRegistry;
+
+pub const DISABLED_REPLY: &str = "command disabled";
+pub const ADMIN_ONLY_REPLY: &str = "only admins can use this command";
+pub const BUSY_REPLY: &str = "busy, try again in a moment";
+
+/// Where the invocations turned away by the concurrency limit are counted.
+pub trait BusyCounter: Send + Sync {
+    fn rejected(&self);
+}
+
+/// A slash command invoked by a member.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invocation {
+    pub name: String,
+    pub guild_id: Option<GuildId>,
+    pub channel_id: ChannelId,
+    pub user_id: UserId,
+    /// The guild member behind the invocation, `None` in direct messages.
+    pub member: Option<Member>,
+    pub options: HashMap<String, String>,
+}
+
+impl Invocation {
+    /// The roles of the invoking member, none in direct messages.
+    pub fn roles(&self) -> &[RoleId] {
+        self.member
+            .as_ref()
+            .map_or(&[], |member| member.roles.as_slice())
+    }
+
+    /// The command with its subcommand group and subcommand, e.g.
+    /// `sprint goal add`.
+    pub fn command_path(&self) -> String {
+        let mut path = self.name.clone();
+        for key in ["subcommand_group", "subcommand"] {
+            if let Some(name) = self.options.get(key) {
+                path.push(' ');
+                path.push_str(name);
+            }
+        }
+        path
+    }
+
+    /// The arguments of the invocation, subcommands aside.
+    pub fn arguments(&self) -> BTreeMap<String, String> {
+        self.options
+            .iter()
+            .filter(|(key, _)| !matches!(key.as_str(), "subcommand_group" | "subcommand"))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+}
+
+/// What the bot answers to an [`Invocation`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Reply {
+    pub content: String,
+    pub embed: Option<Embed>,
+    pub ephemeral: bool,
+}
+
+impl Reply {
+    pub fn public(content: impl Into<String>) -> Self {
+        Self {
+            content: content.into(),
+            embed: None,
+            ephemeral: false,
+        }
+    }
+
+    pub fn ephemeral(content: impl Into<String>) -> Self {
+        Self {
+            content: content.into(),
+            embed: None,
+            ephemeral: true,
+        }
+    }
+
+    pub fn with_embed(mut self, embed: Embed) -> Self {
+        self.embed = Some(embed);
+        self
+    }
+}
+
+/// A rich card attached to a [`Reply`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Embed {
+    pub title: String,
+    pub description: String,
+    /// `(name, value)` pairs, in display order.
+    pub fields: Vec<(String, String)>,
+}
+
+#[async_trait]
+pub trait CommandHandler: Send + Sync {
+    async fn handle(&self, invocation: &Invocation) -> Result<Reply>;
+}
+
+/// Runs the wrapped admin command only for the admins of the guild, see
+/// [`GuildConfig::is_admin`], and audits each run.
+pub struct AdminOnly {
+    handler: Arc<dyn CommandHandler>,
+    guilds: Arc<dyn GuildConfigRepository>,
+    auditor: Auditor,
+}
+
+impl AdminOnly {
+    pub fn new(
+        handler: Arc<dyn CommandHandler>,
+        guilds: Arc<dyn GuildConfigRepository>,
+        auditor: Auditor,
+    ) -> Self {
+        Self {
+            handler,
+            guilds,
+            auditor,
+        }
+    }
+}
+
+#[async_trait]
+impl CommandHandler for AdminOnly {
+    async fn handle(&self, invocation: &Invocation) -> Result<Reply> {
+        let (Some(guild_id), Some(member)) = (invocation.guild_id, &invocation.member) else {
+            return Ok(Reply::ephemeral(ADMIN_ONLY_REPLY));
+        };
+
+        let config = self
+            .guilds
+            .find(guild_id)
+            .await?
+            .unwrap_or_else(|| GuildConfig::new(guild_id));
+        if !config.is_admin(member) {
+            tracing::info!(user_id = %invocation.user_id, "admin command denied");
+            return Ok(Reply::ephemeral(ADMIN_ONLY_REPLY));
+        }
+
+        let reply = self.handler.handle(invocation).await?;
+        // The command already ran, failing now would report it as not done.
+        if let Err(error) = self
+            .auditor
+            .record_command(
+                invocation.user_id.to_string(),
+                guild_id,
+                invocation.command_path(),
+                invocation.arguments(),
+            )
+            .await
+        {
+            tracing::error!(error = ?error, command = %invocation.name, "failed to audit the admin command");
+        }
+
+        Ok(reply)
+    }
+}
+
+/// Routes invocations to their handler, honoring the feature flags.
+#[derive(Clone)]
+pub struct Dispatcher {
+    handlers: HashMap<String, Arc<dyn CommandHandler>>,
+    features: FeatureFlags,
+    in_flight: Option<Arc<Semaphore>>,
+    busy: Option<Arc<dyn BusyCounter>>,
+}
+
+impl Dispatcher {
+    pub fn new(features: FeatureFlags) -> Self {
+        Self {
+            handlers: HashMap::new(),
+            features,
+            in_flight: None,
+            busy: None,
+        }
+    }
+
+    /// Handle at most `max` invocations at once, the others are answered
+    /// [`BUSY_REPLY`] right away instead of queueing during a flood.
+    pub fn with_concurrency_limit(mut self, max: usize) -> Self {
+        self.in_flight = Some(Arc::new(Semaphore::new(max.max(1))));
+        self
+    }
+
+    pub fn with_busy_counter(mut self, busy: Arc<dyn BusyCounter>) -> Self {
+        self.busy = Some(busy);
+        self
+    }
+
+    /// Route to every command of `registry`.
+    pub fn with_registry(mut self, registry: &CommandRegistry) -> Self {
+        for (name, handler) in registry.handlers() {
+            self.handlers.insert(name.to_owned(), handler.clone());
+        }
+        self
+    }
+
+    pub fn with_command(
+        mut self,
+        name: impl Into<String>,
+        handler: Arc<dyn CommandHandler>,
+    ) -> Self {
+        self.handlers.insert(name.into(), handler);
+        self
+    }
+
+    #[tracing::instrument(name = "Dispatch command", skip(self, invocation), fields(command = %invocation.name))]
+    pub async fn dispatch(&self, invocation: &Invocation) -> Result<Reply> {
+        let Some(handler) = self.handlers.get(&invocation.name) else {
+            anyhow::bail!("unknown command {:?}", invocation.name);
+        };
+
+        if !self.features.is_enabled(&invocation.name) {
+            tracing::info!("command is disabled by feature flag");
+            return Ok(Reply::ephemeral(DISABLED_REPLY));
+        }
+
+        // Held until the handler is done.
+        let _permit = match &self.in_flight {
+            Some(in_flight) => match in_flight.try_acquire() {
+                Ok(permit) => Some(permit),
+                Err(_) => {
+                    tracing::warn!("too many concurrent interactions, command rejected");
+                    if let Some(busy) = &self.busy {
+                        busy.rejected();
+                    }
+                    return Ok(Reply::ephemeral(BUSY_REPLY));
+                }
+            },
+            None => None,
+        };
+
+        handler.handle(invocation).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use tokio::sync::Notify;
+
+    use crate::{
+        domain::audit::{AuditAction, AuditEntry, AuditQuery, AuditRepository},
+        drivers::{
+            database::memory::{InMemoryAuditRepository, InMemoryGuildConfigRepository},
+            discord::registry::CommandSpec,
+        },
+    };
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Pong(AtomicUsize);
+
+    #[async_trait]
+    impl CommandHandler for Pong {
+        async fn handle(&self, _: &Invocation) -> Result<Reply> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(Reply::public("pong"))
+        }
+    }
+
+    fn ping() -> Invocation {
+        Invocation {
+            name: "ping".into(),
+            guild_id: Some(GuildId(1)),
+            channel_id: ChannelId(2),
+            user_id: UserId(3),
+            member: None,
+            options: HashMap::new(),
+        }
+    }
+
+    async fn admin_only(
+        handler: Arc<Pong>,
+        roles: &[u64],
+    ) -> (AdminOnly, Invocation, Arc<InMemoryAuditRepository>) {
+        let guilds = Arc::new(InMemoryGuildConfigRepository::default());
+        let mut config = GuildConfig::new(GuildId(1));
+        config.admin_roles = vec![RoleId(10)];
+        guilds.upsert(&config).await.unwrap();
+
+        let mut invocation = ping();
+        invocation.member = Some(Member {
+            roles: roles.iter().copied().map(RoleId).collect(),
+            ..Member::default()
+        });
+
+        invocation.options = HashMap::from([
+            ("subcommand".to_owned(), "run".to_owned()),
+            ("count".to_owned(), "2".to_owned()),
+        ]);
+        let audit_log = Arc::new(InMemoryAuditRepository::default());
+        let command = AdminOnly::new(handler, guilds, Auditor::new(Some(audit_log.clone())));
+
+        (command, invocation, audit_log)
+    }
+
+    #[tokio::test]
+    async fn admin_only_runs_for_admins() {
+        let handler = Arc::new(Pong::default());
+        let (command, invocation, audit_log) = admin_only(handler.clone(), &[10]).await;
+
+        let reply = command.handle(&invocation).await.unwrap();
+
+        assert_eq!(reply, Reply::public("pong"));
+        let entries = audit_log.list(&AuditQuery::default()).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].actor, "3");
+        assert_eq!(entries[0].action, AuditAction::AdminCommand);
+        assert_eq!(entries[0].command.as_deref(), Some("ping run"));
+        assert_eq!(
+            entries[0].arguments,
+            BTreeMap::from([("count".to_owned(), "2".to_owned())])
+        );
+    }
+
+    struct FailingAudit;
+
+    #[async_trait]
+    impl AuditRepository for FailingAudit {
+        async fn insert(&self, _: &AuditEntry) -> Result<()> {
+            anyhow::bail!("audit log unavailable")
+        }
+
+        async fn list(&self, _: &AuditQuery) -> Result<Vec<AuditEntry>> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_audit_keeps_the_reply_of_the_command() {
+        let handler = Arc::new(Pong::default());
+        let (command, invocation, _) = admin_only(handler.clone(), &[10]).await;
+        let command = AdminOnly::new(
+            handler.clone(),
+            command.guilds,
+            Auditor::new(Some(Arc::new(FailingAudit))),
+        );
+
+        let reply = command.handle(&invocation).await.unwrap();
+
+        assert_eq!(reply, Reply::public("pong"));
+        assert_eq!(handler.0.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn admin_only_denies_other_members() {
+        let handler = Arc::new(Pong::default());
+        let (command, mut invocation, audit_log) = admin_only(handler.clone(), &[20]).await;
+
+        let reply = command.handle(&invocation).await.unwrap();
+        assert_eq!(reply, Reply::ephemeral(ADMIN_ONLY_REPLY));
+
+        invocation.guild_id = None;
+        let reply = command.handle(&invocation).await.unwrap();
+        assert_eq!(reply, Reply::ephemeral(ADMIN_ONLY_REPLY));
+
+        assert_eq!(handler.0.load(Ordering::SeqCst), 0);
+        assert!(audit_log
+            .list(&AuditQuery::default())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn disabled_command_short_circuits() {
+        let handler = Arc::new(Pong::default());
+        let features = FeatureFlags::new(HashMap::from([("ping".to_owned(), false)]));
+        let dispatcher = Dispatcher::new(features).with_command("ping", handler.clone());
+
+        let reply = dispatcher.dispatch(&ping()).await.unwrap();
+
+        assert_eq!(reply, Reply::ephemeral(DISABLED_REPLY));
+        assert_eq!(handler.0.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn enabled_command_proceeds() {
+        let handler = Arc::new(Pong::default());
+        let dispatcher =
+            Dispatcher::new(FeatureFlags::default()).with_command("ping", handler.clone());
+
+        let reply = dispatcher.dispatch(&ping()).await.unwrap();
+
+        assert_eq!(reply, Reply::public("pong"));
+        assert_eq!(handler.0.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn registry_commands_are_dispatched() {
+        let handler = Arc::new(Pong::default());
+        let mut registry = CommandRegistry::new();
+        registry
+            .register(CommandSpec::new("ping", "Answer pong"), handler.clone())
+            .unwrap();
+        let dispatcher = Dispatcher::new(FeatureFlags::default()).with_registry(&registry);
+
+        let reply = dispatcher.dispatch(&ping()).await.unwrap();
+
+        assert_eq!(reply, Reply::public("pong"));
+        assert_eq!(handler.0.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn reloaded_flags_apply_to_running_dispatcher() {
+        let handler = Arc::new(Pong::default());
+        let features = FeatureFlags::default();
+        let dispatcher = Dispatcher::new(features.clone()).with_command("ping", handler.clone());
+
+        features.replace(HashMap::from([("ping".to_owned(), false)]));
+
+        let reply = dispatcher.dispatch(&ping()).await.unwrap();
+        assert_eq!(reply.content, DISABLED_REPLY);
+    }
+
+    /// Holds every invocation until released.
+    #[derive(Default)]
+    struct Slow {
+        started: Notify,
+        release: Notify,
+    }
+
+    #[async_trait]
+    impl CommandHandler for Slow {
+        async fn handle(&self, _: &Invocation) -> Result<Reply> {
+            self.started.notify_one();
+            self.release.notified().await;
+            Ok(Reply::public("done"))
+        }
+    }
+
+    #[derive(Default)]
+    struct Busy(AtomicUsize);
+
+    impl BusyCounter for Busy {
+        fn rejected(&self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn invocations_beyond_the_limit_are_rejected() {
+        let slow = Arc::new(Slow::default());
+        let busy = Arc::new(Busy::default());
+        let dispatcher = Dispatcher::new(FeatureFlags::default())
+            .with_command("ping", slow.clone())
+            .with_concurrency_limit(1)
+            .with_busy_counter(busy.clone());
+
+        let running = tokio::spawn({
+            let dispatcher = dispatcher.clone();
+            async move { dispatcher.dispatch(&ping()).await }
+        });
+        slow.started.notified().await;
+
+        let reply = dispatcher.dispatch(&ping()).await.unwrap();
+        assert_eq!(reply, Reply::ephemeral(BUSY_REPLY));
+        assert_eq!(busy.0.load(Ordering::SeqCst), 1);
+
+        slow.release.notify_one();
+        assert_eq!(running.await.unwrap().unwrap(), Reply::public("done"));
+    }
+
+    #[tokio::test]
+    async fn invocations_within_the_limit_proceed() {
+        let handler = Arc::new(Pong::default());
+        let busy = Arc::new(Busy::default());
+        let dispatcher = Dispatcher::new(FeatureFlags::default())
+            .with_command("ping", handler.clone())
+            .with_concurrency_limit(2)
+            .with_busy_counter(busy.clone());
+
+        let invocation = ping();
+        let (first, second) = tokio::join!(
+            dispatcher.dispatch(&invocation),
+            dispatcher.dispatch(&invocation)
+        );
+        assert_eq!(first.unwrap(), Reply::public("pong"));
+        assert_eq!(second.unwrap(), Reply::public("pong"));
+
+        // Permits are given back once handled.
+        for _ in 0..3 {
+            dispatcher.dispatch(&ping()).await.unwrap();
+        }
+        assert_eq!(handler.0.load(Ordering::SeqCst), 5);
+        assert_eq!(busy.0.load(Ordering::SeqCst), 0);
+    }
+}
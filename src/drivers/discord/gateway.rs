@@ -0,0 +1,234 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+/// A change of the connection of a gateway shard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GatewayEvent {
+    /// The shard identified, `shard_count` is the total of shards of the bot.
+    Ready {
+        shard_id: u32,
+        shard_count: u32,
+    },
+    /// The shard reconnected and resumed its session.
+    Resumed {
+        shard_id: u32,
+    },
+    Disconnected {
+        shard_id: u32,
+    },
+}
+
+/// Where the connection of every shard is published.
+pub trait ShardGauge: Send + Sync {
+    fn set_connected(&self, shard_id: u32, connected: bool);
+    /// Forget a shard that no longer exists.
+    fn remove(&self, shard_id: u32);
+}
+
+/// Keeps the shard gauges in line with the gateway events.
+///
+/// Gauges are set, never incremented, so a missed event is fixed by the next
+/// one. A `Ready` with fewer shards, after the bot was resharded, removes the
+/// gauges of the shards that are gone.
+pub struct ShardMonitor {
+    gauge: Arc<dyn ShardGauge>,
+    connected: Mutex<BTreeMap<u32, bool>>,
+}
+
+impl ShardMonitor {
+    pub fn new(gauge: Arc<dyn ShardGauge>) -> Self {
+        Self {
+            gauge,
+            connected: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    #[tracing::instrument(name = "Handle gateway event", skip(self))]
+    pub fn handle(&self, event: GatewayEvent) {
+        let mut connected = self.connected.lock().unwrap();
+
+        match event {
+            GatewayEvent::Ready {
+                shard_id,
+                shard_count,
+            } => {
+                for gone in connected.split_off(&shard_count).into_keys() {
+                    tracing::info!(shard_id = gone, "shard removed");
+                    self.gauge.remove(gone);
+                }
+                self.set(&mut connected, shard_id, true);
+            }
+            GatewayEvent::Resumed { shard_id } => self.set(&mut connected, shard_id, true),
+            GatewayEvent::Disconnected { shard_id } => {
+                tracing::warn!(shard_id, "shard disconnected");
+                self.set(&mut connected, shard_id, false);
+            }
+        }
+    }
+
+    fn set(&self, connected: &mut BTreeMap<u32, bool>, shard_id: u32, state: bool) {
+        connected.insert(shard_id, state);
+        self.gauge.set_connected(shard_id, state);
+    }
+}
+
+/// Gateway intents by name, bit and whether Discord makes them privileged,
+/// which requires turning them on in the developer portal.
+const INTENTS: [(&str, u32, bool); 21] = [
+    ("guilds", 0, false),
+    ("guild_members", 1, true),
+    ("guild_moderation", 2, false),
+    ("guild_expressions", 3, false),
+    ("guild_integrations", 4, false),
+    ("guild_webhooks", 5, false),
+    ("guild_invites", 6, false),
+    ("guild_voice_states", 7, false),
+    ("guild_presences", 8, true),
+    ("guild_messages", 9, false),
+    ("guild_message_reactions", 10, false),
+    ("guild_message_typing", 11, false),
+    ("direct_messages", 12, false),
+    ("direct_message_reactions", 13, false),
+    ("direct_message_typing", 14, false),
+    ("message_content", 15, true),
+    ("guild_scheduled_events", 16, false),
+    ("auto_moderation_configuration", 20, false),
+    ("auto_moderation_execution", 21, false),
+    ("guild_message_polls", 24, false),
+    ("direct_message_polls", 25, false),
+];
+
+/// A name in `discord.intents` that is no gateway intent.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unknown gateway intent {0:?}")]
+pub struct UnknownIntent(pub String);
+
+/// The events the gateway sends the bot, sent as a bit set when identifying.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GatewayIntents(u64);
+
+impl GatewayIntents {
+    /// OR the intents named `names` together, e.g. `guild_messages`.
+    pub fn from_names<S: AsRef<str>>(names: &[S]) -> Result<Self, UnknownIntent> {
+        names.iter().try_fold(Self::default(), |intents, name| {
+            let name = name.as_ref();
+            let (_, bit, _) = INTENTS
+                .iter()
+                .find(|(known, _, _)| *known == name)
+                .ok_or_else(|| UnknownIntent(name.to_owned()))?;
+
+            Ok(Self(intents.0 | 1 << bit))
+        })
+    }
+
+    pub fn bits(self) -> u64 {
+        self.0
+    }
+
+    /// The privileged intents of the set, which Discord refuses to connect
+    /// with unless they are approved for the bot.
+    pub fn privileged(self) -> Vec<&'static str> {
+        INTENTS
+            .iter()
+            .filter(|(_, bit, privileged)| *privileged && self.0 & 1 << bit != 0)
+            .map(|(name, _, _)| *name)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        domain::delivery::SendFailureCounter,
+        observability::metrics::{DiscordMetrics, ShardLabels},
+    };
+
+    use super::*;
+
+    fn connected(metrics: &DiscordMetrics, shard_id: u32) -> i64 {
+        metrics
+            .shard_connected
+            .get_or_create(&ShardLabels { shard_id })
+            .get()
+    }
+
+    #[test]
+    fn ready_connects_and_disconnect_disconnects_the_shard() {
+        let metrics = Arc::new(DiscordMetrics::default());
+        let monitor = ShardMonitor::new(metrics.clone());
+
+        monitor.handle(GatewayEvent::Ready {
+            shard_id: 1,
+            shard_count: 2,
+        });
+        assert_eq!(connected(&metrics, 1), 1);
+
+        monitor.handle(GatewayEvent::Disconnected { shard_id: 1 });
+        assert_eq!(connected(&metrics, 1), 0);
+
+        monitor.handle(GatewayEvent::Resumed { shard_id: 1 });
+        assert_eq!(connected(&metrics, 1), 1);
+    }
+
+    #[test]
+    fn resharding_removes_the_shards_that_are_gone() {
+        let metrics = Arc::new(DiscordMetrics::default());
+        let monitor = ShardMonitor::new(metrics.clone());
+        for shard_id in 0..4 {
+            monitor.handle(GatewayEvent::Ready {
+                shard_id,
+                shard_count: 4,
+            });
+        }
+
+        monitor.handle(GatewayEvent::Ready {
+            shard_id: 0,
+            shard_count: 2,
+        });
+
+        assert!(!metrics.shard_connected.remove(&ShardLabels { shard_id: 3 }));
+        assert_eq!(connected(&metrics, 1), 1);
+    }
+
+    #[test]
+    fn send_failures_are_labeled_with_the_shard() {
+        let metrics = DiscordMetrics::default();
+
+        metrics.shard(2).increment();
+
+        let failures = |shard_id| {
+            metrics
+                .send_failures
+                .get_or_create(&ShardLabels { shard_id })
+                .get()
+        };
+        assert_eq!(failures(2), 1);
+        assert_eq!(failures(0), 0);
+    }
+
+    #[test]
+    fn intent_names_are_ored_together() {
+        let intents =
+            GatewayIntents::from_names(&["guilds", "guild_messages", "message_content"]).unwrap();
+
+        assert_eq!(intents.bits(), 1 | 1 << 9 | 1 << 15);
+        assert_eq!(intents.privileged(), vec!["message_content"]);
+        assert_eq!(
+            GatewayIntents::from_names::<&str>(&[]).unwrap(),
+            GatewayIntents::default()
+        );
+    }
+
+    #[test]
+    fn unknown_intent_names_are_rejected() {
+        let error = GatewayIntents::from_names(&["guilds", "GUILD_MESSAGES"]).unwrap_err();
+
+        assert_eq!(error, UnknownIntent("GUILD_MESSAGES".into()));
+        assert_eq!(
+            error.to_string(),
+            "unknown gateway intent \"GUILD_MESSAGES\""
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+
+use crate::domain::snooze::{Snooze, SnoozeRepository};
+
+use super::{
+    command::{CommandHandler, Invocation, Reply},
+    registry::{CommandOption, CommandSpec},
+};
+
+pub const ME_COMMAND: &str = "me";
+
+const USAGE: &str = "usage: /me snooze <days>";
+
+/// `/me snooze <days>`, pauses the reminders of the member, e.g. during vacations.
+pub struct MeCommand {
+    snoozes: Arc<dyn SnoozeRepository>,
+}
+
+impl MeCommand {
+    pub fn new(snoozes: Arc<dyn SnoozeRepository>) -> Self {
+        Self { snoozes }
+    }
+
+    pub fn spec() -> CommandSpec {
+        CommandSpec::new(ME_COMMAND, "Manage your own settings").with_options(vec![
+            CommandOption::subcommand(
+                "snooze",
+                "Pause your reminders",
+                vec![CommandOption::integer("days", "Days to pause them for").required()],
+            ),
+        ])
+    }
+}
+
+#[async_trait]
+impl CommandHandler for MeCommand {
+    async fn handle(&self, invocation: &Invocation) -> Result<Reply> {
+        let option = |name: &str| invocation.options.get(name).map(String::as_str);
+        let (Some("snooze"), Some(days)) = (option("subcommand"), option("days")) else {
+            return Ok(Reply::ephemeral(USAGE));
+        };
+        let Ok(days) = days.trim().parse() else {
+            return Ok(Reply::ephemeral(USAGE));
+        };
+
+        let snooze = match Snooze::for_days(invocation.user_id, days, Utc::now()) {
+            Ok(snooze) => snooze,
+            Err(error) => return Ok(Reply::ephemeral(error.to_string())),
+        };
+        self.snoozes.upsert(&snooze).await?;
+
+        Ok(Reply::ephemeral(format!(
+            "Reminders snoozed until <t:{}:f>",
+            snooze.until.timestamp()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::{
+        domain::id::{ChannelId, UserId},
+        drivers::database::memory::InMemorySnoozeRepository,
+    };
+
+    use super::*;
+
+    fn snooze(days: &str) -> Invocation {
+        Invocation {
+            name: ME_COMMAND.into(),
+            guild_id: None,
+            channel_id: ChannelId(2),
+            user_id: UserId(3),
+            member: None,
+            options: HashMap::from([
+                ("subcommand".to_owned(), "snooze".to_owned()),
+                ("days".to_owned(), days.to_owned()),
+            ]),
+        }
+    }
+
+    #[tokio::test]
+    async fn snooze_is_stored_for_the_member() {
+        let snoozes = Arc::new(InMemorySnoozeRepository::default());
+
+        let reply = MeCommand::new(snoozes.clone())
+            .handle(&snooze("7"))
+            .await
+            .unwrap();
+
+        assert!(reply.content.starts_with("Reminders snoozed until"));
+        assert!(snoozes.is_snoozed(UserId(3), Utc::now()).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn invalid_days_are_rejected() {
+        let snoozes = Arc::new(InMemorySnoozeRepository::default());
+        let command = MeCommand::new(snoozes.clone());
+
+        assert_eq!(
+            command.handle(&snooze("soon")).await.unwrap().content,
+            USAGE
+        );
+        assert_eq!(
+            command.handle(&snooze("0")).await.unwrap().content,
+            "snooze must be between 1 and 60 days"
+        );
+        assert!(snoozes.find(UserId(3)).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,65 @@
+use std::borrow::Cow;
+
+/// Hard limit for the content of a message.
+pub const MESSAGE_CONTENT_LIMIT: usize = 2000;
+/// Hard limit for the description of an embed.
+pub const EMBED_DESCRIPTION_LIMIT: usize = 4096;
+/// Hard limit for the value of an embed field.
+pub const EMBED_FIELD_VALUE_LIMIT: usize = 1024;
+
+pub const TRUNCATION_NOTE: &str = "...(truncated)";
+
+/// Cap `content` to `limit` characters, replacing the overflow with [`TRUNCATION_NOTE`].
+///
+/// Discord counts characters and not bytes, so we do the same to never split a
+/// multi-byte character in half.
+pub fn truncate(content: &str, limit: usize) -> Cow<'_, str> {
+    if content.chars().count() <= limit {
+        return Cow::Borrowed(content);
+    }
+
+    let note_length = TRUNCATION_NOTE.chars().count();
+    if limit <= note_length {
+        return Cow::Owned(content.chars().take(limit).collect());
+    }
+
+    let kept: String = content.chars().take(limit - note_length).collect();
+    let mut truncated = kept.trim_end().to_owned();
+    truncated.push_str(TRUNCATION_NOTE);
+
+    Cow::Owned(truncated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_under_the_limit_is_untouched() {
+        let content = "yesterday: fixed the scheduler";
+
+        assert_eq!(truncate(content, 100), Cow::Borrowed(content));
+    }
+
+    #[test]
+    fn content_at_the_limit_is_untouched() {
+        let content = "a".repeat(MESSAGE_CONTENT_LIMIT);
+
+        assert_eq!(truncate(&content, MESSAGE_CONTENT_LIMIT), content.as_str());
+    }
+
+    #[test]
+    fn content_over_the_limit_is_truncated_with_a_note() {
+        let content = "é".repeat(MESSAGE_CONTENT_LIMIT + 10);
+
+        let truncated = truncate(&content, MESSAGE_CONTENT_LIMIT);
+
+        assert_eq!(truncated.chars().count(), MESSAGE_CONTENT_LIMIT);
+        assert!(truncated.ends_with(TRUNCATION_NOTE));
+    }
+
+    #[test]
+    fn limit_smaller_than_the_note_hard_cuts() {
+        assert_eq!(truncate("standup", 3), "sta");
+    }
+}
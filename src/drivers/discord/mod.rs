@@ -0,0 +1,11 @@
+pub mod command;
+pub mod gateway;
+pub mod me;
+pub mod message;
+pub mod registry;
+pub mod remind;
+pub mod rest;
+pub mod shard;
+pub mod sprint;
+pub mod standup;
+pub mod whoami;
@@ -0,0 +1,414 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::domain::{
+    guild::{GuildConfig, GuildConfigRepository},
+    id::GuildId,
+};
+
+use super::command::{CommandHandler, Embed, Invocation, Reply};
+
+pub const HELP_COMMAND: &str = "help";
+
+/// What Discord and `/help` are told about a slash command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandSpec {
+    pub name: String,
+    pub description: String,
+    /// Only the guild admins may run it, see [`GuildConfig::is_admin`].
+    pub admin_only: bool,
+    /// Its arguments or subcommands, in the order Discord shows them.
+    pub options: Vec<CommandOption>,
+}
+
+impl CommandSpec {
+    pub fn new(name: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            admin_only: false,
+            options: Vec::new(),
+        }
+    }
+
+    pub fn admin_only(mut self) -> Self {
+        self.admin_only = true;
+        self
+    }
+
+    pub fn with_options(mut self, options: Vec<CommandOption>) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+/// The kinds of [`CommandOption`] the commands use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionKind {
+    Subcommand,
+    SubcommandGroup,
+    String,
+    Integer,
+    Boolean,
+}
+
+/// An argument of a slash command, or one of its subcommands. Whatever the
+/// kind, the handler reads it from [`Invocation::options`] by name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandOption {
+    pub name: String,
+    pub description: String,
+    pub kind: OptionKind,
+    pub required: bool,
+    /// The options of a subcommand, or the subcommands of a group.
+    pub options: Vec<CommandOption>,
+}
+
+impl CommandOption {
+    fn new(name: &str, description: &str, kind: OptionKind) -> Self {
+        Self {
+            name: name.to_owned(),
+            description: description.to_owned(),
+            kind,
+            required: false,
+            options: Vec::new(),
+        }
+    }
+
+    pub fn subcommand(name: &str, description: &str, options: Vec<CommandOption>) -> Self {
+        Self {
+            options,
+            ..Self::new(name, description, OptionKind::Subcommand)
+        }
+    }
+
+    pub fn group(name: &str, description: &str, subcommands: Vec<CommandOption>) -> Self {
+        Self {
+            options: subcommands,
+            ..Self::new(name, description, OptionKind::SubcommandGroup)
+        }
+    }
+
+    pub fn string(name: &str, description: &str) -> Self {
+        Self::new(name, description, OptionKind::String)
+    }
+
+    pub fn integer(name: &str, description: &str) -> Self {
+        Self::new(name, description, OptionKind::Integer)
+    }
+
+    pub fn boolean(name: &str, description: &str) -> Self {
+        Self::new(name, description, OptionKind::Boolean)
+    }
+
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+}
+
+/// The application commands API of Discord, which the specs are sent to
+/// after deploying new commands.
+#[async_trait]
+pub trait CommandRegistrar: Send + Sync {
+    /// Replace the global commands of the bot with `specs`, or the commands
+    /// of `guild_id` only. Guild commands show up at once, which suits
+    /// development, global ones can take up to an hour.
+    async fn overwrite(&self, guild_id: Option<GuildId>, specs: &[CommandSpec]) -> Result<()>;
+}
+
+/// A second command was registered under a taken name.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("command /{0} is already registered")]
+pub struct DuplicateCommand(pub String);
+
+/// Every slash command of the bot by name, the single list the Discord
+/// registration, the dispatcher and `/help` are built from.
+#[derive(Clone, Default)]
+pub struct CommandRegistry {
+    commands: BTreeMap<String, (CommandSpec, Arc<dyn CommandHandler>)>,
+}
+
+impl CommandRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(
+        &mut self,
+        spec: CommandSpec,
+        handler: Arc<dyn CommandHandler>,
+    ) -> Result<(), DuplicateCommand> {
+        if self.commands.contains_key(&spec.name) {
+            return Err(DuplicateCommand(spec.name));
+        }
+
+        self.commands.insert(spec.name.clone(), (spec, handler));
+        Ok(())
+    }
+
+    /// Register `/help`, listing every command registered so far and itself,
+    /// but those disabled in the guild of the invocation.
+    pub fn with_help(
+        mut self,
+        guilds: Arc<dyn GuildConfigRepository>,
+    ) -> Result<Self, DuplicateCommand> {
+        let spec = CommandSpec::new(HELP_COMMAND, "List the commands of the bot");
+        let mut commands = self.specs();
+        commands.push(spec.clone());
+        commands.sort_by(|a, b| a.name.cmp(&b.name));
+
+        self.register(spec, Arc::new(HelpCommand { commands, guilds }))?;
+        Ok(self)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Arc<dyn CommandHandler>> {
+        self.commands.get(name).map(|(_, handler)| handler)
+    }
+
+    /// The commands to register with Discord, by name.
+    pub fn specs(&self) -> Vec<CommandSpec> {
+        self.commands
+            .values()
+            .map(|(spec, _)| spec.clone())
+            .collect()
+    }
+
+    pub fn handlers(&self) -> impl Iterator<Item = (&str, &Arc<dyn CommandHandler>)> {
+        self.commands
+            .iter()
+            .map(|(name, (_, handler))| (name.as_str(), handler))
+    }
+}
+
+/// `/help`, lists the registered commands enabled in the guild.
+struct HelpCommand {
+    commands: Vec<CommandSpec>,
+    guilds: Arc<dyn GuildConfigRepository>,
+}
+
+#[async_trait]
+impl CommandHandler for HelpCommand {
+    async fn handle(&self, invocation: &Invocation) -> Result<Reply> {
+        // Direct messages run every command.
+        let config = match invocation.guild_id {
+            Some(guild_id) => Some(
+                self.guilds
+                    .find(guild_id)
+                    .await?
+                    .unwrap_or_else(|| GuildConfig::new(guild_id)),
+            ),
+            None => None,
+        };
+
+        let fields: Vec<_> = self
+            .commands
+            .iter()
+            .filter(|spec| {
+                config
+                    .as_ref()
+                    .is_none_or(|config| config.is_command_enabled(&spec.name))
+            })
+            .map(|spec| {
+                let description = if spec.admin_only {
+                    format!("{} (admins only)", spec.description)
+                } else {
+                    spec.description.clone()
+                };
+                (format!("/{}", spec.name), description)
+            })
+            .collect();
+
+        let description = if fields.is_empty() {
+            "No commands are enabled in this server".to_owned()
+        } else {
+            String::new()
+        };
+        let embed = Embed {
+            title: "Commands".into(),
+            description,
+            fields,
+        };
+
+        Ok(Reply::ephemeral(String::new()).with_embed(embed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::{
+        domain::id::{ChannelId, UserId},
+        drivers::database::memory::InMemoryGuildConfigRepository,
+    };
+
+    use super::*;
+
+    fn guilds() -> Arc<InMemoryGuildConfigRepository> {
+        Arc::new(InMemoryGuildConfigRepository::default())
+    }
+
+    struct Echo(&'static str);
+
+    #[async_trait]
+    impl CommandHandler for Echo {
+        async fn handle(&self, _: &Invocation) -> Result<Reply> {
+            Ok(Reply::public(self.0))
+        }
+    }
+
+    fn invocation(name: &str) -> Invocation {
+        Invocation {
+            name: name.into(),
+            guild_id: None,
+            channel_id: ChannelId(2),
+            user_id: UserId(3),
+            member: None,
+            options: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn duplicate_names_are_rejected() {
+        let mut registry = CommandRegistry::new();
+        registry
+            .register(CommandSpec::new("me", "Snooze"), Arc::new(Echo("first")))
+            .unwrap();
+
+        let error = registry
+            .register(CommandSpec::new("me", "Other"), Arc::new(Echo("second")))
+            .unwrap_err();
+
+        assert_eq!(error, DuplicateCommand("me".into()));
+        assert_eq!(error.to_string(), "command /me is already registered");
+        assert_eq!(registry.specs(), vec![CommandSpec::new("me", "Snooze")]);
+        assert_eq!(
+            CommandRegistry::new()
+                .with_help(guilds())
+                .unwrap()
+                .with_help(guilds())
+                .err(),
+            Some(DuplicateCommand(HELP_COMMAND.into()))
+        );
+    }
+
+    #[tokio::test]
+    async fn commands_are_looked_up_by_name() {
+        let mut registry = CommandRegistry::new();
+        for name in ["standup", "me"] {
+            registry
+                .register(CommandSpec::new(name, name), Arc::new(Echo(name)))
+                .unwrap();
+        }
+
+        let reply = registry
+            .get("me")
+            .unwrap()
+            .handle(&invocation("me"))
+            .await
+            .unwrap();
+
+        assert_eq!(reply, Reply::public("me"));
+        assert!(registry.get("remind").is_none());
+    }
+
+    #[tokio::test]
+    async fn help_lists_every_command() {
+        let mut registry = CommandRegistry::new();
+        registry
+            .register(
+                CommandSpec::new("standup", "Submit your standup"),
+                Arc::new(Echo("standup")),
+            )
+            .unwrap();
+        let registry = registry.with_help(guilds()).unwrap();
+
+        let reply = registry
+            .get(HELP_COMMAND)
+            .unwrap()
+            .handle(&invocation(HELP_COMMAND))
+            .await
+            .unwrap();
+
+        let names: Vec<_> = reply
+            .embed
+            .unwrap()
+            .fields
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, vec!["/help", "/standup"]);
+        assert_eq!(registry.specs().len(), 2);
+    }
+
+    async fn guild_help(registry: &CommandRegistry) -> Embed {
+        let invocation = Invocation {
+            guild_id: Some(GuildId(1)),
+            ..invocation(HELP_COMMAND)
+        };
+        let reply = registry
+            .get(HELP_COMMAND)
+            .unwrap()
+            .handle(&invocation)
+            .await
+            .unwrap();
+
+        reply.embed.unwrap()
+    }
+
+    #[tokio::test]
+    async fn help_lists_exactly_the_commands_enabled_in_the_guild() {
+        let mut registry = CommandRegistry::new();
+        registry
+            .register(
+                CommandSpec::new("standup", "Submit your standup"),
+                Arc::new(Echo("standup")),
+            )
+            .unwrap();
+        registry
+            .register(
+                CommandSpec::new("remind", "Schedule a reminder"),
+                Arc::new(Echo("remind")),
+            )
+            .unwrap();
+        registry
+            .register(
+                CommandSpec::new("sprint", "Manage the sprint goals").admin_only(),
+                Arc::new(Echo("sprint")),
+            )
+            .unwrap();
+        let guilds = guilds();
+        let mut config = GuildConfig::new(GuildId(1));
+        config.disabled_commands = vec!["remind".into()];
+        guilds.upsert(&config).await.unwrap();
+        let registry = registry.with_help(guilds.clone()).unwrap();
+
+        let embed = guild_help(&registry).await;
+
+        assert_eq!(
+            embed.fields,
+            vec![
+                (
+                    "/help".to_owned(),
+                    "List the commands of the bot".to_owned()
+                ),
+                (
+                    "/sprint".to_owned(),
+                    "Manage the sprint goals (admins only)".to_owned()
+                ),
+                ("/standup".to_owned(), "Submit your standup".to_owned()),
+            ]
+        );
+        assert!(embed.description.is_empty());
+
+        config.disabled_commands = registry.specs().into_iter().map(|spec| spec.name).collect();
+        guilds.upsert(&config).await.unwrap();
+
+        let embed = guild_help(&registry).await;
+        assert!(embed.fields.is_empty());
+        assert_eq!(embed.description, "No commands are enabled in this server");
+    }
+}
@@ -0,0 +1,192 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use chrono_tz::Tz;
+
+use crate::domain::{
+    guild::GuildConfigRepository,
+    reminder::{parse_when, Reminder, ReminderService},
+};
+
+use super::{
+    command::{CommandHandler, Invocation, Reply},
+    registry::{CommandOption, CommandSpec},
+};
+
+pub const REMIND_COMMAND: &str = "remind";
+
+/// `/remind <when> <message> [dm]`, schedules a one-off reminder.
+pub struct RemindCommand {
+    reminders: ReminderService,
+    guilds: Arc<dyn GuildConfigRepository>,
+    default_timezone: Tz,
+}
+
+impl RemindCommand {
+    pub fn new(
+        reminders: ReminderService,
+        guilds: Arc<dyn GuildConfigRepository>,
+        default_timezone: Tz,
+    ) -> Self {
+        Self {
+            reminders,
+            guilds,
+            default_timezone,
+        }
+    }
+
+    pub fn spec() -> CommandSpec {
+        CommandSpec::new(REMIND_COMMAND, "Schedule a reminder").with_options(vec![
+            CommandOption::string("when", "When to remind you, e.g. 30m or tomorrow 9am")
+                .required(),
+            CommandOption::string("message", "What to be reminded of").required(),
+            CommandOption::boolean("dm", "Send it to your direct messages"),
+        ])
+    }
+}
+
+#[async_trait]
+impl CommandHandler for RemindCommand {
+    async fn handle(&self, invocation: &Invocation) -> Result<Reply> {
+        let (Some(when), Some(message)) = (
+            invocation.options.get("when"),
+            invocation.options.get("message"),
+        ) else {
+            return Ok(Reply::ephemeral("usage: /remind <when> <message>"));
+        };
+
+        let now = Utc::now();
+        let timezone = self
+            .guilds
+            .timezone(invocation.guild_id, self.default_timezone)
+            .await?;
+        let due_at = match parse_when(when, now.with_timezone(&timezone)) {
+            Ok(due_at) => due_at,
+            Err(error) => return Ok(Reply::ephemeral(error.to_string())),
+        };
+
+        // Outside of a guild the only place to deliver to is the member DMs.
+        let direct = invocation.guild_id.is_none()
+            || invocation.options.get("dm").is_some_and(|dm| dm == "true");
+
+        self.reminders
+            .schedule(Reminder {
+                id: None,
+                guild_id: invocation.guild_id,
+                channel_id: (!direct).then_some(invocation.channel_id),
+                user_id: invocation.user_id,
+                message: message.clone(),
+                due_at,
+                delivered: false,
+                created_at: now,
+            })
+            .await?;
+
+        Ok(Reply::ephemeral(format!(
+            "I'll remind you <t:{}:R>",
+            due_at.timestamp()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::TimeDelta;
+
+    use crate::{
+        domain::{
+            id::{ChannelId, GuildId, UserId},
+            reminder::ReminderRepository,
+        },
+        drivers::database::memory::{InMemoryGuildConfigRepository, InMemoryReminderRepository},
+    };
+
+    use super::*;
+
+    fn invocation(options: &[(&str, &str)]) -> Invocation {
+        Invocation {
+            name: REMIND_COMMAND.into(),
+            guild_id: Some(GuildId(1)),
+            channel_id: ChannelId(2),
+            user_id: UserId(3),
+            member: None,
+            options: options
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect::<HashMap<_, _>>(),
+        }
+    }
+
+    fn command(repository: Arc<InMemoryReminderRepository>) -> RemindCommand {
+        RemindCommand::new(
+            ReminderService::new(repository),
+            Arc::new(InMemoryGuildConfigRepository::default()),
+            Tz::UTC,
+        )
+    }
+
+    #[tokio::test]
+    async fn schedules_a_channel_reminder() {
+        let repository = Arc::new(InMemoryReminderRepository::default());
+
+        let reply = command(repository.clone())
+            .handle(&invocation(&[("when", "30m"), ("message", "stretch")]))
+            .await
+            .unwrap();
+
+        assert!(reply.ephemeral);
+        assert!(reply.content.starts_with("I'll remind you <t:"));
+
+        let due = repository
+            .due(Utc::now() + TimeDelta::minutes(31))
+            .await
+            .unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].message, "stretch");
+        assert_eq!(due[0].channel_id, Some(ChannelId(2)));
+    }
+
+    #[tokio::test]
+    async fn dm_reminders_have_no_channel() {
+        let repository = Arc::new(InMemoryReminderRepository::default());
+
+        command(repository.clone())
+            .handle(&invocation(&[
+                ("when", "1h"),
+                ("message", "stretch"),
+                ("dm", "true"),
+            ]))
+            .await
+            .unwrap();
+
+        let due = repository
+            .due(Utc::now() + TimeDelta::hours(2))
+            .await
+            .unwrap();
+        assert_eq!(due[0].channel_id, None);
+    }
+
+    #[tokio::test]
+    async fn invalid_time_is_reported_to_the_member() {
+        let repository = Arc::new(InMemoryReminderRepository::default());
+
+        let reply = command(repository.clone())
+            .handle(&invocation(&[("when", "someday"), ("message", "stretch")]))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            reply,
+            Reply::ephemeral("expected a time like `30m`, `2h` or `tomorrow 9am`")
+        );
+        assert!(repository
+            .due(Utc::now() + TimeDelta::days(400))
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
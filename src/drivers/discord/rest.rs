@@ -0,0 +1,798 @@
+//! The REST API of Discord, which the slash commands are registered and
+//! answered with and the messages of the bot are posted and cleaned up with.
+
+use std::{borrow::Cow, time::Duration};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::{header::AUTHORIZATION, Method, Response, StatusCode};
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Deserialize, Serialize};
+use serde_aux::field_attributes::deserialize_number_from_string;
+
+use crate::{
+    configuration::DiscordSettings,
+    domain::{
+        cleanup::{ChannelHistory, ChannelMessage, MissingPermissions},
+        delivery::{Destination, MessageSender, OutgoingMessage},
+        id::{ApplicationId, ChannelId, GuildId, MessageId, UserId},
+    },
+};
+
+use super::{
+    command::{Embed, Reply},
+    message::{truncate, EMBED_DESCRIPTION_LIMIT, EMBED_FIELD_VALUE_LIMIT},
+    registry::{CommandOption, CommandRegistrar, CommandSpec, OptionKind},
+};
+
+/// Discord turns away the requests of bots without one in this format.
+const USER_AGENT: &str = concat!(
+    "DiscordBot (https://github.com/talDoFlemis/scrum-discord-bot, ",
+    env!("CARGO_PKG_VERSION"),
+    ")"
+);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+/// A rate limited request is tried again this many times, after waiting as
+/// long as Discord asks to.
+const RATE_LIMIT_RETRIES: u32 = 3;
+/// Rate limits longer than this fail the request instead of holding it.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(30);
+/// Interaction responses with this flag are only shown to the invoking member.
+const EPHEMERAL_FLAG: u64 = 1 << 6;
+
+/// Discord answered a request with an error status.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("discord answered {status}: {body}")]
+pub struct DiscordError {
+    pub status: StatusCode,
+    pub body: String,
+}
+
+/// The application the bot token belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Application {
+    pub id: ApplicationId,
+    /// The user the bot posts as.
+    pub bot_id: UserId,
+}
+
+/// A client of the REST API, authenticated as the bot.
+///
+/// What it posts is cut to `discord.max_message_length` and to the limits of
+/// the embeds, Discord refuses longer messages outright.
+#[derive(Clone)]
+pub struct DiscordRest {
+    http: reqwest::Client,
+    api_url: String,
+    token: SecretString,
+    application: Application,
+    message_limit: usize,
+}
+
+impl DiscordRest {
+    /// Look up the application of `discord.token`, so a revoked token fails
+    /// at startup rather than on the first message. `None` without a token.
+    pub async fn connect(settings: &DiscordSettings) -> Result<Option<Self>> {
+        let Some(token) = settings.token.clone() else {
+            return Ok(None);
+        };
+
+        let http = reqwest::Client::builder()
+            .user_agent(USER_AGENT)
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .context("expected to build the discord client")?;
+        let mut rest = Self {
+            http,
+            api_url: settings.api_url.trim_end_matches('/').to_owned(),
+            token,
+            application: Application {
+                id: ApplicationId(0),
+                bot_id: UserId(0),
+            },
+            message_limit: settings.message_length_limit(),
+        };
+
+        let application: ApplicationPayload = rest
+            .call(Method::GET, "/applications/@me", None)
+            .await?
+            .json()
+            .await
+            .context("expected the application of the bot")?;
+        rest.application = Application {
+            id: ApplicationId(application.id),
+            // Bots made before applications had their own id share it.
+            bot_id: UserId(application.bot.map_or(application.id, |bot| bot.id)),
+        };
+        tracing::info!(application_id = %rest.application.id, "connected to discord");
+
+        Ok(Some(rest))
+    }
+
+    pub fn application(&self) -> Application {
+        self.application
+    }
+
+    pub(super) fn token(&self) -> &SecretString {
+        &self.token
+    }
+
+    /// The URL the gateway is connected to.
+    pub async fn gateway_url(&self) -> Result<String> {
+        let gateway: GatewayPayload = self
+            .call(Method::GET, "/gateway/bot", None)
+            .await?
+            .json()
+            .await
+            .context("expected the gateway of the bot")?;
+
+        Ok(gateway.url)
+    }
+
+    /// Answer the interaction `interaction_id` with `reply`, within the three
+    /// seconds Discord waits for it.
+    #[tracing::instrument(name = "Respond to interaction", skip(self, token, reply))]
+    pub async fn respond(&self, interaction_id: u64, token: &str, reply: &Reply) -> Result<()> {
+        let path = format!("/interactions/{interaction_id}/{token}/callback");
+        let body = serde_json::json!({
+            // A message in the channel of the command.
+            "type": 4,
+            "data": MessagePayload::new(reply, self.message_limit),
+        });
+
+        self.call(Method::POST, &path, Some(&body)).await?;
+        Ok(())
+    }
+
+    /// The direct message channel of the bot with `user_id`, opened if needed.
+    async fn direct_channel(&self, user_id: UserId) -> Result<ChannelId> {
+        let body = serde_json::json!({ "recipient_id": user_id.to_string() });
+        let channel: ChannelPayload = self
+            .call(Method::POST, "/users/@me/channels", Some(&body))
+            .await?
+            .json()
+            .await
+            .context("expected the direct message channel")?;
+
+        Ok(ChannelId(channel.id))
+    }
+
+    /// Send a request to `path`, waiting out the rate limits.
+    async fn call(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&serde_json::Value>,
+    ) -> Result<Response> {
+        let url = format!("{}{}", self.api_url, path);
+        let mut retries = 0;
+
+        loop {
+            let mut request = self
+                .http
+                .request(method.clone(), &url)
+                .header(AUTHORIZATION, format!("Bot {}", self.token.expose_secret()));
+            if let Some(body) = body {
+                request = request.json(body);
+            }
+
+            let response = request
+                .send()
+                .await
+                .with_context(|| format!("expected discord to answer {} {}", method, path))?;
+            let status = response.status();
+            if status.is_success() {
+                return Ok(response);
+            }
+
+            let body = response.text().await.unwrap_or_default();
+            if status == StatusCode::TOO_MANY_REQUESTS && retries < RATE_LIMIT_RETRIES {
+                let retry_after = serde_json::from_str::<RateLimited>(&body)
+                    .ok()
+                    .map(|limited| Duration::from_secs_f64(limited.retry_after.max(0.0)))
+                    .filter(|wait| *wait <= MAX_RETRY_AFTER);
+                if let Some(retry_after) = retry_after {
+                    tracing::warn!(?retry_after, path, "rate limited by discord");
+                    tokio::time::sleep(retry_after).await;
+                    retries += 1;
+                    continue;
+                }
+            }
+
+            return Err(DiscordError { status, body }.into());
+        }
+    }
+}
+
+#[async_trait]
+impl CommandRegistrar for DiscordRest {
+    #[tracing::instrument(name = "Overwrite commands", skip(self, specs))]
+    async fn overwrite(&self, guild_id: Option<GuildId>, specs: &[CommandSpec]) -> Result<()> {
+        let application_id = self.application.id;
+        let path = match guild_id {
+            Some(guild_id) => format!("/applications/{application_id}/guilds/{guild_id}/commands"),
+            None => format!("/applications/{application_id}/commands"),
+        };
+        let commands: Vec<_> = specs.iter().map(CommandPayload::from).collect();
+
+        self.call(Method::PUT, &path, Some(&serde_json::to_value(commands)?))
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MessageSender for DiscordRest {
+    #[tracing::instrument(name = "Post message", skip(self, message), fields(destination = ?message.destination))]
+    async fn send(&self, message: &OutgoingMessage) -> Result<()> {
+        let channel_id = match message.destination {
+            Destination::Channel(channel_id) => channel_id,
+            Destination::Direct(user_id) => self.direct_channel(user_id).await?,
+        };
+        let body = serde_json::json!({
+            "content": truncate(&message.content, self.message_limit),
+        });
+
+        self.call(
+            Method::POST,
+            &format!("/channels/{channel_id}/messages"),
+            Some(&body),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ChannelHistory for DiscordRest {
+    #[tracing::instrument(name = "Read channel history", skip(self))]
+    async fn messages(
+        &self,
+        channel_id: ChannelId,
+        before: Option<MessageId>,
+        limit: usize,
+    ) -> Result<Vec<ChannelMessage>> {
+        let mut path = format!("/channels/{channel_id}/messages?limit={limit}");
+        if let Some(before) = before {
+            path.push_str(&format!("&before={before}"));
+        }
+
+        let messages: Vec<HistoryMessagePayload> = self
+            .call(Method::GET, &path, None)
+            .await
+            .map_err(forbidden_as_missing_permissions)?
+            .json()
+            .await
+            .context("expected the messages of the channel")?;
+
+        Ok(messages
+            .into_iter()
+            .map(|message| ChannelMessage {
+                id: MessageId(message.id),
+                author_id: UserId(message.author.id),
+                created_at: message.timestamp,
+            })
+            .collect())
+    }
+
+    #[tracing::instrument(name = "Delete message", skip(self))]
+    async fn delete(&self, channel_id: ChannelId, message_id: MessageId) -> Result<()> {
+        self.call(
+            Method::DELETE,
+            &format!("/channels/{channel_id}/messages/{message_id}"),
+            None,
+        )
+        .await
+        .map_err(forbidden_as_missing_permissions)?;
+        Ok(())
+    }
+}
+
+/// A `403` is Discord refusing the bot access to the channel.
+fn forbidden_as_missing_permissions(error: anyhow::Error) -> anyhow::Error {
+    match error.downcast_ref::<DiscordError>() {
+        Some(refused) if refused.status == StatusCode::FORBIDDEN => {
+            error.context(MissingPermissions)
+        }
+        _ => error,
+    }
+}
+
+#[derive(Deserialize)]
+struct ApplicationPayload {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    id: u64,
+    bot: Option<UserPayload>,
+}
+
+#[derive(Deserialize)]
+struct UserPayload {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    id: u64,
+}
+
+#[derive(Deserialize)]
+struct ChannelPayload {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    id: u64,
+}
+
+#[derive(Deserialize)]
+struct HistoryMessagePayload {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    id: u64,
+    author: UserPayload,
+    timestamp: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+struct GatewayPayload {
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct RateLimited {
+    /// Seconds to wait before trying again.
+    retry_after: f64,
+}
+
+/// The content and embed of a message, cut to the limits of Discord.
+#[derive(Serialize)]
+struct MessagePayload<'a> {
+    content: Cow<'a, str>,
+    embeds: Vec<EmbedPayload<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    flags: Option<u64>,
+}
+
+impl<'a> MessagePayload<'a> {
+    fn new(reply: &'a Reply, limit: usize) -> Self {
+        Self {
+            content: truncate(&reply.content, limit),
+            embeds: reply.embed.iter().map(EmbedPayload::from).collect(),
+            flags: reply.ephemeral.then_some(EPHEMERAL_FLAG),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct EmbedPayload<'a> {
+    title: &'a str,
+    description: Cow<'a, str>,
+    fields: Vec<FieldPayload<'a>>,
+}
+
+impl<'a> From<&'a Embed> for EmbedPayload<'a> {
+    fn from(embed: &'a Embed) -> Self {
+        Self {
+            title: &embed.title,
+            description: truncate(&embed.description, EMBED_DESCRIPTION_LIMIT),
+            fields: embed
+                .fields
+                .iter()
+                .map(|(name, value)| FieldPayload {
+                    name,
+                    value: truncate(value, EMBED_FIELD_VALUE_LIMIT),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct FieldPayload<'a> {
+    name: &'a str,
+    value: Cow<'a, str>,
+}
+
+/// A slash command as the application commands API takes it.
+#[derive(Serialize)]
+struct CommandPayload<'a> {
+    name: &'a str,
+    description: &'a str,
+    options: Vec<OptionPayload<'a>>,
+}
+
+impl<'a> From<&'a CommandSpec> for CommandPayload<'a> {
+    fn from(spec: &'a CommandSpec) -> Self {
+        Self {
+            name: &spec.name,
+            description: &spec.description,
+            options: spec.options.iter().map(OptionPayload::from).collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct OptionPayload<'a> {
+    #[serde(rename = "type")]
+    kind: u8,
+    name: &'a str,
+    description: &'a str,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    required: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    options: Vec<OptionPayload<'a>>,
+}
+
+impl<'a> From<&'a CommandOption> for OptionPayload<'a> {
+    fn from(option: &'a CommandOption) -> Self {
+        let kind = match option.kind {
+            OptionKind::Subcommand => 1,
+            OptionKind::SubcommandGroup => 2,
+            OptionKind::String => 3,
+            OptionKind::Integer => 4,
+            OptionKind::Boolean => 5,
+        };
+
+        Self {
+            kind,
+            name: &option.name,
+            description: &option.description,
+            required: option.required,
+            options: option.options.iter().map(OptionPayload::from).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{
+        collections::VecDeque,
+        sync::{Arc, Mutex},
+    };
+
+    use axum::{
+        body::Bytes,
+        http::{HeaderMap, Method as HttpMethod, StatusCode as HttpStatus, Uri},
+        response::IntoResponse,
+        Json, Router,
+    };
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    /// A request received by [`MockDiscord`].
+    #[derive(Debug, Clone, PartialEq)]
+    pub(crate) struct Received {
+        pub method: String,
+        pub path: String,
+        pub authorization: String,
+        pub body: Value,
+    }
+
+    /// A local stand-in for the REST API, answering the queued responses in
+    /// order and `200 {}` once they run out.
+    #[derive(Clone, Default)]
+    pub(crate) struct MockDiscord {
+        pub received: Arc<Mutex<Vec<Received>>>,
+        responses: Arc<Mutex<VecDeque<(u16, Value)>>>,
+    }
+
+    impl MockDiscord {
+        pub(crate) fn respond(&self, status: u16, body: Value) {
+            self.responses.lock().unwrap().push_back((status, body));
+        }
+
+        pub(crate) fn received(&self) -> Vec<Received> {
+            self.received.lock().unwrap().clone()
+        }
+
+        /// Serve the mock and connect a client to it as application 10 with
+        /// bot user 11.
+        pub(crate) async fn client(&self) -> DiscordRest {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let address = listener.local_addr().unwrap();
+            let mock = self.clone();
+            let router = Router::new().fallback(
+                move |method: HttpMethod, uri: Uri, headers: HeaderMap, body: Bytes| {
+                    let mock = mock.clone();
+                    async move { mock.answer(method, uri, headers, body) }
+                },
+            );
+            tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+            let settings = DiscordSettings {
+                max_message_length: 20,
+                max_concurrent_interactions: 1,
+                intents: vec![],
+                token: Some(SecretString::from("secret")),
+                api_url: format!("http://{address}/api/"),
+            };
+            DiscordRest::connect(&settings).await.unwrap().unwrap()
+        }
+
+        fn answer(
+            &self,
+            method: HttpMethod,
+            uri: Uri,
+            headers: HeaderMap,
+            body: Bytes,
+        ) -> impl IntoResponse {
+            let path = uri.path().trim_start_matches("/api").to_owned();
+            let path = match uri.query() {
+                Some(query) => format!("{path}?{query}"),
+                None => path,
+            };
+            if path == "/applications/@me" {
+                return (
+                    HttpStatus::OK,
+                    Json(json!({ "id": "10", "bot": { "id": "11" } })),
+                );
+            }
+
+            self.received.lock().unwrap().push(Received {
+                method: method.to_string(),
+                path,
+                authorization: headers["authorization"].to_str().unwrap().to_owned(),
+                body: serde_json::from_slice(&body).unwrap_or(Value::Null),
+            });
+            let (status, body) = self
+                .responses
+                .lock()
+                .unwrap()
+                .pop_front()
+                .unwrap_or((200, json!({})));
+
+            (HttpStatus::from_u16(status).unwrap(), Json(body))
+        }
+    }
+
+    #[tokio::test]
+    async fn connect_looks_up_the_application_of_the_token() {
+        let rest = MockDiscord::default().client().await;
+
+        assert_eq!(
+            rest.application(),
+            Application {
+                id: ApplicationId(10),
+                bot_id: UserId(11),
+            }
+        );
+
+        let settings = DiscordSettings {
+            token: None,
+            ..crate::configuration::test_settings().discord
+        };
+        assert!(DiscordRest::connect(&settings).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn commands_are_overwritten_globally_or_in_a_guild() {
+        let discord = MockDiscord::default();
+        let rest = discord.client().await;
+        let specs = [
+            CommandSpec::new("me", "Manage your own settings").with_options(vec![
+                CommandOption::subcommand(
+                    "snooze",
+                    "Pause your reminders",
+                    vec![CommandOption::integer("days", "Days to pause them for").required()],
+                ),
+            ]),
+        ];
+
+        rest.overwrite(None, &specs).await.unwrap();
+        rest.overwrite(Some(GuildId(1)), &[]).await.unwrap();
+
+        let received = discord.received();
+        assert_eq!(
+            received[0],
+            Received {
+                method: "PUT".into(),
+                path: "/applications/10/commands".into(),
+                authorization: "Bot secret".into(),
+                body: json!([{
+                    "name": "me",
+                    "description": "Manage your own settings",
+                    "options": [{
+                        "type": 1,
+                        "name": "snooze",
+                        "description": "Pause your reminders",
+                        "options": [{
+                            "type": 4,
+                            "name": "days",
+                            "description": "Days to pause them for",
+                            "required": true,
+                        }],
+                    }],
+                }]),
+            }
+        );
+        assert_eq!(received[1].path, "/applications/10/guilds/1/commands");
+        assert_eq!(received[1].body, json!([]));
+    }
+
+    #[tokio::test]
+    async fn replies_are_posted_as_interaction_responses() {
+        let discord = MockDiscord::default();
+        let rest = discord.client().await;
+        let reply = Reply::ephemeral("hi").with_embed(Embed {
+            title: "Commands".into(),
+            description: String::new(),
+            fields: vec![("/help".into(), "List the commands".into())],
+        });
+
+        rest.respond(5, "abc", &reply).await.unwrap();
+
+        let received = discord.received();
+        assert_eq!(received[0].path, "/interactions/5/abc/callback");
+        assert_eq!(
+            received[0].body,
+            json!({
+                "type": 4,
+                "data": {
+                    "content": "hi",
+                    "embeds": [{
+                        "title": "Commands",
+                        "description": "",
+                        "fields": [{ "name": "/help", "value": "List the commands" }],
+                    }],
+                    "flags": 64,
+                },
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn messages_are_posted_to_their_channel_or_direct_messages() {
+        let discord = MockDiscord::default();
+        let rest = discord.client().await;
+
+        rest.send(&OutgoingMessage {
+            destination: Destination::Channel(ChannelId(2)),
+            content: "Standup time!".into(),
+        })
+        .await
+        .unwrap();
+        discord.respond(200, json!({ "id": "4", "type": 1 }));
+        rest.send(&OutgoingMessage {
+            destination: Destination::Direct(UserId(3)),
+            content: "Reminder".into(),
+        })
+        .await
+        .unwrap();
+
+        let received = discord.received();
+        let requests: Vec<_> = received
+            .iter()
+            .map(|received| (received.path.as_str(), &received.body))
+            .collect();
+        assert_eq!(
+            requests,
+            vec![
+                (
+                    "/channels/2/messages",
+                    &json!({ "content": "Standup time!" })
+                ),
+                ("/users/@me/channels", &json!({ "recipient_id": "3" })),
+                ("/channels/4/messages", &json!({ "content": "Reminder" })),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn long_messages_and_replies_are_truncated() {
+        let discord = MockDiscord::default();
+        let rest = discord.client().await;
+        let long = "a".repeat(5000);
+
+        rest.send(&OutgoingMessage {
+            destination: Destination::Channel(ChannelId(2)),
+            content: long.clone(),
+        })
+        .await
+        .unwrap();
+        let reply = Reply::public(long.clone()).with_embed(Embed {
+            title: "Standup".into(),
+            description: long.clone(),
+            fields: vec![("Today".into(), long)],
+        });
+        rest.respond(5, "abc", &reply).await.unwrap();
+
+        let received = discord.received();
+        let length = |value: &serde_json::Value| value.as_str().unwrap().chars().count();
+        // The mock client caps the messages at 20 characters.
+        assert_eq!(length(&received[0].body["content"]), 20);
+        let data = &received[1].body["data"];
+        assert_eq!(length(&data["content"]), 20);
+        assert_eq!(
+            length(&data["embeds"][0]["description"]),
+            EMBED_DESCRIPTION_LIMIT
+        );
+        assert_eq!(
+            length(&data["embeds"][0]["fields"][0]["value"]),
+            EMBED_FIELD_VALUE_LIMIT
+        );
+    }
+
+    #[tokio::test]
+    async fn channel_history_is_read_page_by_page_and_deleted() {
+        let discord = MockDiscord::default();
+        let rest = discord.client().await;
+        discord.respond(
+            200,
+            json!([{
+                "id": "21",
+                "author": { "id": "11" },
+                "timestamp": "2024-10-15T12:00:00.000000+00:00",
+                "content": "Standup time!",
+            }]),
+        );
+        discord.respond(204, Value::Null);
+
+        let messages = rest
+            .messages(ChannelId(2), Some(MessageId(30)), 100)
+            .await
+            .unwrap();
+        rest.delete(ChannelId(2), MessageId(21)).await.unwrap();
+
+        assert_eq!(
+            messages,
+            vec![ChannelMessage {
+                id: MessageId(21),
+                author_id: UserId(11),
+                created_at: "2024-10-15T12:00:00Z".parse().unwrap(),
+            }]
+        );
+        let requests: Vec<_> = discord
+            .received()
+            .into_iter()
+            .map(|received| (received.method, received.path))
+            .collect();
+        assert_eq!(
+            requests,
+            vec![
+                (
+                    "GET".to_owned(),
+                    "/channels/2/messages?limit=100&before=30".to_owned()
+                ),
+                ("DELETE".to_owned(), "/channels/2/messages/21".to_owned()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn forbidden_channels_are_missing_permissions() {
+        let discord = MockDiscord::default();
+        let rest = discord.client().await;
+        discord.respond(403, json!({ "message": "Missing Access", "code": 50001 }));
+        discord.respond(
+            403,
+            json!({ "message": "Missing Permissions", "code": 50013 }),
+        );
+        discord.respond(500, json!({}));
+
+        let read = rest.messages(ChannelId(2), None, 100).await.unwrap_err();
+        let delete = rest.delete(ChannelId(2), MessageId(21)).await.unwrap_err();
+        let failed = rest.delete(ChannelId(2), MessageId(22)).await.unwrap_err();
+
+        assert!(read.is::<MissingPermissions>(), "{read:#}");
+        assert!(delete.is::<MissingPermissions>(), "{delete:#}");
+        assert!(!failed.is::<MissingPermissions>(), "{failed:#}");
+    }
+
+    #[tokio::test]
+    async fn rate_limited_requests_are_tried_again() {
+        let discord = MockDiscord::default();
+        let rest = discord.client().await;
+        discord.respond(429, json!({ "retry_after": 0.01, "global": false }));
+
+        rest.overwrite(None, &[]).await.unwrap();
+
+        assert_eq!(discord.received().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn error_statuses_are_returned() {
+        let discord = MockDiscord::default();
+        let rest = discord.client().await;
+        discord.respond(403, json!({ "message": "Missing Access", "code": 50001 }));
+
+        let error = rest.overwrite(None, &[]).await.unwrap_err();
+
+        let error = error.downcast_ref::<DiscordError>().unwrap();
+        assert_eq!(error.status, StatusCode::FORBIDDEN);
+        assert!(error.body.contains("Missing Access"), "{}", error.body);
+    }
+}
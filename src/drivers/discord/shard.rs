@@ -0,0 +1,589 @@
+//! The gateway connection of the bot, which the slash commands arrive on.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use anyhow::{Context, Result};
+use futures::{SinkExt, StreamExt};
+use secrecy::ExposeSecret;
+use serde::{de, Deserialize, Deserializer};
+use serde_aux::field_attributes::deserialize_number_from_string;
+use serde_json::{json, Value};
+use tokio::time::{interval_at, Instant, MissedTickBehavior};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+use crate::domain::{
+    guild::Member,
+    id::{ChannelId, GuildId, RoleId, UserId},
+};
+
+use super::{
+    command::{Dispatcher, Invocation, Reply},
+    gateway::{GatewayEvent, GatewayIntents, ShardMonitor},
+    rest::DiscordRest,
+};
+
+/// The bot runs a single shard, which Discord allows up to 2500 guilds.
+const SHARD_ID: u32 = 0;
+const SHARD_COUNT: u32 = 1;
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// Answered when a command fails, the error itself is only logged.
+pub const FAILED_REPLY: &str = "something went wrong, try again later";
+
+/// The gateway closed the connection for a reason reconnecting won't fix,
+/// such as a revoked token or intents the bot isn't approved for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("the gateway refused the connection with close code {0}")]
+pub struct Refused(pub u16);
+
+/// What a dropped connection resumes, so the events in between are replayed.
+struct Session {
+    id: String,
+    resume_url: String,
+    sequence: Option<u64>,
+}
+
+/// Keeps the shard of the bot connected to the gateway, publishing its state
+/// to the [`ShardMonitor`] and dispatching the slash commands it receives.
+#[derive(Clone)]
+pub struct Shard {
+    rest: DiscordRest,
+    dispatcher: Dispatcher,
+    monitor: Arc<ShardMonitor>,
+    intents: GatewayIntents,
+}
+
+impl Shard {
+    pub fn new(
+        rest: DiscordRest,
+        dispatcher: Dispatcher,
+        monitor: Arc<ShardMonitor>,
+        intents: GatewayIntents,
+    ) -> Self {
+        Self {
+            rest,
+            dispatcher,
+            monitor,
+            intents,
+        }
+    }
+
+    /// Stay connected until the task is dropped, reconnecting with a backoff
+    /// unless the gateway [`Refused`] the bot.
+    pub async fn run(self) {
+        let mut session = None;
+        let mut backoff = MIN_BACKOFF;
+
+        loop {
+            match self.connect(&mut session).await {
+                Ok(ready) => {
+                    if ready {
+                        backoff = MIN_BACKOFF;
+                    }
+                }
+                Err(error) if error.is::<Refused>() => {
+                    tracing::error!(error = %error, "stopped connecting to the gateway");
+                    self.monitor
+                        .handle(GatewayEvent::Disconnected { shard_id: SHARD_ID });
+                    return;
+                }
+                Err(error) => tracing::warn!(error = ?error, "gateway connection failed"),
+            }
+
+            self.monitor
+                .handle(GatewayEvent::Disconnected { shard_id: SHARD_ID });
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    }
+
+    /// Connect once, resuming `session` when there is one, until the gateway
+    /// asks for a reconnect. Returns whether the shard got ready or resumed.
+    async fn connect(&self, session: &mut Option<Session>) -> Result<bool> {
+        let url = match session {
+            Some(session) => session.resume_url.clone(),
+            None => self.rest.gateway_url().await?,
+        };
+        let (mut socket, _) = connect_async(format!("{}/?v=10&encoding=json", url))
+            .await
+            .context("expected to connect to the gateway")?;
+
+        let hello = match socket.next().await {
+            Some(Ok(Message::Text(text))) => serde_json::from_str::<Payload>(&text)?,
+            other => anyhow::bail!("expected hello from the gateway, got {:?}", other),
+        };
+        let period = hello
+            .d
+            .get("heartbeat_interval")
+            .and_then(Value::as_u64)
+            .map(Duration::from_millis)
+            .context("expected a heartbeat interval")?;
+
+        let token = self.rest.token().expose_secret();
+        let handshake = match session {
+            Some(session) => json!({
+                "op": 6,
+                "d": { "token": token, "session_id": session.id, "seq": session.sequence },
+            }),
+            None => json!({
+                "op": 2,
+                "d": {
+                    "token": token,
+                    "intents": self.intents.bits(),
+                    "shard": [SHARD_ID, SHARD_COUNT],
+                    "properties": {
+                        "os": std::env::consts::OS,
+                        "browser": "scrum-discord-bot",
+                        "device": "scrum-discord-bot",
+                    },
+                },
+            }),
+        };
+        socket.send(Message::Text(handshake.to_string())).await?;
+
+        let mut heartbeat = interval_at(Instant::now() + period, period);
+        heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut acknowledged = true;
+        let mut sequence = session.as_ref().and_then(|session| session.sequence);
+        let mut ready = false;
+
+        loop {
+            let message = tokio::select! {
+                _ = heartbeat.tick() => {
+                    // A zombie connection, Discord asks to reconnect and resume.
+                    anyhow::ensure!(acknowledged, "heartbeat not acknowledged");
+                    acknowledged = false;
+                    let beat = json!({ "op": 1, "d": sequence });
+                    socket.send(Message::Text(beat.to_string())).await?;
+                    continue;
+                }
+                message = socket.next() => message,
+            };
+
+            let text = match message.transpose()? {
+                Some(Message::Text(text)) => text,
+                Some(Message::Close(frame)) => {
+                    let code = frame.map_or(1000, |frame| u16::from(frame.code));
+                    if (4010..=4014).contains(&code) || code == 4004 {
+                        return Err(Refused(code).into());
+                    }
+                    tracing::info!(code, "gateway closed the connection");
+                    return Ok(ready);
+                }
+                Some(_) => continue,
+                None => return Ok(ready),
+            };
+
+            let payload: Payload = serde_json::from_str(&text)?;
+            if payload.s.is_some() {
+                sequence = payload.s;
+                if let Some(session) = session.as_mut() {
+                    session.sequence = sequence;
+                }
+            }
+
+            match payload.op {
+                0 => match payload.t.as_deref() {
+                    Some("READY") => {
+                        let ready_event: ReadyEvent = serde_json::from_value(payload.d)?;
+                        *session = Some(Session {
+                            id: ready_event.session_id,
+                            resume_url: ready_event.resume_gateway_url,
+                            sequence,
+                        });
+                        ready = true;
+                        self.monitor.handle(GatewayEvent::Ready {
+                            shard_id: SHARD_ID,
+                            shard_count: SHARD_COUNT,
+                        });
+                    }
+                    Some("RESUMED") => {
+                        ready = true;
+                        self.monitor
+                            .handle(GatewayEvent::Resumed { shard_id: SHARD_ID });
+                    }
+                    Some("INTERACTION_CREATE") => {
+                        let shard = self.clone();
+                        tokio::spawn(async move { shard.interaction(payload.d).await });
+                    }
+                    _ => {}
+                },
+                // The gateway wants a heartbeat right away.
+                1 => {
+                    let beat = json!({ "op": 1, "d": sequence });
+                    socket.send(Message::Text(beat.to_string())).await?;
+                }
+                7 => {
+                    tracing::info!("gateway asked to reconnect");
+                    return Ok(ready);
+                }
+                9 => {
+                    if payload.d != Value::Bool(true) {
+                        *session = None;
+                    }
+                    tracing::info!("gateway invalidated the session");
+                    return Ok(ready);
+                }
+                11 => acknowledged = true,
+                _ => {}
+            }
+        }
+    }
+
+    /// Dispatch a slash command and answer it, failures included.
+    async fn interaction(&self, data: Value) {
+        let interaction: Interaction = match serde_json::from_value(data) {
+            Ok(interaction) => interaction,
+            Err(error) => {
+                tracing::warn!(error = %error, "malformed interaction");
+                return;
+            }
+        };
+        let Some(invocation) = interaction.invocation() else {
+            return;
+        };
+
+        let reply = match self.dispatcher.dispatch(&invocation).await {
+            Ok(reply) => reply,
+            Err(error) => {
+                tracing::error!(error = ?error, command = %invocation.name, "command failed");
+                Reply::ephemeral(FAILED_REPLY)
+            }
+        };
+        if let Err(error) = self
+            .rest
+            .respond(interaction.id, &interaction.token, &reply)
+            .await
+        {
+            tracing::warn!(error = ?error, command = %invocation.name, "failed to answer the command");
+        }
+    }
+}
+
+/// A message of the gateway.
+#[derive(Deserialize)]
+struct Payload {
+    op: u8,
+    #[serde(default)]
+    d: Value,
+    s: Option<u64>,
+    t: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ReadyEvent {
+    session_id: String,
+    resume_gateway_url: String,
+}
+
+#[derive(Deserialize)]
+struct Interaction {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    id: u64,
+    token: String,
+    #[serde(rename = "type")]
+    kind: u8,
+    #[serde(default, deserialize_with = "optional_number")]
+    guild_id: Option<u64>,
+    #[serde(default, deserialize_with = "optional_number")]
+    channel_id: Option<u64>,
+    /// Set in guilds.
+    member: Option<InteractionMember>,
+    /// Set in direct messages.
+    user: Option<InteractionUser>,
+    data: Option<CommandData>,
+}
+
+#[derive(Deserialize)]
+struct InteractionMember {
+    user: InteractionUser,
+    #[serde(default)]
+    roles: Vec<String>,
+    #[serde(default, deserialize_with = "optional_number")]
+    permissions: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct InteractionUser {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    id: u64,
+}
+
+#[derive(Deserialize)]
+struct CommandData {
+    name: String,
+    #[serde(default)]
+    options: Vec<CommandDataOption>,
+}
+
+#[derive(Deserialize)]
+struct CommandDataOption {
+    name: String,
+    #[serde(rename = "type")]
+    kind: u8,
+    value: Option<Value>,
+    #[serde(default)]
+    options: Vec<CommandDataOption>,
+}
+
+impl Interaction {
+    /// The slash command of the interaction, `None` for the other kinds.
+    fn invocation(&self) -> Option<Invocation> {
+        const APPLICATION_COMMAND: u8 = 2;
+
+        let data = self
+            .data
+            .as_ref()
+            .filter(|_| self.kind == APPLICATION_COMMAND)?;
+        let user_id = match (&self.member, &self.user) {
+            (Some(member), _) => member.user.id,
+            (None, Some(user)) => user.id,
+            (None, None) => return None,
+        };
+        let member = self.member.as_ref().map(|member| Member {
+            roles: member
+                .roles
+                .iter()
+                .filter_map(|role| role.parse().ok().map(RoleId))
+                .collect(),
+            permissions: member.permissions.unwrap_or_default(),
+            owner: false,
+        });
+        let mut options = HashMap::new();
+        flatten(&data.options, &mut options);
+
+        Some(Invocation {
+            name: data.name.clone(),
+            guild_id: self.guild_id.map(GuildId),
+            channel_id: ChannelId(self.channel_id?),
+            user_id: UserId(user_id),
+            member,
+            options,
+        })
+    }
+}
+
+/// Discord sends the snowflakes and permissions as strings.
+fn optional_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|number| number.parse().map_err(de::Error::custom))
+        .transpose()
+}
+
+/// Put the subcommand, its group and every argument in `into` by name, the
+/// way the handlers read them.
+fn flatten(options: &[CommandDataOption], into: &mut HashMap<String, String>) {
+    const SUBCOMMAND: u8 = 1;
+    const SUBCOMMAND_GROUP: u8 = 2;
+
+    for option in options {
+        match option.kind {
+            SUBCOMMAND => {
+                into.insert("subcommand".into(), option.name.clone());
+            }
+            SUBCOMMAND_GROUP => {
+                into.insert("subcommand_group".into(), option.name.clone());
+            }
+            _ => {
+                let value = match &option.value {
+                    Some(Value::String(value)) => value.clone(),
+                    Some(value) => value.to_string(),
+                    None => continue,
+                };
+                into.insert(option.name.clone(), value);
+            }
+        }
+        flatten(&option.options, into);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use async_trait::async_trait;
+
+    use crate::{
+        domain::feature::FeatureFlags,
+        drivers::discord::{command::CommandHandler, rest::tests::MockDiscord},
+        observability::metrics::{DiscordMetrics, ShardLabels},
+    };
+
+    use super::*;
+
+    fn interaction(data: Value) -> Interaction {
+        serde_json::from_value(data).unwrap()
+    }
+
+    #[test]
+    fn subcommands_and_arguments_are_flattened_into_the_invocation() {
+        let interaction = interaction(json!({
+            "id": "5",
+            "token": "abc",
+            "type": 2,
+            "guild_id": "1",
+            "channel_id": "2",
+            "member": {
+                "user": { "id": "3" },
+                "roles": ["7"],
+                "permissions": "8",
+            },
+            "data": {
+                "name": "sprint",
+                "options": [{
+                    "name": "goal",
+                    "type": 2,
+                    "options": [{
+                        "name": "add",
+                        "type": 1,
+                        "options": [
+                            { "name": "text", "type": 3, "value": "dashboard" },
+                            { "name": "points", "type": 4, "value": 3 },
+                        ],
+                    }],
+                }],
+            },
+        }));
+
+        let invocation = interaction.invocation().unwrap();
+
+        assert_eq!(
+            invocation,
+            Invocation {
+                name: "sprint".into(),
+                guild_id: Some(GuildId(1)),
+                channel_id: ChannelId(2),
+                user_id: UserId(3),
+                member: Some(Member {
+                    roles: vec![RoleId(7)],
+                    permissions: 8,
+                    owner: false,
+                }),
+                options: HashMap::from([
+                    ("subcommand_group".to_owned(), "goal".to_owned()),
+                    ("subcommand".to_owned(), "add".to_owned()),
+                    ("text".to_owned(), "dashboard".to_owned()),
+                    ("points".to_owned(), "3".to_owned()),
+                ]),
+            }
+        );
+    }
+
+    #[test]
+    fn direct_messages_have_no_member_and_other_interactions_are_skipped() {
+        let direct = interaction(json!({
+            "id": "5",
+            "token": "abc",
+            "type": 2,
+            "channel_id": "2",
+            "user": { "id": "3" },
+            "data": { "name": "whoami" },
+        }));
+        let ping = interaction(json!({ "id": "5", "token": "abc", "type": 1 }));
+
+        let invocation = direct.invocation().unwrap();
+
+        assert_eq!(invocation.guild_id, None);
+        assert_eq!(invocation.member, None);
+        assert_eq!(invocation.user_id, UserId(3));
+        assert!(ping.invocation().is_none());
+    }
+
+    struct Pong;
+
+    #[async_trait]
+    impl CommandHandler for Pong {
+        async fn handle(&self, invocation: &Invocation) -> Result<Reply> {
+            Ok(Reply::ephemeral(format!("pong {}", invocation.user_id)))
+        }
+    }
+
+    /// A gateway that says hello, checks the identify, gets the shard ready
+    /// and sends a `/ping`.
+    async fn gateway() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let send = |payload: Value| Message::Text(payload.to_string());
+
+            socket
+                .send(send(
+                    json!({ "op": 10, "d": { "heartbeat_interval": 45000 } }),
+                ))
+                .await
+                .unwrap();
+            let Some(Ok(Message::Text(identify))) = socket.next().await else {
+                panic!("expected identify");
+            };
+            let identify: Value = serde_json::from_str(&identify).unwrap();
+            assert_eq!(identify["op"], 2);
+            assert_eq!(identify["d"]["token"], "secret");
+            assert_eq!(identify["d"]["intents"], 1);
+
+            socket
+                .send(send(json!({
+                    "op": 0,
+                    "t": "READY",
+                    "s": 1,
+                    "d": { "session_id": "session", "resume_gateway_url": "ws://localhost" },
+                })))
+                .await
+                .unwrap();
+            socket
+                .send(send(json!({
+                    "op": 0,
+                    "t": "INTERACTION_CREATE",
+                    "s": 2,
+                    "d": {
+                        "id": "5",
+                        "token": "abc",
+                        "type": 2,
+                        "channel_id": "2",
+                        "user": { "id": "3" },
+                        "data": { "name": "ping" },
+                    },
+                })))
+                .await
+                .unwrap();
+
+            // Held open until the test is done.
+            while socket.next().await.is_some() {}
+        });
+
+        url
+    }
+
+    #[tokio::test]
+    async fn shard_gets_ready_and_answers_the_commands() {
+        let discord = MockDiscord::default();
+        let rest = discord.client().await;
+        discord.respond(200, json!({ "url": gateway().await }));
+        let metrics = Arc::new(DiscordMetrics::default());
+        let dispatcher =
+            Dispatcher::new(FeatureFlags::default()).with_command("ping", Arc::new(Pong));
+        let shard = Shard::new(
+            rest,
+            dispatcher,
+            Arc::new(ShardMonitor::new(metrics.clone())),
+            GatewayIntents::from_names(&["guilds"]).unwrap(),
+        );
+
+        let task = tokio::spawn(shard.run());
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while discord.received().len() < 2 && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        task.abort();
+
+        let received = discord.received();
+        assert_eq!(received[1].path, "/interactions/5/abc/callback");
+        assert_eq!(received[1].body["data"]["content"], "pong 3");
+        let connected = metrics
+            .shard_connected
+            .get_or_create(&ShardLabels { shard_id: SHARD_ID })
+            .get();
+        assert_eq!(connected, 1);
+    }
+}
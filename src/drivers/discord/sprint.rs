@@ -0,0 +1,655 @@
+use std::{collections::HashSet, sync::Arc};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
+use chrono_tz::Tz;
+
+use crate::{
+    domain::{
+        audit::Auditor,
+        burndown::{toggle_completion, GoalCompletionRepository},
+        guild::{GuildConfig, GuildConfigRepository},
+        sprint::{Goal, Sprint, SprintRepository, SprintStatus},
+        standup::StandupRepository,
+    },
+    i18n::{t, Locale},
+};
+
+use super::{
+    command::{CommandHandler, Embed, Invocation, Reply, ADMIN_ONLY_REPLY},
+    registry::{CommandOption, CommandSpec},
+};
+
+pub const SPRINT_COMMAND: &str = "sprint";
+
+/// `/sprint status` sums up the running sprint. `/sprint goal list` shows its
+/// goals, `/sprint goal add <text> [points]` and `/sprint goal done <id>`
+/// change them, for admins only and audited. Marking a done goal done again
+/// opens it back.
+pub struct SprintCommand {
+    sprints: Arc<dyn SprintRepository>,
+    completions: Arc<dyn GoalCompletionRepository>,
+    standups: Arc<dyn StandupRepository>,
+    guilds: Arc<dyn GuildConfigRepository>,
+    default_timezone: Tz,
+    auditor: Auditor,
+}
+
+impl SprintCommand {
+    pub fn new(
+        sprints: Arc<dyn SprintRepository>,
+        completions: Arc<dyn GoalCompletionRepository>,
+        standups: Arc<dyn StandupRepository>,
+        guilds: Arc<dyn GuildConfigRepository>,
+        default_timezone: Tz,
+        auditor: Auditor,
+    ) -> Self {
+        Self {
+            sprints,
+            completions,
+            standups,
+            guilds,
+            default_timezone,
+            auditor,
+        }
+    }
+
+    pub fn spec() -> CommandSpec {
+        CommandSpec::new(SPRINT_COMMAND, "Follow the running sprint").with_options(vec![
+            CommandOption::subcommand("status", "Sum up the running sprint", vec![]),
+            CommandOption::group(
+                "goal",
+                "The goals of the running sprint",
+                vec![
+                    CommandOption::subcommand("list", "List the goals", vec![]),
+                    CommandOption::subcommand(
+                        "add",
+                        "Add a goal, admins only",
+                        vec![
+                            CommandOption::string("text", "Title of the goal").required(),
+                            CommandOption::integer("points", "Story points of the goal"),
+                        ],
+                    ),
+                    CommandOption::subcommand(
+                        "done",
+                        "Mark a goal done, or open it back, admins only",
+                        vec![
+                            CommandOption::integer("id", "Number of the goal in the list")
+                                .required(),
+                        ],
+                    ),
+                ],
+            ),
+        ])
+    }
+
+    /// The titles of the goals of `sprint` marked done.
+    async fn done_goals(&self, sprint: &Sprint) -> Result<HashSet<String>> {
+        let sprint_id = sprint.id.expect("stored sprints have an id");
+
+        Ok(self
+            .completions
+            .list(sprint_id)
+            .await?
+            .into_iter()
+            .map(|completion| completion.goal)
+            .collect())
+    }
+
+    /// The embed listing the goals of `sprint`, numbered as `done` expects.
+    async fn goals_reply(&self, sprint: &Sprint, reply: Reply, locale: Locale) -> Result<Reply> {
+        let done = self.done_goals(sprint).await?;
+
+        Ok(reply.with_embed(goals_embed(sprint, &done, locale)))
+    }
+
+    async fn status_reply(
+        &self,
+        sprint: &Sprint,
+        config: &GuildConfig,
+        today: NaiveDate,
+        locale: Locale,
+    ) -> Result<Reply> {
+        let (done, participants) = tokio::try_join!(
+            self.done_goals(sprint),
+            self.standups.participants(config.guild_id, today),
+        )?;
+        let status = SprintStatus::new(sprint, today, &done, &config.roster, &participants);
+
+        Ok(Reply::ephemeral("").with_embed(status_embed(sprint, &status, locale)))
+    }
+}
+
+#[async_trait]
+impl CommandHandler for SprintCommand {
+    async fn handle(&self, invocation: &Invocation) -> Result<Reply> {
+        let Some(guild_id) = invocation.guild_id else {
+            return Ok(Reply::ephemeral(t(Locale::En, "sprint.guild_only", &[])));
+        };
+
+        let config = self
+            .guilds
+            .find(guild_id)
+            .await?
+            .unwrap_or_else(|| GuildConfig::new(guild_id));
+        let locale = config.locale();
+        let usage = || Ok(Reply::ephemeral(t(locale, "sprint.goal_usage", &[])));
+
+        let option = |name: &str| invocation.options.get(name).map(String::as_str);
+        let subcommand = match (option("subcommand_group"), option("subcommand")) {
+            (None, Some(subcommand @ "status"))
+            | (Some("goal"), Some(subcommand @ ("add" | "done" | "list"))) => subcommand,
+            _ => return usage(),
+        };
+
+        let mutates = matches!(subcommand, "add" | "done");
+        let is_admin = invocation
+            .member
+            .as_ref()
+            .is_some_and(|member| config.is_admin(member));
+        if mutates && !is_admin {
+            tracing::info!(user_id = %invocation.user_id, "sprint goal change denied");
+            return Ok(Reply::ephemeral(ADMIN_ONLY_REPLY));
+        }
+
+        let now = Utc::now();
+        let team = config
+            .team_for(invocation.channel_id, invocation.roles())
+            .map(|team| team.name.as_str());
+        let today = config.local_date(now, self.default_timezone);
+        let Some(mut sprint) = self.sprints.find_active(guild_id, team, today).await? else {
+            let key = if subcommand == "status" {
+                "sprint.status_no_active"
+            } else {
+                "sprint.no_active"
+            };
+            return Ok(Reply::ephemeral(t(locale, key, &[])));
+        };
+
+        match subcommand {
+            "status" => return self.status_reply(&sprint, &config, today, locale).await,
+            "add" => {
+                let Some(title) = option("text")
+                    .map(str::trim)
+                    .filter(|text| !text.is_empty())
+                else {
+                    return usage();
+                };
+                let points = match option("points").map(str::parse) {
+                    None => 0,
+                    Some(Ok(points)) => points,
+                    Some(Err(_)) => return usage(),
+                };
+
+                let goal = Goal {
+                    title: title.to_owned(),
+                    points,
+                };
+                // A single write, goals added at the same time are all kept.
+                let sprint_id = sprint.id.expect("stored sprints have an id");
+                if !self.sprints.add_goal(sprint_id, &goal).await? {
+                    return Ok(Reply::ephemeral(t(
+                        locale,
+                        "sprint.goal_duplicate",
+                        &[("title", &goal.title)],
+                    )));
+                }
+                sprint = self.sprints.find(sprint_id).await?.unwrap_or(sprint);
+            }
+            "done" => {
+                let Some(id) = option("id") else {
+                    return usage();
+                };
+                let Some(goal) = id.parse().ok().and_then(|number| sprint.goal(number)) else {
+                    return Ok(Reply::ephemeral(t(
+                        locale,
+                        "sprint.goal_unknown",
+                        &[("id", id)],
+                    )));
+                };
+
+                let sprint_id = sprint.id.expect("stored sprints have an id");
+                toggle_completion(
+                    self.completions.as_ref(),
+                    sprint_id,
+                    &goal.title,
+                    invocation.user_id,
+                    now,
+                )
+                .await?;
+            }
+            _ => {}
+        }
+        if mutates {
+            if let Err(error) = self
+                .auditor
+                .record_command(
+                    invocation.user_id.to_string(),
+                    guild_id,
+                    invocation.command_path(),
+                    invocation.arguments(),
+                )
+                .await
+            {
+                tracing::error!(error = ?error, command = %invocation.name, "failed to audit the sprint command");
+            }
+        }
+
+        // Changes are shown to the channel, listing only to the member.
+        let reply = if mutates {
+            Reply::public("")
+        } else {
+            Reply::ephemeral("")
+        };
+        self.goals_reply(&sprint, reply, locale).await
+    }
+}
+
+fn status_embed(sprint: &Sprint, status: &SprintStatus, locale: Locale) -> Embed {
+    let participation = if status.roster == 0 {
+        t(locale, "sprint.status_no_roster", &[])
+    } else {
+        let percent = (status.participation() * 100.0).round();
+        t(
+            locale,
+            "sprint.status_answered",
+            &[
+                ("answered", &status.answered.to_string()),
+                ("roster", &status.roster.to_string()),
+                ("percent", &percent.to_string()),
+            ],
+        )
+    };
+
+    Embed {
+        title: t(locale, "sprint.status_title", &[("name", &sprint.name)]),
+        description: t(
+            locale,
+            "sprint.status_dates",
+            &[
+                ("start", &sprint.start_date.to_string()),
+                ("end", &sprint.end_date.to_string()),
+            ],
+        ),
+        fields: vec![
+            (
+                t(locale, "sprint.status_days_left", &[]),
+                status.days_remaining.to_string(),
+            ),
+            (
+                t(locale, "sprint.status_goals", &[]),
+                t(
+                    locale,
+                    "sprint.status_goals_done",
+                    &[
+                        ("done", &status.goals_done.to_string()),
+                        ("total", &status.goals_total.to_string()),
+                    ],
+                ),
+            ),
+            (t(locale, "sprint.status_participation", &[]), participation),
+        ],
+    }
+}
+
+fn goals_embed(sprint: &Sprint, done: &HashSet<String>, locale: Locale) -> Embed {
+    let fields: Vec<_> = sprint
+        .goals
+        .iter()
+        .enumerate()
+        .map(|(index, goal)| {
+            let status = if done.contains(&goal.title) {
+                "sprint.goal_done"
+            } else {
+                "sprint.goal_open"
+            };
+            (
+                format!("{}. {}", index + 1, goal.title),
+                t(locale, status, &[("points", &goal.points.to_string())]),
+            )
+        })
+        .collect();
+
+    let description = if fields.is_empty() {
+        t(locale, "sprint.goals_empty", &[])
+    } else {
+        let completed = sprint
+            .goals
+            .iter()
+            .filter(|goal| done.contains(&goal.title))
+            .count();
+        t(
+            locale,
+            "sprint.goals_summary",
+            &[
+                ("start", &sprint.start_date.to_string()),
+                ("end", &sprint.end_date.to_string()),
+                ("done", &completed.to_string()),
+                ("total", &fields.len().to_string()),
+            ],
+        )
+    };
+
+    Embed {
+        title: t(locale, "sprint.goals_title", &[("name", &sprint.name)]),
+        description,
+        fields,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::Days;
+
+    use crate::{
+        domain::{
+            audit::{AuditQuery, AuditRepository},
+            guild::Member,
+            id::{ChannelId, GuildId, UserId},
+            standup::StandupEntry,
+        },
+        drivers::database::memory::{
+            InMemoryAuditRepository, InMemoryGoalCompletionRepository,
+            InMemoryGuildConfigRepository, InMemorySprintRepository, InMemoryStandupRepository,
+        },
+    };
+
+    use super::*;
+
+    struct Fixture {
+        command: SprintCommand,
+        sprints: Arc<InMemorySprintRepository>,
+        completions: Arc<InMemoryGoalCompletionRepository>,
+        standups: Arc<InMemoryStandupRepository>,
+        guilds: Arc<InMemoryGuildConfigRepository>,
+        audit_log: Arc<InMemoryAuditRepository>,
+    }
+
+    /// A command over a guild running a sprint with a `scheduler` goal, or none.
+    async fn fixture(running: bool) -> Fixture {
+        let sprints = Arc::new(InMemorySprintRepository::default());
+        if running {
+            let today = Utc::now().date_naive();
+            sprints
+                .insert(&Sprint {
+                    id: None,
+                    guild_id: GuildId(1),
+                    team: None,
+                    name: "Sprint 1".into(),
+                    start_date: today - Days::new(2),
+                    end_date: today + Days::new(11),
+                    goals: vec![Goal {
+                        title: "scheduler".into(),
+                        points: 5,
+                    }],
+                })
+                .await
+                .unwrap();
+        }
+        let completions = Arc::new(InMemoryGoalCompletionRepository::default());
+        let standups = Arc::new(InMemoryStandupRepository::default());
+        let guilds = Arc::new(InMemoryGuildConfigRepository::default());
+        let audit_log = Arc::new(InMemoryAuditRepository::default());
+
+        Fixture {
+            command: SprintCommand::new(
+                sprints.clone(),
+                completions.clone(),
+                standups.clone(),
+                guilds.clone(),
+                Tz::UTC,
+                Auditor::new(Some(audit_log.clone())),
+            ),
+            sprints,
+            completions,
+            standups,
+            guilds,
+            audit_log,
+        }
+    }
+
+    fn invocation(admin: bool, options: &[(&str, &str)]) -> Invocation {
+        Invocation {
+            options: [("subcommand_group", "goal")]
+                .iter()
+                .chain(options)
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect::<HashMap<_, _>>(),
+            ..status(admin)
+        }
+    }
+
+    fn status(admin: bool) -> Invocation {
+        Invocation {
+            name: SPRINT_COMMAND.into(),
+            guild_id: Some(GuildId(1)),
+            channel_id: ChannelId(2),
+            user_id: UserId(3),
+            member: Some(Member {
+                owner: admin,
+                ..Member::default()
+            }),
+            options: HashMap::from([("subcommand".to_owned(), "status".to_owned())]),
+        }
+    }
+
+    async fn stored(fixture: &Fixture) -> Sprint {
+        let today = Utc::now().date_naive();
+        fixture
+            .sprints
+            .find_active(GuildId(1), None, today)
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn adds_a_goal_to_the_running_sprint() {
+        let fixture = fixture(true).await;
+
+        let reply = fixture
+            .command
+            .handle(&invocation(
+                true,
+                &[
+                    ("subcommand", "add"),
+                    ("text", " dashboard "),
+                    ("points", "3"),
+                ],
+            ))
+            .await
+            .unwrap();
+
+        assert!(!reply.ephemeral);
+        let embed = reply.embed.unwrap();
+        assert_eq!(embed.title, "Goals of Sprint 1");
+        assert_eq!(
+            embed.fields[1],
+            ("2. dashboard".to_owned(), "open, 3 points".to_owned())
+        );
+        assert_eq!(stored(&fixture).await.goals.len(), 2);
+
+        let entries = fixture
+            .audit_log
+            .list(&AuditQuery::default())
+            .await
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].command.as_deref(), Some("sprint goal add"));
+        assert_eq!(entries[0].arguments["text"], " dashboard ");
+    }
+
+    #[tokio::test]
+    async fn duplicate_goals_are_refused() {
+        let fixture = fixture(true).await;
+
+        let reply = fixture
+            .command
+            .handle(&invocation(
+                true,
+                &[("subcommand", "add"), ("text", "scheduler")],
+            ))
+            .await
+            .unwrap();
+
+        assert!(reply.ephemeral);
+        assert_eq!(reply.content, "scheduler is already a goal");
+        assert_eq!(stored(&fixture).await.goals.len(), 1);
+        assert!(fixture
+            .audit_log
+            .list(&AuditQuery::default())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn done_toggles_the_goal() {
+        let fixture = fixture(true).await;
+        let done = invocation(true, &[("subcommand", "done"), ("id", "1")]);
+
+        let reply = fixture.command.handle(&done).await.unwrap();
+        let embed = reply.embed.unwrap();
+        assert_eq!(embed.fields[0].1, "done, 5 points");
+        assert!(
+            embed.description.ends_with("1 of 1 done"),
+            "{}",
+            embed.description
+        );
+
+        let reply = fixture.command.handle(&done).await.unwrap();
+        assert_eq!(reply.embed.unwrap().fields[0].1, "open, 5 points");
+        let sprint_id = stored(&fixture).await.id.unwrap();
+        assert!(fixture
+            .completions
+            .list(sprint_id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn done_with_an_unknown_id_is_refused() {
+        let fixture = fixture(true).await;
+
+        for id in ["0", "2", "scheduler"] {
+            let reply = fixture
+                .command
+                .handle(&invocation(true, &[("subcommand", "done"), ("id", id)]))
+                .await
+                .unwrap();
+
+            assert_eq!(
+                reply.content,
+                format!("No goal {id}, see /sprint goal list")
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn only_admins_change_goals() {
+        let fixture = fixture(true).await;
+
+        for options in [
+            &[("subcommand", "add"), ("text", "dashboard")][..],
+            &[("subcommand", "done"), ("id", "1")][..],
+        ] {
+            let reply = fixture
+                .command
+                .handle(&invocation(false, options))
+                .await
+                .unwrap();
+            assert_eq!(reply.content, ADMIN_ONLY_REPLY);
+        }
+
+        let reply = fixture
+            .command
+            .handle(&invocation(false, &[("subcommand", "list")]))
+            .await
+            .unwrap();
+        assert!(reply.ephemeral);
+        assert_eq!(reply.embed.unwrap().fields.len(), 1);
+        assert_eq!(stored(&fixture).await.goals.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn answers_when_no_sprint_is_running() {
+        let fixture = fixture(false).await;
+
+        let reply = fixture
+            .command
+            .handle(&invocation(
+                true,
+                &[("subcommand", "add"), ("text", "dashboard")],
+            ))
+            .await
+            .unwrap();
+
+        assert!(reply.ephemeral);
+        assert_eq!(reply.content, "No sprint is running today");
+    }
+
+    #[tokio::test]
+    async fn status_sums_up_the_running_sprint() {
+        let fixture = fixture(true).await;
+        let today = Utc::now().date_naive();
+        let mut config = GuildConfig::new(GuildId(1));
+        config.roster = vec![UserId(3), UserId(4)];
+        fixture.guilds.upsert(&config).await.unwrap();
+        fixture
+            .standups
+            .insert(&StandupEntry {
+                id: None,
+                guild_id: GuildId(1),
+                channel_id: ChannelId(2),
+                user_id: UserId(3),
+                team: None,
+                date: today,
+                yesterday: "reviewed PRs".into(),
+                today: "write the scheduler".into(),
+                blockers: String::new(),
+                sprint_id: None,
+                created_at: Utc::now(),
+            })
+            .await
+            .unwrap();
+        fixture
+            .command
+            .handle(&invocation(true, &[("subcommand", "done"), ("id", "1")]))
+            .await
+            .unwrap();
+
+        let reply = fixture.command.handle(&status(false)).await.unwrap();
+
+        assert!(reply.ephemeral);
+        let embed = reply.embed.unwrap();
+        assert_eq!(embed.title, "Status of Sprint 1");
+        assert_eq!(
+            embed.fields,
+            vec![
+                ("Days left".to_owned(), "11".to_owned()),
+                ("Goals".to_owned(), "1 of 1 done".to_owned()),
+                (
+                    "Standup today".to_owned(),
+                    "1 of 2 answered (50%)".to_owned()
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn status_without_a_running_sprint_says_so() {
+        let fixture = fixture(false).await;
+
+        let reply = fixture.command.handle(&status(false)).await.unwrap();
+
+        assert!(reply.ephemeral);
+        assert_eq!(
+            reply.content,
+            "No sprint is running today, ask an admin to plan the next one"
+        );
+    }
+}
@@ -0,0 +1,751 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use chrono_tz::Tz;
+
+use crate::{
+    domain::{
+        conflict::Conflict,
+        debounce::EditDebouncer,
+        guild::{GuildConfig, GuildConfigRepository, StandupVisibility},
+        standup::{
+            HistoryPage, HistoryQuery, InvalidStandup, StandupEntry, StandupRules, StandupService,
+            MAX_HISTORY_DAYS,
+        },
+    },
+    i18n::{t, Locale},
+};
+
+use super::{
+    command::{CommandHandler, Embed, Invocation, Reply},
+    message::{truncate, EMBED_FIELD_VALUE_LIMIT},
+    registry::{CommandOption, CommandSpec},
+};
+
+pub const STANDUP_COMMAND: &str = "standup";
+
+/// Days `/standup history` looks back on when none are given.
+const DEFAULT_HISTORY_DAYS: u32 = 7;
+
+/// `/standup submit` records today's answers, `/standup edit` corrects them
+/// and `/standup history [days] [page]` shows the member their own.
+pub struct StandupCommand {
+    standups: StandupService,
+    guilds: Arc<dyn GuildConfigRepository>,
+    default_timezone: Tz,
+    rules: StandupRules,
+    debouncer: Option<EditDebouncer>,
+}
+
+impl StandupCommand {
+    pub fn new(
+        standups: StandupService,
+        guilds: Arc<dyn GuildConfigRepository>,
+        default_timezone: Tz,
+    ) -> Self {
+        Self {
+            standups,
+            guilds,
+            default_timezone,
+            rules: StandupRules::default(),
+            debouncer: None,
+        }
+    }
+
+    /// Turn away the submissions breaking `rules` before they are saved.
+    pub fn with_rules(mut self, rules: StandupRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Coalesce quick successive edits of an entry into a single save.
+    ///
+    /// The first edit of the day still creates the entry right away.
+    pub fn with_debouncer(mut self, debouncer: EditDebouncer) -> Self {
+        self.debouncer = Some(debouncer);
+        self
+    }
+
+    pub fn spec() -> CommandSpec {
+        let answers = || {
+            vec![
+                CommandOption::string("yesterday", "What you did yesterday").required(),
+                CommandOption::string("today", "What you will do today").required(),
+                CommandOption::string("blockers", "Anything blocking you"),
+            ]
+        };
+
+        CommandSpec::new(STANDUP_COMMAND, "Submit your daily standup").with_options(vec![
+            CommandOption::subcommand("submit", "Submit today's standup", answers()),
+            CommandOption::subcommand("edit", "Correct today's standup", answers()),
+            CommandOption::subcommand(
+                "history",
+                "Show your past standups",
+                vec![
+                    CommandOption::integer("days", "Days to look back on"),
+                    CommandOption::integer("page", "Page of the history"),
+                ],
+            ),
+        ])
+    }
+}
+
+#[async_trait]
+impl CommandHandler for StandupCommand {
+    async fn handle(&self, invocation: &Invocation) -> Result<Reply> {
+        let Some(guild_id) = invocation.guild_id else {
+            return Ok(Reply::ephemeral(t(Locale::En, "standup.guild_only", &[])));
+        };
+
+        let config = self
+            .guilds
+            .find(guild_id)
+            .await?
+            .unwrap_or_else(|| GuildConfig::new(guild_id));
+        let locale = config.locale();
+
+        let option = |name: &str| invocation.options.get(name).cloned();
+        if option("subcommand").as_deref() == Some("history") {
+            return self.history(invocation, &config).await;
+        }
+
+        let (Some(subcommand), Some(yesterday), Some(today)) =
+            (option("subcommand"), option("yesterday"), option("today"))
+        else {
+            return Ok(Reply::ephemeral(t(locale, "standup.usage", &[])));
+        };
+
+        let now = Utc::now();
+
+        let mut entry = StandupEntry {
+            id: None,
+            guild_id,
+            channel_id: invocation.channel_id,
+            user_id: invocation.user_id,
+            team: config
+                .team_for(invocation.channel_id, invocation.roles())
+                .map(|team| team.name.clone()),
+            date: config.local_date(now, self.default_timezone),
+            yesterday,
+            today,
+            blockers: option("blockers").unwrap_or_default(),
+            sprint_id: None,
+            created_at: now,
+        };
+
+        if let Err(invalid) = self.rules.apply(&mut entry) {
+            return Ok(Reply::ephemeral(invalid_reply(&invalid, locale)));
+        }
+
+        let outside_window = match config.standup_window() {
+            Some((from, to)) if !config.is_within_standup_window(now, self.default_timezone) => {
+                let from = from.format("%H:%M").to_string();
+                let to = to.format("%H:%M").to_string();
+                let args = [("from", from.as_str()), ("to", to.as_str())];
+                if config.reject_outside_window {
+                    return Ok(Reply::ephemeral(t(locale, "standup.window_closed", &args)));
+                }
+                Some(t(locale, "standup.outside_window", &args))
+            }
+            _ => None,
+        };
+
+        let (entry, title) = match subcommand.as_str() {
+            "submit" => match self.standups.submit(entry).await {
+                Ok(entry) => (entry, "standup.submitted"),
+                Err(error) if error.is::<Conflict>() => {
+                    return Ok(Reply::ephemeral(t(
+                        locale,
+                        "standup.already_submitted",
+                        &[],
+                    )));
+                }
+                Err(error) => return Err(error),
+            },
+            "edit" => {
+                if let Some(debouncer) = &self.debouncer {
+                    if self
+                        .standups
+                        .has_submitted(entry.guild_id, entry.user_id, entry.channel_id, entry.date)
+                        .await?
+                    {
+                        debouncer.edit(entry.clone());
+                        return Ok(entry_reply(
+                            &config,
+                            &entry,
+                            "standup.updated",
+                            outside_window,
+                        ));
+                    }
+                }
+                let upserted = self.standups.edit(entry).await?;
+                let title = if upserted.created {
+                    "standup.submitted"
+                } else {
+                    "standup.updated"
+                };
+                (upserted.entry, title)
+            }
+            _ => return Ok(Reply::ephemeral(t(locale, "standup.usage", &[]))),
+        };
+
+        Ok(entry_reply(&config, &entry, title, outside_window))
+    }
+}
+
+impl StandupCommand {
+    async fn history(&self, invocation: &Invocation, config: &GuildConfig) -> Result<Reply> {
+        let locale = config.locale();
+        let number = |name: &str, default| match invocation.options.get(name) {
+            Some(value) => value.trim().parse().ok().filter(|value| *value > 0),
+            None => Some(default),
+        };
+        let (Some(days), Some(page)) = (number("days", DEFAULT_HISTORY_DAYS), number("page", 1))
+        else {
+            let max = MAX_HISTORY_DAYS.to_string();
+            return Ok(Reply::ephemeral(t(
+                locale,
+                "standup.history_usage",
+                &[("max", &max)],
+            )));
+        };
+
+        let today = config.local_date(Utc::now(), self.default_timezone);
+        let query = HistoryQuery::last_days(
+            config.guild_id,
+            invocation.user_id,
+            today,
+            days,
+            page as usize,
+        );
+        let history = self.standups.history(&query).await?;
+
+        if history.entries.is_empty() {
+            let days = ((query.to - query.from).num_days() + 1).to_string();
+            return Ok(Reply::ephemeral(t(
+                locale,
+                "standup.history_empty",
+                &[("days", &days)],
+            )));
+        }
+
+        Ok(Reply::ephemeral(String::new())
+            .with_embed(history_embed(&query, &history, page, locale)))
+    }
+}
+
+fn history_embed(query: &HistoryQuery, history: &HistoryPage, page: u32, locale: Locale) -> Embed {
+    let mut description = t(
+        locale,
+        "standup.history_range",
+        &[
+            ("from", &query.from.to_string()),
+            ("to", &query.to.to_string()),
+            ("page", &page.to_string()),
+        ],
+    );
+    if history.has_more {
+        description.push('\n');
+        description.push_str(&t(
+            locale,
+            "standup.history_more",
+            &[("page", &(page + 1).to_string())],
+        ));
+    }
+
+    let fields = history
+        .entries
+        .iter()
+        .map(|entry| {
+            let answers = [
+                ("standup.yesterday", &entry.yesterday),
+                ("standup.today", &entry.today),
+                ("standup.blockers", &entry.blockers),
+            ]
+            .iter()
+            .map(|(name, value)| {
+                let value = if value.trim().is_empty() { "-" } else { value };
+                format!("{}: {}", t(locale, name, &[]), value)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+            (
+                entry.date.to_string(),
+                truncate(&answers, EMBED_FIELD_VALUE_LIMIT).into_owned(),
+            )
+        })
+        .collect();
+
+    Embed {
+        title: t(locale, "standup.history_title", &[]),
+        description,
+        fields,
+    }
+}
+
+fn invalid_reply(invalid: &InvalidStandup, locale: Locale) -> String {
+    let field_name = |field: &str| t(locale, &format!("standup.{}", field), &[]);
+
+    match invalid {
+        InvalidStandup::Empty => t(locale, "standup.empty", &[]),
+        InvalidStandup::TooShort { field, min } => t(
+            locale,
+            "standup.too_short",
+            &[("field", &field_name(field)), ("min", &min.to_string())],
+        ),
+        InvalidStandup::TooLong { field, max } => t(
+            locale,
+            "standup.too_long",
+            &[("field", &field_name(field)), ("max", &max.to_string())],
+        ),
+    }
+}
+
+/// The entry with the `notice`, visible as the guild configured.
+fn entry_reply(
+    config: &GuildConfig,
+    entry: &StandupEntry,
+    title: &str,
+    notice: Option<String>,
+) -> Reply {
+    let notice = notice.unwrap_or_default();
+    let reply = match config.standup_visibility {
+        StandupVisibility::Public => Reply::public(notice),
+        StandupVisibility::Ephemeral => Reply::ephemeral(notice),
+    };
+
+    reply.with_embed(embed(entry, title, config.locale()))
+}
+
+fn embed(entry: &StandupEntry, title: &str, locale: Locale) -> Embed {
+    let field = |name: &str, value: &str| {
+        let value = if value.trim().is_empty() { "-" } else { value };
+        (
+            t(locale, name, &[]),
+            truncate(value, EMBED_FIELD_VALUE_LIMIT).into_owned(),
+        )
+    };
+    let user = format!("<@{}>", entry.user_id);
+    let date = entry.date.to_string();
+
+    Embed {
+        title: t(locale, title, &[]),
+        description: t(
+            locale,
+            "standup.summary",
+            &[("user", &user), ("date", &date)],
+        ),
+        fields: vec![
+            field("standup.yesterday", &entry.yesterday),
+            field("standup.today", &entry.today),
+            field("standup.blockers", &entry.blockers),
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, time::Duration};
+
+    use crate::{
+        domain::{
+            id::{ChannelId, GuildId, UserId},
+            standup::{StandupRepository, MAX_ANSWER_LENGTH},
+        },
+        drivers::database::memory::{
+            InMemoryGuildConfigRepository, InMemorySprintRepository, InMemoryStandupRepository,
+        },
+    };
+
+    use super::*;
+
+    fn invocation(options: &[(&str, &str)]) -> Invocation {
+        Invocation {
+            name: STANDUP_COMMAND.into(),
+            guild_id: Some(GuildId(1)),
+            channel_id: ChannelId(2),
+            user_id: UserId(3),
+            member: None,
+            options: options
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect::<HashMap<_, _>>(),
+        }
+    }
+
+    fn command() -> StandupCommand {
+        StandupCommand::new(
+            StandupService::new(
+                Arc::new(InMemoryStandupRepository::default()),
+                Arc::new(InMemorySprintRepository::default()),
+            ),
+            Arc::new(InMemoryGuildConfigRepository::default()),
+            Tz::UTC,
+        )
+    }
+
+    async fn command_for(config: GuildConfig) -> StandupCommand {
+        let guilds = Arc::new(InMemoryGuildConfigRepository::default());
+        guilds.upsert(&config).await.unwrap();
+
+        StandupCommand::new(
+            StandupService::new(
+                Arc::new(InMemoryStandupRepository::default()),
+                Arc::new(InMemorySprintRepository::default()),
+            ),
+            guilds,
+            Tz::UTC,
+        )
+    }
+
+    fn today(reply: &Reply) -> &str {
+        &reply.embed.as_ref().unwrap().fields[1].1
+    }
+
+    #[tokio::test]
+    async fn edit_updates_the_entry_of_today() {
+        let command = command();
+        command
+            .handle(&invocation(&[
+                ("subcommand", "submit"),
+                ("yesterday", "reviwed PRs"),
+                ("today", "shcduler"),
+            ]))
+            .await
+            .unwrap();
+
+        let reply = command
+            .handle(&invocation(&[
+                ("subcommand", "edit"),
+                ("yesterday", "reviewed PRs"),
+                ("today", "scheduler"),
+            ]))
+            .await
+            .unwrap();
+
+        let embed = reply.embed.as_ref().unwrap();
+        assert_eq!(embed.title, "Standup updated");
+        assert_eq!(embed.fields[0].1, "reviewed PRs");
+        assert_eq!(today(&reply), "scheduler");
+        assert_eq!(embed.fields[2].1, "-");
+    }
+
+    #[tokio::test]
+    async fn quick_edits_are_saved_once_the_window_closes() {
+        let standups = Arc::new(InMemoryStandupRepository::default());
+        let service = StandupService::new(
+            standups.clone(),
+            Arc::new(InMemorySprintRepository::default()),
+        );
+        let command = StandupCommand::new(
+            service.clone(),
+            Arc::new(InMemoryGuildConfigRepository::default()),
+            Tz::UTC,
+        )
+        .with_debouncer(EditDebouncer::new(service, Duration::from_millis(50)));
+        let submitted = command
+            .handle(&invocation(&[
+                ("subcommand", "edit"),
+                ("yesterday", "reviewed PRs"),
+                ("today", "sch"),
+            ]))
+            .await
+            .unwrap();
+        assert_eq!(submitted.embed.as_ref().unwrap().title, "Standup submitted");
+
+        for draft in ["schedu", "scheduler"] {
+            let reply = command
+                .handle(&invocation(&[
+                    ("subcommand", "edit"),
+                    ("yesterday", "reviewed PRs"),
+                    ("today", draft),
+                ]))
+                .await
+                .unwrap();
+            assert_eq!(reply.embed.as_ref().unwrap().title, "Standup updated");
+            assert_eq!(today(&reply), draft);
+        }
+
+        let saved = |standups: Arc<InMemoryStandupRepository>| async move {
+            let query =
+                HistoryQuery::last_days(GuildId(1), UserId(3), Utc::now().date_naive(), 1, 1);
+            standups.history(&query).await.unwrap()[0].today.clone()
+        };
+        assert_eq!(saved(standups.clone()).await, "sch");
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(saved(standups).await, "scheduler");
+    }
+
+    #[tokio::test]
+    async fn first_edit_in_another_channel_is_saved_right_away() {
+        let standups = Arc::new(InMemoryStandupRepository::default());
+        let service = StandupService::new(
+            standups.clone(),
+            Arc::new(InMemorySprintRepository::default()),
+        );
+        let command = StandupCommand::new(
+            service.clone(),
+            Arc::new(InMemoryGuildConfigRepository::default()),
+            Tz::UTC,
+        )
+        .with_debouncer(EditDebouncer::new(service, Duration::from_secs(60)));
+        let options = [
+            ("subcommand", "edit"),
+            ("yesterday", "reviewed PRs"),
+            ("today", "scheduler"),
+        ];
+        command.handle(&invocation(&options)).await.unwrap();
+
+        let mut elsewhere = invocation(&options);
+        elsewhere.channel_id = ChannelId(4);
+        let reply = command.handle(&elsewhere).await.unwrap();
+
+        assert_eq!(reply.embed.as_ref().unwrap().title, "Standup submitted");
+        let query = HistoryQuery::last_days(GuildId(1), UserId(3), Utc::now().date_naive(), 1, 1);
+        let mut channels: Vec<_> = standups
+            .history(&query)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.channel_id)
+            .collect();
+        channels.sort_by_key(|channel_id| channel_id.0);
+        assert_eq!(channels, [ChannelId(2), ChannelId(4)]);
+    }
+
+    #[tokio::test]
+    async fn edit_without_an_entry_submits_one() {
+        let reply = command()
+            .handle(&invocation(&[
+                ("subcommand", "edit"),
+                ("yesterday", "reviewed PRs"),
+                ("today", "scheduler"),
+            ]))
+            .await
+            .unwrap();
+
+        assert_eq!(reply.embed.as_ref().unwrap().title, "Standup submitted");
+        assert_eq!(today(&reply), "scheduler");
+    }
+
+    #[tokio::test]
+    async fn history_without_entries_says_so() {
+        let reply = command()
+            .handle(&invocation(&[("subcommand", "history"), ("days", "90")]))
+            .await
+            .unwrap();
+
+        assert_eq!(reply, Reply::ephemeral("No standups in the last 30 days"));
+    }
+
+    #[tokio::test]
+    async fn history_shows_the_entries_of_the_member() {
+        let command = command();
+        command
+            .handle(&invocation(&[
+                ("subcommand", "submit"),
+                ("yesterday", "reviewed PRs"),
+                ("today", "scheduler"),
+            ]))
+            .await
+            .unwrap();
+
+        let reply = command
+            .handle(&invocation(&[("subcommand", "history")]))
+            .await
+            .unwrap();
+
+        assert!(reply.ephemeral);
+        let embed = reply.embed.unwrap();
+        assert_eq!(embed.title, "Your standups");
+        assert!(
+            embed.description.ends_with("page 1"),
+            "{}",
+            embed.description
+        );
+        assert_eq!(embed.fields.len(), 1);
+        assert_eq!(
+            embed.fields[0].1,
+            "Yesterday: reviewed PRs\nToday: scheduler\nBlockers: -"
+        );
+
+        let reply = command
+            .handle(&invocation(&[("subcommand", "history"), ("page", "0")]))
+            .await
+            .unwrap();
+        assert_eq!(
+            reply.content,
+            "usage: /standup history [days] [page], days up to 30"
+        );
+    }
+
+    #[tokio::test]
+    async fn second_submit_of_the_day_points_to_edit() {
+        let command = command();
+        let submit = invocation(&[
+            ("subcommand", "submit"),
+            ("yesterday", "reviewed PRs"),
+            ("today", "scheduler"),
+        ]);
+        command.handle(&submit).await.unwrap();
+
+        let reply = command.handle(&submit).await.unwrap();
+
+        assert_eq!(
+            reply,
+            Reply::ephemeral(
+                "You already submitted today's standup, use /standup edit to correct it"
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn guild_visibility_decides_who_sees_the_standup() {
+        let submit = invocation(&[
+            ("subcommand", "submit"),
+            ("yesterday", "reviewed PRs"),
+            ("today", "scheduler"),
+        ]);
+
+        let reply = command().handle(&submit).await.unwrap();
+        assert!(reply.ephemeral);
+
+        let mut config = GuildConfig::new(GuildId(1));
+        config.standup_visibility = StandupVisibility::Public;
+        let command = command_for(config).await;
+
+        let reply = command.handle(&submit).await.unwrap();
+        assert!(!reply.ephemeral);
+        assert_eq!(today(&reply), "scheduler");
+
+        let reply = command
+            .handle(&invocation(&[("subcommand", "edit")]))
+            .await
+            .unwrap();
+        assert!(reply.ephemeral, "usage errors stay private");
+    }
+
+    #[tokio::test]
+    async fn missing_answers_reply_with_the_usage() {
+        let reply = command()
+            .handle(&invocation(&[("subcommand", "edit")]))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            reply,
+            Reply::ephemeral("usage: /standup <submit|edit> <yesterday> <today> [blockers]")
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_answers_are_turned_away_before_saving() {
+        let standups = Arc::new(InMemoryStandupRepository::default());
+        let command = StandupCommand::new(
+            StandupService::new(
+                standups.clone(),
+                Arc::new(InMemorySprintRepository::default()),
+            ),
+            Arc::new(InMemoryGuildConfigRepository::default()),
+            Tz::UTC,
+        )
+        .with_rules(StandupRules::new(5));
+        let submit = |yesterday: String, today: &str| {
+            invocation(&[
+                ("subcommand", "submit"),
+                ("yesterday", &yesterday),
+                ("today", today),
+            ])
+        };
+
+        let long = "a".repeat(MAX_ANSWER_LENGTH + 1);
+        let replies = [
+            (
+                submit("  ".into(), ""),
+                "Tell what you did yesterday or will do today",
+            ),
+            (
+                submit("ok".into(), "scheduler"),
+                "Yesterday needs at least 5 characters",
+            ),
+            (
+                submit(long, "scheduler"),
+                "Yesterday is limited to 1000 characters",
+            ),
+        ];
+        for (invocation, expected) in replies {
+            let reply = command.handle(&invocation).await.unwrap();
+            assert_eq!(reply, Reply::ephemeral(expected));
+        }
+        let today = Utc::now().date_naive();
+        assert!(standups
+            .participants(GuildId(1), today)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let reply = command
+            .handle(&submit(" reviewed PRs ".into(), ""))
+            .await
+            .unwrap();
+        assert_eq!(reply.embed.unwrap().fields[0].1, "reviewed PRs");
+    }
+
+    #[tokio::test]
+    async fn replies_in_the_guild_locale() {
+        let mut config = GuildConfig::new(GuildId(1));
+        config.locale = Some("pt-BR".into());
+        let command = command_for(config).await;
+
+        let reply = command
+            .handle(&invocation(&[
+                ("subcommand", "submit"),
+                ("yesterday", "revisei PRs"),
+                ("today", "scheduler"),
+            ]))
+            .await
+            .unwrap();
+
+        let embed = reply.embed.unwrap();
+        assert_eq!(embed.title, "Standup enviado");
+        assert_eq!(embed.fields[0].0, "Ontem");
+        assert!(embed.description.starts_with("<@3> em "));
+    }
+
+    #[tokio::test]
+    async fn standups_outside_the_window_warn_or_are_rejected() {
+        let mut config = GuildConfig::new(GuildId(1));
+        // Half a day away from now, whatever the time the test runs at.
+        config.reminder_time = Some((Utc::now() + chrono::TimeDelta::hours(12)).time());
+        config.standup_window_minutes = Some(30);
+        let submit = invocation(&[
+            ("subcommand", "submit"),
+            ("yesterday", "reviewed PRs"),
+            ("today", "scheduler"),
+        ]);
+
+        let reply = command_for(config.clone())
+            .await
+            .handle(&submit)
+            .await
+            .unwrap();
+        assert!(
+            reply.content.starts_with("Sent outside the standup window"),
+            "{}",
+            reply.content
+        );
+        assert!(reply.embed.is_some());
+
+        config.reject_outside_window = true;
+        let reply = command_for(config).await.handle(&submit).await.unwrap();
+        assert!(
+            reply.content.starts_with("Standups are only accepted from"),
+            "{}",
+            reply.content
+        );
+        assert_eq!(reply.embed, None);
+    }
+}
@@ -0,0 +1,246 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono_tz::Tz;
+
+use crate::domain::guild::{GuildConfig, GuildConfigRepository};
+
+use super::{
+    command::{CommandHandler, Embed, Invocation, Reply},
+    registry::CommandSpec,
+};
+
+pub const WHOAMI_COMMAND: &str = "whoami";
+
+const NOT_SET: &str = "not set";
+
+/// `/whoami`, shows what the bot knows about the member to debug permissions.
+pub struct WhoamiCommand {
+    guilds: Arc<dyn GuildConfigRepository>,
+    default_timezone: Tz,
+}
+
+impl WhoamiCommand {
+    pub fn new(guilds: Arc<dyn GuildConfigRepository>, default_timezone: Tz) -> Self {
+        Self {
+            guilds,
+            default_timezone,
+        }
+    }
+
+    pub fn spec() -> CommandSpec {
+        CommandSpec::new(WHOAMI_COMMAND, "Show what the bot knows about you")
+    }
+}
+
+#[async_trait]
+impl CommandHandler for WhoamiCommand {
+    async fn handle(&self, invocation: &Invocation) -> Result<Reply> {
+        let config = match invocation.guild_id {
+            Some(guild_id) => Some(
+                self.guilds
+                    .find(guild_id)
+                    .await?
+                    .unwrap_or_else(|| GuildConfig::new(guild_id)),
+            ),
+            None => None,
+        };
+
+        Ok(Reply::ephemeral(String::new()).with_embed(whoami(
+            invocation,
+            config.as_ref(),
+            self.default_timezone,
+        )))
+    }
+}
+
+/// The card answered to `/whoami`, `config` is `None` in direct messages.
+pub fn whoami(
+    invocation: &Invocation,
+    config: Option<&GuildConfig>,
+    default_timezone: Tz,
+) -> Embed {
+    let field = |name: &str, value: String| (name.to_owned(), value);
+
+    let roles = invocation.roles();
+    let roles = if roles.is_empty() {
+        "none".to_owned()
+    } else {
+        roles
+            .iter()
+            .map(|role| role.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let admin = config
+        .zip(invocation.member.as_ref())
+        .is_some_and(|(config, member)| config.is_admin(member));
+
+    let mut fields = vec![
+        field("User", invocation.user_id.to_string()),
+        field("Roles", roles),
+        field("Admin", if admin { "yes" } else { "no" }.to_owned()),
+    ];
+
+    match config {
+        Some(config) => {
+            let timezone = config.tz(default_timezone);
+            let timezone = if config.timezone.as_deref() == Some(timezone.name()) {
+                timezone.name().to_owned()
+            } else {
+                format!("{} (default)", timezone.name())
+            };
+
+            fields.extend([
+                field("Guild", config.guild_id.to_string()),
+                field(
+                    "Team",
+                    config
+                        .team_for(invocation.channel_id, invocation.roles())
+                        .map_or_else(|| "none".to_owned(), |team| team.name.clone()),
+                ),
+                field(
+                    "Standup channel",
+                    config
+                        .standup_channel_id
+                        .map_or_else(|| NOT_SET.to_owned(), |channel| format!("<#{}>", channel)),
+                ),
+                field(
+                    "Standup time",
+                    config.reminder_time.map_or_else(
+                        || NOT_SET.to_owned(),
+                        |time| time.format("%H:%M").to_string(),
+                    ),
+                ),
+                field("Timezone", timezone),
+                field(
+                    "Locale",
+                    config
+                        .locale
+                        .clone()
+                        .unwrap_or_else(|| "en (default)".to_owned()),
+                ),
+            ]);
+        }
+        None => fields.push(field("Guild", "direct message".to_owned())),
+    }
+
+    Embed {
+        title: "Who am I".to_owned(),
+        description: format!("<@{}>", invocation.user_id),
+        fields,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::NaiveTime;
+
+    use crate::{
+        domain::{
+            guild::{Member, Team},
+            id::{ChannelId, GuildId, RoleId, UserId},
+        },
+        drivers::database::memory::InMemoryGuildConfigRepository,
+    };
+
+    use super::*;
+
+    fn invocation(guild_id: Option<GuildId>, member: Option<Member>) -> Invocation {
+        Invocation {
+            name: WHOAMI_COMMAND.into(),
+            guild_id,
+            channel_id: ChannelId(2),
+            user_id: UserId(3),
+            member,
+            options: HashMap::new(),
+        }
+    }
+
+    fn value<'a>(embed: &'a Embed, name: &str) -> &'a str {
+        embed
+            .fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.as_str())
+            .unwrap_or_else(|| panic!("missing field {}", name))
+    }
+
+    #[test]
+    fn shows_the_member_and_the_resolved_guild_config() {
+        let member = Member {
+            roles: vec![RoleId(10), RoleId(20)],
+            ..Member::default()
+        };
+        let mut config = GuildConfig::new(GuildId(1));
+        config.standup_channel_id = Some(ChannelId(100));
+        config.reminder_time = NaiveTime::from_hms_opt(9, 30, 0);
+        config.timezone = Some("America/Sao_Paulo".into());
+        config.locale = Some("pt-BR".into());
+        config.admin_roles = vec![RoleId(10)];
+        config.teams = vec![Team {
+            name: "backend".into(),
+            role_id: Some(RoleId(20)),
+            channel_id: None,
+        }];
+
+        let embed = whoami(
+            &invocation(Some(GuildId(1)), Some(member)),
+            Some(&config),
+            Tz::UTC,
+        );
+
+        assert_eq!(embed.description, "<@3>");
+        assert_eq!(value(&embed, "User"), "3");
+        assert_eq!(value(&embed, "Roles"), "10, 20");
+        assert_eq!(value(&embed, "Admin"), "yes");
+        assert_eq!(value(&embed, "Guild"), "1");
+        assert_eq!(value(&embed, "Team"), "backend");
+        assert_eq!(value(&embed, "Standup channel"), "<#100>");
+        assert_eq!(value(&embed, "Standup time"), "09:30");
+        assert_eq!(value(&embed, "Timezone"), "America/Sao_Paulo");
+        assert_eq!(value(&embed, "Locale"), "pt-BR");
+    }
+
+    #[test]
+    fn unset_config_shows_the_defaults() {
+        let member = Member {
+            roles: vec![],
+            permissions: 1 << 11,
+            owner: false,
+        };
+        let mut config = GuildConfig::new(GuildId(1));
+        config.timezone = Some("Not/AZone".into());
+
+        let embed = whoami(
+            &invocation(Some(GuildId(1)), Some(member)),
+            Some(&config),
+            Tz::America__Fortaleza,
+        );
+
+        assert_eq!(value(&embed, "Roles"), "none");
+        assert_eq!(value(&embed, "Admin"), "no");
+        assert_eq!(value(&embed, "Team"), "none");
+        assert_eq!(value(&embed, "Standup channel"), NOT_SET);
+        assert_eq!(value(&embed, "Standup time"), NOT_SET);
+        assert_eq!(value(&embed, "Timezone"), "America/Fortaleza (default)");
+        assert_eq!(value(&embed, "Locale"), "en (default)");
+    }
+
+    #[tokio::test]
+    async fn direct_messages_have_no_guild() {
+        let command =
+            WhoamiCommand::new(Arc::new(InMemoryGuildConfigRepository::default()), Tz::UTC);
+
+        let reply = command.handle(&invocation(None, None)).await.unwrap();
+
+        assert!(reply.ephemeral);
+        let embed = reply.embed.unwrap();
+        assert_eq!(value(&embed, "Guild"), "direct message");
+        assert_eq!(value(&embed, "Admin"), "no");
+        assert_eq!(embed.fields.len(), 4);
+    }
+}
@@ -0,0 +1,162 @@
+use std::{future::Future, net::SocketAddr, sync::Arc, time::Duration};
+
+use anyhow::{Context, Result};
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::Server;
+use tonic_health::{server::HealthReporter, ServingStatus};
+
+use crate::{configuration::GrpcSettings, drivers::database::Ping};
+
+/// How often the database is pinged to refresh the reported health.
+pub const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Serve the standard `grpc.health.v1.Health` service plus reflection until `shutdown` resolves.
+///
+/// The overall server status follows the MongoDB ping.
+pub async fn serve<F>(listener: TcpListener, database: Arc<dyn Ping>, shutdown: F) -> Result<()>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let (reporter, health_service) = tonic_health::server::health_reporter();
+
+    let reflection_service = tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
+        .build_v1()
+        .context("expected to build grpc reflection service")?;
+
+    let watcher = tokio::spawn(watch_database(reporter, database, HEALTH_CHECK_INTERVAL));
+
+    let result = Server::builder()
+        .trace_fn(|request| tracing::info_span!("grpc", path = %request.uri().path()))
+        .add_service(health_service)
+        .add_service(reflection_service)
+        .serve_with_incoming_shutdown(TcpListenerStream::new(listener), shutdown)
+        .await
+        .context("expected to serve grpc");
+
+    watcher.abort();
+
+    result
+}
+
+/// Bind the gRPC listener and serve it in the background.
+pub async fn spawn<F>(settings: &GrpcSettings, database: Arc<dyn Ping>, shutdown: F) -> Result<()>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let address = SocketAddr::from(([0, 0, 0, 0], settings.port));
+    let listener = TcpListener::bind(address)
+        .await
+        .context("expected to create grpc listener")?;
+
+    tracing::info!("listening on address for grpc {:?}", address);
+
+    tokio::spawn(async move {
+        serve(listener, database, shutdown)
+            .await
+            .expect("expected to serve grpc");
+    });
+
+    Ok(())
+}
+
+async fn watch_database(mut reporter: HealthReporter, database: Arc<dyn Ping>, every: Duration) {
+    let mut interval = tokio::time::interval(every);
+
+    loop {
+        interval.tick().await;
+
+        let status = match database.ping().await {
+            Ok(()) => ServingStatus::Serving,
+            Err(error) => {
+                tracing::warn!(error = ?error, "mongodb ping failed, reporting not serving");
+                ServingStatus::NotServing
+            }
+        };
+
+        reporter.set_service_status("", status).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use tonic_health::pb::{
+        health_check_response::ServingStatus, health_client::HealthClient, HealthCheckRequest,
+    };
+
+    use super::*;
+
+    struct Up;
+
+    #[async_trait]
+    impl Ping for Up {
+        async fn ping(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    struct Down;
+
+    #[async_trait]
+    impl Ping for Down {
+        async fn ping(&self) -> Result<()> {
+            anyhow::bail!("connection refused")
+        }
+    }
+
+    /// The status reported for `database`, polled until it is `expected` or
+    /// a second went by.
+    async fn reported_status(database: Arc<dyn Ping>, expected: ServingStatus) -> ServingStatus {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+
+        let server = tokio::spawn(serve(listener, database, async {
+            let _ = stopped.await;
+        }));
+
+        let channel = tonic::transport::Endpoint::from_shared(format!("http://{}", address))
+            .unwrap()
+            .connect()
+            .await
+            .unwrap();
+        let mut client = HealthClient::new(channel);
+
+        let mut status = ServingStatus::Unknown;
+        for _ in 0..50 {
+            status = client
+                .check(HealthCheckRequest {
+                    service: String::new(),
+                })
+                .await
+                .unwrap()
+                .into_inner()
+                .status();
+            if status == expected {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        stop.send(()).unwrap();
+        server.await.unwrap().unwrap();
+
+        status
+    }
+
+    #[tokio::test]
+    async fn health_check_is_serving_when_mongodb_is_up() {
+        let status = reported_status(Arc::new(Up), ServingStatus::Serving).await;
+
+        assert_eq!(status, ServingStatus::Serving);
+    }
+
+    #[tokio::test]
+    async fn health_check_is_not_serving_when_mongodb_is_down() {
+        let status = reported_status(Arc::new(Down), ServingStatus::NotServing).await;
+
+        assert_eq!(status, ServingStatus::NotServing);
+    }
+}
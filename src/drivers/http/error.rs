@@ -0,0 +1,161 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use bson::oid::ObjectId;
+use serde::Serialize;
+
+use crate::{domain::conflict::Conflict, drivers::database::is_unavailable};
+
+/// Errors returned by the HTTP handlers, always rendered as a JSON envelope.
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("missing or invalid api key")]
+    Unauthorized,
+    #[error("api key is not allowed to access this resource")]
+    Forbidden,
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    BadRequest(String),
+    #[error("{0}")]
+    Conflict(String),
+    /// A service the request depends on, other than the database, failed.
+    #[error("{0}")]
+    BadGateway(String),
+    #[error("database unavailable")]
+    Unavailable(anyhow::Error),
+    #[error(transparent)]
+    Internal(anyhow::Error),
+    /// A handler panicked, the incident id is logged with the panic.
+    #[error("internal server error")]
+    Panic { incident_id: String },
+}
+
+impl From<anyhow::Error> for ApiError {
+    /// An unreachable database is not a bug of the request, it is reported as
+    /// unavailable for clients and load balancers to retry elsewhere.
+    fn from(error: anyhow::Error) -> Self {
+        if let Some(conflict) = error.downcast_ref::<Conflict>() {
+            ApiError::Conflict(conflict.to_string())
+        } else if is_unavailable(&error) {
+            ApiError::Unavailable(error)
+        } else {
+            ApiError::Internal(error)
+        }
+    }
+}
+
+/// Parse the hex ObjectId of a path, a malformed one is a bad request.
+pub fn parse_object_id(id: &str) -> Result<ObjectId, ApiError> {
+    ObjectId::parse_str(id).map_err(|_| ApiError::BadRequest(format!("invalid id {:?}", id)))
+}
+
+#[derive(Serialize)]
+struct ErrorEnvelope<'a> {
+    error: ErrorBody<'a>,
+}
+
+#[derive(Serialize)]
+struct ErrorBody<'a> {
+    code: &'a str,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    incident_id: Option<&'a str>,
+}
+
+impl ApiError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden => StatusCode::FORBIDDEN,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::BadGateway(_) => StatusCode::BAD_GATEWAY,
+            ApiError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::Internal(_) | ApiError::Panic { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::Unauthorized => "unauthorized",
+            ApiError::Forbidden => "forbidden",
+            ApiError::NotFound(_) => "not_found",
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::Conflict(_) => "conflict",
+            ApiError::BadGateway(_) => "bad_gateway",
+            ApiError::Unavailable(_) => "unavailable",
+            ApiError::Internal(_) | ApiError::Panic { .. } => "internal",
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let message = match &self {
+            ApiError::Unavailable(error) => {
+                tracing::warn!(error = ?error, "database unavailable");
+                self.to_string()
+            }
+            ApiError::Internal(error) => {
+                tracing::error!(error = ?error, "request failed");
+                "internal server error".to_owned()
+            }
+            other => other.to_string(),
+        };
+
+        let incident_id = match &self {
+            ApiError::Panic { incident_id } => Some(incident_id.as_str()),
+            _ => None,
+        };
+        let envelope = ErrorEnvelope {
+            error: ErrorBody {
+                code: self.code(),
+                message,
+                incident_id,
+            },
+        };
+
+        (self.status(), Json(envelope)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use anyhow::Context;
+
+    use super::*;
+
+    #[test]
+    fn unreachable_database_is_unavailable() {
+        let error: anyhow::Result<()> = Err(mongodb::error::Error::from(io::ErrorKind::TimedOut))
+            .context("expected to find active sprint");
+
+        let error = ApiError::from(error.unwrap_err());
+
+        assert_eq!(error.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(error.code(), "unavailable");
+    }
+
+    #[test]
+    fn conflicts_are_reported_as_such() {
+        let error = anyhow::Error::new(Conflict).context("expected to insert sprint");
+
+        let error = ApiError::from(error);
+
+        assert_eq!(error.status(), StatusCode::CONFLICT);
+        assert_eq!(error.to_string(), "conflicts with an existing record");
+    }
+
+    #[test]
+    fn other_errors_are_internal() {
+        let error = ApiError::from(anyhow::anyhow!("expected standup entry to serialize"));
+
+        assert_eq!(error.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
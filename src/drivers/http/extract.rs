@@ -0,0 +1,176 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+    async_trait,
+    extract::{FromRef, FromRequestParts, Path, Query},
+    http::request::Parts,
+};
+
+use crate::{
+    domain::{
+        guild::{GuildConfig, GuildConfigRepository},
+        id::GuildId,
+    },
+    drivers::http::{error::ApiError, middlewares::auth::Caller},
+};
+
+const GUILD_ID_PARAM: &str = "guild_id";
+
+/// The guild a request acts on, taken from the `guild_id` path or query
+/// parameter, once the api key is known to be scoped to it.
+///
+/// Requires the [`Caller`] stored by the api key middlewares.
+#[derive(Debug, Clone)]
+pub struct GuildContext {
+    pub caller: Caller,
+    pub config: GuildConfig,
+}
+
+impl GuildContext {
+    pub fn guild_id(&self) -> GuildId {
+        self.config.guild_id
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for GuildContext
+where
+    Arc<dyn GuildConfigRepository>: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let caller = parts
+            .extensions
+            .get::<Caller>()
+            .cloned()
+            .ok_or(ApiError::Unauthorized)?;
+        let guild_id = guild_id(parts, state).await?;
+
+        // Checked before the lookup, unscoped keys can't probe for guilds.
+        caller.authorize(guild_id)?;
+
+        let guilds = Arc::<dyn GuildConfigRepository>::from_ref(state);
+        let config = guilds
+            .find(guild_id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("no guild {}", guild_id)))?;
+
+        Ok(Self { caller, config })
+    }
+}
+
+async fn guild_id<S: Send + Sync>(parts: &mut Parts, state: &S) -> Result<GuildId, ApiError> {
+    let from_path = Path::<HashMap<String, String>>::from_request_parts(parts, state)
+        .await
+        .ok()
+        .and_then(|Path(mut params)| params.remove(GUILD_ID_PARAM));
+    let raw = match from_path {
+        Some(raw) => raw,
+        None => Query::<HashMap<String, String>>::from_request_parts(parts, state)
+            .await
+            .ok()
+            .and_then(|Query(mut params)| params.remove(GUILD_ID_PARAM))
+            .ok_or_else(|| ApiError::BadRequest(format!("missing {}", GUILD_ID_PARAM)))?,
+    };
+
+    raw.parse()
+        .map(GuildId)
+        .map_err(|_| ApiError::BadRequest(format!("invalid guild id {:?}", raw)))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        http::{Request, StatusCode},
+        middleware,
+        routing::get,
+        Router,
+    };
+    use tower::ServiceExt;
+
+    use crate::{
+        configuration::test_keys,
+        drivers::{
+            database::memory::InMemoryGuildConfigRepository,
+            http::middlewares::auth::{require_api_key, ApiKeys, API_KEY_HEADER},
+        },
+    };
+
+    use super::*;
+
+    async fn handler(guild: GuildContext) -> String {
+        format!("{} {}", guild.guild_id(), guild.caller.label)
+    }
+
+    async fn test_router() -> Router {
+        let guilds = InMemoryGuildConfigRepository::default();
+        for guild_id in [1, 2] {
+            guilds
+                .upsert(&GuildConfig::new(GuildId(guild_id)))
+                .await
+                .unwrap();
+        }
+        let guilds: Arc<dyn GuildConfigRepository> = Arc::new(guilds);
+
+        let keys = ApiKeys::new(test_keys());
+
+        Router::new()
+            .route("/guilds/:guild_id", get(handler))
+            .route("/report", get(handler))
+            .route_layer(middleware::from_fn_with_state(keys, require_api_key))
+            .with_state(guilds)
+    }
+
+    async fn get_with(uri: &str, key: &str) -> (StatusCode, String) {
+        let response = test_router()
+            .await
+            .oneshot(
+                Request::get(uri)
+                    .header(API_KEY_HEADER, key)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn scoped_key_acts_on_its_guild() {
+        assert_eq!(
+            get_with("/guilds/1", "key").await,
+            (StatusCode::OK, "1 dashboard".to_owned())
+        );
+        assert_eq!(
+            get_with("/report?guild_id=1", "key").await,
+            (StatusCode::OK, "1 dashboard".to_owned())
+        );
+        assert_eq!(
+            get_with("/guilds/2", "admin-key").await,
+            (StatusCode::OK, "2 ops".to_owned())
+        );
+    }
+
+    #[tokio::test]
+    async fn key_not_scoped_to_the_guild_is_forbidden() {
+        let (status, _) = get_with("/guilds/2", "key").await;
+
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn unknown_guild_is_not_found() {
+        let (status, body) = get_with("/guilds/3", "key").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(body.contains("no guild 3"), "{body}");
+
+        let (status, _) = get_with("/report?guild_id=abc", "admin-key").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,715 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use axum::{
+    extract::{rejection::JsonRejection, Query, State},
+    http::StatusCode,
+    middleware,
+    routing::post,
+    Extension, Json, Router,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tracing::Instrument;
+
+use crate::{
+    configuration::{ConfigReloader, ReloadDiff},
+    domain::{
+        cleanup::{CleanupOutcome, MessageCleanup},
+        id::{ChannelId, GuildId},
+        reminder::{ReminderSender, ReminderService},
+        replay::{PromptReplay, ReplayOutcome},
+    },
+    drivers::{
+        discord::registry::{CommandRegistrar, CommandSpec},
+        http::{
+            error::ApiError,
+            middlewares::{
+                accept,
+                auth::{require_admin, ApiKeys, Caller},
+            },
+        },
+    },
+    observability::trace::{follow_current, TraceFlusher},
+};
+
+#[derive(Clone)]
+pub struct AdminState {
+    pub reloader: ConfigReloader,
+    pub traces: Arc<dyn TraceFlusher>,
+    /// Fires the due reminders on demand, `None` where Discord isn't running.
+    pub reminders: Option<AdminReminders>,
+    /// Registers the slash commands on demand, `None` where Discord isn't
+    /// running.
+    pub commands: Option<AdminCommands>,
+    /// Deletes the old messages of the bot on demand, `None` where Discord
+    /// isn't running.
+    pub cleanup: Option<MessageCleanup>,
+    /// Posts the standup prompt of today again on demand, `None` where
+    /// Discord isn't running.
+    pub replay: Option<PromptReplay>,
+}
+
+/// What `POST /admin/reminders/fire` sends the due reminders with.
+#[derive(Clone)]
+pub struct AdminReminders {
+    pub service: ReminderService,
+    pub sender: Arc<dyn ReminderSender>,
+}
+
+/// What `POST /admin/register-commands` registers and with.
+#[derive(Clone)]
+pub struct AdminCommands {
+    pub specs: Vec<CommandSpec>,
+    pub registrar: Arc<dyn CommandRegistrar>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RegisterParams {
+    /// Register in this guild only, global when unset.
+    pub guild_id: Option<GuildId>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RegisterResponse {
+    pub registered: usize,
+    pub guild_id: Option<GuildId>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CleanupParams {
+    pub channel_id: ChannelId,
+    /// Delete the messages of the bot older than this, at least a day.
+    pub older_than_days: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReplayRequest {
+    pub guild_id: GuildId,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FlushResponse {
+    pub flushed: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FireResponse {
+    pub accepted: bool,
+}
+
+/// Admin only routes to operate the running bot.
+pub fn router(state: AdminState, keys: ApiKeys) -> Router {
+    Router::new()
+        .route("/admin/config/reload", post(reload_config))
+        .route("/admin/flush-traces", post(flush_traces))
+        .route("/admin/reminders/fire", post(fire_reminders))
+        .route("/admin/reminders/replay", post(replay_reminder))
+        .route("/admin/register-commands", post(register_commands))
+        .route("/admin/cleanup-messages", post(cleanup_messages))
+        .route_layer(accept::layer(accept::JSON))
+        .route_layer(middleware::from_fn_with_state(keys, require_admin))
+        .with_state(state)
+}
+
+/// Same as a SIGHUP, for platforms where signals are awkward to send.
+#[tracing::instrument(name = "Reload config handler", skip(reloader))]
+pub async fn reload_config(
+    State(AdminState { reloader, .. }): State<AdminState>,
+) -> Result<Json<ReloadDiff>, ApiError> {
+    let diff = reloader.reload().map_err(|error| {
+        tracing::warn!(error = %error, "rejected configuration reload");
+        ApiError::BadRequest(error.to_string())
+    })?;
+    tracing::info!("configuration reloaded");
+
+    Ok(Json(diff))
+}
+
+/// Export the buffered spans now instead of waiting for the batch, to check
+/// that the collector is reachable.
+#[tracing::instrument(name = "Flush traces handler", skip(traces))]
+pub async fn flush_traces(
+    State(AdminState { traces, .. }): State<AdminState>,
+) -> Result<Json<FlushResponse>, ApiError> {
+    // The batch processor blocks the thread until its task has exported.
+    tokio::task::spawn_blocking(move || traces.force_flush())
+        .await
+        .context("expected trace flush to finish")?
+        .map_err(|error| {
+            tracing::warn!(error = ?error, "failed to flush traces");
+            ApiError::BadGateway(format!("{:#}", error))
+        })?;
+
+    Ok(Json(FlushResponse { flushed: true }))
+}
+
+/// Send the due reminders now instead of on the next tick.
+///
+/// The sends go on in the background, Discord rate limits would hold the
+/// request otherwise. Their spans stay in the trace of the request.
+#[tracing::instrument(name = "Fire reminders handler", skip(reminders))]
+pub async fn fire_reminders(
+    State(AdminState { reminders, .. }): State<AdminState>,
+) -> Result<(StatusCode, Json<FireResponse>), ApiError> {
+    let AdminReminders { service, sender } =
+        reminders.ok_or_else(|| ApiError::NotFound("reminders are not running".into()))?;
+
+    let span = follow_current(tracing::info_span!(parent: None, "Fire reminders from admin"));
+    tokio::spawn(
+        async move {
+            match service.fire_due(Utc::now(), sender.as_ref()).await {
+                Ok(delivered) => tracing::info!(delivered, "fired due reminders"),
+                Err(error) => tracing::warn!(error = ?error, "failed to fire due reminders"),
+            }
+        }
+        .instrument(span),
+    );
+
+    Ok((StatusCode::ACCEPTED, Json(FireResponse { accepted: true })))
+}
+
+/// Post the standup prompt of today to the standup channel of the guild now,
+/// e.g. after the daily one was missed or the channel changed. The replay is
+/// audited as a manual reminder of the caller.
+#[tracing::instrument(name = "Replay reminder handler", skip(replay, caller, body))]
+pub async fn replay_reminder(
+    State(AdminState { replay, .. }): State<AdminState>,
+    Extension(caller): Extension<Caller>,
+    body: Result<Json<ReplayRequest>, JsonRejection>,
+) -> Result<Json<ReplayOutcome>, ApiError> {
+    let replay = replay.ok_or_else(|| ApiError::NotFound("discord is not running".into()))?;
+    let Json(request) = body.map_err(|rejection| ApiError::BadRequest(rejection.body_text()))?;
+
+    let outcome = replay
+        .replay(&caller.label, request.guild_id, Utc::now())
+        .await
+        .map_err(|error| {
+            tracing::warn!(error = ?error, "failed to replay the standup prompt");
+            ApiError::BadGateway(format!("{:#}", error))
+        })?
+        .ok_or_else(|| ApiError::NotFound("the guild has no standup channel".into()))?;
+
+    Ok(Json(outcome))
+}
+
+/// Register every slash command again, e.g. after deploying new ones. With
+/// `?guild_id=` only in that guild, where they are usable at once.
+#[tracing::instrument(name = "Register commands handler", skip(commands))]
+pub async fn register_commands(
+    State(AdminState { commands, .. }): State<AdminState>,
+    Query(params): Query<RegisterParams>,
+) -> Result<Json<RegisterResponse>, ApiError> {
+    let AdminCommands { specs, registrar } =
+        commands.ok_or_else(|| ApiError::NotFound("discord is not running".into()))?;
+
+    registrar
+        .overwrite(params.guild_id, &specs)
+        .await
+        .map_err(|error| {
+            tracing::warn!(error = ?error, "failed to register the commands");
+            ApiError::BadGateway(format!("{:#}", error))
+        })?;
+    tracing::info!(count = specs.len(), guild_id = ?params.guild_id, "registered the commands");
+
+    Ok(Json(RegisterResponse {
+        registered: specs.len(),
+        guild_id: params.guild_id,
+    }))
+}
+
+/// Delete the reminders and announcements the bot left in a channel more
+/// than `older_than_days` ago. The messages of the members are kept.
+///
+/// The deletes are paced for the Discord rate limits, a long history takes a
+/// while. Missing permissions stop it with what was deleted so far.
+#[tracing::instrument(name = "Cleanup messages handler", skip(cleanup))]
+pub async fn cleanup_messages(
+    State(AdminState { cleanup, .. }): State<AdminState>,
+    Query(params): Query<CleanupParams>,
+) -> Result<Json<CleanupOutcome>, ApiError> {
+    let cleanup = cleanup.ok_or_else(|| ApiError::NotFound("discord is not running".into()))?;
+    if params.older_than_days == 0 {
+        return Err(ApiError::BadRequest(
+            "older_than_days must be at least 1".into(),
+        ));
+    }
+
+    let outcome = cleanup
+        .clean(params.channel_id, params.older_than_days, Utc::now())
+        .await
+        .map_err(|error| {
+            tracing::warn!(error = ?error, "failed to clean up the channel");
+            ApiError::BadGateway(format!("{:#}", error))
+        })?;
+
+    Ok(Json(outcome))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use async_trait::async_trait;
+    use axum::{
+        body::{to_bytes, Body},
+        http::{Request, StatusCode},
+    };
+    use axum_tracing_opentelemetry::middleware::OtelAxumLayer;
+    use opentelemetry::trace::{SpanKind, TracerProvider as _};
+    use opentelemetry_sdk::{
+        export::trace::SpanData, testing::trace::InMemorySpanExporter, trace::TracerProvider,
+    };
+    use tokio::sync::mpsc;
+    use tower::ServiceExt;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use crate::{
+        configuration::{test_keys, test_settings},
+        domain::{
+            audit::{AuditAction, AuditQuery, AuditRepository, Auditor},
+            delivery::{Destination, MessageSender, OutgoingMessage},
+            feature::FeatureFlags,
+            guild::{GuildConfig, GuildConfigRepository},
+            id::{ChannelId, UserId},
+            reminder::Reminder,
+            scheduler::ScheduledSender,
+            standup::StandupPrompt,
+        },
+        drivers::{
+            database::memory::{
+                InMemoryAuditRepository, InMemoryGuildConfigRepository, InMemoryReminderRepository,
+            },
+            http::middlewares::auth::API_KEY_HEADER,
+        },
+    };
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Flusher {
+        calls: AtomicUsize,
+        fail: bool,
+    }
+
+    impl TraceFlusher for Flusher {
+        fn force_flush(&self) -> anyhow::Result<()> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.fail {
+                anyhow::bail!("collector unreachable");
+            }
+            Ok(())
+        }
+    }
+
+    fn test_router(reloader: ConfigReloader) -> Router {
+        with_flusher(reloader, Arc::new(Flusher::default()))
+    }
+
+    fn with_flusher(reloader: ConfigReloader, traces: Arc<dyn TraceFlusher>) -> Router {
+        admin_router(AdminState {
+            reloader,
+            traces,
+            reminders: None,
+            commands: None,
+            cleanup: None,
+            replay: None,
+        })
+    }
+
+    fn admin_router(state: AdminState) -> Router {
+        let keys = ApiKeys::new(test_keys());
+
+        router(state, keys)
+    }
+
+    fn post(uri: &str, key: &str) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header(API_KEY_HEADER, key)
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    fn reload(key: &str) -> Request<Body> {
+        post("/admin/config/reload", key)
+    }
+
+    async fn json(response: axum::response::Response) -> serde_json::Value {
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    fn features() -> FeatureFlags {
+        FeatureFlags::new(HashMap::from([
+            ("standup".to_owned(), true),
+            ("remind".to_owned(), false),
+        ]))
+    }
+
+    #[tokio::test]
+    async fn reload_applies_and_returns_the_diff() {
+        let features = features();
+        let reloader = ConfigReloader::with_loader(features.clone(), || {
+            let mut settings = test_settings();
+            settings.features = HashMap::from([
+                ("standup".to_owned(), false),
+                ("remind".to_owned(), false),
+                ("me".to_owned(), true),
+            ]);
+            Ok(settings)
+        });
+
+        let response = test_router(reloader)
+            .oneshot(reload("admin-key"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            json(response).await,
+            serde_json::json!({
+                "features": {
+                    "me": { "before": null, "after": true },
+                    "standup": { "before": true, "after": false },
+                }
+            })
+        );
+        assert!(!features.is_enabled("standup"));
+    }
+
+    #[tokio::test]
+    async fn invalid_config_is_rejected_without_applying_anything() {
+        let features = features();
+        let reloader = ConfigReloader::with_loader(features.clone(), || {
+            let mut settings = test_settings();
+            settings.features = HashMap::from([("standup".to_owned(), false)]);
+            settings.application.default_timezone = "Mars/Olympus".into();
+            Ok(settings)
+        });
+
+        let response = test_router(reloader)
+            .oneshot(reload("admin-key"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            json(response).await["error"]["message"],
+            "invalid configuration: application.default_timezone must be an IANA timezone, got \"Mars/Olympus\""
+        );
+        assert!(features.is_enabled("standup"));
+    }
+
+    #[tokio::test]
+    async fn reload_requires_an_admin_api_key() {
+        let reloader = ConfigReloader::with_loader(features(), || Ok(test_settings()));
+
+        let response = test_router(reloader).oneshot(reload("key")).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn flush_traces_forces_a_flush() {
+        let flusher = Arc::new(Flusher::default());
+        let reloader = ConfigReloader::with_loader(features(), || Ok(test_settings()));
+
+        let response = with_flusher(reloader, flusher.clone())
+            .oneshot(post("/admin/flush-traces", "admin-key"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(json(response).await, serde_json::json!({ "flushed": true }));
+        assert_eq!(flusher.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn failed_flush_is_a_bad_gateway() {
+        let flusher = Arc::new(Flusher {
+            fail: true,
+            ..Flusher::default()
+        });
+        let reloader = ConfigReloader::with_loader(features(), || Ok(test_settings()));
+
+        let response = with_flusher(reloader, flusher)
+            .oneshot(post("/admin/flush-traces", "admin-key"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+        assert_eq!(
+            json(response).await["error"]["message"],
+            "collector unreachable"
+        );
+    }
+
+    struct Discord(mpsc::UnboundedSender<OutgoingMessage>);
+
+    #[async_trait]
+    impl MessageSender for Discord {
+        async fn send(&self, message: &OutgoingMessage) -> anyhow::Result<()> {
+            self.0.send(message.clone())?;
+            Ok(())
+        }
+    }
+
+    fn finished(exporter: &InMemorySpanExporter, name: &str) -> Option<SpanData> {
+        exporter
+            .get_finished_spans()
+            .unwrap()
+            .into_iter()
+            .find(|span| span.name == name)
+    }
+
+    #[tokio::test]
+    async fn fired_reminders_are_sent_in_the_trace_of_the_request() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = TracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let service = ReminderService::new(Arc::new(InMemoryReminderRepository::default()));
+        service
+            .schedule(Reminder {
+                id: None,
+                guild_id: None,
+                channel_id: Some(ChannelId(2)),
+                user_id: UserId(3),
+                message: "retro at 3pm".into(),
+                due_at: Utc::now() - chrono::TimeDelta::minutes(1),
+                delivered: false,
+                created_at: Utc::now(),
+            })
+            .await
+            .unwrap();
+        let (sent, mut received) = mpsc::unbounded_channel();
+        let reminders = AdminReminders {
+            service,
+            sender: Arc::new(ScheduledSender::new(Arc::new(Discord(sent)), false)),
+        };
+        let router = admin_router(AdminState {
+            reloader: ConfigReloader::with_loader(features(), || Ok(test_settings())),
+            traces: Arc::new(Flusher::default()),
+            reminders: Some(reminders),
+            commands: None,
+            cleanup: None,
+            replay: None,
+        })
+        .layer(OtelAxumLayer::default());
+
+        let response = router
+            .oneshot(post("/admin/reminders/fire", "admin-key"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        drop(response);
+
+        assert_eq!(received.recv().await.unwrap().content, "retro at 3pm");
+        let send = tokio::time::timeout(Duration::from_secs(1), async {
+            loop {
+                match finished(&exporter, "Discord send") {
+                    Some(span) => return span,
+                    None => tokio::task::yield_now().await,
+                }
+            }
+        })
+        .await
+        .unwrap();
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let request = spans
+            .iter()
+            .find(|span| span.span_kind == SpanKind::Server)
+            .unwrap();
+        assert_eq!(
+            send.span_context.trace_id(),
+            request.span_context.trace_id()
+        );
+
+        let parents: HashMap<_, _> = spans
+            .iter()
+            .map(|span| (span.span_context.span_id(), span.parent_span_id))
+            .collect();
+        let mut ancestor = send.parent_span_id;
+        while ancestor != request.span_context.span_id() {
+            ancestor = *parents
+                .get(&ancestor)
+                .expect("the send span descends from the request span");
+        }
+    }
+
+    #[tokio::test]
+    async fn firing_without_reminders_is_not_found() {
+        let reloader = ConfigReloader::with_loader(features(), || Ok(test_settings()));
+
+        let response = test_router(reloader)
+            .oneshot(post("/admin/reminders/fire", "admin-key"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[derive(Default)]
+    struct Registrar {
+        calls: std::sync::Mutex<Vec<(Option<GuildId>, Vec<String>)>>,
+    }
+
+    #[async_trait]
+    impl CommandRegistrar for Registrar {
+        async fn overwrite(
+            &self,
+            guild_id: Option<GuildId>,
+            specs: &[CommandSpec],
+        ) -> anyhow::Result<()> {
+            let names = specs.iter().map(|spec| spec.name.clone()).collect();
+            self.calls.lock().unwrap().push((guild_id, names));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn register_commands_overwrites_them_globally_or_per_guild() {
+        let registrar = Arc::new(Registrar::default());
+        let router = admin_router(AdminState {
+            reloader: ConfigReloader::with_loader(features(), || Ok(test_settings())),
+            traces: Arc::new(Flusher::default()),
+            reminders: None,
+            commands: Some(AdminCommands {
+                specs: vec![
+                    CommandSpec::new("standup", "Submit your standup"),
+                    CommandSpec::new("me", "Snooze the reminders"),
+                ],
+                registrar: registrar.clone(),
+            }),
+            cleanup: None,
+            replay: None,
+        });
+
+        let response = router
+            .clone()
+            .oneshot(post("/admin/register-commands", "admin-key"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            json(response).await,
+            serde_json::json!({ "registered": 2, "guild_id": null })
+        );
+
+        let response = router
+            .oneshot(post("/admin/register-commands?guild_id=7", "admin-key"))
+            .await
+            .unwrap();
+        assert_eq!(json(response).await["guild_id"], 7);
+
+        let names = vec!["standup".to_owned(), "me".to_owned()];
+        assert_eq!(
+            *registrar.calls.lock().unwrap(),
+            vec![(None, names.clone()), (Some(GuildId(7)), names)]
+        );
+    }
+
+    #[tokio::test]
+    async fn cleanup_without_discord_is_not_found() {
+        let reloader = ConfigReloader::with_loader(features(), || Ok(test_settings()));
+
+        let response = test_router(reloader)
+            .oneshot(post(
+                "/admin/cleanup-messages?channel_id=2&older_than_days=7",
+                "admin-key",
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn registering_without_discord_is_not_found() {
+        let reloader = ConfigReloader::with_loader(features(), || Ok(test_settings()));
+
+        let response = test_router(reloader)
+            .oneshot(post("/admin/register-commands", "admin-key"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn replayed_prompt_is_sent_and_audited_as_a_manual_reminder() {
+        let guilds = Arc::new(InMemoryGuildConfigRepository::default());
+        let mut config = GuildConfig::new(GuildId(1));
+        config.standup_channel_id = Some(ChannelId(2));
+        guilds.upsert(&config).await.unwrap();
+        let audit_log = Arc::new(InMemoryAuditRepository::default());
+        let (sent, mut received) = mpsc::unbounded_channel();
+        let replay = PromptReplay::new(
+            guilds,
+            Arc::new(Discord(sent)),
+            StandupPrompt::new("Standup time in {channel}!").unwrap(),
+            chrono_tz::Tz::UTC,
+            Auditor::new(Some(audit_log.clone())),
+        );
+        let router = admin_router(AdminState {
+            reloader: ConfigReloader::with_loader(features(), || Ok(test_settings())),
+            traces: Arc::new(Flusher::default()),
+            reminders: None,
+            commands: None,
+            cleanup: None,
+            replay: Some(replay),
+        });
+
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/admin/reminders/replay")
+                    .header(API_KEY_HEADER, "admin-key")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{ "guild_id": 1 }"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(json(response).await["channel_id"], 2);
+        assert_eq!(
+            received.try_recv().unwrap(),
+            OutgoingMessage {
+                destination: Destination::Channel(ChannelId(2)),
+                content: "Standup time in <#2>!".into(),
+            }
+        );
+
+        let entries = audit_log.list(&AuditQuery::default()).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].actor, "ops");
+        assert_eq!(entries[0].action, AuditAction::ManualReminder);
+        assert_eq!(entries[0].command.as_deref(), Some("reminders/replay"));
+    }
+
+    #[tokio::test]
+    async fn replaying_without_discord_is_not_found() {
+        let reloader = ConfigReloader::with_loader(features(), || Ok(test_settings()));
+
+        let response = test_router(reloader)
+            .oneshot(post("/admin/reminders/replay", "admin-key"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,150 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use axum::{
+    extract::{Query, State},
+    middleware,
+    routing::get,
+    Json, Router,
+};
+use bson::Document;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::{
+    domain::{
+        audit::{AuditAction, AuditEntry, AuditQuery, AuditRepository},
+        id::GuildId,
+    },
+    drivers::http::{
+        error::ApiError,
+        middlewares::{
+            accept,
+            auth::{require_admin, ApiKeys},
+        },
+    },
+};
+
+#[derive(Debug, Serialize)]
+pub struct AuditEntryResponse {
+    pub actor: String,
+    pub guild_id: Option<GuildId>,
+    pub action: AuditAction,
+    pub command: Option<String>,
+    pub arguments: BTreeMap<String, String>,
+    pub before: Option<Document>,
+    pub after: Option<Document>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl From<AuditEntry> for AuditEntryResponse {
+    fn from(entry: AuditEntry) -> Self {
+        Self {
+            actor: entry.actor,
+            guild_id: entry.guild_id,
+            action: entry.action,
+            command: entry.command,
+            arguments: entry.arguments,
+            before: entry.before,
+            after: entry.after,
+            timestamp: entry.timestamp,
+        }
+    }
+}
+
+/// Admin only routes to inspect the audit log.
+pub fn router(repository: Arc<dyn AuditRepository>, keys: ApiKeys) -> Router {
+    Router::new()
+        .route("/audit", get(list_audit_entries))
+        .route_layer(accept::layer(accept::JSON))
+        .route_layer(middleware::from_fn_with_state(keys, require_admin))
+        .with_state(repository)
+}
+
+#[tracing::instrument(name = "List audit entries handler", skip(repository))]
+pub async fn list_audit_entries(
+    State(repository): State<Arc<dyn AuditRepository>>,
+    Query(query): Query<AuditQuery>,
+) -> Result<Json<Vec<AuditEntryResponse>>, ApiError> {
+    let entries = repository.list(&query).await?;
+
+    Ok(Json(entries.into_iter().map(Into::into).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        http::{Request, StatusCode},
+    };
+    use tower::ServiceExt;
+
+    use crate::{
+        configuration::test_keys,
+        drivers::{
+            database::memory::InMemoryAuditRepository, http::middlewares::auth::API_KEY_HEADER,
+        },
+    };
+
+    use super::*;
+
+    async fn test_router() -> Router {
+        let repository = Arc::new(InMemoryAuditRepository::default());
+        for (actor, guild_id) in [("1", 10), ("2", 20)] {
+            let entry = AuditEntry::new(actor, GuildId(guild_id), AuditAction::Reindex);
+            repository.insert(&entry).await.unwrap();
+        }
+
+        let keys = ApiKeys::new(test_keys());
+
+        router(repository, keys)
+    }
+
+    fn request(uri: &str, key: Option<&str>) -> Request<Body> {
+        let mut builder = Request::builder().uri(uri);
+        if let Some(key) = key {
+            builder = builder.header(API_KEY_HEADER, key);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn admin_can_query_audit_entries() {
+        let response = test_router()
+            .await
+            .oneshot(request("/audit?guild_id=20", Some("admin-key")))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let entries: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(entries.as_array().unwrap().len(), 1);
+        assert_eq!(entries[0]["actor"], "2");
+        assert_eq!(entries[0]["action"], "reindex");
+    }
+
+    #[tokio::test]
+    async fn audit_requires_an_api_key() {
+        let response = test_router()
+            .await
+            .oneshot(request("/audit", None))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn audit_requires_an_admin_api_key() {
+        let response = test_router()
+            .await
+            .oneshot(request("/audit", Some("key")))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let envelope: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(envelope["error"]["code"], "forbidden");
+    }
+}
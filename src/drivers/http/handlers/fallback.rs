@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use axum::{extract::State, http::Uri, Router};
+
+use crate::{drivers::http::error::ApiError, observability::metrics::HttpMetrics};
+
+/// Answers unmatched routes with the JSON error envelope, merge it last.
+pub fn router(metrics: Arc<HttpMetrics>) -> Router {
+    Router::new().fallback(not_found).with_state(metrics)
+}
+
+#[tracing::instrument(name = "Not found handler", skip(metrics))]
+pub async fn not_found(State(metrics): State<Arc<HttpMetrics>>, uri: Uri) -> ApiError {
+    metrics.not_found.inc();
+
+    ApiError::NotFound(format!("no route for {}", uri.path()))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        http::{header::CONTENT_TYPE, Request, StatusCode},
+        routing::get,
+    };
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn test_router(metrics: Arc<HttpMetrics>) -> Router {
+        Router::new()
+            .route("/healthz", get(|| async { "200" }))
+            .merge(router(metrics))
+    }
+
+    fn request(uri: &str) -> Request<Body> {
+        Request::builder().uri(uri).body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn unknown_path_returns_the_json_envelope() {
+        let metrics = Arc::new(HttpMetrics::new());
+
+        let response = test_router(metrics.clone())
+            .oneshot(request("/nope"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/json");
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let envelope: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(envelope["error"]["code"], "not_found");
+        assert_eq!(envelope["error"]["message"], "no route for /nope");
+        assert_eq!(metrics.not_found.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn known_routes_are_not_shadowed() {
+        let metrics = Arc::new(HttpMetrics::new());
+
+        let response = test_router(metrics.clone())
+            .oneshot(request("/healthz"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(metrics.not_found.get(), 0);
+    }
+}
@@ -0,0 +1,234 @@
+use std::{sync::Arc, time::Duration};
+
+use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
+use futures::future::join_all;
+use serde::Serialize;
+
+use crate::drivers::database::Ping;
+
+/// How long a single dependency may take to answer the readiness check.
+pub const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// A dependency reported by `/readyz`.
+#[derive(Clone)]
+pub struct Dependency {
+    pub name: &'static str,
+    /// Whether the API can't serve requests while it is down.
+    pub critical: bool,
+    pub check: Arc<dyn Ping>,
+}
+
+impl Dependency {
+    pub fn critical(name: &'static str, check: Arc<dyn Ping>) -> Self {
+        Self {
+            name,
+            critical: true,
+            check,
+        }
+    }
+
+    /// A dependency that is reported but never fails the readiness check.
+    pub fn optional(name: &'static str, check: Arc<dyn Ping>) -> Self {
+        Self {
+            name,
+            critical: false,
+            check,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Ok,
+    /// Only optional dependencies are down.
+    Degraded,
+    Unavailable,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyStatus {
+    Up,
+    Down,
+}
+
+/// Why a dependency is down is only logged, the report is public.
+#[derive(Debug, Serialize)]
+pub struct DependencyReport {
+    pub name: &'static str,
+    pub status: DependencyStatus,
+    #[serde(skip)]
+    pub critical: bool,
+}
+
+impl DependencyReport {
+    fn is_up(&self) -> bool {
+        self.status == DependencyStatus::Up
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReadinessReport {
+    pub status: Status,
+    pub dependencies: Vec<DependencyReport>,
+}
+
+/// `/readyz`, the dependency report. Liveness stays on the static `/healthz`.
+pub fn router(dependencies: Vec<Dependency>) -> Router {
+    Router::new()
+        .route("/readyz", get(readiness))
+        .with_state(Arc::new(dependencies))
+}
+
+/// Not instrumented, probes are kept out of the traces.
+pub async fn readiness(
+    State(dependencies): State<Arc<Vec<Dependency>>>,
+) -> (StatusCode, Json<ReadinessReport>) {
+    let reports = join_all(dependencies.iter().map(check)).await;
+
+    let status = if reports
+        .iter()
+        .any(|report| report.critical && !report.is_up())
+    {
+        Status::Unavailable
+    } else if reports.iter().any(|report| !report.is_up()) {
+        Status::Degraded
+    } else {
+        Status::Ok
+    };
+
+    let code = match status {
+        Status::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+        Status::Ok | Status::Degraded => StatusCode::OK,
+    };
+
+    (
+        code,
+        Json(ReadinessReport {
+            status,
+            dependencies: reports,
+        }),
+    )
+}
+
+async fn check(dependency: &Dependency) -> DependencyReport {
+    let result = match tokio::time::timeout(CHECK_TIMEOUT, dependency.check.ping()).await {
+        Ok(result) => result,
+        Err(_) => Err(anyhow::anyhow!("timed out after {:?}", CHECK_TIMEOUT)),
+    };
+
+    let status = match result {
+        Ok(()) => DependencyStatus::Up,
+        Err(error) => {
+            tracing::warn!(dependency = dependency.name, error = ?error, "dependency is down");
+            DependencyStatus::Down
+        }
+    };
+
+    DependencyReport {
+        name: dependency.name,
+        status,
+        critical: dependency.critical,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use axum::{
+        body::{to_bytes, Body},
+        http::Request,
+    };
+    use tokio::net::TcpListener;
+    use tower::ServiceExt;
+
+    use crate::observability::collector::{Collector, PROBE_TIMEOUT};
+
+    use super::*;
+
+    struct Up;
+
+    #[async_trait]
+    impl Ping for Up {
+        async fn ping(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    struct Down;
+
+    #[async_trait]
+    impl Ping for Down {
+        async fn ping(&self) -> Result<()> {
+            anyhow::bail!("connection refused")
+        }
+    }
+
+    fn collector(address: std::net::SocketAddr) -> Arc<dyn Ping> {
+        Arc::new(Collector {
+            endpoint: format!("http://{}", address),
+            timeout: PROBE_TIMEOUT,
+        })
+    }
+
+    async fn get_readiness(dependencies: Vec<Dependency>) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder()
+            .uri("/readyz")
+            .body(Body::empty())
+            .unwrap();
+        let response = router(dependencies).oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn reachable_collector_is_reported_up() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+
+        let (status, body) = get_readiness(vec![
+            Dependency::critical("mongodb", Arc::new(Up)),
+            Dependency::optional("otlp_collector", collector(listener.local_addr().unwrap())),
+        ])
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "ok");
+        assert_eq!(body["dependencies"][1]["name"], "otlp_collector");
+        assert_eq!(body["dependencies"][1]["status"], "up");
+    }
+
+    #[tokio::test]
+    async fn unreachable_collector_degrades_without_failing() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        drop(listener);
+
+        let (status, body) = get_readiness(vec![
+            Dependency::critical("mongodb", Arc::new(Up)),
+            Dependency::optional("otlp_collector", collector(address)),
+        ])
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "degraded");
+        assert_eq!(body["dependencies"][0]["status"], "up");
+        assert_eq!(body["dependencies"][1]["status"], "down");
+    }
+
+    #[tokio::test]
+    async fn critical_dependency_down_is_unavailable() {
+        let (status, body) =
+            get_readiness(vec![Dependency::critical("mongodb", Arc::new(Down))]).await;
+
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], "unavailable");
+        assert_eq!(
+            body["dependencies"][0],
+            serde_json::json!({"name": "mongodb", "status": "down"})
+        );
+    }
+}
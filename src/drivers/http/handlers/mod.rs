@@ -0,0 +1,11 @@
+pub mod admin;
+pub mod audit;
+pub mod fallback;
+pub mod health;
+pub mod report;
+pub mod retro;
+pub mod schema;
+pub mod snooze;
+pub mod sprint;
+pub mod standup;
+pub mod whoami;
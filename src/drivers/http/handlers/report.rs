@@ -0,0 +1,234 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    middleware,
+    routing::get,
+    Extension, Json, Router,
+};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        blockers::{BlockerKeywords, KeywordCount, DEFAULT_TOP_KEYWORDS, MAX_TOP_KEYWORDS},
+        guild::{GuildConfig, GuildConfigRepository},
+        id::GuildId,
+        standup::StandupRepository,
+    },
+    drivers::http::{
+        error::ApiError,
+        middlewares::{
+            accept,
+            auth::{require_api_key, ApiKeys, Caller},
+        },
+    },
+};
+
+#[derive(Clone)]
+pub struct ReportState {
+    pub standups: Arc<dyn StandupRepository>,
+    pub guilds: Arc<dyn GuildConfigRepository>,
+    /// Left out of the keywords on top of those of the guild language.
+    pub stopwords: Arc<[String]>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BlockersParams {
+    pub guild_id: GuildId,
+    /// The first date of the range, inclusive.
+    pub from: NaiveDate,
+    /// The last date of the range, inclusive.
+    pub to: NaiveDate,
+    /// How many keywords, at most [`MAX_TOP_KEYWORDS`].
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BlockersResponse {
+    pub guild_id: GuildId,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Most frequent first, empty when nobody was blocked.
+    pub keywords: Vec<KeywordCount>,
+}
+
+pub fn router(state: ReportState, keys: ApiKeys) -> Router {
+    Router::new()
+        .route("/reports/blockers", get(blocker_keywords))
+        .route_layer(accept::layer(accept::JSON))
+        .route_layer(middleware::from_fn_with_state(keys, require_api_key))
+        .with_state(state)
+}
+
+/// The words the guild members were blocked by the most over the range.
+#[tracing::instrument(name = "Blocker keywords handler", skip(state, caller))]
+pub async fn blocker_keywords(
+    State(state): State<ReportState>,
+    Extension(caller): Extension<Caller>,
+    Query(params): Query<BlockersParams>,
+) -> Result<Json<BlockersResponse>, ApiError> {
+    caller.authorize(params.guild_id)?;
+    if params.to < params.from {
+        return Err(ApiError::BadRequest(format!(
+            "the range ends on {}, before it starts on {}",
+            params.to, params.from
+        )));
+    }
+
+    let (config, blockers) = tokio::try_join!(
+        state.guilds.find(params.guild_id),
+        state
+            .standups
+            .blockers_between(params.guild_id, params.from, params.to),
+    )?;
+    let locale = config
+        .unwrap_or_else(|| GuildConfig::new(params.guild_id))
+        .locale();
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_TOP_KEYWORDS)
+        .min(MAX_TOP_KEYWORDS);
+
+    Ok(Json(BlockersResponse {
+        guild_id: params.guild_id,
+        from: params.from,
+        to: params.to,
+        keywords: BlockerKeywords::new(locale, &state.stopwords).top(&blockers, limit),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        http::{Request, StatusCode},
+    };
+    use chrono::Utc;
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    use crate::{
+        configuration::test_keys,
+        domain::{
+            id::{ChannelId, UserId},
+            standup::StandupEntry,
+        },
+        drivers::{
+            database::memory::{InMemoryGuildConfigRepository, InMemoryStandupRepository},
+            http::middlewares::auth::API_KEY_HEADER,
+        },
+    };
+
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 10, day).unwrap()
+    }
+
+    async fn test_router(blockers: &[(u32, &str)]) -> Router {
+        let standups = Arc::new(InMemoryStandupRepository::default());
+        for (index, (day, blockers)) in blockers.iter().enumerate() {
+            standups
+                .insert(&StandupEntry {
+                    id: None,
+                    guild_id: GuildId(1),
+                    channel_id: ChannelId(2),
+                    user_id: UserId(index as u64),
+                    team: None,
+                    date: date(*day),
+                    yesterday: "reviewed PRs".into(),
+                    today: "write the scheduler".into(),
+                    blockers: blockers.to_string(),
+                    sprint_id: None,
+                    created_at: Utc::now(),
+                })
+                .await
+                .unwrap();
+        }
+        let keys = ApiKeys::new(test_keys());
+
+        router(
+            ReportState {
+                standups,
+                guilds: Arc::new(InMemoryGuildConfigRepository::default()),
+                stopwords: Arc::from(vec!["ops".to_owned()]),
+            },
+            keys,
+        )
+    }
+
+    async fn get(router: Router, uri: &str) -> (StatusCode, Value) {
+        let request = Request::builder()
+            .uri(uri)
+            .header(API_KEY_HEADER, "key")
+            .body(Body::empty())
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn top_blocker_keywords_of_the_range() {
+        let router = test_router(&[
+            (14, "Waiting on the review of PR 42"),
+            (15, "waiting for staging access"),
+            (16, "Staging is down, waiting on ops"),
+            (16, ""),
+            // Outside of the range.
+            (20, "staging staging staging"),
+        ])
+        .await;
+
+        let (status, body) = get(
+            router,
+            "/reports/blockers?guild_id=1&from=2024-10-14&to=2024-10-18&limit=2",
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body["keywords"],
+            json!([
+                { "keyword": "waiting", "count": 3 },
+                { "keyword": "staging", "count": 2 },
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn empty_ranges_have_no_keywords() {
+        let router = test_router(&[(14, "waiting on review")]).await;
+
+        let (status, body) = get(
+            router,
+            "/reports/blockers?guild_id=1&from=2024-11-01&to=2024-11-30",
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["keywords"], json!([]));
+    }
+
+    #[tokio::test]
+    async fn reversed_ranges_and_other_guilds_are_refused() {
+        let router = test_router(&[]).await;
+
+        let (status, _) = get(
+            router.clone(),
+            "/reports/blockers?guild_id=1&from=2024-10-18&to=2024-10-14",
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = get(
+            router,
+            "/reports/blockers?guild_id=2&from=2024-10-14&to=2024-10-18",
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+}
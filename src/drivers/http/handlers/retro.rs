@@ -0,0 +1,459 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{rejection::JsonRejection, Path, State},
+    http::StatusCode,
+    middleware,
+    routing::{get, patch, post},
+    Extension, Json, Router,
+};
+use bson::oid::ObjectId;
+use chrono::{NaiveDate, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        guild::GuildConfigRepository,
+        id::{GuildId, UserId},
+        retro::{ActionItem, ActionItemRepository, Retro, RetroRepository},
+    },
+    drivers::http::{
+        error::{parse_object_id, ApiError},
+        middlewares::{
+            accept,
+            auth::{require_api_key, ApiKeys, Caller},
+        },
+    },
+};
+
+#[derive(Clone)]
+pub struct RetroState {
+    pub retros: Arc<dyn RetroRepository>,
+    pub actions: Arc<dyn ActionItemRepository>,
+    pub guilds: Arc<dyn GuildConfigRepository>,
+    pub default_timezone: Tz,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RetroRequest {
+    pub guild_id: GuildId,
+    pub team: Option<String>,
+    pub held_on: NaiveDate,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RetroResponse {
+    pub id: Option<String>,
+    pub guild_id: GuildId,
+    pub team: Option<String>,
+    pub held_on: NaiveDate,
+}
+
+impl From<Retro> for RetroResponse {
+    fn from(retro: Retro) -> Self {
+        Self {
+            id: retro.id.map(|id| id.to_hex()),
+            guild_id: retro.guild_id,
+            team: retro.team,
+            held_on: retro.held_on,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ActionItemRequest {
+    pub text: String,
+    pub owner: UserId,
+    pub due_date: NaiveDate,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ActionItemPatch {
+    pub completed: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ActionItemResponse {
+    pub id: Option<String>,
+    pub retro_id: String,
+    pub text: String,
+    pub owner: UserId,
+    pub due_date: NaiveDate,
+    pub completed_on: Option<NaiveDate>,
+    /// Still open when the next retro of the team was held.
+    pub carried_over: bool,
+}
+
+impl ActionItemResponse {
+    fn new(item: ActionItem, next: Option<&Retro>, today: NaiveDate) -> Self {
+        Self {
+            carried_over: item.is_carried_over(next, today),
+            id: item.id.map(|id| id.to_hex()),
+            retro_id: item.retro_id.to_hex(),
+            text: item.text,
+            owner: item.owner,
+            due_date: item.due_date,
+            completed_on: item.completed_on,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ActionItemsResponse {
+    pub retro_id: String,
+    /// The retro the items were due for, `null` until the team holds it.
+    pub next_retro_id: Option<String>,
+    pub actions: Vec<ActionItemResponse>,
+}
+
+pub fn router(state: RetroState, keys: ApiKeys) -> Router {
+    Router::new()
+        .route("/retros", post(create_retro))
+        .route(
+            "/retros/:id/actions",
+            get(list_action_items).post(create_action_item),
+        )
+        .route("/actions/:id", patch(update_action_item))
+        .route_layer(accept::layer(accept::JSON))
+        .route_layer(middleware::from_fn_with_state(keys, require_api_key))
+        .with_state(state)
+}
+
+#[tracing::instrument(name = "Create retro handler", skip(state, caller, body))]
+pub async fn create_retro(
+    State(state): State<RetroState>,
+    Extension(caller): Extension<Caller>,
+    body: Result<Json<RetroRequest>, JsonRejection>,
+) -> Result<(StatusCode, Json<RetroResponse>), ApiError> {
+    let Json(request) = body.map_err(|rejection| ApiError::BadRequest(rejection.body_text()))?;
+    caller.authorize(request.guild_id)?;
+
+    let mut retro = Retro {
+        id: None,
+        guild_id: request.guild_id,
+        team: request.team,
+        held_on: request.held_on,
+        created_at: Utc::now(),
+    };
+    retro.id = Some(state.retros.insert(&retro).await?);
+
+    Ok((StatusCode::CREATED, Json(retro.into())))
+}
+
+/// The action items of the retro, flagged when carried over to the next one.
+#[tracing::instrument(name = "List action items handler", skip(state, caller))]
+pub async fn list_action_items(
+    State(state): State<RetroState>,
+    Extension(caller): Extension<Caller>,
+    Path(id): Path<String>,
+) -> Result<Json<ActionItemsResponse>, ApiError> {
+    let retro = find_retro(&state, &caller, &id).await?;
+    let retro_id = retro.id.expect("stored retros have an id");
+
+    let (next, items, today) = tokio::try_join!(
+        state.retros.next_after(&retro),
+        state.actions.list_for_retro(retro_id),
+        guild_today(&state, retro.guild_id),
+    )?;
+
+    Ok(Json(ActionItemsResponse {
+        retro_id: retro_id.to_hex(),
+        next_retro_id: next.as_ref().and_then(|next| next.id).map(|id| id.to_hex()),
+        actions: items
+            .into_iter()
+            .map(|item| ActionItemResponse::new(item, next.as_ref(), today))
+            .collect(),
+    }))
+}
+
+#[tracing::instrument(name = "Create action item handler", skip(state, caller, body))]
+pub async fn create_action_item(
+    State(state): State<RetroState>,
+    Extension(caller): Extension<Caller>,
+    Path(id): Path<String>,
+    body: Result<Json<ActionItemRequest>, JsonRejection>,
+) -> Result<(StatusCode, Json<ActionItemResponse>), ApiError> {
+    let Json(request) = body.map_err(|rejection| ApiError::BadRequest(rejection.body_text()))?;
+    let retro = find_retro(&state, &caller, &id).await?;
+
+    let text = request.text.trim();
+    if text.is_empty() {
+        return Err(ApiError::BadRequest("the action item has no text".into()));
+    }
+
+    let mut item = ActionItem {
+        id: None,
+        retro_id: retro.id.expect("stored retros have an id"),
+        guild_id: retro.guild_id,
+        text: text.to_owned(),
+        owner: request.owner,
+        due_date: request.due_date,
+        completed_on: None,
+    };
+    item.id = Some(state.actions.insert(&item).await?);
+    let next = state.retros.next_after(&retro).await?;
+    let today = guild_today(&state, retro.guild_id).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ActionItemResponse::new(item, next.as_ref(), today)),
+    ))
+}
+
+/// Mark the item done today in the guild timezone, or open it again.
+#[tracing::instrument(name = "Update action item handler", skip(state, caller, body))]
+pub async fn update_action_item(
+    State(state): State<RetroState>,
+    Extension(caller): Extension<Caller>,
+    Path(id): Path<String>,
+    body: Result<Json<ActionItemPatch>, JsonRejection>,
+) -> Result<Json<ActionItemResponse>, ApiError> {
+    let Json(patch) = body.map_err(|rejection| ApiError::BadRequest(rejection.body_text()))?;
+    let item_id = parse_object_id(&id)?;
+    let not_found = || ApiError::NotFound(format!("no action item {}", id));
+
+    let item = state.actions.find(item_id).await?.ok_or_else(not_found)?;
+    caller.authorize(item.guild_id)?;
+
+    let today = guild_today(&state, item.guild_id).await?;
+    let item = state
+        .actions
+        .set_completed(item_id, patch.completed.then_some(today))
+        .await?
+        .ok_or_else(not_found)?;
+
+    let retro = state.retros.find(item.retro_id).await?;
+    let next = match &retro {
+        Some(retro) => state.retros.next_after(retro).await?,
+        None => None,
+    };
+
+    Ok(Json(ActionItemResponse::new(item, next.as_ref(), today)))
+}
+
+async fn guild_today(state: &RetroState, guild_id: GuildId) -> anyhow::Result<NaiveDate> {
+    let timezone = state
+        .guilds
+        .timezone(Some(guild_id), state.default_timezone)
+        .await?;
+
+    Ok(Utc::now().with_timezone(&timezone).date_naive())
+}
+
+/// The retro `id`, once the api key is known to be scoped to its guild.
+async fn find_retro(state: &RetroState, caller: &Caller, id: &str) -> Result<Retro, ApiError> {
+    let retro_id: ObjectId = parse_object_id(id)?;
+    let retro = state
+        .retros
+        .find(retro_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("no retro {}", id)))?;
+
+    caller.authorize(retro.guild_id)?;
+
+    Ok(retro)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        http::{Method, Request},
+    };
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    use crate::{
+        configuration::test_keys,
+        drivers::{
+            database::memory::{
+                InMemoryActionItemRepository, InMemoryGuildConfigRepository,
+                InMemoryRetroRepository,
+            },
+            http::middlewares::auth::API_KEY_HEADER,
+        },
+    };
+
+    use super::*;
+
+    fn test_router() -> Router {
+        state_router(Arc::new(InMemoryActionItemRepository::default()))
+    }
+
+    fn state_router(actions: Arc<InMemoryActionItemRepository>) -> Router {
+        let keys = ApiKeys::new(test_keys());
+        let state = RetroState {
+            retros: Arc::new(InMemoryRetroRepository::default()),
+            actions,
+            guilds: Arc::new(InMemoryGuildConfigRepository::default()),
+            default_timezone: Tz::UTC,
+        };
+
+        router(state, keys)
+    }
+
+    async fn send(
+        router: &Router,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(API_KEY_HEADER, "key")
+            .header("content-type", "application/json")
+            .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+            .unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    async fn create_retro(router: &Router, held_on: &str) -> String {
+        let (status, body) = send(
+            router,
+            Method::POST,
+            "/retros",
+            Some(json!({ "guild_id": 1, "held_on": held_on })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        body["id"].as_str().unwrap().to_owned()
+    }
+
+    async fn create_action(router: &Router, retro_id: &str, text: &str) -> String {
+        let (status, body) = send(
+            router,
+            Method::POST,
+            &format!("/retros/{}/actions", retro_id),
+            Some(json!({ "text": text, "owner": 3, "due_date": "2024-10-18" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body["completed_on"], Value::Null);
+
+        body["id"].as_str().unwrap().to_owned()
+    }
+
+    async fn complete(router: &Router, action_id: &str, completed: bool) -> (StatusCode, Value) {
+        send(
+            router,
+            Method::PATCH,
+            &format!("/actions/{}", action_id),
+            Some(json!({ "completed": completed })),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn actions_are_listed_with_their_retro() {
+        let router = test_router();
+        let retro_id = create_retro(&router, "2024-10-11").await;
+        create_action(&router, &retro_id, "pair on reviews").await;
+
+        let (status, body) = send(
+            &router,
+            Method::GET,
+            &format!("/retros/{}/actions", retro_id),
+            None,
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["next_retro_id"], Value::Null);
+        let actions = body["actions"].as_array().unwrap();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0]["text"], "pair on reviews");
+        assert_eq!(actions[0]["owner"], 3);
+        assert_eq!(actions[0]["carried_over"], false);
+    }
+
+    #[tokio::test]
+    async fn patch_marks_the_action_complete_and_open_again() {
+        let router = test_router();
+        let retro_id = create_retro(&router, "2024-10-11").await;
+        let action_id = create_action(&router, &retro_id, "pair on reviews").await;
+
+        let (status, body) = complete(&router, &action_id, true).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["completed_on"], Utc::now().date_naive().to_string());
+
+        let (status, body) = complete(&router, &action_id, false).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["completed_on"], Value::Null);
+
+        let (status, _) = complete(&router, &ObjectId::new().to_hex(), true).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn actions_open_at_the_next_retro_are_carried_over() {
+        let actions = Arc::new(InMemoryActionItemRepository::default());
+        let router = state_router(actions.clone());
+        let first = create_retro(&router, "2024-10-11").await;
+        let open = create_action(&router, &first, "pair on reviews").await;
+        let done = create_action(&router, &first, "write the runbook").await;
+        let done_on = NaiveDate::from_ymd_opt(2024, 10, 20).unwrap();
+        actions
+            .set_completed(parse_object_id(&done).unwrap(), Some(done_on))
+            .await
+            .unwrap();
+
+        let list = |retro_id: String| {
+            let router = router.clone();
+            async move {
+                send(
+                    &router,
+                    Method::GET,
+                    &format!("/retros/{}/actions", retro_id),
+                    None,
+                )
+                .await
+                .1
+            }
+        };
+        let carried_over = |body: &Value| -> Vec<(String, bool)> {
+            body["actions"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|action| {
+                    (
+                        action["id"].as_str().unwrap().to_owned(),
+                        action["carried_over"].as_bool().unwrap(),
+                    )
+                })
+                .collect()
+        };
+
+        let body = list(first.clone()).await;
+        assert!(carried_over(&body).iter().all(|(_, carried)| !carried));
+
+        let second = create_retro(&router, "2024-10-25").await;
+        let body = list(first).await;
+        assert_eq!(body["next_retro_id"], second);
+        let flags = carried_over(&body);
+        assert!(flags.contains(&(open, true)));
+        assert!(flags.contains(&(done, false)));
+    }
+
+    #[tokio::test]
+    async fn retros_of_other_guilds_are_forbidden() {
+        let router = test_router();
+
+        let (status, _) = send(
+            &router,
+            Method::POST,
+            "/retros",
+            Some(json!({ "guild_id": 2, "held_on": "2024-10-11" })),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+}
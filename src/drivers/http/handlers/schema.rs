@@ -0,0 +1,73 @@
+use axum::{routing::get, Json, Router};
+use schemars::{schema::RootSchema, schema_for};
+
+use crate::drivers::http::middlewares::accept;
+
+use super::standup::StandupResponse;
+
+/// JSON Schemas of the API payloads, for integrators to validate against.
+///
+/// Derived from the serialized types, so they can't drift from the API.
+pub fn router() -> Router {
+    Router::new()
+        .route("/schema/standup", get(standup_schema))
+        .route_layer(accept::layer(accept::JSON))
+}
+
+#[tracing::instrument(name = "Standup schema handler")]
+pub async fn standup_schema() -> Json<RootSchema> {
+    Json(schema_for!(StandupResponse))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        http::{Request, StatusCode},
+    };
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn standup_schema_lists_the_required_fields_and_types() {
+        let response = router()
+            .oneshot(Request::get("/schema/standup").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let schema: Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(schema["title"], "StandupResponse");
+        let mut required: Vec<_> = schema["required"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|field| field.as_str().unwrap())
+            .collect();
+        required.sort_unstable();
+        assert_eq!(
+            required,
+            [
+                "blockers",
+                "channel_id",
+                "created_at",
+                "date",
+                "guild_id",
+                "today",
+                "user_id",
+                "yesterday",
+            ]
+        );
+
+        let properties = &schema["properties"];
+        assert_eq!(properties["id"]["type"], json!(["string", "null"]));
+        assert_eq!(properties["date"]["format"], "date");
+        assert_eq!(properties["created_at"]["format"], "date-time");
+        assert_eq!(properties["today"]["type"], "string");
+        assert_eq!(properties["guild_id"]["type"], "integer");
+    }
+}
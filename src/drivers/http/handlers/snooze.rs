@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    middleware,
+    routing::{delete, get},
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::{
+    domain::{
+        id::UserId,
+        snooze::{Snooze, SnoozeRepository},
+    },
+    drivers::http::{
+        error::ApiError,
+        middlewares::{
+            accept,
+            auth::{require_admin, ApiKeys},
+        },
+    },
+};
+
+#[derive(Debug, Serialize)]
+pub struct SnoozeResponse {
+    pub user_id: UserId,
+    pub until: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Snooze> for SnoozeResponse {
+    fn from(snooze: Snooze) -> Self {
+        Self {
+            user_id: snooze.user_id,
+            until: snooze.until,
+            created_at: snooze.created_at,
+        }
+    }
+}
+
+/// Admin only routes to inspect and lift the snoozes of the members.
+pub fn router(repository: Arc<dyn SnoozeRepository>, keys: ApiKeys) -> Router {
+    Router::new()
+        .route("/snoozes", get(list_snoozes))
+        .route("/snoozes/:user_id", delete(delete_snooze))
+        .route_layer(accept::layer(accept::JSON))
+        .route_layer(middleware::from_fn_with_state(keys, require_admin))
+        .with_state(repository)
+}
+
+#[tracing::instrument(name = "List snoozes handler", skip(repository))]
+pub async fn list_snoozes(
+    State(repository): State<Arc<dyn SnoozeRepository>>,
+) -> Result<Json<Vec<SnoozeResponse>>, ApiError> {
+    let snoozes = repository.list_active(Utc::now()).await?;
+
+    Ok(Json(snoozes.into_iter().map(Into::into).collect()))
+}
+
+#[tracing::instrument(name = "Delete snooze handler", skip(repository))]
+pub async fn delete_snooze(
+    State(repository): State<Arc<dyn SnoozeRepository>>,
+    Path(user_id): Path<UserId>,
+) -> Result<StatusCode, ApiError> {
+    if !repository.delete(user_id).await? {
+        return Err(ApiError::NotFound(format!(
+            "no snooze for user {}",
+            user_id
+        )));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        http::Request,
+    };
+    use chrono::TimeDelta;
+    use tower::ServiceExt;
+
+    use crate::{
+        configuration::test_keys,
+        drivers::{
+            database::memory::InMemorySnoozeRepository, http::middlewares::auth::API_KEY_HEADER,
+        },
+    };
+
+    use super::*;
+
+    async fn test_router() -> Router {
+        let repository = Arc::new(InMemorySnoozeRepository::default());
+        let now = Utc::now();
+        repository
+            .upsert(&Snooze::for_days(UserId(1), 3, now).unwrap())
+            .await
+            .unwrap();
+        repository
+            .upsert(&Snooze {
+                user_id: UserId(2),
+                until: now - TimeDelta::days(1),
+                created_at: now - TimeDelta::days(8),
+            })
+            .await
+            .unwrap();
+
+        let keys = ApiKeys::new(test_keys());
+
+        router(repository, keys)
+    }
+
+    fn request(method: &str, uri: &str) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(API_KEY_HEADER, "admin-key")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn lists_only_active_snoozes() {
+        let response = test_router()
+            .await
+            .oneshot(request("GET", "/snoozes"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let snoozes: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(snoozes.as_array().unwrap().len(), 1);
+        assert_eq!(snoozes[0]["user_id"], 1);
+    }
+
+    #[tokio::test]
+    async fn deletes_a_snooze_once() {
+        let router = test_router().await;
+
+        let response = router
+            .clone()
+            .oneshot(request("DELETE", "/snoozes/1"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let response = router
+            .oneshot(request("DELETE", "/snoozes/1"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,739 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{rejection::JsonRejection, Path, Query, State},
+    middleware,
+    response::Html,
+    routing::{get, put},
+    Extension, Json, Router,
+};
+use chrono::{NaiveDate, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        burndown::{burndown, BurndownPoint, GoalCompletionRepository},
+        guild::{GuildConfig, GuildConfigRepository},
+        id::GuildId,
+        report::SprintReport,
+        sprint::{DuplicateSprints, Goal, Sprint, SprintRepository},
+        standup::StandupRepository,
+    },
+    drivers::http::{
+        error::{parse_object_id, ApiError},
+        html,
+        middlewares::{
+            accept,
+            auth::{require_api_key, ApiKeys, Caller},
+        },
+    },
+};
+
+#[derive(Clone)]
+pub struct SprintState {
+    pub sprints: Arc<dyn SprintRepository>,
+    pub completions: Arc<dyn GoalCompletionRepository>,
+    pub standups: Arc<dyn StandupRepository>,
+    pub guilds: Arc<dyn GuildConfigRepository>,
+    pub default_timezone: Tz,
+    /// What a sprint with the exact dates of another of its team gets.
+    pub duplicates: DuplicateSprints,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SprintResponse {
+    pub id: Option<String>,
+    pub guild_id: GuildId,
+    pub team: Option<String>,
+    pub name: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub goals: Vec<Goal>,
+}
+
+impl From<Sprint> for SprintResponse {
+    fn from(sprint: Sprint) -> Self {
+        Self {
+            id: sprint.id.map(|id| id.to_hex()),
+            guild_id: sprint.guild_id,
+            team: sprint.team,
+            name: sprint.name,
+            start_date: sprint.start_date,
+            end_date: sprint.end_date,
+            goals: sprint.goals,
+        }
+    }
+}
+
+/// A sprint as sent to `PUT /sprints/:id`, the id comes from the path.
+#[derive(Debug, Deserialize)]
+pub struct SprintRequest {
+    pub guild_id: GuildId,
+    pub team: Option<String>,
+    pub name: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    #[serde(default)]
+    pub goals: Vec<Goal>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BurndownResponse {
+    pub sprint_id: String,
+    pub series: Vec<BurndownPoint>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CurrentSprintParams {
+    pub guild_id: GuildId,
+    /// The team to look up, the guild wide team when omitted.
+    pub team: Option<String>,
+}
+
+pub fn router(state: SprintState, keys: ApiKeys) -> Router {
+    Router::new()
+        .route("/sprints/current", get(current_sprint))
+        .route("/sprints/:id", put(put_sprint))
+        .route("/sprints/:id/burndown", get(sprint_burndown))
+        .route_layer(accept::layer(accept::JSON))
+        .route(
+            "/sprints/:id/report.html",
+            get(sprint_report).layer(accept::layer(accept::HTML)),
+        )
+        .route_layer(middleware::from_fn_with_state(keys, require_api_key))
+        .with_state(state)
+}
+
+/// The sprint running today in the guild timezone, or the default one.
+#[tracing::instrument(name = "Current sprint handler", skip(state, caller))]
+pub async fn current_sprint(
+    State(state): State<SprintState>,
+    Extension(caller): Extension<Caller>,
+    Query(params): Query<CurrentSprintParams>,
+) -> Result<Json<SprintResponse>, ApiError> {
+    caller.authorize(params.guild_id)?;
+    let config = state
+        .guilds
+        .find(params.guild_id)
+        .await?
+        .unwrap_or_else(|| GuildConfig::new(params.guild_id));
+    let today = config.local_date(Utc::now(), state.default_timezone);
+
+    let sprint = state
+        .sprints
+        .find_active(params.guild_id, params.team.as_deref(), today)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("no active sprint on {}", today)))?;
+
+    Ok(Json(sprint.into()))
+}
+
+/// Create the sprint, or replace it when it exists.
+///
+/// Goal points are unsigned, negative ones are rejected with the rest of the body.
+#[tracing::instrument(name = "Put sprint handler", skip(state, caller, body))]
+pub async fn put_sprint(
+    State(state): State<SprintState>,
+    Extension(caller): Extension<Caller>,
+    Path(id): Path<String>,
+    body: Result<Json<SprintRequest>, JsonRejection>,
+) -> Result<Json<SprintResponse>, ApiError> {
+    let sprint_id = parse_object_id(&id)?;
+    let Json(request) = body.map_err(|rejection| ApiError::BadRequest(rejection.body_text()))?;
+    caller.authorize(request.guild_id)?;
+
+    let sprint = Sprint {
+        id: Some(sprint_id),
+        guild_id: request.guild_id,
+        team: request.team,
+        name: request.name,
+        start_date: request.start_date,
+        end_date: request.end_date,
+        goals: request.goals,
+    };
+    sprint
+        .validate()
+        .map_err(|invalid| ApiError::BadRequest(invalid.to_string()))?;
+
+    // Moving a sprint to another guild would hand it to keys of that guild.
+    if let Some(stored) = state.sprints.find(sprint_id).await? {
+        if stored.guild_id != sprint.guild_id {
+            return Err(ApiError::Conflict(format!(
+                "sprint {} belongs to another guild",
+                id
+            )));
+        }
+    }
+
+    if let Some(duplicate) = state.sprints.find_same_dates(&sprint).await? {
+        let duplicate_id = duplicate.id.map(|id| id.to_hex()).unwrap_or_default();
+        match state.duplicates {
+            DuplicateSprints::Reject => {
+                return Err(ApiError::Conflict(format!(
+                    "sprint {} already covers {} to {}",
+                    duplicate_id, sprint.start_date, sprint.end_date
+                )))
+            }
+            DuplicateSprints::Warn => tracing::warn!(
+                sprint_id = %id,
+                duplicate_id = %duplicate_id,
+                "sprint covers the same dates as another"
+            ),
+        }
+    }
+
+    state.sprints.upsert(&sprint).await?;
+
+    Ok(Json(sprint.into()))
+}
+
+/// Remaining goals and points per day of the sprint, for charts.
+#[tracing::instrument(name = "Sprint burndown handler", skip(state, caller))]
+pub async fn sprint_burndown(
+    State(state): State<SprintState>,
+    Extension(caller): Extension<Caller>,
+    Path(id): Path<String>,
+) -> Result<Json<BurndownResponse>, ApiError> {
+    let sprint = find_sprint(&state, &caller, &id).await?;
+    let sprint_id = sprint.id.expect("stored sprints have an id");
+
+    let timezone = state
+        .guilds
+        .timezone(Some(sprint.guild_id), state.default_timezone)
+        .await?;
+    let today = Utc::now().with_timezone(&timezone).date_naive();
+    let completions = state.completions.list(sprint_id).await?;
+
+    Ok(Json(BurndownResponse {
+        sprint_id: sprint_id.to_hex(),
+        series: burndown(&sprint, &completions, timezone, today),
+    }))
+}
+
+/// A printable page with the goals, participation and blockers of the sprint.
+#[tracing::instrument(name = "Sprint report handler", skip(state, caller))]
+pub async fn sprint_report(
+    State(state): State<SprintState>,
+    Extension(caller): Extension<Caller>,
+    Path(id): Path<String>,
+) -> Result<Html<String>, ApiError> {
+    let sprint = find_sprint(&state, &caller, &id).await?;
+    let sprint_id = sprint.id.expect("stored sprints have an id");
+
+    let config = state
+        .guilds
+        .find(sprint.guild_id)
+        .await?
+        .unwrap_or_else(|| GuildConfig::new(sprint.guild_id));
+    let timezone = config.tz(state.default_timezone);
+    let today = config.local_date(Utc::now(), state.default_timezone);
+    let (standups, completions) = tokio::try_join!(
+        state.standups.list_for_sprint(sprint_id),
+        state.completions.list(sprint_id),
+    )?;
+
+    let report = SprintReport::new(sprint, &config, &standups, &completions, timezone, today);
+
+    Ok(Html(html::sprint_report(&report)))
+}
+
+/// The sprint `id`, once the api key is known to be scoped to its guild.
+async fn find_sprint(state: &SprintState, caller: &Caller, id: &str) -> Result<Sprint, ApiError> {
+    let sprint = state
+        .sprints
+        .find(parse_object_id(id)?)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("no sprint {}", id)))?;
+    caller.authorize(sprint.guild_id)?;
+
+    Ok(sprint)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        http::{Request, StatusCode},
+    };
+    use chrono::{Days, TimeDelta};
+    use tower::ServiceExt;
+
+    use bson::oid::ObjectId;
+
+    use crate::{
+        configuration::test_keys,
+        domain::{
+            burndown::GoalCompletion,
+            id::{ChannelId, UserId},
+            sprint::Goal,
+            standup::StandupEntry,
+        },
+        drivers::{
+            database::memory::{
+                InMemoryGoalCompletionRepository, InMemoryGuildConfigRepository,
+                InMemorySprintRepository, InMemoryStandupRepository,
+            },
+            http::middlewares::auth::API_KEY_HEADER,
+        },
+    };
+
+    use super::*;
+
+    async fn test_router(sprints: &[(&str, i64, i64)]) -> Router {
+        let sprints: Vec<_> = sprints
+            .iter()
+            .map(|&(name, start, end)| (None, name, start, end))
+            .collect();
+        team_router(&sprints).await
+    }
+
+    async fn team_router(sprints: &[(Option<&str>, &str, i64, i64)]) -> Router {
+        let today = Utc::now().date_naive();
+        let repository = Arc::new(InMemorySprintRepository::default());
+        for (team, name, start, end) in sprints {
+            let sprint = Sprint {
+                id: None,
+                guild_id: GuildId(1),
+                team: team.map(Into::into),
+                name: name.to_string(),
+                start_date: shift(today, *start),
+                end_date: shift(today, *end),
+                goals: vec![],
+            };
+            repository.insert(&sprint).await.unwrap();
+        }
+
+        state_router(
+            repository,
+            Arc::new(InMemoryGoalCompletionRepository::default()),
+            Arc::new(InMemoryStandupRepository::default()),
+        )
+    }
+
+    fn state_router(
+        sprints: Arc<InMemorySprintRepository>,
+        completions: Arc<InMemoryGoalCompletionRepository>,
+        standups: Arc<InMemoryStandupRepository>,
+    ) -> Router {
+        policy_router(sprints, completions, standups, DuplicateSprints::Reject)
+    }
+
+    fn policy_router(
+        sprints: Arc<InMemorySprintRepository>,
+        completions: Arc<InMemoryGoalCompletionRepository>,
+        standups: Arc<InMemoryStandupRepository>,
+        duplicates: DuplicateSprints,
+    ) -> Router {
+        let keys = ApiKeys::new(test_keys());
+        let state = SprintState {
+            sprints,
+            completions,
+            standups,
+            guilds: Arc::new(InMemoryGuildConfigRepository::default()),
+            default_timezone: Tz::UTC,
+            duplicates,
+        };
+
+        router(state, keys)
+    }
+
+    /// A sprint started two days ago with the goals, and the goals completed yesterday.
+    async fn burndown_router(goals: &[(&str, u32)], completed: &[&str]) -> (Router, ObjectId) {
+        sprint_router(goals, completed, &[]).await
+    }
+
+    /// Same as [`burndown_router`], with the `(user, blockers)` standups of yesterday.
+    async fn sprint_router(
+        goals: &[(&str, u32)],
+        completed: &[&str],
+        standups: &[(u64, &str)],
+    ) -> (Router, ObjectId) {
+        let today = Utc::now().date_naive();
+        let sprints = Arc::new(InMemorySprintRepository::default());
+        let sprint_id = sprints
+            .insert(&Sprint {
+                id: None,
+                guild_id: GuildId(1),
+                team: None,
+                name: "Sprint 1".into(),
+                start_date: shift(today, -2),
+                end_date: shift(today, 11),
+                goals: goals
+                    .iter()
+                    .map(|&(title, points)| Goal {
+                        title: title.into(),
+                        points,
+                    })
+                    .collect(),
+            })
+            .await
+            .unwrap();
+
+        let completions = Arc::new(InMemoryGoalCompletionRepository::default());
+        for goal in completed {
+            completions
+                .insert(&GoalCompletion {
+                    id: None,
+                    sprint_id,
+                    goal: goal.to_string(),
+                    completed_by: UserId(3),
+                    completed_at: Utc::now() - TimeDelta::days(1),
+                })
+                .await
+                .unwrap();
+        }
+
+        let repository = Arc::new(InMemoryStandupRepository::default());
+        for &(user_id, blockers) in standups {
+            repository
+                .insert(&StandupEntry {
+                    id: None,
+                    guild_id: GuildId(1),
+                    channel_id: ChannelId(2),
+                    user_id: UserId(user_id),
+                    team: None,
+                    date: shift(today, -1),
+                    yesterday: String::new(),
+                    today: String::new(),
+                    blockers: blockers.into(),
+                    sprint_id: Some(sprint_id),
+                    created_at: Utc::now() - TimeDelta::days(1),
+                })
+                .await
+                .unwrap();
+        }
+
+        (state_router(sprints, completions, repository), sprint_id)
+    }
+
+    fn shift(date: NaiveDate, days: i64) -> NaiveDate {
+        if days >= 0 {
+            date + Days::new(days as u64)
+        } else {
+            date - Days::new(days.unsigned_abs())
+        }
+    }
+
+    async fn get_current(router: Router) -> (StatusCode, serde_json::Value) {
+        get(router, "/sprints/current?guild_id=1").await
+    }
+
+    async fn get(router: Router, uri: &str) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder()
+            .uri(uri)
+            .header(API_KEY_HEADER, "key")
+            .body(Body::empty())
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    async fn put(router: Router, id: ObjectId, body: serde_json::Value) -> (StatusCode, String) {
+        let request = Request::put(format!("/sprints/{}", id))
+            .header(API_KEY_HEADER, "key")
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    fn sprint_body(name: &str, start_date: &str, end_date: &str) -> serde_json::Value {
+        serde_json::json!({
+            "guild_id": 1,
+            "name": name,
+            "start_date": start_date,
+            "end_date": end_date,
+            "goals": [{ "title": "scheduler", "points": 5 }],
+        })
+    }
+
+    #[tokio::test]
+    async fn put_creates_then_updates_the_sprint() {
+        let sprints = Arc::new(InMemorySprintRepository::default());
+        let router = state_router(
+            sprints.clone(),
+            Arc::new(InMemoryGoalCompletionRepository::default()),
+            Arc::new(InMemoryStandupRepository::default()),
+        );
+        let id = ObjectId::new();
+
+        let (status, body) = put(
+            router.clone(),
+            id,
+            sprint_body("Sprint 1", "2024-10-14", "2024-10-25"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["id"], id.to_hex());
+        assert_eq!(body["goals"][0]["points"], 5);
+
+        let (status, _) = put(
+            router,
+            id,
+            sprint_body("Sprint 1, extended", "2024-10-14", "2024-10-28"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let stored = sprints.find(id).await.unwrap().unwrap();
+        assert_eq!(stored.name, "Sprint 1, extended");
+        assert_eq!(
+            stored.end_date,
+            NaiveDate::from_ymd_opt(2024, 10, 28).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn put_rejects_a_sprint_ending_before_it_starts() {
+        let sprints = Arc::new(InMemorySprintRepository::default());
+        let router = state_router(
+            sprints.clone(),
+            Arc::new(InMemoryGoalCompletionRepository::default()),
+            Arc::new(InMemoryStandupRepository::default()),
+        );
+        let id = ObjectId::new();
+
+        let (status, body) = put(
+            router,
+            id,
+            sprint_body("Sprint 1", "2024-10-25", "2024-10-14"),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body.contains("before it starts"), "{body}");
+        assert!(sprints.find(id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn put_rejects_negative_points() {
+        let mut body = sprint_body("Sprint 1", "2024-10-14", "2024-10-25");
+        body["goals"][0]["points"] = serde_json::json!(-3);
+
+        let (status, _) = put(test_router(&[]).await, ObjectId::new(), body).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn put_applies_the_duplicate_dates_policy() {
+        let router = |duplicates| {
+            let sprints = Arc::new(InMemorySprintRepository::default());
+            let router = policy_router(
+                sprints.clone(),
+                Arc::new(InMemoryGoalCompletionRepository::default()),
+                Arc::new(InMemoryStandupRepository::default()),
+                duplicates,
+            );
+            (router, sprints)
+        };
+        let body = || sprint_body("Sprint 1", "2024-10-14", "2024-10-25");
+
+        let (rejecting, _) = router(DuplicateSprints::Reject);
+        put(rejecting.clone(), ObjectId::new(), body()).await;
+        let (status, body_text) = put(rejecting, ObjectId::new(), body()).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert!(body_text.contains("already covers"), "{body_text}");
+
+        let (warning, sprints) = router(DuplicateSprints::Warn);
+        let id = ObjectId::new();
+        put(warning.clone(), ObjectId::new(), body()).await;
+        let (status, _) = put(warning, id, body()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(sprints.find(id).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn put_rejects_guilds_outside_the_key_scope() {
+        let mut body = sprint_body("Sprint 1", "2024-10-14", "2024-10-25");
+        body["guild_id"] = serde_json::json!(2);
+
+        let (status, _) = put(test_router(&[]).await, ObjectId::new(), body).await;
+
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn reads_outside_the_key_scope_are_forbidden() {
+        let (router, sprint_id) = burndown_router(&[("scheduler", 5)], &[]).await;
+
+        for uri in [
+            "/sprints/current?guild_id=1".to_owned(),
+            format!("/sprints/{}/burndown", sprint_id),
+            format!("/sprints/{}/report.html", sprint_id),
+        ] {
+            let request = Request::builder()
+                .uri(&uri)
+                .header(API_KEY_HEADER, "other-key")
+                .body(Body::empty())
+                .unwrap();
+            let response = router.clone().oneshot(request).await.unwrap();
+
+            assert_eq!(response.status(), StatusCode::FORBIDDEN, "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn returns_the_single_active_sprint() {
+        let router = test_router(&[("past", -30, -16), ("current", -2, 11)]).await;
+
+        let (status, body) = get_current(router).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["name"], "current");
+    }
+
+    #[tokio::test]
+    async fn returns_not_found_without_active_sprint() {
+        let router = test_router(&[("past", -30, -16), ("future", 5, 19)]).await;
+
+        let (status, body) = get_current(router).await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "not_found");
+    }
+
+    #[tokio::test]
+    async fn overlapping_sprints_resolve_to_the_latest_start() {
+        let router = test_router(&[("older", -10, 3), ("newer", -1, 13)]).await;
+
+        let (status, body) = get_current(router).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["name"], "newer");
+    }
+
+    #[tokio::test]
+    async fn teams_only_see_their_own_sprint() {
+        let sprints = [
+            (Some("backend"), "backend 3", -2, 11),
+            (Some("frontend"), "frontend 7", -5, 8),
+        ];
+
+        let (status, body) = get(
+            team_router(&sprints).await,
+            "/sprints/current?guild_id=1&team=frontend",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["name"], "frontend 7");
+        assert_eq!(body["team"], "frontend");
+
+        let (status, _) = get(team_router(&sprints).await, "/sprints/current?guild_id=1").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn burndown_has_a_point_per_elapsed_day() {
+        let (router, sprint_id) =
+            burndown_router(&[("scheduler", 5), ("dashboard", 3)], &["dashboard"]).await;
+
+        let (status, body) = get(router, &format!("/sprints/{}/burndown", sprint_id)).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["sprint_id"], sprint_id.to_hex());
+        let remaining: Vec<_> = body["series"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|point| point["remaining_points"].as_u64().unwrap())
+            .collect();
+        assert_eq!(remaining, vec![8, 5, 5]);
+    }
+
+    #[tokio::test]
+    async fn burndown_of_a_sprint_without_goals_is_empty() {
+        let (router, sprint_id) = burndown_router(&[], &["dashboard"]).await;
+
+        let (status, body) = get(router, &format!("/sprints/{}/burndown", sprint_id)).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["series"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn burndown_of_an_unknown_sprint_is_not_found() {
+        let (router, _) = burndown_router(&[], &[]).await;
+
+        let (status, _) = get(
+            router.clone(),
+            &format!("/sprints/{}/burndown", ObjectId::new()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _) = get(router, "/sprints/current-ish/burndown").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn report_renders_the_sprint_and_its_totals() {
+        let (router, sprint_id) = sprint_router(
+            &[("scheduler", 5), ("dashboard", 3)],
+            &["dashboard"],
+            &[(3, ""), (4, "waiting on <review>")],
+        )
+        .await;
+
+        let request = Request::builder()
+            .uri(format!("/sprints/{}/report.html", sprint_id))
+            .header(API_KEY_HEADER, "key")
+            .body(Body::empty())
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()["content-type"],
+            "text/html; charset=utf-8"
+        );
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let html = String::from_utf8(body.to_vec()).unwrap();
+
+        assert!(html.contains("<title>Sprint 1</title>"), "{html}");
+        assert!(html.contains("<tr><th>Goals completed</th><td>1 / 2</td></tr>"));
+        assert!(html.contains("<tr><th>Points completed</th><td>3 / 8</td></tr>"));
+        assert!(html.contains("<tr><th>Participants</th><td>2</td></tr>"));
+        assert!(html.contains("waiting on &lt;review&gt;"));
+    }
+
+    #[tokio::test]
+    async fn each_route_accepts_its_own_media_type() {
+        let (router, sprint_id) = burndown_router(&[("scheduler", 5)], &[]).await;
+        let status = |uri: String, accept: &'static str| {
+            let request = Request::builder()
+                .uri(uri)
+                .header(API_KEY_HEADER, "key")
+                .header("accept", accept)
+                .body(Body::empty())
+                .unwrap();
+            let router = router.clone();
+            async move { router.oneshot(request).await.unwrap().status() }
+        };
+
+        let report = format!("/sprints/{}/report.html", sprint_id);
+        let burndown = format!("/sprints/{}/burndown", sprint_id);
+        assert_eq!(status(report.clone(), "text/html").await, StatusCode::OK);
+        assert_eq!(
+            status(report, "application/json").await,
+            StatusCode::NOT_ACCEPTABLE
+        );
+        assert_eq!(
+            status(burndown.clone(), "application/json").await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(burndown, "text/html").await,
+            StatusCode::NOT_ACCEPTABLE
+        );
+    }
+}
@@ -0,0 +1,423 @@
+use std::{convert::Infallible, sync::Arc, time::Duration};
+
+use axum::{
+    extract::{Path, Query, State},
+    middleware,
+    response::sse::{Event, KeepAlive, Sse},
+    routing::get,
+    Extension, Json, Router,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use futures::{stream, Stream, StreamExt};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        id::{ChannelId, GuildId, UserId},
+        standup::{StandupEntry, StandupFeed, StandupRepository},
+    },
+    drivers::http::{
+        error::{parse_object_id, ApiError},
+        middlewares::{
+            accept,
+            auth::{require_api_key, ApiKeys, Caller},
+        },
+        server::Draining,
+    },
+};
+
+/// A [`StandupEntry`] as the API serves it, ids as hex strings.
+///
+/// Its JSON Schema is served on `GET /schema/standup`.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct StandupResponse {
+    pub id: Option<String>,
+    pub guild_id: GuildId,
+    pub channel_id: ChannelId,
+    pub user_id: UserId,
+    /// The team the member answered for, `null` for the guild wide team.
+    pub team: Option<String>,
+    pub date: NaiveDate,
+    pub yesterday: String,
+    pub today: String,
+    pub blockers: String,
+    /// The sprint running on `date` when the entry was saved, if any.
+    pub sprint_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<StandupEntry> for StandupResponse {
+    fn from(entry: StandupEntry) -> Self {
+        Self {
+            id: entry.id.map(|id| id.to_hex()),
+            guild_id: entry.guild_id,
+            channel_id: entry.channel_id,
+            user_id: entry.user_id,
+            team: entry.team,
+            date: entry.date,
+            yesterday: entry.yesterday,
+            today: entry.today,
+            blockers: entry.blockers,
+            sprint_id: entry.sprint_id.map(|id| id.to_hex()),
+            created_at: entry.created_at,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct StandupState {
+    pub standups: Arc<dyn StandupRepository>,
+    pub feed: StandupFeed,
+    /// Time between the heartbeat comments of the stream.
+    pub heartbeat: Duration,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StreamParams {
+    pub guild_id: GuildId,
+}
+
+pub fn router(state: StandupState, keys: ApiKeys) -> Router {
+    Router::new()
+        .route("/standups/:id", get(get_standup))
+        .route_layer(accept::layer(accept::JSON))
+        .route(
+            "/standups/stream",
+            get(stream_standups).layer(accept::layer(accept::EVENT_STREAM)),
+        )
+        .route_layer(middleware::from_fn_with_state(keys, require_api_key))
+        .with_state(state)
+}
+
+#[tracing::instrument(name = "Get standup handler", skip(state, caller))]
+pub async fn get_standup(
+    State(state): State<StandupState>,
+    Extension(caller): Extension<Caller>,
+    Path(id): Path<String>,
+) -> Result<Json<StandupResponse>, ApiError> {
+    let entry = state
+        .standups
+        .find(parse_object_id(&id)?)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("no standup {}", id)))?;
+    caller.authorize(entry.guild_id)?;
+
+    Ok(Json(entry.into()))
+}
+
+/// Server-Sent Events of the standups of a guild as they are saved.
+///
+/// The subscription lives as long as the response body, it is dropped when
+/// the client disconnects or the server starts shutting down.
+#[tracing::instrument(name = "Stream standups handler", skip(state, caller, draining))]
+pub async fn stream_standups(
+    State(state): State<StandupState>,
+    Extension(caller): Extension<Caller>,
+    draining: Draining,
+    Query(params): Query<StreamParams>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let guild_id = params.guild_id;
+    caller.authorize(guild_id)?;
+
+    let events = stream::unfold(state.feed.listen(), move |mut entries| async move {
+        loop {
+            let entry = entries.recv().await?;
+            if entry.guild_id == guild_id {
+                let event = standup_event(entry);
+                return Some((Ok(event), entries));
+            }
+        }
+    })
+    .take_until(draining.wait());
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::new().interval(state.heartbeat)))
+}
+
+fn standup_event(entry: StandupEntry) -> Event {
+    let event = Event::default().event("standup");
+
+    match event.json_data(StandupResponse::from(entry)) {
+        Ok(event) => event,
+        Err(error) => {
+            tracing::warn!(error = ?error, "failed to serialize standup event");
+            Event::default().comment("failed to serialize standup")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body, Bytes},
+        http::{
+            header::{ACCEPT, CONTENT_TYPE},
+            HeaderValue, Request, StatusCode,
+        },
+    };
+    use bson::oid::ObjectId;
+    use futures::StreamExt;
+    use hyper_util::rt::TokioIo;
+    use tokio::{
+        net::{TcpListener, TcpStream},
+        sync::oneshot,
+        time::timeout,
+    };
+    use tower::ServiceExt;
+
+    use crate::{
+        configuration::{test_keys, test_settings},
+        domain::standup::StandupService,
+        drivers::{
+            database::memory::{InMemorySprintRepository, InMemoryStandupRepository},
+            http::{middlewares::auth::API_KEY_HEADER, server},
+        },
+    };
+
+    use super::*;
+
+    fn entry(guild_id: u64) -> StandupEntry {
+        StandupEntry {
+            id: None,
+            guild_id: GuildId(guild_id),
+            channel_id: ChannelId(2),
+            user_id: UserId(3),
+            team: None,
+            date: NaiveDate::from_ymd_opt(2024, 10, 15).unwrap(),
+            yesterday: "reviewed PRs".into(),
+            today: "scheduler".into(),
+            blockers: String::new(),
+            sprint_id: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn state(standups: Arc<InMemoryStandupRepository>, heartbeat: Duration) -> StandupState {
+        StandupState {
+            standups,
+            feed: StandupFeed::new(16),
+            heartbeat,
+        }
+    }
+
+    fn test_router_with(state: StandupState) -> Router {
+        let keys = ApiKeys::new(test_keys());
+
+        router(state, keys)
+    }
+
+    async fn test_router() -> (Router, ObjectId) {
+        let repository = Arc::new(InMemoryStandupRepository::default());
+        let id = repository.insert(&entry(1)).await.unwrap();
+
+        let state = state(repository, Duration::from_secs(15));
+        (test_router_with(state), id)
+    }
+
+    fn request(uri: &str) -> Request<Body> {
+        Request::builder()
+            .uri(uri)
+            .header(API_KEY_HEADER, "key")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    async fn get(router: Router, uri: &str) -> (StatusCode, serde_json::Value) {
+        let response = router.oneshot(request(uri)).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn returns_the_entry_by_id() {
+        let (router, id) = test_router().await;
+
+        let (status, body) = get(router, &format!("/standups/{}", id)).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["id"], id.to_hex());
+        assert_eq!(body["today"], "scheduler");
+        assert_eq!(body["date"], "2024-10-15");
+    }
+
+    #[tokio::test]
+    async fn returns_not_found_for_an_unknown_id() {
+        let (router, _) = test_router().await;
+
+        let (status, body) = get(router, &format!("/standups/{}", ObjectId::new())).await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "not_found");
+    }
+
+    #[tokio::test]
+    async fn key_scoped_to_another_guild_is_forbidden() {
+        let (router, id) = test_router().await;
+
+        for uri in [
+            format!("/standups/{}", id),
+            "/standups/stream?guild_id=1".to_owned(),
+        ] {
+            let mut request = request(&uri);
+            request
+                .headers_mut()
+                .insert(API_KEY_HEADER, HeaderValue::from_static("other-key"));
+            let response = router.clone().oneshot(request).await.unwrap();
+
+            assert_eq!(response.status(), StatusCode::FORBIDDEN, "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn returns_bad_request_for_a_malformed_id() {
+        let (router, _) = test_router().await;
+
+        let (status, body) = get(router, "/standups/not-an-id").await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["code"], "bad_request");
+        assert_eq!(body["error"]["message"], "invalid id \"not-an-id\"");
+    }
+
+    #[tokio::test]
+    async fn each_route_accepts_its_own_media_type() {
+        let (router, id) = test_router().await;
+        let status = |uri: String, accept: &'static str| {
+            let mut request = request(&uri);
+            request
+                .headers_mut()
+                .insert(ACCEPT, HeaderValue::from_static(accept));
+            let router = router.clone();
+            async move { router.oneshot(request).await.unwrap().status() }
+        };
+
+        let entry = format!("/standups/{}", id);
+        let stream = "/standups/stream?guild_id=1".to_owned();
+        assert_eq!(
+            status(entry.clone(), "application/json").await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(entry, "text/event-stream").await,
+            StatusCode::NOT_ACCEPTABLE
+        );
+        assert_eq!(
+            status(stream.clone(), "text/event-stream").await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(stream, "application/json").await,
+            StatusCode::NOT_ACCEPTABLE
+        );
+    }
+
+    async fn next_chunk(
+        body: &mut (impl Stream<Item = Result<Bytes, axum::Error>> + Unpin),
+    ) -> String {
+        let chunk = timeout(Duration::from_secs(1), body.next())
+            .await
+            .expect("expected an event before the timeout")
+            .unwrap()
+            .unwrap();
+
+        String::from_utf8(chunk.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn submitted_entries_are_streamed_to_subscribers() {
+        let standups = Arc::new(InMemoryStandupRepository::default());
+        let state = state(standups.clone(), Duration::from_secs(60));
+        let feed = state.feed.clone();
+        let service = StandupService::new(standups, Arc::new(InMemorySprintRepository::default()))
+            .with_feed(feed.clone());
+
+        let response = test_router_with(state)
+            .oneshot(request("/standups/stream?guild_id=1"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], "text/event-stream");
+        let mut body = response.into_body().into_data_stream();
+
+        service.submit(entry(7)).await.unwrap();
+        let submitted = service.submit(entry(1)).await.unwrap();
+
+        let event = next_chunk(&mut body).await;
+        let data = event
+            .strip_prefix("event: standup\ndata: ")
+            .and_then(|data| data.strip_suffix("\n\n"))
+            .unwrap();
+        let data: serde_json::Value = serde_json::from_str(data).unwrap();
+        assert_eq!(data["id"], submitted.id.unwrap().to_hex());
+        assert_eq!(data["guild_id"], 1);
+
+        drop(body);
+        assert_eq!(feed.listeners(), 0);
+    }
+
+    #[tokio::test]
+    async fn idle_stream_sends_heartbeats() {
+        let state = state(
+            Arc::new(InMemoryStandupRepository::default()),
+            Duration::from_millis(10),
+        );
+        let _feed = state.feed.clone();
+
+        let response = test_router_with(state)
+            .oneshot(request("/standups/stream?guild_id=1"))
+            .await
+            .unwrap();
+        let mut body = response.into_body().into_data_stream();
+
+        assert!(next_chunk(&mut body).await.starts_with(':'));
+    }
+
+    #[tokio::test]
+    async fn open_stream_ends_when_the_server_shuts_down() {
+        let state = state(
+            Arc::new(InMemoryStandupRepository::default()),
+            Duration::from_secs(60),
+        );
+        let mut settings = test_settings().http;
+        settings.shutdown_grace_secs = 60;
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let (stop, stopped) = oneshot::channel::<()>();
+        let server = tokio::spawn(async move {
+            server::serve(listener, test_router_with(state), &settings, async {
+                let _ = stopped.await;
+            })
+            .await
+        });
+
+        let stream = TcpStream::connect(address).await.unwrap();
+        let (mut sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+            .await
+            .unwrap();
+        tokio::spawn(connection);
+        let response = sender
+            .send_request(request(&format!(
+                "http://{}/standups/stream?guild_id=1",
+                address
+            )))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        stop.send(()).unwrap();
+        timeout(Duration::from_secs(5), server)
+            .await
+            .expect("the server stops well before the grace period")
+            .unwrap()
+            .unwrap();
+        let mut body = Body::new(response.into_body()).into_data_stream();
+        while let Some(chunk) = timeout(Duration::from_secs(1), body.next())
+            .await
+            .expect("expected the stream to end")
+        {
+            chunk.unwrap();
+        }
+    }
+}
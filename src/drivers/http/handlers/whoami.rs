@@ -0,0 +1,95 @@
+use axum::{middleware, routing::get, Extension, Json, Router};
+use serde::Serialize;
+
+use crate::{
+    domain::id::GuildId,
+    drivers::http::middlewares::{
+        accept,
+        auth::{require_api_key, ApiKeys, Caller},
+    },
+};
+
+/// The identity behind an api key, never the key itself.
+#[derive(Debug, Serialize)]
+pub struct WhoamiResponse {
+    pub label: String,
+    /// Admin keys act on every guild.
+    pub admin: bool,
+    pub guilds: Vec<GuildId>,
+}
+
+impl From<Caller> for WhoamiResponse {
+    fn from(caller: Caller) -> Self {
+        Self {
+            label: caller.label,
+            admin: caller.admin,
+            guilds: caller.guilds,
+        }
+    }
+}
+
+/// Lets API consumers check which key they are using and its scopes.
+pub fn router(keys: ApiKeys) -> Router {
+    Router::new()
+        .route("/whoami", get(whoami))
+        .route_layer(accept::layer(accept::JSON))
+        .route_layer(middleware::from_fn_with_state(keys, require_api_key))
+}
+
+#[tracing::instrument(name = "Whoami handler", skip(caller), fields(label = %caller.label))]
+pub async fn whoami(Extension(caller): Extension<Caller>) -> Json<WhoamiResponse> {
+    Json(caller.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        http::{Request, StatusCode},
+    };
+    use tower::ServiceExt;
+
+    use crate::{configuration::test_keys, drivers::http::middlewares::auth::API_KEY_HEADER};
+
+    use super::*;
+
+    fn test_router() -> Router {
+        router(ApiKeys::new(test_keys()))
+    }
+
+    async fn get_whoami(key: Option<&str>) -> (StatusCode, serde_json::Value) {
+        let mut request = Request::builder().uri("/whoami");
+        if let Some(key) = key {
+            request = request.header(API_KEY_HEADER, key);
+        }
+        let response = test_router()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn returns_the_label_and_scopes_of_the_key() {
+        let (status, body) = get_whoami(Some("key")).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body,
+            serde_json::json!({ "label": "dashboard", "admin": false, "guilds": [1, 3] })
+        );
+    }
+
+    #[tokio::test]
+    async fn unauthenticated_requests_are_unauthorized() {
+        for key in [None, Some("wrong")] {
+            let (status, body) = get_whoami(key).await;
+
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+            assert_eq!(body["error"]["code"], "unauthorized");
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! Self-contained HTML pages, styles inlined so they print to PDF as they are.
+
+use crate::domain::{report::SprintReport, template};
+
+const SPRINT_REPORT: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; color: #222; }}
+h1 {{ margin-bottom: 0; }}
+table {{ border-collapse: collapse; margin: 1em 0; }}
+th, td {{ border: 1px solid #999; padding: 0.3em 0.8em; text-align: left; }}
+th {{ background: #eee; }}
+@media print {{ body {{ margin: 0; }} }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<p>{guild}{team} &middot; {start_date} to {end_date}</p>
+<h2>Summary</h2>
+<table>
+<tr><th>Goals completed</th><td>{completed_goals} / {total_goals}</td></tr>
+<tr><th>Points completed</th><td>{completed_points} / {total_points}</td></tr>
+<tr><th>Standups</th><td>{standups}</td></tr>
+<tr><th>Participants</th><td>{participants}</td></tr>
+<tr><th>Blockers</th><td>{blocker_count}</td></tr>
+</table>
+<h2>Participation</h2>
+<table>
+<tr><th>Date</th><th>Participants</th><th>Roster</th></tr>
+{participation}
+</table>
+<h2>Burndown</h2>
+<table>
+<tr><th>Date</th><th>Remaining goals</th><th>Remaining points</th></tr>
+{burndown}
+</table>
+<h2>Blockers</h2>
+<table>
+<tr><th>Date</th><th>Member</th><th>Blocker</th></tr>
+{blockers}
+</table>
+</body>
+</html>
+"#;
+
+/// Escape `text` for HTML text and attribute values.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn row(cells: &[String]) -> String {
+    let cells: String = cells
+        .iter()
+        .map(|cell| format!("<td>{}</td>", escape(cell)))
+        .collect();
+
+    format!("<tr>{}</tr>", cells)
+}
+
+fn rows(rows: impl Iterator<Item = String>, columns: usize) -> String {
+    let rows: Vec<_> = rows.collect();
+    if rows.is_empty() {
+        return format!("<tr><td colspan=\"{}\">none</td></tr>", columns);
+    }
+
+    rows.join("\n")
+}
+
+pub fn sprint_report(report: &SprintReport) -> String {
+    let sprint = &report.sprint;
+    let team = sprint
+        .team
+        .as_deref()
+        .map(|team| format!(" &middot; {}", escape(team)))
+        .unwrap_or_default();
+
+    let participation = rows(
+        report.participation.iter().map(|day| {
+            row(&[
+                day.date.to_string(),
+                day.participants.to_string(),
+                day.roster.to_string(),
+            ])
+        }),
+        3,
+    );
+    let burndown = rows(
+        report.burndown.iter().map(|point| {
+            row(&[
+                point.date.to_string(),
+                point.remaining_goals.to_string(),
+                point.remaining_points.to_string(),
+            ])
+        }),
+        3,
+    );
+    let blockers = rows(
+        report.blockers.iter().map(|blocker| {
+            row(&[
+                blocker.date.to_string(),
+                format!("@{}", blocker.user_id),
+                blocker.text.clone(),
+            ])
+        }),
+        3,
+    );
+
+    template::render(
+        SPRINT_REPORT,
+        &[
+            ("title", &escape(&sprint.name)),
+            ("guild", &escape(&report.guild_name)),
+            ("team", &team),
+            ("start_date", &sprint.start_date.to_string()),
+            ("end_date", &sprint.end_date.to_string()),
+            ("completed_goals", &report.completed_goals.to_string()),
+            ("total_goals", &report.total_goals.to_string()),
+            ("completed_points", &report.completed_points.to_string()),
+            ("total_points", &report.total_points.to_string()),
+            ("standups", &report.standups.to_string()),
+            ("participants", &report.participants.to_string()),
+            ("blocker_count", &report.blockers.len().to_string()),
+            ("participation", &participation),
+            ("burndown", &burndown),
+            ("blockers", &blockers),
+        ],
+    )
+    .expect("every placeholder of the sprint report has a value")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_markup() {
+        assert_eq!(
+            escape(r#"<script>alert("x & y")</script>"#),
+            "&lt;script&gt;alert(&quot;x &amp; y&quot;)&lt;/script&gt;"
+        );
+    }
+}
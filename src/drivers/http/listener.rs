@@ -0,0 +1,87 @@
+use std::net::SocketAddr;
+
+use anyhow::{Context, Result};
+use tokio::net::{TcpListener, TcpSocket};
+
+use crate::configuration::HttpSettings;
+
+/// Bind a listener tuned by the socket options of [`HttpSettings`].
+///
+/// Sockets accepted from the listener inherit `nodelay` and `keepalive`.
+pub fn bind(address: SocketAddr, settings: &HttpSettings) -> Result<TcpListener> {
+    let socket = match address {
+        SocketAddr::V4(_) => TcpSocket::new_v4(),
+        SocketAddr::V6(_) => TcpSocket::new_v6(),
+    }
+    .context("expected to create socket")?;
+
+    socket
+        .set_reuseaddr(true)
+        .context("expected to set SO_REUSEADDR")?;
+    socket
+        .set_nodelay(settings.nodelay)
+        .context("expected to set TCP_NODELAY")?;
+    socket
+        .set_keepalive(settings.keepalive)
+        .context("expected to set SO_KEEPALIVE")?;
+
+    socket
+        .bind(address)
+        .with_context(|| format!("expected to bind {}", address))?;
+
+    socket
+        .listen(settings.backlog)
+        .context("expected to listen on socket")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use tokio::net::TcpStream;
+
+    use super::*;
+
+    fn settings(nodelay: bool, keepalive: bool) -> HttpSettings {
+        HttpSettings {
+            port: 0,
+            host: "127.0.0.1".into(),
+            prefix: "".into(),
+            timeout: 10,
+            response_timeout: None,
+            route_timeouts: HashMap::new(),
+            api_keys: vec![],
+            nodelay,
+            keepalive,
+            backlog: 128,
+            compression_min_size: 1024,
+            normalize_trailing_slash: true,
+            collapse_slashes: false,
+            redact_headers: vec![],
+            access_log_sample_rate: 1,
+            sse_heartbeat_secs: 15,
+            http2: false,
+            shutdown_grace_secs: 10,
+            tls: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn socket_options_are_applied_to_accepted_sockets() {
+        for (nodelay, keepalive) in [(true, true), (false, false)] {
+            let listener = bind(
+                "127.0.0.1:0".parse().unwrap(),
+                &settings(nodelay, keepalive),
+            )
+            .unwrap();
+            let address = listener.local_addr().unwrap();
+
+            let _client = TcpStream::connect(address).await.unwrap();
+            let (accepted, _) = listener.accept().await.unwrap();
+
+            assert_eq!(accepted.nodelay().unwrap(), nodelay);
+            let accepted = socket2::SockRef::from(&accepted);
+            assert_eq!(accepted.keepalive().unwrap(), keepalive);
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use std::{fmt, marker::PhantomData};
+
+use axum::http::{header, Request, Response, StatusCode};
+use tower_http::validate_request::{ValidateRequest, ValidateRequestHeaderLayer};
+
+// What each route answers with, layered on the routes with [`layer`] so a
+// route serving another media type isn't turned away.
+pub const JSON: &[&str] = &["application/json"];
+pub const EVENT_STREAM: &[&str] = &["text/event-stream"];
+pub const HTML: &[&str] = &["text/html"];
+
+/// Rejects with 406 the requests whose `Accept` header allows none of the
+/// media types, requests without one are let through.
+pub struct AcceptAny<ResBody> {
+    media_types: &'static [&'static str],
+    _body: PhantomData<fn() -> ResBody>,
+}
+
+impl<ResBody> AcceptAny<ResBody> {
+    pub fn new(media_types: &'static [&'static str]) -> Self {
+        Self {
+            media_types,
+            _body: PhantomData,
+        }
+    }
+
+    fn accepts(&self, range: &str) -> bool {
+        let range = range.split(';').next().unwrap_or_default().trim();
+        let Some((kind, subtype)) = range.split_once('/') else {
+            return false;
+        };
+
+        self.media_types.iter().any(|media_type| {
+            let (expected_kind, expected_subtype) =
+                media_type.split_once('/').unwrap_or((media_type, ""));
+
+            match (kind, subtype) {
+                ("*", "*") => true,
+                (kind, "*") => kind.eq_ignore_ascii_case(expected_kind),
+                (kind, subtype) => {
+                    kind.eq_ignore_ascii_case(expected_kind)
+                        && subtype.eq_ignore_ascii_case(expected_subtype)
+                }
+            }
+        })
+    }
+}
+
+impl<ResBody> Clone for AcceptAny<ResBody> {
+    fn clone(&self) -> Self {
+        Self::new(self.media_types)
+    }
+}
+
+impl<ResBody> fmt::Debug for AcceptAny<ResBody> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AcceptAny").field(&self.media_types).finish()
+    }
+}
+
+impl<B, ResBody: Default> ValidateRequest<B> for AcceptAny<ResBody> {
+    type ResponseBody = ResBody;
+
+    fn validate(&mut self, req: &mut Request<B>) -> Result<(), Response<Self::ResponseBody>> {
+        let mut values = req.headers().get_all(header::ACCEPT).iter().peekable();
+        if values.peek().is_none() {
+            return Ok(());
+        }
+
+        let accepted = values
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .any(|range| self.accepts(range));
+        if accepted {
+            return Ok(());
+        }
+
+        let mut response = Response::new(ResBody::default());
+        *response.status_mut() = StatusCode::NOT_ACCEPTABLE;
+        Err(response)
+    }
+}
+
+/// Answer 406 to the requests of the routes it wraps that accept none of `media_types`.
+pub fn layer<ResBody>(
+    media_types: &'static [&'static str],
+) -> ValidateRequestHeaderLayer<AcceptAny<ResBody>> {
+    ValidateRequestHeaderLayer::custom(AcceptAny::new(media_types))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const API_MEDIA_TYPES: &[&str] = &["application/json", "text/event-stream", "text/html"];
+
+    fn validate_with(media_types: &'static [&'static str], accept: Option<&str>) -> bool {
+        let mut req = Request::builder();
+        if let Some(accept) = accept {
+            req = req.header(header::ACCEPT, accept);
+        }
+
+        AcceptAny::<()>::new(media_types)
+            .validate(&mut req.body(()).unwrap())
+            .is_ok()
+    }
+
+    fn validate(accept: Option<&str>) -> bool {
+        validate_with(API_MEDIA_TYPES, accept)
+    }
+
+    #[test]
+    fn api_media_types_are_accepted() {
+        assert!(validate(None));
+        assert!(validate(Some("application/json")));
+        assert!(validate(Some("text/event-stream")));
+        assert!(validate(Some("text/html")));
+        assert!(validate(Some("image/png, */*;q=0.8")));
+        assert!(validate(Some("application/*")));
+        assert!(validate(Some("Text/Event-Stream; charset=utf-8")));
+    }
+
+    #[test]
+    fn other_media_types_are_not_acceptable() {
+        assert!(!validate(Some("text/plain")));
+        assert!(!validate(Some("image/png, application/xml")));
+        assert!(!validate(Some("garbage")));
+    }
+
+    #[test]
+    fn routes_only_accept_their_own_media_types() {
+        assert!(validate_with(JSON, Some("application/json")));
+        assert!(!validate_with(JSON, Some("text/html")));
+        assert!(validate_with(
+            HTML,
+            Some("text/html, application/xhtml+xml")
+        ));
+        assert!(!validate_with(HTML, Some("application/json")));
+        assert!(validate_with(EVENT_STREAM, Some("text/*")));
+        assert!(!validate_with(EVENT_STREAM, Some("application/json")));
+    }
+}
@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use secrecy::ExposeSecret;
+
+use crate::{configuration::ApiKeySettings, domain::id::GuildId, drivers::http::error::ApiError};
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// The identity resolved from the api key of a request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Caller {
+    pub label: String,
+    pub admin: bool,
+    pub guilds: Vec<GuildId>,
+}
+
+impl Caller {
+    /// Whether the key is scoped to `guild_id`.
+    pub fn can_act_on(&self, guild_id: GuildId) -> bool {
+        self.admin || self.guilds.contains(&guild_id)
+    }
+
+    /// [`ApiError::Forbidden`] unless the key is scoped to `guild_id`, the
+    /// guild a request names or the one of the stored entity it acts on.
+    pub fn authorize(&self, guild_id: GuildId) -> Result<(), ApiError> {
+        if self.can_act_on(guild_id) {
+            Ok(())
+        } else {
+            Err(ApiError::Forbidden)
+        }
+    }
+}
+
+/// The api keys accepted by the HTTP API.
+#[derive(Clone, Default)]
+pub struct ApiKeys(Arc<Vec<ApiKeySettings>>);
+
+impl ApiKeys {
+    pub fn new(keys: Vec<ApiKeySettings>) -> Self {
+        Self(Arc::new(keys))
+    }
+
+    pub fn authenticate(&self, key: &str) -> Option<Caller> {
+        self.0
+            .iter()
+            .find(|candidate| candidate.key.expose_secret() == key)
+            .map(|candidate| Caller {
+                label: candidate.label.clone(),
+                admin: candidate.admin,
+                guilds: candidate.guilds.clone(),
+            })
+    }
+
+    fn caller(&self, req: &Request) -> Result<Caller, ApiError> {
+        req.headers()
+            .get(API_KEY_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|key| self.authenticate(key))
+            .ok_or(ApiError::Unauthorized)
+    }
+}
+
+/// Only let requests authenticated with a known api key through.
+///
+/// The resolved [`Caller`] is stored in the request extensions for the handlers.
+#[tracing::instrument(name = "Require api key middleware", skip(keys, req, next))]
+pub async fn require_api_key(
+    State(keys): State<ApiKeys>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let caller = keys.caller(&req)?;
+
+    req.extensions_mut().insert(caller);
+
+    Ok(next.run(req).await)
+}
+
+/// Only let requests authenticated with an admin api key through.
+///
+/// The resolved [`Caller`] is stored in the request extensions for the handlers.
+#[tracing::instrument(name = "Require admin middleware", skip(keys, req, next))]
+pub async fn require_admin(
+    State(keys): State<ApiKeys>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let caller = keys.caller(&req)?;
+    if !caller.admin {
+        return Err(ApiError::Forbidden);
+    }
+
+    req.extensions_mut().insert(caller);
+
+    Ok(next.run(req).await)
+}
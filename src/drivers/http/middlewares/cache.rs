@@ -0,0 +1,92 @@
+use axum::http::{header::CACHE_CONTROL, HeaderValue};
+use tower_http::set_header::SetResponseHeaderLayer;
+
+/// Keep intermediaries from caching responses, which all hold live data.
+///
+/// Handlers that set their own `Cache-Control` keep it.
+pub fn layer() -> SetResponseHeaderLayer<HeaderValue> {
+    SetResponseHeaderLayer::if_not_present(CACHE_CONTROL, HeaderValue::from_static("no-store"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use axum::{body::Body, http::Request, routing::get, Router};
+    use chrono::{NaiveDate, Utc};
+    use tower::ServiceExt;
+
+    use crate::{
+        configuration::test_keys,
+        domain::{
+            id::{ChannelId, GuildId, UserId},
+            standup::{StandupEntry, StandupFeed, StandupRepository},
+        },
+        drivers::{
+            database::memory::InMemoryStandupRepository,
+            http::{
+                handlers::standup::{self, StandupState},
+                middlewares::auth::{ApiKeys, API_KEY_HEADER},
+            },
+        },
+    };
+
+    use super::*;
+
+    async fn cache_control(router: Router, uri: &str) -> Option<HeaderValue> {
+        let request = Request::builder()
+            .uri(uri)
+            .header(API_KEY_HEADER, "key")
+            .body(Body::empty())
+            .unwrap();
+        let response = router.layer(layer()).oneshot(request).await.unwrap();
+
+        response.headers().get(CACHE_CONTROL).cloned()
+    }
+
+    #[tokio::test]
+    async fn standup_responses_are_not_stored() {
+        let standups = Arc::new(InMemoryStandupRepository::default());
+        let id = standups
+            .insert(&StandupEntry {
+                id: None,
+                guild_id: GuildId(1),
+                channel_id: ChannelId(2),
+                user_id: UserId(3),
+                team: None,
+                date: NaiveDate::from_ymd_opt(2024, 10, 15).unwrap(),
+                yesterday: String::new(),
+                today: "scheduler".into(),
+                blockers: String::new(),
+                sprint_id: None,
+                created_at: Utc::now(),
+            })
+            .await
+            .unwrap();
+        let keys = ApiKeys::new(test_keys());
+        let router = standup::router(
+            StandupState {
+                standups,
+                feed: StandupFeed::new(16),
+                heartbeat: Duration::from_secs(15),
+            },
+            keys,
+        );
+
+        let value = cache_control(router, &format!("/standups/{}", id)).await;
+
+        assert_eq!(value.unwrap(), "no-store");
+    }
+
+    #[tokio::test]
+    async fn handler_cache_control_is_kept() {
+        let router = Router::new().route(
+            "/report",
+            get(|| async { ([(CACHE_CONTROL, "private, max-age=60")], "report") }),
+        );
+
+        let value = cache_control(router, "/report").await;
+
+        assert_eq!(value.unwrap(), "private, max-age=60");
+    }
+}
@@ -0,0 +1,66 @@
+use tower_http::{
+    compression::{
+        predicate::{And, DefaultPredicate, Predicate, SizeAbove},
+        CompressionLayer,
+    },
+    CompressionLevel,
+};
+
+/// Compress responses of at least `min_size` bytes, on top of the default
+/// rules that skip gRPC, images and server-sent events.
+///
+/// Streaming bodies of unknown size are always compressed.
+pub fn layer(min_size: u16) -> CompressionLayer<And<DefaultPredicate, SizeAbove>> {
+    CompressionLayer::new()
+        .quality(CompressionLevel::Fastest)
+        .compress_when(DefaultPredicate::new().and(SizeAbove::new(min_size)))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{header, Request},
+        routing::get,
+        Router,
+    };
+    use tower::ServiceExt;
+
+    use super::*;
+
+    const MIN_SIZE: u16 = 256;
+
+    fn router() -> Router {
+        Router::new()
+            .route(
+                "/small",
+                get(|| async { "a".repeat(MIN_SIZE as usize - 1) }),
+            )
+            .route("/large", get(|| async { "a".repeat(MIN_SIZE as usize) }))
+            .layer(layer(MIN_SIZE))
+    }
+
+    async fn content_encoding(uri: &str) -> Option<String> {
+        let request = Request::builder()
+            .uri(uri)
+            .header(header::ACCEPT_ENCODING, "gzip")
+            .body(Body::empty())
+            .unwrap();
+        let response = router().oneshot(request).await.unwrap();
+
+        response
+            .headers()
+            .get(header::CONTENT_ENCODING)
+            .map(|value| value.to_str().unwrap().to_owned())
+    }
+
+    #[tokio::test]
+    async fn responses_below_the_threshold_are_not_compressed() {
+        assert_eq!(content_encoding("/small").await, None);
+    }
+
+    #[tokio::test]
+    async fn responses_at_the_threshold_are_compressed() {
+        assert_eq!(content_encoding("/large").await.as_deref(), Some("gzip"));
+    }
+}
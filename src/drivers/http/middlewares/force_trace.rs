@@ -0,0 +1,213 @@
+//! Lets trusted callers force the sampling of their request trace.
+//!
+//! The sampler follows the decision of the caller, so forcing a trace is
+//! marking the W3C `traceparent` of the request as sampled, or starting a
+//! sampled one, before the telemetry layer reads it.
+
+use std::{net::SocketAddr, sync::Arc};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use ipnet::IpNet;
+use opentelemetry_sdk::trace::{IdGenerator, RandomIdGenerator};
+
+pub const FORCE_TRACE_HEADER: &str = "x-force-trace";
+const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// The networks allowed to force a trace.
+#[derive(Clone, Debug, Default)]
+pub struct ForceTrace {
+    trusted: Arc<Vec<IpNet>>,
+}
+
+impl ForceTrace {
+    pub fn new(trusted: Vec<IpNet>) -> Self {
+        Self {
+            trusted: Arc::new(trusted),
+        }
+    }
+
+    fn applies(&self, req: &Request) -> bool {
+        let forced = req
+            .headers()
+            .get(FORCE_TRACE_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.trim().eq_ignore_ascii_case("true"));
+        if !forced {
+            return false;
+        }
+
+        let remote = req
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(remote)| remote.ip());
+        let trusted = remote.is_some_and(|ip| self.trusted.iter().any(|net| net.contains(&ip)));
+        if !trusted {
+            tracing::debug!(?remote, "ignored x-force-trace from untrusted source");
+        }
+
+        trusted
+    }
+}
+
+#[tracing::instrument(name = "Force trace middleware", skip(force, req, next))]
+pub async fn force_trace(
+    State(force): State<ForceTrace>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    if force.applies(&req) {
+        mark_sampled(req.headers_mut());
+    }
+
+    next.run(req).await
+}
+
+/// Set the sampled flag of the `traceparent`, starting a new trace when it is
+/// missing or malformed.
+fn mark_sampled(headers: &mut HeaderMap) {
+    let parent = headers
+        .get(TRACEPARENT_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| {
+            let (prefix, flags) = value.rsplit_once('-')?;
+            let flags = u8::from_str_radix(flags, 16).ok()?;
+            let valid = prefix.len() == 52 && prefix.starts_with("00-");
+            valid.then(|| format!("{}-{:02x}", prefix, flags | 1))
+        })
+        .unwrap_or_else(|| {
+            let ids = RandomIdGenerator::default();
+            format!("00-{}-{}-01", ids.new_trace_id(), ids.new_span_id())
+        });
+
+    if let Ok(parent) = HeaderValue::from_str(&parent) {
+        headers.insert(TRACEPARENT_HEADER, parent);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, middleware, routing::get, Router};
+    use opentelemetry::{
+        propagation::{Extractor, TextMapPropagator},
+        trace::{SamplingDecision, SpanKind, TraceContextExt, TraceId},
+        Context,
+    };
+    use opentelemetry_sdk::{propagation::TraceContextPropagator, trace::ShouldSample};
+    use tokio::sync::Mutex;
+    use tower::ServiceExt;
+
+    use crate::observability::trace::sampler;
+
+    use super::*;
+
+    struct Headers<'a>(&'a HeaderMap);
+
+    impl Extractor for Headers<'_> {
+        fn get(&self, key: &str) -> Option<&str> {
+            self.0.get(key).and_then(|value| value.to_str().ok())
+        }
+
+        fn keys(&self) -> Vec<&str> {
+            self.0.keys().map(|name| name.as_str()).collect()
+        }
+    }
+
+    /// Whether a sampler sampling no new traces samples the request as it
+    /// reaches the handler.
+    async fn sampled(remote: &str, headers: &[(&str, &str)]) -> bool {
+        let seen = Arc::new(Mutex::new(HeaderMap::new()));
+        let captured = seen.clone();
+        let router = Router::new()
+            .route(
+                "/",
+                get(move |headers: HeaderMap| async move {
+                    *captured.lock().await = headers;
+                }),
+            )
+            .layer(middleware::from_fn_with_state(
+                ForceTrace::new(vec!["10.0.0.0/8".parse().unwrap()]),
+                force_trace,
+            ));
+
+        let mut req = Request::builder().uri("/");
+        for (name, value) in headers {
+            req = req.header(*name, *value);
+        }
+        let mut req = req.body(Body::empty()).unwrap();
+        req.extensions_mut()
+            .insert(ConnectInfo(remote.parse::<SocketAddr>().unwrap()));
+        router.oneshot(req).await.unwrap();
+
+        let headers = seen.lock().await;
+        let parent = TraceContextPropagator::new().extract(&Headers(&headers));
+        let trace_id = match parent.span().span_context().trace_id() {
+            TraceId::INVALID => TraceId::from_u128(42),
+            trace_id => trace_id,
+        };
+        let result = sampler(0.0).should_sample(
+            Some(&parent).filter(|cx: &&Context| cx.has_active_span()),
+            trace_id,
+            "request",
+            &SpanKind::Server,
+            &[],
+            &[],
+        );
+
+        result.decision == SamplingDecision::RecordAndSample
+    }
+
+    const UNSAMPLED_PARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00";
+
+    #[tokio::test]
+    async fn trusted_header_forces_a_sampled_trace() {
+        assert!(sampled("10.1.2.3:5000", &[(FORCE_TRACE_HEADER, "true")]).await);
+        assert!(
+            sampled(
+                "10.1.2.3:5000",
+                &[
+                    (FORCE_TRACE_HEADER, "TRUE"),
+                    (TRACEPARENT_HEADER, UNSAMPLED_PARENT)
+                ]
+            )
+            .await
+        );
+    }
+
+    #[tokio::test]
+    async fn other_requests_follow_the_configured_sampler() {
+        assert!(!sampled("10.1.2.3:5000", &[]).await);
+        assert!(!sampled("10.1.2.3:5000", &[(FORCE_TRACE_HEADER, "false")]).await);
+        assert!(!sampled("192.168.0.1:5000", &[(FORCE_TRACE_HEADER, "true")]).await);
+        assert!(
+            !sampled(
+                "192.168.0.1:5000",
+                &[
+                    (FORCE_TRACE_HEADER, "true"),
+                    (TRACEPARENT_HEADER, UNSAMPLED_PARENT)
+                ]
+            )
+            .await
+        );
+    }
+
+    #[test]
+    fn sampled_flag_keeps_the_caller_trace() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            TRACEPARENT_HEADER,
+            HeaderValue::from_static(UNSAMPLED_PARENT),
+        );
+
+        mark_sampled(&mut headers);
+
+        assert_eq!(
+            headers[TRACEPARENT_HEADER],
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+        );
+    }
+}
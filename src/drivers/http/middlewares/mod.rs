@@ -1,3 +1,14 @@
+pub mod accept;
+pub mod auth;
+pub mod cache;
+pub mod compression;
+pub mod force_trace;
+pub mod panic;
+pub mod path;
+pub mod redact;
+pub mod telemetry;
+pub mod timeout;
+
 use std::{sync::Arc, time::Instant};
 
 use axum::{
@@ -8,17 +19,57 @@ use axum::{
 
 use crate::observability::metrics::{HttpMetrics, HttpRequestLabels};
 
+/// State of [`metrics_middleware`].
+#[derive(Clone)]
+pub struct MetricsState {
+    metrics: Arc<HttpMetrics>,
+    /// Removed from the front of the `path` label, `None` to keep it whole.
+    strip_prefix: Option<String>,
+}
+
+impl MetricsState {
+    pub fn new(metrics: Arc<HttpMetrics>) -> Self {
+        Self {
+            metrics,
+            strip_prefix: None,
+        }
+    }
+
+    /// Label the routes nested under `prefix` without it, so the series keep
+    /// their name when `http.prefix` changes. Empty keeps the paths whole.
+    pub fn with_strip_prefix(mut self, prefix: &str) -> Self {
+        self.strip_prefix = (!prefix.is_empty()).then(|| prefix.to_owned());
+        self
+    }
+
+    /// `path` without the prefix, only when it is a whole leading segment:
+    /// `/apikeys` keeps its name under the `/api` prefix.
+    fn path_label(&self, path: &str) -> String {
+        let stripped = self
+            .strip_prefix
+            .as_deref()
+            .and_then(|prefix| path.strip_prefix(prefix))
+            .filter(|rest| rest.is_empty() || rest.starts_with('/'));
+
+        match stripped {
+            Some("") => "/".to_owned(),
+            Some(rest) => rest.to_owned(),
+            None => path.to_owned(),
+        }
+    }
+}
+
 #[tracing::instrument(name = "Metrics middleware", skip(state, req, next))]
 pub async fn metrics_middleware(
-    State(state): State<Arc<HttpMetrics>>,
+    State(state): State<MetricsState>,
     req: Request,
     next: Next,
 ) -> impl IntoResponse {
     let start = Instant::now();
     let path = if let Some(matched_path) = req.extensions().get::<MatchedPath>() {
-        matched_path.as_str().to_owned()
+        state.path_label(matched_path.as_str())
     } else {
-        req.uri().path().to_owned()
+        state.path_label(req.uri().path())
     };
     let method = req.method().to_string();
 
@@ -33,16 +84,87 @@ pub async fn metrics_middleware(
         status_code,
     };
 
-    state.total_requests.get_or_create(&labels).inc();
+    let metrics = &state.metrics;
+    metrics.total_requests.get_or_create(&labels).inc();
 
     if status_code > 200 && status_code < 400 {
-        state
+        metrics
             .latency_success
             .get_or_create(&labels)
             .observe(latency)
     } else {
-        state.latency_error.get_or_create(&labels).observe(latency)
+        metrics
+            .latency_error
+            .get_or_create(&labels)
+            .observe(latency)
     }
 
     response
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    async fn request_count(state: MetricsState, metrics: &HttpMetrics, path: &str) -> u64 {
+        let router = Router::new()
+            .route("/sprints/:id", get(|| async { "sprint" }))
+            .route_layer(middleware::from_fn_with_state(state, metrics_middleware));
+        let router = Router::new().nest("/api", router);
+
+        let request = Request::builder()
+            .uri("/api/sprints/42")
+            .body(Body::empty())
+            .unwrap();
+        router.oneshot(request).await.unwrap();
+
+        metrics
+            .total_requests
+            .get_or_create(&HttpRequestLabels {
+                method: "GET".into(),
+                path: path.into(),
+                status_code: 200,
+            })
+            .get()
+    }
+
+    #[tokio::test]
+    async fn path_label_omits_the_stripped_prefix() {
+        let metrics = Arc::new(HttpMetrics::new());
+        let state = MetricsState::new(metrics.clone()).with_strip_prefix("/api");
+
+        assert_eq!(request_count(state, &metrics, "/sprints/:id").await, 1);
+        assert_eq!(
+            metrics
+                .total_requests
+                .get_or_create(&HttpRequestLabels {
+                    method: "GET".into(),
+                    path: "/api/sprints/:id".into(),
+                    status_code: 200,
+                })
+                .get(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn path_label_keeps_the_prefix_by_default() {
+        let metrics = Arc::new(HttpMetrics::new());
+        let state = MetricsState::new(metrics.clone());
+
+        assert_eq!(request_count(state, &metrics, "/api/sprints/:id").await, 1);
+    }
+
+    #[test]
+    fn only_whole_segments_are_stripped() {
+        let state = MetricsState::new(Arc::new(HttpMetrics::new())).with_strip_prefix("/api");
+
+        assert_eq!(state.path_label("/api"), "/");
+        assert_eq!(state.path_label("/api/healthz"), "/healthz");
+        assert_eq!(state.path_label("/apikeys"), "/apikeys");
+        assert_eq!(state.path_label("/metrics"), "/metrics");
+    }
+}
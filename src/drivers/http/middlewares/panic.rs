@@ -0,0 +1,91 @@
+use std::any::Any;
+
+use axum::response::{IntoResponse, Response};
+use uuid::Uuid;
+
+use crate::drivers::http::error::ApiError;
+
+/// Answer a panicked handler with the JSON error envelope, with an incident id
+/// logged next to the panic message for support to find it.
+///
+/// Used with `CatchPanicLayer::custom`.
+pub fn handle_panic(panic: Box<dyn Any + Send + 'static>) -> Response {
+    let incident_id = Uuid::new_v4().to_string();
+    let message = panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic");
+
+    tracing::error!(incident_id = %incident_id, panic = %message, "handler panicked");
+
+    ApiError::Panic { incident_id }.into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Write,
+        sync::{Arc, Mutex},
+    };
+
+    use axum::{
+        body::{to_bytes, Body},
+        http::{Request, StatusCode},
+        routing::get,
+        Router,
+    };
+    use tower::ServiceExt;
+    use tower_http::catch_panic::CatchPanicLayer;
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    async fn boom() -> &'static str {
+        panic!("standup exploded")
+    }
+
+    #[tokio::test]
+    async fn panics_are_answered_with_the_error_envelope() {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let app = Router::new()
+            .route("/boom", get(boom))
+            .layer(CatchPanicLayer::custom(handle_panic));
+
+        let response = app
+            .oneshot(Request::get("/boom").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["code"], "internal");
+        assert_eq!(body["error"]["message"], "internal server error");
+
+        let incident_id = body["error"]["incident_id"].as_str().unwrap();
+        assert!(Uuid::parse_str(incident_id).is_ok());
+
+        let logs = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        assert!(logs.contains(incident_id), "{logs}");
+        assert!(logs.contains("standup exploded"), "{logs}");
+    }
+}
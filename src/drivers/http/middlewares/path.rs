@@ -0,0 +1,206 @@
+use std::borrow::Cow;
+
+use axum::{
+    extract::Request,
+    http::{uri::PathAndQuery, Uri},
+    Router,
+};
+use tower::ServiceExt;
+
+use crate::configuration::{normalize_prefix, HttpSettings};
+
+/// Serve `router` under `prefix`, normalized first so `api`, `/api` and
+/// `/api/` nest the same routes. Settings built by hand skip the
+/// normalization done when they are loaded, and `Router::nest` panics
+/// without a leading slash.
+pub fn nest_under_prefix(prefix: &str, router: Router) -> Router {
+    let prefix = normalize_prefix(prefix);
+
+    // axum refuses to nest a router with a fallback at the root.
+    if prefix.is_empty() {
+        router
+    } else {
+        Router::new().nest(&prefix, router)
+    }
+}
+
+/// How request paths are rewritten before they are routed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathNormalization {
+    /// `/healthz/` is routed as `/healthz`.
+    pub trim_trailing_slash: bool,
+    /// `//api///healthz` is routed as `/api/healthz`.
+    pub collapse_slashes: bool,
+}
+
+impl PathNormalization {
+    pub fn from_settings(settings: &HttpSettings) -> Self {
+        Self {
+            trim_trailing_slash: settings.normalize_trailing_slash,
+            collapse_slashes: settings.collapse_slashes,
+        }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        !self.trim_trailing_slash && !self.collapse_slashes
+    }
+
+    pub fn normalize<'a>(&self, path: &'a str) -> Cow<'a, str> {
+        let mut path = Cow::Borrowed(path);
+
+        if self.collapse_slashes && path.contains("//") {
+            let mut collapsed = String::with_capacity(path.len());
+            for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+                collapsed.push('/');
+                collapsed.push_str(segment);
+            }
+            if path.ends_with('/') {
+                collapsed.push('/');
+            }
+            path = Cow::Owned(collapsed);
+        }
+
+        if self.trim_trailing_slash && path.len() > 1 && path.ends_with('/') {
+            let trimmed = path.trim_end_matches('/');
+            path = Cow::Owned(if trimmed.is_empty() { "/" } else { trimmed }.to_owned());
+        }
+
+        path
+    }
+
+    /// Rewrite the paths in front of `router`.
+    ///
+    /// Layers added with [`Router::layer`] run after the route was matched, so
+    /// they can't change which handler a request reaches.
+    pub fn apply(self, router: Router) -> Router {
+        if self.is_identity() {
+            return router;
+        }
+
+        Router::new().fallback_service(router.map_request(move |mut req: Request| {
+            if let Some(uri) = self.rewrite(req.uri()) {
+                *req.uri_mut() = uri;
+            }
+            req
+        }))
+    }
+
+    fn rewrite(&self, uri: &Uri) -> Option<Uri> {
+        let Cow::Owned(path) = self.normalize(uri.path()) else {
+            return None;
+        };
+        let path_and_query = match uri.query() {
+            Some(query) => format!("{path}?{query}"),
+            None => path,
+        };
+
+        let mut parts = uri.clone().into_parts();
+        parts.path_and_query = Some(PathAndQuery::try_from(path_and_query).ok()?);
+        Uri::from_parts(parts).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, extract::RawQuery, http::StatusCode, routing::get};
+
+    use super::*;
+
+    fn normalization(trim_trailing_slash: bool, collapse_slashes: bool) -> PathNormalization {
+        PathNormalization {
+            trim_trailing_slash,
+            collapse_slashes,
+        }
+    }
+
+    async fn status(normalization: PathNormalization, uri: &str) -> StatusCode {
+        let router = Router::new().route(
+            "/api/healthz",
+            get(|RawQuery(query): RawQuery| async move { query.unwrap_or_default() }),
+        );
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+        normalization
+            .apply(router)
+            .oneshot(request)
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn prefixes_nest_the_same_routes_with_or_without_slashes() {
+        for prefix in ["api", "/api", "/api/", " api/ "] {
+            let router = Router::new().route("/healthz", get(|| async { "200" }));
+            let router = nest_under_prefix(prefix, router);
+            let request = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+            let found = router.clone().oneshot(request("/api/healthz")).await;
+            assert_eq!(found.unwrap().status(), StatusCode::OK, "{:?}", prefix);
+            let missing = router.oneshot(request("/healthz")).await;
+            assert_eq!(
+                missing.unwrap().status(),
+                StatusCode::NOT_FOUND,
+                "{:?}",
+                prefix
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn empty_prefixes_keep_the_routes_at_the_root() {
+        for prefix in ["", "/"] {
+            let router = Router::new().route("/healthz", get(|| async { "200" }));
+            let request = Request::builder()
+                .uri("/healthz")
+                .body(Body::empty())
+                .unwrap();
+
+            let response = nest_under_prefix(prefix, router).oneshot(request).await;
+
+            assert_eq!(response.unwrap().status(), StatusCode::OK, "{:?}", prefix);
+        }
+    }
+
+    #[test]
+    fn normalizes_paths() {
+        let both = normalization(true, true);
+
+        assert_eq!(both.normalize("/api/healthz"), "/api/healthz");
+        assert_eq!(both.normalize("/api/healthz/"), "/api/healthz");
+        assert_eq!(both.normalize("//api///healthz//"), "/api/healthz");
+        assert_eq!(both.normalize("/"), "/");
+        assert_eq!(both.normalize("//"), "/");
+        assert_eq!(normalization(true, false).normalize("//api/"), "//api");
+        assert_eq!(normalization(false, true).normalize("//api//"), "/api/");
+    }
+
+    #[tokio::test]
+    async fn trailing_slash_reaches_the_handler() {
+        assert_eq!(
+            status(normalization(true, false), "/api/healthz/?verbose=1").await,
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn exact_paths_are_required_when_normalization_is_off() {
+        let off = normalization(false, false);
+
+        assert_eq!(status(off, "/api/healthz").await, StatusCode::OK);
+        assert_eq!(status(off, "/api/healthz/").await, StatusCode::NOT_FOUND);
+        assert_eq!(status(off, "//api/healthz").await, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn duplicate_slashes_are_collapsed_when_enabled() {
+        assert_eq!(
+            status(normalization(false, true), "//api///healthz").await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(normalization(true, false), "//api///healthz").await,
+            StatusCode::NOT_FOUND
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use axum::http::{HeaderMap, HeaderName, Request, Response, StatusCode};
+use tower_http::trace::{OnRequest, OnResponse};
+use tracing::Span;
+
+use crate::domain::audit::REDACTED;
+
+/// Headers whose values never reach the logs, matched case insensitively.
+#[derive(Debug, Clone, Default)]
+pub struct RedactHeaders(Arc<[String]>);
+
+impl RedactHeaders {
+    pub fn new(names: impl IntoIterator<Item = String>) -> Self {
+        Self(
+            names
+                .into_iter()
+                .map(|name| name.to_ascii_lowercase())
+                .collect(),
+        )
+    }
+
+    pub fn contains(&self, name: &HeaderName) -> bool {
+        // Header names are always lowercase.
+        self.0.iter().any(|redacted| redacted == name.as_str())
+    }
+
+    /// Debug formats `headers` like a [`HeaderMap`], masking the redacted values.
+    pub fn headers<'a>(&'a self, headers: &'a HeaderMap) -> RedactedHeaders<'a> {
+        RedactedHeaders {
+            redact: self,
+            headers,
+        }
+    }
+}
+
+pub struct RedactedHeaders<'a> {
+    redact: &'a RedactHeaders,
+    headers: &'a HeaderMap,
+}
+
+impl fmt::Debug for RedactedHeaders<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut map = f.debug_map();
+        for (name, value) in self.headers {
+            if self.redact.contains(name) {
+                map.entry(name, &REDACTED);
+            } else {
+                map.entry(name, value);
+            }
+        }
+        map.finish()
+    }
+}
+
+/// Logs the start of a request with its headers, at info level.
+#[derive(Debug, Clone)]
+pub struct LogRequest(pub RedactHeaders);
+
+impl<B> OnRequest<B> for LogRequest {
+    fn on_request(&mut self, request: &Request<B>, _: &Span) {
+        tracing::info!(
+            request_headers = ?self.0.headers(request.headers()),
+            "started processing request"
+        );
+    }
+}
+
+/// Picks one in `rate` successful responses to log, every other one is.
+#[derive(Debug, Clone)]
+pub struct AccessLogSampler {
+    rate: u64,
+    successes: Arc<AtomicU64>,
+}
+
+impl AccessLogSampler {
+    /// Log one in `rate` successful responses, all of them when 0 or 1.
+    pub fn new(rate: u32) -> Self {
+        Self {
+            rate: u64::from(rate.max(1)),
+            successes: Arc::default(),
+        }
+    }
+
+    pub fn should_log(&self, status: StatusCode) -> bool {
+        if !status.is_success() {
+            return true;
+        }
+
+        self.successes
+            .fetch_add(1, Ordering::Relaxed)
+            .is_multiple_of(self.rate)
+    }
+}
+
+impl Default for AccessLogSampler {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+/// Logs the end of a request with the response headers, at info level.
+#[derive(Debug, Clone)]
+pub struct LogResponse {
+    redact: RedactHeaders,
+    sampler: AccessLogSampler,
+}
+
+impl LogResponse {
+    pub fn new(redact: RedactHeaders) -> Self {
+        Self {
+            redact,
+            sampler: AccessLogSampler::default(),
+        }
+    }
+
+    pub fn with_sampler(mut self, sampler: AccessLogSampler) -> Self {
+        self.sampler = sampler;
+        self
+    }
+}
+
+impl<B> OnResponse<B> for LogResponse {
+    fn on_response(self, response: &Response<B>, latency: Duration, _: &Span) {
+        if !self.sampler.should_log(response.status()) {
+            return;
+        }
+
+        tracing::info!(
+            latency = %format_args!("{} μs", latency.as_micros()),
+            status = response.status().as_u16(),
+            response_headers = ?self.redact.headers(response.headers()),
+            "finished processing request"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{self, Write},
+        sync::Mutex,
+    };
+
+    use axum::{body::Body, http::header, routing::get, Router};
+    use tower::ServiceExt;
+    use tower_http::trace::TraceLayer;
+    use tracing_bunyan_formatter::{BunyanFormattingLayer, JsonStorageLayer};
+    use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn matches_names_case_insensitively() {
+        let redact = RedactHeaders::new(["Authorization".to_owned()]);
+
+        assert!(redact.contains(&header::AUTHORIZATION));
+        assert!(!redact.contains(&header::ACCEPT));
+    }
+
+    #[tokio::test]
+    async fn redacted_headers_are_masked_in_logs() {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber =
+            Registry::default()
+                .with(JsonStorageLayer)
+                .with(BunyanFormattingLayer::new("test".into(), move || {
+                    writer.clone()
+                }));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let redact = RedactHeaders::new(["authorization".to_owned(), "set-cookie".to_owned()]);
+        let router = Router::new()
+            .route(
+                "/login",
+                get(|| async { ([(header::SET_COOKIE, "session=s3cr3t")], "ok") }),
+            )
+            .layer(
+                TraceLayer::new_for_http()
+                    .on_request(LogRequest(redact.clone()))
+                    .on_response(LogResponse::new(redact)),
+            );
+        let request = Request::builder()
+            .uri("/login")
+            .header(header::AUTHORIZATION, "Bearer t0k3n")
+            .header(header::ACCEPT, "application/json")
+            .body(Body::empty())
+            .unwrap();
+        router.oneshot(request).await.unwrap();
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        assert!(!output.contains("t0k3n"));
+        assert!(!output.contains("s3cr3t"));
+        assert!(output.contains(r#"\"authorization\": \"[REDACTED]\""#));
+        assert!(output.contains(r#"\"set-cookie\": \"[REDACTED]\""#));
+        assert!(output.contains(r#"\"accept\": \"application/json\""#));
+    }
+
+    #[tokio::test]
+    async fn successes_are_sampled_and_errors_always_logged() {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber =
+            Registry::default()
+                .with(JsonStorageLayer)
+                .with(BunyanFormattingLayer::new("test".into(), move || {
+                    writer.clone()
+                }));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let router = Router::new()
+            .route("/healthz", get(|| async { "200" }))
+            .route("/broken", get(|| async { StatusCode::BAD_GATEWAY }))
+            .layer(TraceLayer::new_for_http().on_response(
+                LogResponse::new(RedactHeaders::default()).with_sampler(AccessLogSampler::new(4)),
+            ));
+        let request = |uri| Request::builder().uri(uri).body(Body::empty()).unwrap();
+        for _ in 0..20 {
+            router.clone().oneshot(request("/healthz")).await.unwrap();
+        }
+        for _ in 0..3 {
+            router.clone().oneshot(request("/broken")).await.unwrap();
+        }
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        let logged = |status: u16| {
+            output
+                .lines()
+                .filter(|line| line.contains("finished processing request"))
+                .filter(|line| line.contains(&format!("\"status\":{}", status)))
+                .count()
+        };
+        assert_eq!(logged(200), 5);
+        assert_eq!(logged(502), 3);
+    }
+}
@@ -0,0 +1,149 @@
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum::{extract::Request, response::Response};
+use futures::future::BoxFuture;
+use tower::{Layer, Service, ServiceExt};
+
+/// Apply `layer` to every request but the ones whose path is in `paths`.
+///
+/// Used to keep probes and scrapes out of the trace backend.
+#[derive(Clone)]
+pub struct ExcludePathsLayer<L> {
+    layer: L,
+    paths: Arc<[String]>,
+}
+
+impl<L> ExcludePathsLayer<L> {
+    pub fn new(layer: L, paths: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            layer,
+            paths: paths.into_iter().collect(),
+        }
+    }
+}
+
+impl<S, L> Layer<S> for ExcludePathsLayer<L>
+where
+    S: Clone,
+    L: Layer<S>,
+{
+    type Service = ExcludePaths<S, L::Service>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ExcludePaths {
+            plain: inner.clone(),
+            layered: self.layer.layer(inner),
+            paths: self.paths.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ExcludePaths<S, T> {
+    plain: S,
+    layered: T,
+    paths: Arc<[String]>,
+}
+
+impl<S, T> Service<Request> for ExcludePaths<S, T>
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+    S::Future: Send,
+    T: Service<Request, Response = Response, Error = S::Error> + Clone + Send + 'static,
+    T::Future: Send,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Response, S::Error>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // Both services are driven to readiness by `oneshot` on a clone.
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let excluded = self.paths.iter().any(|path| path == req.uri().path());
+
+        if excluded {
+            Box::pin(self.plain.clone().oneshot(req))
+        } else {
+            Box::pin(self.layered.clone().oneshot(req))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, http::StatusCode, routing::get, Router};
+    use axum_tracing_opentelemetry::middleware::OtelAxumLayer;
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_sdk::{testing::trace::InMemorySpanExporter, trace::TracerProvider};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use crate::drivers::http::{handlers::health, middlewares::path::nest_under_prefix};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn excluded_paths_produce_no_exported_span() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = TracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let router = Router::new()
+            .route("/healthz", get(|| async { "200" }))
+            .route("/standups", get(|| async { "[]" }))
+            .layer(ExcludePathsLayer::new(
+                OtelAxumLayer::default(),
+                ["/healthz".to_owned()],
+            ));
+
+        let request = |uri| Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+        router.clone().oneshot(request("/healthz")).await.unwrap();
+        assert!(exporter.get_finished_spans().unwrap().is_empty());
+
+        router.oneshot(request("/standups")).await.unwrap();
+        assert_eq!(exporter.get_finished_spans().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn excluded_paths_are_relative_to_the_prefix() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = TracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let router = Router::new()
+            .route("/standups", get(|| async { "[]" }))
+            .merge(health::router(Vec::new()))
+            .layer(ExcludePathsLayer::new(
+                OtelAxumLayer::default(),
+                ["/healthz".to_owned(), "/readyz".to_owned()],
+            ));
+        let router = nest_under_prefix("/api", router);
+
+        let request = |uri| Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+        let response = router
+            .clone()
+            .oneshot(request("/api/readyz"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(exporter.get_finished_spans().unwrap().is_empty());
+
+        router.oneshot(request("/api/standups")).await.unwrap();
+        assert_eq!(exporter.get_finished_spans().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,223 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Router,
+};
+use tower_http::timeout::{RequestBodyTimeoutLayer, ResponseBodyTimeoutLayer};
+
+use crate::configuration::{normalize_prefix, HttpSettings};
+
+/// How long each route may take to respond, `http.timeout` unless the route
+/// pattern has an override in `http.route_timeouts`.
+///
+/// Replaces a global `TimeoutLayer`, which would cut the overridden routes
+/// short whatever their own timeout.
+#[derive(Debug, Clone)]
+pub struct RouteTimeouts {
+    default: Duration,
+    /// Keyed by the full route pattern, prefix included.
+    overrides: HashMap<String, Duration>,
+}
+
+impl RouteTimeouts {
+    pub fn new(default: Duration, overrides: HashMap<String, Duration>) -> Self {
+        Self { default, overrides }
+    }
+
+    pub fn from_settings(settings: &HttpSettings) -> Self {
+        let prefix = normalize_prefix(&settings.prefix);
+        let overrides = settings
+            .route_timeouts
+            .iter()
+            .map(|(pattern, secs)| (format!("{}{}", prefix, pattern), Duration::from_secs(*secs)))
+            .collect();
+
+        Self::new(Duration::from_secs(settings.timeout), overrides)
+    }
+
+    /// The timeout of the route matching `pattern`, `None` for the fallback.
+    pub fn get(&self, pattern: Option<&str>) -> Duration {
+        pattern
+            .and_then(|pattern| self.overrides.get(pattern))
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
+
+/// Bound how long the request and response bodies may wait between two
+/// chunks, `response` only when set.
+///
+/// These are idle timeouts, a Server-Sent Events stream sending its
+/// heartbeats lives on past `request` for as long as it is read.
+pub fn with_body_timeouts(router: Router, request: Duration, response: Option<Duration>) -> Router {
+    let router = router.layer(RequestBodyTimeoutLayer::new(request));
+
+    match response {
+        Some(response) => router.layer(ResponseBodyTimeoutLayer::new(response)),
+        None => router,
+    }
+}
+
+/// Answer `408 Request Timeout` once the route timeout has elapsed.
+pub async fn route_timeout(
+    State(timeouts): State<Arc<RouteTimeouts>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let pattern = req.extensions().get::<MatchedPath>().cloned();
+    let duration = timeouts.get(pattern.as_ref().map(MatchedPath::as_str));
+
+    match tokio::time::timeout(duration, next.run(req)).await {
+        Ok(response) => response,
+        Err(_) => {
+            tracing::warn!(?duration, "request timed out");
+            StatusCode::REQUEST_TIMEOUT.into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use axum::{
+        body::{to_bytes, Body},
+        middleware,
+        response::sse::{Event, Sse},
+        routing::{get, post},
+    };
+    use futures::{stream, Stream};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    async fn slow() -> &'static str {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        "done"
+    }
+
+    async fn status(uri: &str) -> StatusCode {
+        let timeouts = RouteTimeouts::new(
+            Duration::from_millis(50),
+            HashMap::from([(
+                "/sprints/:id/report.html".to_owned(),
+                Duration::from_secs(5),
+            )]),
+        );
+        let router = Router::new()
+            .route("/sprints/:id/report.html", get(slow))
+            .route("/sprints/:id/burndown", get(slow))
+            .layer(middleware::from_fn_with_state(
+                Arc::new(timeouts),
+                route_timeout,
+            ));
+
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        router.oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn overridden_route_tolerates_a_longer_handler() {
+        assert_eq!(status("/sprints/1/report.html").await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn other_routes_use_the_global_timeout() {
+        assert_eq!(
+            status("/sprints/1/burndown").await,
+            StatusCode::REQUEST_TIMEOUT
+        );
+    }
+
+    fn ticks(every: Duration, count: usize) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+        let events = stream::unfold(0, move |sent| async move {
+            if sent == count {
+                return None;
+            }
+            tokio::time::sleep(every).await;
+            Some((Ok(Event::default().data("tick")), sent + 1))
+        });
+
+        Sse::new(events)
+    }
+
+    fn streaming_router() -> Router {
+        let router = Router::new()
+            .route(
+                "/standups/stream",
+                get(|| async { ticks(Duration::from_millis(30), 8) }),
+            )
+            .route(
+                "/stalled",
+                get(|| async { ticks(Duration::from_millis(300), 1) }),
+            )
+            .route("/standups", post(|body: String| async move { body }));
+
+        with_body_timeouts(
+            router,
+            Duration::from_millis(50),
+            Some(Duration::from_millis(100)),
+        )
+    }
+
+    #[tokio::test]
+    async fn event_stream_outlives_the_request_timeout() {
+        let request = Request::builder()
+            .uri("/standups/stream")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = streaming_router().oneshot(request).await.unwrap();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert_eq!(body.matches("data: tick").count(), 8);
+    }
+
+    #[tokio::test]
+    async fn idle_response_body_is_cut() {
+        let request = Request::builder()
+            .uri("/stalled")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = streaming_router().oneshot(request).await.unwrap();
+
+        assert!(to_bytes(response.into_body(), usize::MAX).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn slow_request_body_times_out() {
+        let chunks = stream::once(async {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            Ok::<_, Infallible>("yesterday: reviews")
+        });
+        let request = Request::builder()
+            .method("POST")
+            .uri("/standups")
+            .body(Body::from_stream(chunks))
+            .unwrap();
+
+        let response = streaming_router().oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn overrides_are_prefixed() {
+        let mut settings = crate::configuration::test_settings().http;
+        settings.prefix = "/api".into();
+        settings.timeout = 10;
+        settings.route_timeouts = HashMap::from([("/export".to_owned(), 120)]);
+
+        let timeouts = RouteTimeouts::from_settings(&settings);
+
+        assert_eq!(timeouts.get(Some("/api/export")), Duration::from_secs(120));
+        assert_eq!(timeouts.get(Some("/export")), Duration::from_secs(10));
+        assert_eq!(timeouts.get(None), Duration::from_secs(10));
+    }
+}
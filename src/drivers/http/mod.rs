@@ -1,2 +1,9 @@
+pub mod error;
+pub mod extract;
+pub mod handlers;
+pub mod html;
+pub mod listener;
 pub mod middlewares;
-
+pub mod server;
+pub mod state;
+pub mod tls;
@@ -0,0 +1,416 @@
+use std::{future::Future, net::SocketAddr, sync::Arc, time::Duration};
+
+use anyhow::Result;
+use axum::{
+    async_trait,
+    body::Body,
+    extract::{ConnectInfo, FromRequestParts, Request},
+    http::request::Parts,
+    Router,
+};
+use hyper::body::Incoming;
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo},
+    server::conn::auto::Builder,
+    service::TowerToHyperService,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::TcpListener,
+    sync::watch,
+};
+use tower::ServiceExt;
+
+use crate::{configuration::HttpSettings, drivers::http::tls};
+
+/// How long a client has to finish the TLS handshake.
+pub const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The first and the longest wait after a failed accept, e.g. when the
+/// process is out of file descriptors.
+const ACCEPT_BACKOFF_MIN: Duration = Duration::from_millis(5);
+const ACCEPT_BACKOFF_MAX: Duration = Duration::from_secs(1);
+
+/// Serve `app` until `shutdown` resolves, then wait up to
+/// [`HttpSettings::shutdown_grace_secs`] for in flight connections.
+///
+/// Unlike `axum::serve`, the protocols are chosen by [`HttpSettings::http2`]
+/// and TLS is terminated when [`HttpSettings::tls`] is set.
+/// Connection upgrades are not supported, nothing in the API needs them.
+/// The peer address is available to handlers as [`ConnectInfo<SocketAddr>`],
+/// and the start of the shutdown as [`Draining`].
+pub async fn serve<F>(
+    listener: TcpListener,
+    app: Router,
+    settings: &HttpSettings,
+    shutdown: F,
+) -> Result<()>
+where
+    F: Future<Output = ()> + Send,
+{
+    let acceptor = settings
+        .tls
+        .as_ref()
+        .map(|tls| tls::acceptor(tls, settings.http2))
+        .transpose()?;
+
+    let mut builder = Builder::new(TokioExecutor::new());
+    if !settings.http2 {
+        builder = builder.http1_only();
+    }
+
+    // Every connection being served holds a receiver until it is closed.
+    let draining = Arc::new(watch::channel(false).0);
+    let mut backoff = ACCEPT_BACKOFF_MIN;
+    tokio::pin!(shutdown);
+
+    loop {
+        let accepted = tokio::select! {
+            accepted = listener.accept() => accepted,
+            _ = &mut shutdown => break,
+        };
+        let (stream, remote) = match accepted {
+            Ok(accepted) => {
+                backoff = ACCEPT_BACKOFF_MIN;
+                accepted
+            }
+            Err(error) => {
+                tracing::warn!(error = ?error, ?backoff, "failed to accept connection");
+                tokio::select! {
+                    _ = tokio::time::sleep(backoff) => {}
+                    _ = &mut shutdown => break,
+                }
+                backoff = (backoff * 2).min(ACCEPT_BACKOFF_MAX);
+                continue;
+            }
+        };
+
+        if let Err(error) = stream.set_nodelay(settings.nodelay) {
+            tracing::warn!(error = ?error, "failed to set TCP_NODELAY");
+        }
+
+        let builder = builder.clone();
+        let acceptor = acceptor.clone();
+        let app = app.clone();
+
+        let Some(acceptor) = acceptor else {
+            let drained = draining.subscribe();
+            tokio::spawn(async move {
+                serve_connection(&builder, stream, remote, app, drained).await;
+            });
+            continue;
+        };
+
+        // A pending handshake doesn't hold the shutdown back.
+        let draining = draining.clone();
+        tokio::spawn(async move {
+            let handshake = tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(stream));
+            let stream = match handshake.await {
+                Ok(Ok(stream)) => stream,
+                Ok(Err(error)) => {
+                    tracing::debug!(error = ?error, %remote, "TLS handshake failed");
+                    return;
+                }
+                Err(_) => {
+                    tracing::debug!(%remote, "TLS handshake timed out");
+                    return;
+                }
+            };
+
+            let drained = draining.subscribe();
+            if *drained.borrow() {
+                return;
+            }
+            serve_connection(&builder, stream, remote, app, drained).await;
+        });
+    }
+
+    draining.send_replace(true);
+    let grace = Duration::from_secs(settings.shutdown_grace_secs);
+    if tokio::time::timeout(grace, draining.closed())
+        .await
+        .is_err()
+    {
+        tracing::warn!(
+            ?grace,
+            "connections still open after the grace period, dropping them"
+        );
+    }
+
+    Ok(())
+}
+
+/// Resolves once [`serve`] stops accepting connections, so that endless
+/// responses such as event streams can end.
+///
+/// Never resolves for a request that didn't come through [`serve`].
+#[derive(Clone)]
+pub struct Draining(Option<watch::Receiver<bool>>);
+
+impl Draining {
+    pub async fn wait(self) {
+        match self.0 {
+            // A dropped sender means the server is gone as well.
+            Some(mut drained) => {
+                let _ = drained.wait_for(|drained| *drained).await;
+            }
+            None => std::future::pending().await,
+        }
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Draining
+where
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .extensions
+            .get::<Draining>()
+            .cloned()
+            .unwrap_or(Draining(None)))
+    }
+}
+
+async fn serve_connection<I>(
+    builder: &Builder<TokioExecutor>,
+    io: I,
+    remote: SocketAddr,
+    app: Router,
+    mut drained: watch::Receiver<bool>,
+) where
+    I: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let draining = Draining(Some(drained.clone()));
+    let service = app.map_request(move |req: Request<Incoming>| {
+        let mut req = req.map(Body::new);
+        req.extensions_mut().insert(ConnectInfo(remote));
+        req.extensions_mut().insert(draining.clone());
+        req
+    });
+    let connection = builder.serve_connection(TokioIo::new(io), TowerToHyperService::new(service));
+    tokio::pin!(connection);
+
+    let result = tokio::select! {
+        result = connection.as_mut() => result,
+        _ = drained.changed() => {
+            connection.as_mut().graceful_shutdown();
+            connection.await
+        }
+    };
+
+    if let Err(error) = result {
+        tracing::debug!(error = ?error, "connection closed with error");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use axum::{http::StatusCode, routing::get};
+    use tokio::{
+        net::TcpStream,
+        sync::{oneshot, Notify},
+        task::JoinHandle,
+    };
+    use tokio_rustls::{
+        rustls::{Certificate, ClientConfig, RootCertStore, ServerName},
+        TlsConnector,
+    };
+
+    use crate::configuration::TlsSettings;
+
+    use super::*;
+
+    const CERT_PATH: &str = "tests/fixtures/tls/cert.pem";
+    const KEY_PATH: &str = "tests/fixtures/tls/key.pem";
+
+    fn settings(http2: bool, tls: bool) -> HttpSettings {
+        HttpSettings {
+            port: 0,
+            host: "127.0.0.1".into(),
+            prefix: "".into(),
+            timeout: 10,
+            response_timeout: None,
+            route_timeouts: HashMap::new(),
+            api_keys: vec![],
+            nodelay: true,
+            keepalive: true,
+            backlog: 128,
+            compression_min_size: 1024,
+            normalize_trailing_slash: true,
+            collapse_slashes: false,
+            redact_headers: vec![],
+            access_log_sample_rate: 1,
+            sse_heartbeat_secs: 15,
+            http2,
+            shutdown_grace_secs: 10,
+            tls: tls.then(|| TlsSettings {
+                cert_path: CERT_PATH.into(),
+                key_path: KEY_PATH.into(),
+            }),
+        }
+    }
+
+    struct TestServer {
+        address: std::net::SocketAddr,
+        stop: oneshot::Sender<()>,
+        handle: JoinHandle<Result<()>>,
+    }
+
+    impl TestServer {
+        async fn start(settings: HttpSettings) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let address = listener.local_addr().unwrap();
+            let app = Router::new().route("/healthz", get(|| async { "200" }));
+            let (stop, stopped) = oneshot::channel::<()>();
+            let handle = tokio::spawn(async move {
+                serve(listener, app, &settings, async {
+                    let _ = stopped.await;
+                })
+                .await
+            });
+
+            Self {
+                address,
+                stop,
+                handle,
+            }
+        }
+
+        fn healthz(&self) -> Request<Body> {
+            Request::builder()
+                .uri(format!("http://{}/healthz", self.address))
+                .body(Body::empty())
+                .unwrap()
+        }
+
+        async fn stop(self) {
+            self.stop.send(()).unwrap();
+            self.handle.await.unwrap().unwrap();
+        }
+    }
+
+    async fn get_healthz_over_h2(http2: bool) -> hyper::Result<StatusCode> {
+        let server = TestServer::start(settings(http2, false)).await;
+
+        let stream = TcpStream::connect(server.address).await.unwrap();
+        let status = async {
+            let (mut sender, connection) =
+                hyper::client::conn::http2::handshake(TokioExecutor::new(), TokioIo::new(stream))
+                    .await?;
+            tokio::spawn(connection);
+
+            Ok(sender.send_request(server.healthz()).await?.status())
+        }
+        .await;
+
+        server.stop().await;
+
+        status
+    }
+
+    #[tokio::test]
+    async fn stuck_connections_are_dropped_after_the_grace_period() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let requested = Arc::new(Notify::new());
+        let started = requested.clone();
+        let app = Router::new().route(
+            "/metrics",
+            get(move || async move {
+                started.notify_one();
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                "slow"
+            }),
+        );
+        let mut settings = settings(false, false);
+        settings.shutdown_grace_secs = 1;
+        let (stop, stopped) = oneshot::channel::<()>();
+        let handle = tokio::spawn(async move {
+            serve(listener, app, &settings, async {
+                let _ = stopped.await;
+            })
+            .await
+        });
+
+        let stream = TcpStream::connect(address).await.unwrap();
+        let (mut sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+            .await
+            .unwrap();
+        tokio::spawn(connection);
+        let request = Request::builder()
+            .uri(format!("http://{}/metrics", address))
+            .body(Body::empty())
+            .unwrap();
+        tokio::spawn(async move { sender.send_request(request).await });
+        requested.notified().await;
+
+        let stopping = tokio::time::Instant::now();
+        stop.send(()).unwrap();
+        tokio::time::timeout(Duration::from_secs(5), handle)
+            .await
+            .expect("the server exits after the grace period")
+            .unwrap()
+            .unwrap();
+        assert!(stopping.elapsed() >= Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn h2_client_reaches_healthz_when_enabled() {
+        assert_eq!(get_healthz_over_h2(true).await.unwrap(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn h2_client_is_rejected_by_default() {
+        assert!(get_healthz_over_h2(false).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn https_client_reaches_healthz_with_self_signed_cert() {
+        let server = TestServer::start(settings(false, true)).await;
+
+        let pem = std::fs::read(CERT_PATH).unwrap();
+        let mut roots = RootCertStore::empty();
+        for cert in rustls_pemfile::certs(&mut pem.as_slice()).unwrap() {
+            roots.add(&Certificate(cert)).unwrap();
+        }
+        let config = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+
+        let stream = TcpStream::connect(server.address).await.unwrap();
+        let stream = TlsConnector::from(Arc::new(config))
+            .connect(ServerName::try_from("localhost").unwrap(), stream)
+            .await
+            .unwrap();
+        let (mut sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+            .await
+            .unwrap();
+        tokio::spawn(connection);
+
+        let response = sender.send_request(server.healthz()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        drop(sender);
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    async fn pending_tls_handshake_does_not_hold_the_shutdown() {
+        let server = TestServer::start(settings(false, true)).await;
+
+        let _silent = TcpStream::connect(server.address).await.unwrap();
+        // Let the server accept it and start the handshake.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        tokio::time::timeout(Duration::from_secs(5), server.stop())
+            .await
+            .expect("the server stops without waiting for the handshake");
+    }
+}
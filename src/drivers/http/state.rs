@@ -0,0 +1,268 @@
+use std::sync::Arc;
+
+use axum::extract::FromRef;
+use mongodb::Database;
+
+use crate::{
+    configuration::Settings,
+    domain::{
+        audit::AuditRepository,
+        burndown::GoalCompletionRepository,
+        delivery::FailedMessageRepository,
+        guild::GuildConfigRepository,
+        reminder::ReminderRepository,
+        retro::{ActionItemRepository, RetroRepository},
+        snooze::SnoozeRepository,
+        sprint::SprintRepository,
+        standup::StandupRepository,
+    },
+    drivers::{
+        database::{
+            audit::MongoAuditRepository,
+            breaker::CircuitBreaker,
+            failed_message::MongoFailedMessageRepository,
+            goal_completion::MongoGoalCompletionRepository,
+            guarded::Guarded,
+            guild::MongoGuildConfigRepository,
+            reminder::MongoReminderRepository,
+            retro::{MongoActionItemRepository, MongoRetroRepository},
+            snooze::MongoSnoozeRepository,
+            sprint::MongoSprintRepository,
+            standup::MongoStandupRepository,
+            WriteRetry,
+        },
+        http::handlers::{report::ReportState, retro::RetroState, sprint::SprintState},
+    },
+    observability::metrics::{HttpMetrics, Metrics},
+};
+
+/// Every repository the handlers read from.
+#[derive(Clone)]
+pub struct Repositories {
+    pub audit: Arc<dyn AuditRepository>,
+    pub snoozes: Arc<dyn SnoozeRepository>,
+    pub reminders: Arc<dyn ReminderRepository>,
+    /// The dead-letter log of the messages Discord refused.
+    pub failed_messages: Arc<dyn FailedMessageRepository>,
+    pub standups: Arc<dyn StandupRepository>,
+    pub sprints: Arc<dyn SprintRepository>,
+    pub completions: Arc<dyn GoalCompletionRepository>,
+    pub guilds: Arc<dyn GuildConfigRepository>,
+    pub retros: Arc<dyn RetroRepository>,
+    pub actions: Arc<dyn ActionItemRepository>,
+}
+
+impl Repositories {
+    /// The MongoDB repositories of `database`, all behind the same `breaker`
+    /// and retrying their writes following `retry`.
+    pub fn guarded(database: &Database, breaker: CircuitBreaker, retry: WriteRetry) -> Self {
+        Self {
+            audit: Arc::new(Guarded::new(
+                MongoAuditRepository::new(database).with_retry(retry.clone()),
+                breaker.clone(),
+            )),
+            snoozes: Arc::new(Guarded::new(
+                MongoSnoozeRepository::new(database).with_retry(retry.clone()),
+                breaker.clone(),
+            )),
+            reminders: Arc::new(Guarded::new(
+                MongoReminderRepository::new(database).with_retry(retry.clone()),
+                breaker.clone(),
+            )),
+            failed_messages: Arc::new(Guarded::new(
+                MongoFailedMessageRepository::new(database).with_retry(retry.clone()),
+                breaker.clone(),
+            )),
+            standups: Arc::new(Guarded::new(
+                MongoStandupRepository::new(database).with_retry(retry.clone()),
+                breaker.clone(),
+            )),
+            sprints: Arc::new(Guarded::new(
+                MongoSprintRepository::new(database).with_retry(retry.clone()),
+                breaker.clone(),
+            )),
+            completions: Arc::new(Guarded::new(
+                MongoGoalCompletionRepository::new(database).with_retry(retry.clone()),
+                breaker.clone(),
+            )),
+            guilds: Arc::new(Guarded::new(
+                MongoGuildConfigRepository::new(database).with_retry(retry.clone()),
+                breaker.clone(),
+            )),
+            retros: Arc::new(Guarded::new(
+                MongoRetroRepository::new(database).with_retry(retry.clone()),
+                breaker.clone(),
+            )),
+            actions: Arc::new(Guarded::new(
+                MongoActionItemRepository::new(database).with_retry(retry.clone()),
+                breaker,
+            )),
+        }
+    }
+}
+
+/// What the routers of the API share, handlers extract the part they need
+/// through [`FromRef`].
+#[derive(Clone)]
+pub struct AppState {
+    pub settings: Arc<Settings>,
+    pub metrics: Arc<Metrics>,
+    pub db: Database,
+    pub repositories: Repositories,
+}
+
+impl FromRef<AppState> for Arc<Settings> {
+    fn from_ref(state: &AppState) -> Self {
+        state.settings.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<Metrics> {
+    fn from_ref(state: &AppState) -> Self {
+        state.metrics.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<HttpMetrics> {
+    fn from_ref(state: &AppState) -> Self {
+        state.metrics.http.clone()
+    }
+}
+
+impl FromRef<AppState> for Database {
+    fn from_ref(state: &AppState) -> Self {
+        state.db.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<dyn AuditRepository> {
+    fn from_ref(state: &AppState) -> Self {
+        state.repositories.audit.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<dyn SnoozeRepository> {
+    fn from_ref(state: &AppState) -> Self {
+        state.repositories.snoozes.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<dyn StandupRepository> {
+    fn from_ref(state: &AppState) -> Self {
+        state.repositories.standups.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<dyn GuildConfigRepository> {
+    fn from_ref(state: &AppState) -> Self {
+        state.repositories.guilds.clone()
+    }
+}
+
+impl FromRef<AppState> for SprintState {
+    fn from_ref(state: &AppState) -> Self {
+        let repositories = &state.repositories;
+
+        Self {
+            sprints: repositories.sprints.clone(),
+            completions: repositories.completions.clone(),
+            standups: repositories.standups.clone(),
+            guilds: repositories.guilds.clone(),
+            default_timezone: state.settings.application.default_tz(),
+            duplicates: state.settings.scrum.duplicate_sprints,
+        }
+    }
+}
+
+impl FromRef<AppState> for RetroState {
+    fn from_ref(state: &AppState) -> Self {
+        let repositories = &state.repositories;
+
+        Self {
+            retros: repositories.retros.clone(),
+            actions: repositories.actions.clone(),
+            guilds: repositories.guilds.clone(),
+            default_timezone: state.settings.application.default_tz(),
+        }
+    }
+}
+
+impl FromRef<AppState> for ReportState {
+    fn from_ref(state: &AppState) -> Self {
+        Self {
+            standups: state.repositories.standups.clone(),
+            guilds: state.repositories.guilds.clone(),
+            stopwords: state.settings.scrum.blocker_stopwords.clone().into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono_tz::Tz;
+
+    use crate::{
+        configuration::test_settings,
+        domain::{guild::GuildConfig, id::GuildId},
+        drivers::database::memory::{
+            InMemoryActionItemRepository, InMemoryAuditRepository, InMemoryFailedMessageRepository,
+            InMemoryGoalCompletionRepository, InMemoryGuildConfigRepository,
+            InMemoryReminderRepository, InMemoryRetroRepository, InMemorySnoozeRepository,
+            InMemorySprintRepository, InMemoryStandupRepository,
+        },
+        observability::metrics::init_metrics,
+    };
+
+    use super::*;
+
+    async fn app_state() -> AppState {
+        let mut settings = test_settings();
+        settings.application.default_timezone = "America/Recife".into();
+        let (metrics, _) = init_metrics(&settings);
+        // The client connects lazily, nothing is reached here.
+        let client =
+            mongodb::Client::with_options(settings.database.connect_options().unwrap()).unwrap();
+
+        AppState {
+            db: client.database(settings.mongo_database_name().unwrap()),
+            settings: Arc::new(settings),
+            metrics,
+            repositories: Repositories {
+                audit: Arc::new(InMemoryAuditRepository::default()),
+                snoozes: Arc::new(InMemorySnoozeRepository::default()),
+                reminders: Arc::new(InMemoryReminderRepository::default()),
+                failed_messages: Arc::new(InMemoryFailedMessageRepository::default()),
+                standups: Arc::new(InMemoryStandupRepository::default()),
+                sprints: Arc::new(InMemorySprintRepository::default()),
+                completions: Arc::new(InMemoryGoalCompletionRepository::default()),
+                guilds: Arc::new(InMemoryGuildConfigRepository::default()),
+                retros: Arc::new(InMemoryRetroRepository::default()),
+                actions: Arc::new(InMemoryActionItemRepository::default()),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn sub_states_share_the_app_state() {
+        let state = app_state().await;
+        state
+            .repositories
+            .guilds
+            .upsert(&GuildConfig::new(GuildId(1)))
+            .await
+            .unwrap();
+
+        let guilds = Arc::<dyn GuildConfigRepository>::from_ref(&state);
+        assert!(guilds.find(GuildId(1)).await.unwrap().is_some());
+
+        let sprints = SprintState::from_ref(&state);
+        assert!(Arc::ptr_eq(&sprints.guilds, &state.repositories.guilds));
+        assert_eq!(sprints.default_timezone, Tz::America__Recife);
+
+        let retros = RetroState::from_ref(&state);
+        assert!(Arc::ptr_eq(&retros.actions, &state.repositories.actions));
+
+        let http = Arc::<HttpMetrics>::from_ref(&state);
+        assert!(Arc::ptr_eq(&http, &state.metrics.http));
+    }
+}
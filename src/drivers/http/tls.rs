@@ -0,0 +1,59 @@
+use std::{fs::File, io::BufReader, path::Path, sync::Arc};
+
+use anyhow::{Context, Result};
+use rustls_pemfile::Item;
+use tokio_rustls::{
+    rustls::{Certificate, PrivateKey, ServerConfig},
+    TlsAcceptor,
+};
+
+use crate::configuration::TlsSettings;
+
+/// Build the acceptor terminating TLS for the HTTP server.
+///
+/// ALPN only advertises `h2` when HTTP/2 is enabled.
+pub fn acceptor(settings: &TlsSettings, http2: bool) -> Result<TlsAcceptor> {
+    let certs = load_certs(&settings.cert_path)?;
+    let key = load_key(&settings.key_path)?;
+
+    let mut config = ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .context("expected certificate to match private key")?;
+
+    config.alpn_protocols = if http2 {
+        vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+    } else {
+        vec![b"http/1.1".to_vec()]
+    };
+
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+fn open(path: &Path) -> Result<BufReader<File>> {
+    let file = File::open(path).with_context(|| format!("expected to open {:?}", path))?;
+
+    Ok(BufReader::new(file))
+}
+
+fn load_certs(path: &Path) -> Result<Vec<Certificate>> {
+    let certs = rustls_pemfile::certs(&mut open(path)?)
+        .with_context(|| format!("expected PEM certificates in {:?}", path))?;
+    anyhow::ensure!(!certs.is_empty(), "no certificate found in {:?}", path);
+
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+fn load_key(path: &Path) -> Result<PrivateKey> {
+    let items = rustls_pemfile::read_all(&mut open(path)?)
+        .with_context(|| format!("expected PEM private key in {:?}", path))?;
+
+    items
+        .into_iter()
+        .find_map(|item| match item {
+            Item::PKCS8Key(key) | Item::RSAKey(key) | Item::ECKey(key) => Some(PrivateKey(key)),
+            _ => None,
+        })
+        .with_context(|| format!("no private key found in {:?}", path))
+}
@@ -1 +1,4 @@
+pub mod database;
+pub mod discord;
+pub mod grpc;
 pub mod http;
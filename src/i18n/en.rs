@@ -0,0 +1,86 @@
+pub const CATALOG: &[(&str, &str)] = &[
+    (
+        "standup.guild_only",
+        "standups can only be sent from a server",
+    ),
+    (
+        "standup.usage",
+        "usage: /standup <submit|edit> <yesterday> <today> [blockers]",
+    ),
+    (
+        "standup.empty",
+        "Tell what you did yesterday or will do today",
+    ),
+    (
+        "standup.too_short",
+        "{field} needs at least {min} characters",
+    ),
+    ("standup.too_long", "{field} is limited to {max} characters"),
+    ("standup.submitted", "Standup submitted"),
+    ("standup.updated", "Standup updated"),
+    (
+        "standup.already_submitted",
+        "You already submitted today's standup, use /standup edit to correct it",
+    ),
+    ("standup.summary", "{user} on {date}"),
+    ("standup.yesterday", "Yesterday"),
+    ("standup.today", "Today"),
+    ("standup.blockers", "Blockers"),
+    ("standup.history_title", "Your standups"),
+    ("standup.history_range", "{from} to {to}, page {page}"),
+    ("standup.history_more", "More with page {page}"),
+    (
+        "standup.history_empty",
+        "No standups in the last {days} days",
+    ),
+    (
+        "standup.history_usage",
+        "usage: /standup history [days] [page], days up to {max}",
+    ),
+    (
+        "standup.outside_window",
+        "Sent outside the standup window, from {from} to {to}",
+    ),
+    (
+        "standup.window_closed",
+        "Standups are only accepted from {from} to {to}",
+    ),
+    (
+        "standup.nudge",
+        "{mentions} today's standup is still waiting for you",
+    ),
+    (
+        "sprint.guild_only",
+        "sprints can only be managed from a server",
+    ),
+    (
+        "sprint.goal_usage",
+        "usage: /sprint status or /sprint goal <add <text> [points]|done <id>|list>",
+    ),
+    ("sprint.no_active", "No sprint is running today"),
+    ("sprint.goals_title", "Goals of {name}"),
+    (
+        "sprint.goals_summary",
+        "{start} to {end}, {done} of {total} done",
+    ),
+    ("sprint.goals_empty", "No goals yet"),
+    ("sprint.goal_done", "done, {points} points"),
+    ("sprint.goal_open", "open, {points} points"),
+    ("sprint.goal_duplicate", "{title} is already a goal"),
+    ("sprint.goal_unknown", "No goal {id}, see /sprint goal list"),
+    (
+        "sprint.status_no_active",
+        "No sprint is running today, ask an admin to plan the next one",
+    ),
+    ("sprint.status_title", "Status of {name}"),
+    ("sprint.status_dates", "{start} to {end}"),
+    ("sprint.status_days_left", "Days left"),
+    ("sprint.status_goals", "Goals"),
+    ("sprint.status_goals_done", "{done} of {total} done"),
+    ("sprint.status_participation", "Standup today"),
+    (
+        "sprint.status_answered",
+        "{answered} of {roster} answered ({percent}%)",
+    ),
+    ("sprint.status_no_roster", "No roster set"),
+];
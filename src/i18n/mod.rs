@@ -0,0 +1,120 @@
+//! Translations of the bot responses.
+//!
+//! Messages are looked up by key in the catalog of the guild locale and fall
+//! back to English, so a catalog may lag behind when new messages are added.
+
+mod en;
+mod pt;
+
+use crate::domain::template;
+
+type Catalog = &'static [(&'static str, &'static str)];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    En,
+    Pt,
+}
+
+impl Locale {
+    /// Read a language tag like `pt` or `pt-BR`, regions share their language catalog.
+    pub fn parse(tag: &str) -> Option<Self> {
+        let language = tag.split(['-', '_']).next()?;
+
+        match language.to_ascii_lowercase().as_str() {
+            "en" => Some(Self::En),
+            "pt" => Some(Self::Pt),
+            _ => None,
+        }
+    }
+
+    fn catalog(&self) -> Catalog {
+        match self {
+            Self::En => en::CATALOG,
+            Self::Pt => pt::CATALOG,
+        }
+    }
+}
+
+/// The message `key` in `locale`, with its `{name}` placeholders replaced by `args`.
+///
+/// Keys missing from every catalog render as the key itself so a typo shows up
+/// in the reply instead of failing the command.
+pub fn t(locale: Locale, key: &str, args: &[(&str, &str)]) -> String {
+    translate(locale.catalog(), key, args)
+}
+
+fn translate(catalog: Catalog, key: &str, args: &[(&str, &str)]) -> String {
+    let Some(message) = lookup(catalog, key).or_else(|| lookup(en::CATALOG, key)) else {
+        tracing::warn!(key, "missing translation");
+        return key.to_owned();
+    };
+
+    template::render(message, args).unwrap_or_else(|error| {
+        tracing::warn!(key, error = %error, "failed to render translation");
+        message.to_owned()
+    })
+}
+
+fn lookup(catalog: Catalog, key: &str) -> Option<&'static str> {
+    catalog
+        .iter()
+        .find(|(candidate, _)| *candidate == key)
+        .map(|(_, message)| *message)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn parses_language_tags() {
+        assert_eq!(Locale::parse("pt"), Some(Locale::Pt));
+        assert_eq!(Locale::parse("pt-BR"), Some(Locale::Pt));
+        assert_eq!(Locale::parse("EN_us"), Some(Locale::En));
+        assert_eq!(Locale::parse("de"), None);
+        assert_eq!(Locale::parse(""), None);
+    }
+
+    #[test]
+    fn looks_up_the_key_in_the_locale() {
+        assert_eq!(t(Locale::En, "standup.updated", &[]), "Standup updated");
+        assert_eq!(t(Locale::Pt, "standup.updated", &[]), "Standup atualizado");
+    }
+
+    #[test]
+    fn interpolates_arguments() {
+        let args = [("user", "<@3>"), ("date", "2024-10-15")];
+
+        assert_eq!(
+            t(Locale::Pt, "standup.summary", &args),
+            "<@3> em 2024-10-15"
+        );
+        // A missing argument leaves the message as written.
+        assert_eq!(
+            t(Locale::En, "standup.summary", &args[..1]),
+            "{user} on {date}"
+        );
+    }
+
+    #[test]
+    fn falls_back_to_english_then_to_the_key() {
+        let partial: Catalog = &[("standup.today", "Hoje")];
+
+        assert_eq!(translate(partial, "standup.today", &[]), "Hoje");
+        assert_eq!(translate(partial, "standup.yesterday", &[]), "Yesterday");
+        assert_eq!(t(Locale::Pt, "standup.unknown", &[]), "standup.unknown");
+    }
+
+    #[test]
+    fn catalogs_only_translate_english_keys() {
+        let english: HashSet<_> = en::CATALOG.iter().map(|(key, _)| key).collect();
+
+        for (key, _) in pt::CATALOG {
+            assert!(english.contains(key), "{key} is not an english key");
+        }
+    }
+}
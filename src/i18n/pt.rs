@@ -0,0 +1,89 @@
+pub const CATALOG: &[(&str, &str)] = &[
+    (
+        "standup.guild_only",
+        "standups só podem ser enviados de um servidor",
+    ),
+    (
+        "standup.usage",
+        "uso: /standup <submit|edit> <yesterday> <today> [blockers]",
+    ),
+    (
+        "standup.empty",
+        "Conte o que você fez ontem ou vai fazer hoje",
+    ),
+    (
+        "standup.too_short",
+        "{field} precisa de pelo menos {min} caracteres",
+    ),
+    ("standup.too_long", "{field} é limitado a {max} caracteres"),
+    ("standup.submitted", "Standup enviado"),
+    ("standup.updated", "Standup atualizado"),
+    (
+        "standup.already_submitted",
+        "Você já enviou o standup de hoje, use /standup edit para corrigi-lo",
+    ),
+    ("standup.summary", "{user} em {date}"),
+    ("standup.yesterday", "Ontem"),
+    ("standup.today", "Hoje"),
+    ("standup.blockers", "Impedimentos"),
+    ("standup.history_title", "Seus standups"),
+    ("standup.history_range", "{from} a {to}, página {page}"),
+    ("standup.history_more", "Mais na página {page}"),
+    (
+        "standup.history_empty",
+        "Nenhum standup nos últimos {days} dias",
+    ),
+    (
+        "standup.history_usage",
+        "uso: /standup history [days] [page], até {max} dias",
+    ),
+    (
+        "standup.outside_window",
+        "Enviado fora da janela do standup, das {from} às {to}",
+    ),
+    (
+        "standup.window_closed",
+        "Standups só são aceitos das {from} às {to}",
+    ),
+    (
+        "standup.nudge",
+        "{mentions} a standup de hoje ainda está esperando por vocês",
+    ),
+    (
+        "sprint.guild_only",
+        "sprints só podem ser gerenciadas em um servidor",
+    ),
+    (
+        "sprint.goal_usage",
+        "uso: /sprint status ou /sprint goal <add <text> [points]|done <id>|list>",
+    ),
+    ("sprint.no_active", "Nenhuma sprint em andamento hoje"),
+    ("sprint.goals_title", "Metas de {name}"),
+    (
+        "sprint.goals_summary",
+        "{start} a {end}, {done} de {total} concluídas",
+    ),
+    ("sprint.goals_empty", "Nenhuma meta ainda"),
+    ("sprint.goal_done", "concluída, {points} pontos"),
+    ("sprint.goal_open", "aberta, {points} pontos"),
+    ("sprint.goal_duplicate", "{title} já é uma meta"),
+    (
+        "sprint.goal_unknown",
+        "Nenhuma meta {id}, veja /sprint goal list",
+    ),
+    (
+        "sprint.status_no_active",
+        "Nenhuma sprint em andamento hoje, peça a um admin para planejar a próxima",
+    ),
+    ("sprint.status_title", "Status de {name}"),
+    ("sprint.status_dates", "{start} a {end}"),
+    ("sprint.status_days_left", "Dias restantes"),
+    ("sprint.status_goals", "Metas"),
+    ("sprint.status_goals_done", "{done} de {total} concluídas"),
+    ("sprint.status_participation", "Daily de hoje"),
+    (
+        "sprint.status_answered",
+        "{answered} de {roster} responderam ({percent}%)",
+    ),
+    ("sprint.status_no_roster", "Nenhum roster definido"),
+];
@@ -1,6 +1,8 @@
 pub mod configuration;
-pub mod observability;
+pub mod domain;
 pub mod drivers;
+pub mod i18n;
+pub mod observability;
 
 pub fn add(left: u64, right: u64) -> u64 {
     left + right
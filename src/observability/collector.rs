@@ -0,0 +1,152 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use axum::http::Uri;
+use tokio::net::TcpStream;
+
+use crate::{
+    configuration::{OtlpSignal, Settings},
+    drivers::database::Ping,
+};
+
+/// How long to wait for the collector to accept a connection.
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+const DEFAULT_OTLP_GRPC_PORT: u16 = 4317;
+
+/// Open a TCP connection to the OTLP `endpoint` to check it is listening.
+pub async fn probe(endpoint: &str, timeout: Duration) -> Result<()> {
+    let uri: Uri = endpoint
+        .parse()
+        .with_context(|| format!("expected a valid otlp endpoint, got {:?}", endpoint))?;
+    let host = uri
+        .host()
+        .with_context(|| format!("expected otlp endpoint {:?} to have a host", endpoint))?;
+    let port = uri.port_u16().unwrap_or(DEFAULT_OTLP_GRPC_PORT);
+
+    tokio::time::timeout(timeout, TcpStream::connect((host, port)))
+        .await
+        .context("timed out connecting to the otlp collector")?
+        .context("expected to connect to the otlp collector")?;
+
+    Ok(())
+}
+
+/// The OTLP collector as a dependency of the readiness report.
+#[derive(Debug, Clone)]
+pub struct Collector {
+    pub endpoint: String,
+    pub timeout: Duration,
+}
+
+impl Collector {
+    /// The collector the traces of `settings` go to, `None` when telemetry is
+    /// disabled.
+    pub fn from_settings(settings: &Settings) -> Option<Self> {
+        if !settings.otel.enable {
+            return None;
+        }
+
+        let endpoint = settings.otel.endpoint(OtlpSignal::Traces).ok()?;
+        Some(Self {
+            endpoint,
+            timeout: PROBE_TIMEOUT,
+        })
+    }
+}
+
+#[async_trait]
+impl Ping for Collector {
+    #[tracing::instrument(name = "Ping otlp collector", skip(self), fields(endpoint = %self.endpoint))]
+    async fn ping(&self) -> Result<()> {
+        probe(&self.endpoint, self.timeout).await
+    }
+}
+
+/// Warn loudly when telemetry is enabled but the collector can't be reached.
+///
+/// The batch exporters keep buffering and will deliver once the collector
+/// recovers, so this never fails the startup.
+pub async fn warn_if_unreachable(settings: &Settings) {
+    let Some(collector) = Collector::from_settings(settings) else {
+        return;
+    };
+
+    if let Err(error) = probe(&collector.endpoint, PROBE_TIMEOUT).await {
+        tracing::warn!(
+            endpoint = %collector.endpoint,
+            error = ?error,
+            "OTLP COLLECTOR UNREACHABLE: traces and logs will be buffered until it recovers"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Write,
+        sync::{Arc, Mutex},
+    };
+
+    use tokio::net::TcpListener;
+
+    use crate::{
+        configuration::test_settings,
+        observability::{metrics::ExportMetrics, trace::init_trace},
+    };
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    async fn unreachable_endpoint() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        drop(listener);
+
+        format!("http://{}", address)
+    }
+
+    #[tokio::test]
+    async fn probe_succeeds_for_a_listening_collector() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+
+        probe(&endpoint, PROBE_TIMEOUT).await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn unreachable_collector_warns_but_tracing_starts() {
+        let mut settings = test_settings();
+        settings.otel.enable = true;
+        settings.otel.endpoint = Some(unreachable_endpoint().await);
+
+        let provider = init_trace(&settings, Arc::new(ExportMetrics::default()));
+        assert!(provider.is_ok());
+
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        warn_if_unreachable(&settings).await;
+
+        let logs = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        assert!(logs.contains("WARN"), "{logs}");
+        assert!(logs.contains("OTLP COLLECTOR UNREACHABLE"), "{logs}");
+    }
+}
@@ -0,0 +1,154 @@
+//! Count the telemetry exports, so a collector silently dropping everything
+//! shows up on the Prometheus side.
+
+use std::{fmt, sync::Arc};
+
+use anyhow::{Context, Result};
+use futures::future::BoxFuture;
+use opentelemetry::global::{self, Error};
+use opentelemetry_sdk::{
+    export::trace::{ExportResult, SpanData, SpanExporter},
+    Resource,
+};
+
+/// Where the exports of the telemetry are counted, by signal.
+pub trait ExportRecorder: Send + Sync {
+    /// A batch was handed to the exporter.
+    fn exported(&self, signal: &'static str);
+    /// The SDK reported an error while exporting.
+    fn failed(&self, signal: &'static str);
+}
+
+/// The signal an SDK error is about, `None` for the propagation errors which
+/// have nothing to do with the exports.
+fn error_signal(error: &Error) -> Option<&'static str> {
+    match error {
+        Error::Trace(_) => Some("traces"),
+        Error::Metric(_) => Some("metrics"),
+        Error::Log(_) => Some("logs"),
+        Error::Propagation(_) => None,
+        _ => Some("other"),
+    }
+}
+
+/// Counts the errors reported by the SDK before printing them to stderr, as
+/// the default handler does.
+fn error_handler(recorder: Arc<dyn ExportRecorder>) -> impl Fn(Error) + Send + Sync + 'static {
+    move |error| {
+        if let Some(signal) = error_signal(&error) {
+            recorder.failed(signal);
+        }
+        // The subscriber may be the one failing to export, stderr is safer.
+        eprintln!("OpenTelemetry error occurred. {error}");
+    }
+}
+
+/// Replace the global error handler of the SDK with one counting the errors.
+///
+/// The handler is global, set it once at startup.
+pub fn set_error_handler(recorder: Arc<dyn ExportRecorder>) -> Result<()> {
+    global::set_error_handler(error_handler(recorder))
+        .context("expected to set the opentelemetry error handler")
+}
+
+/// Counts the span batches handed to `inner`, the failures reach the error
+/// handler through the batch processor.
+pub struct CountingSpanExporter<E> {
+    inner: E,
+    recorder: Arc<dyn ExportRecorder>,
+}
+
+impl<E> CountingSpanExporter<E> {
+    pub fn new(inner: E, recorder: Arc<dyn ExportRecorder>) -> Self {
+        Self { inner, recorder }
+    }
+}
+
+impl<E: fmt::Debug> fmt::Debug for CountingSpanExporter<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CountingSpanExporter")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<E: SpanExporter> SpanExporter for CountingSpanExporter<E> {
+    fn export(&mut self, batch: Vec<SpanData>) -> BoxFuture<'static, ExportResult> {
+        self.recorder.exported("traces");
+        self.inner.export(batch)
+    }
+
+    fn shutdown(&mut self) {
+        self.inner.shutdown();
+    }
+
+    fn force_flush(&mut self) -> BoxFuture<'static, ExportResult> {
+        self.inner.force_flush()
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.inner.set_resource(resource);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry::{
+        propagation::PropagationError,
+        trace::{TraceError, Tracer, TracerProvider as _},
+    };
+    use opentelemetry_sdk::{testing::trace::InMemorySpanExporter, trace::TracerProvider};
+    use prometheus_client::{encoding::text::encode, registry::Registry};
+
+    use crate::observability::metrics::ExportMetrics;
+
+    use super::*;
+
+    #[test]
+    fn error_handler_counts_the_export_errors_by_signal() {
+        let metrics = Arc::new(ExportMetrics::default());
+        let mut registry = Registry::default();
+        metrics.register(&mut registry);
+        let handle = error_handler(metrics.clone());
+
+        handle(Error::Trace(TraceError::ExportTimedOut(
+            std::time::Duration::from_secs(10),
+        )));
+        handle(Error::Trace(TraceError::from("collector unreachable")));
+        handle(Error::Propagation(PropagationError::extract(
+            "invalid traceparent",
+            "TraceContextPropagator",
+        )));
+
+        let mut encoded = String::new();
+        encode(&mut encoded, &registry).unwrap();
+
+        assert!(
+            encoded.contains(r#"otel_export_errors_total{signal="traces"} 2"#),
+            "{encoded}"
+        );
+        assert!(!encoded.contains("propagation"), "{encoded}");
+    }
+
+    #[test]
+    fn exporter_counts_the_batches() {
+        let metrics = Arc::new(ExportMetrics::default());
+        let mut registry = Registry::default();
+        metrics.register(&mut registry);
+        let exporter = CountingSpanExporter::new(InMemorySpanExporter::default(), metrics.clone());
+        let provider = TracerProvider::builder()
+            .with_simple_exporter(exporter)
+            .build();
+
+        provider.tracer("test").in_span("standup", |_| {});
+        provider.tracer("test").in_span("reminder", |_| {});
+
+        let mut encoded = String::new();
+        encode(&mut encoded, &registry).unwrap();
+
+        assert!(
+            encoded.contains(r#"otel_exports_total{signal="traces"} 2"#),
+            "{encoded}"
+        );
+    }
+}
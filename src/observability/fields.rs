@@ -0,0 +1,203 @@
+//! Rename or drop the top level fields of the JSON log records before they are written.
+
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+    sync::Arc,
+};
+
+use serde_json::Value;
+use tracing_subscriber::fmt::MakeWriter;
+
+use crate::configuration::LoggingSettings;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FieldMapping {
+    rename: HashMap<String, String>,
+    drop: Vec<String>,
+}
+
+impl FieldMapping {
+    pub fn new(rename: HashMap<String, String>, drop: Vec<String>) -> Self {
+        Self { rename, drop }
+    }
+
+    pub fn from_settings(settings: &LoggingSettings) -> Self {
+        Self::new(settings.rename.clone(), settings.drop.clone())
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.rename.is_empty() && self.drop.is_empty()
+    }
+
+    /// Apply the mapping to a single JSON `line`, anything else is left untouched.
+    pub fn apply(&self, line: &[u8]) -> Vec<u8> {
+        let Ok(Value::Object(mut record)) = serde_json::from_slice::<Value>(line) else {
+            return line.to_vec();
+        };
+
+        for field in &self.drop {
+            record.remove(field);
+        }
+        for (from, to) in &self.rename {
+            if let Some(value) = record.remove(from) {
+                record.insert(to.clone(), value);
+            }
+        }
+
+        let mut mapped = serde_json::to_vec(&record).expect("a json map always serializes");
+        if line.ends_with(b"\n") {
+            mapped.push(b'\n');
+        }
+        mapped
+    }
+}
+
+/// Wraps the sink of the formatting layer to apply a [`FieldMapping`] to every record.
+#[derive(Clone)]
+pub struct MakeMappedWriter<M> {
+    inner: M,
+    mapping: Arc<FieldMapping>,
+}
+
+impl<M> MakeMappedWriter<M> {
+    pub fn new(inner: M, mapping: FieldMapping) -> Self {
+        Self {
+            inner,
+            mapping: Arc::new(mapping),
+        }
+    }
+}
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for MakeMappedWriter<M> {
+    type Writer = MappedWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        MappedWriter {
+            inner: self.inner.make_writer(),
+            mapping: self.mapping.clone(),
+            buffer: Vec::new(),
+        }
+    }
+}
+
+/// Buffers a record until its line is complete, then writes it mapped.
+pub struct MappedWriter<W: Write> {
+    inner: W,
+    mapping: Arc<FieldMapping>,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> MappedWriter<W> {
+    fn write_lines(&mut self) -> io::Result<()> {
+        while let Some(end) = self.buffer.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            if self.mapping.is_identity() {
+                self.inner.write_all(&line)?;
+            } else {
+                self.inner.write_all(&self.mapping.apply(&line))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for MappedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        self.write_lines()?;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_lines()?;
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Drop for MappedWriter<W> {
+    fn drop(&mut self) {
+        // A trailing record without newline is still worth emitting.
+        let rest = std::mem::take(&mut self.buffer);
+        let _ = self.inner.write_all(&self.mapping.apply(&rest));
+        let _ = self.inner.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use tracing_bunyan_formatter::{BunyanFormattingLayer, JsonStorageLayer};
+    use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn records(mapping: FieldMapping) -> Vec<serde_json::Map<String, Value>> {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let sink = MakeMappedWriter::new(move || writer.clone(), mapping);
+        let subscriber = Registry::default()
+            .with(JsonStorageLayer)
+            .with(BunyanFormattingLayer::new("test".into(), sink));
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(standup = "daily", "reminder sent");
+        });
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn fields_are_renamed_and_dropped() {
+        let mapping = FieldMapping::new(
+            HashMap::from([("msg".to_owned(), "message".to_owned())]),
+            vec!["line".to_owned(), "hostname".to_owned()],
+        );
+
+        let records = records(mapping);
+
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record["message"], "reminder sent");
+        assert_eq!(record["standup"], "daily");
+        assert!(!record.contains_key("msg"));
+        assert!(!record.contains_key("line"));
+        assert!(!record.contains_key("hostname"));
+        assert!(record.contains_key("target"));
+    }
+
+    #[test]
+    fn empty_mapping_keeps_bunyan_fields() {
+        let records = records(FieldMapping::default());
+
+        assert_eq!(records[0]["msg"], "reminder sent");
+        assert!(records[0].contains_key("line"));
+    }
+
+    #[test]
+    fn non_json_lines_are_left_untouched() {
+        let mapping = FieldMapping::new(HashMap::new(), vec!["msg".to_owned()]);
+
+        assert_eq!(mapping.apply(b"plain text\n"), b"plain text\n");
+    }
+}
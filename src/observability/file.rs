@@ -0,0 +1,92 @@
+//! The rotating log file of `logging.file`, next to stdout.
+
+use anyhow::{Context, Result};
+use tracing_appender::rolling::{RollingFileAppender, RollingWriter, Rotation};
+use tracing_subscriber::fmt::{writer::OptionalWriter, MakeWriter};
+
+use crate::configuration::{LogFileSettings, LogRotation};
+
+/// Where the log records are also written, nowhere when no file is
+/// configured.
+pub struct LogFile(Option<RollingFileAppender>);
+
+impl LogFile {
+    pub fn none() -> Self {
+        Self(None)
+    }
+
+    /// Open the file of the current period, creating the directory.
+    pub fn from_settings(settings: Option<&LogFileSettings>) -> Result<Self> {
+        let Some(settings) = settings else {
+            return Ok(Self::none());
+        };
+
+        let rotation = match settings.rotation {
+            LogRotation::Minutely => Rotation::MINUTELY,
+            LogRotation::Hourly => Rotation::HOURLY,
+            LogRotation::Daily => Rotation::DAILY,
+            LogRotation::Never => Rotation::NEVER,
+        };
+        let appender = RollingFileAppender::builder()
+            .rotation(rotation)
+            .filename_prefix(&settings.prefix)
+            .build(&settings.directory)
+            .with_context(|| format!("expected to open log file in {:?}", settings.directory))?;
+
+        Ok(Self(Some(appender)))
+    }
+}
+
+impl<'a> MakeWriter<'a> for LogFile {
+    type Writer = OptionalWriter<RollingWriter<'a>>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.0.as_ref().map(MakeWriter::make_writer).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use bson::oid::ObjectId;
+    use tracing_bunyan_formatter::{BunyanFormattingLayer, JsonStorageLayer};
+    use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+    use super::*;
+
+    fn settings(directory: PathBuf) -> LogFileSettings {
+        LogFileSettings {
+            directory,
+            prefix: "scrum-bot.log".into(),
+            rotation: LogRotation::Daily,
+        }
+    }
+
+    #[test]
+    fn records_are_written_to_the_configured_directory() {
+        let directory = std::env::temp_dir().join(format!("scrum-bot-logs-{}", ObjectId::new()));
+        let file = LogFile::from_settings(Some(&settings(directory.clone()))).unwrap();
+        let subscriber = Registry::default()
+            .with(JsonStorageLayer)
+            .with(BunyanFormattingLayer::new("test".into(), file));
+
+        tracing::subscriber::with_default(subscriber, || tracing::info!("reminder sent"));
+
+        let files: Vec<_> = fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        fs::remove_dir_all(&directory).unwrap();
+        assert_eq!(files.len(), 1);
+        let name = files[0].file_name().unwrap().to_str().unwrap();
+        assert!(name.starts_with("scrum-bot.log."), "{}", name);
+    }
+
+    #[test]
+    fn nothing_is_written_without_a_file() {
+        let file = LogFile::from_settings(None).unwrap();
+
+        assert!(file.0.is_none());
+    }
+}
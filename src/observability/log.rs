@@ -2,7 +2,7 @@ use anyhow::{Context, Result};
 use opentelemetry_otlp::WithExportConfig;
 use opentelemetry_sdk::{logs::LoggerProvider, runtime};
 
-use crate::configuration::Settings;
+use crate::configuration::{OtlpSignal, Settings};
 
 pub fn init_log(settings: &Settings) -> Result<LoggerProvider> {
     let logger_provider = match settings.otel.enable {
@@ -11,7 +11,7 @@ pub fn init_log(settings: &Settings) -> Result<LoggerProvider> {
             .with_exporter(
                 opentelemetry_otlp::new_exporter()
                     .tonic()
-                    .with_endpoint(&settings.otel.endpoint),
+                    .with_endpoint(settings.otel.endpoint(OtlpSignal::Logs)?),
             )
             .with_resource(settings.get_resource())
             .install_batch(runtime::Tokio)
@@ -0,0 +1,128 @@
+use anyhow::{Context, Result};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{metrics::SdkMeterProvider, runtime};
+
+use crate::configuration::{OtlpSignal, Settings};
+
+pub fn init_meter(settings: &Settings) -> Result<SdkMeterProvider> {
+    let meter_provider = match settings.otel.enable {
+        true => opentelemetry_otlp::new_pipeline()
+            .metrics(runtime::Tokio)
+            .with_exporter(
+                opentelemetry_otlp::new_exporter()
+                    .tonic()
+                    .with_endpoint(settings.otel.endpoint(OtlpSignal::Metrics)?),
+            )
+            .with_resource(settings.get_resource())
+            .build()
+            .context("expected to genereate otlp meter provider")?,
+        false => SdkMeterProvider::builder()
+            .with_resource(settings.get_resource())
+            .build(),
+    };
+
+    Ok(meter_provider)
+}
+
+/// Export what is left in the meter provider and stop its readers.
+///
+/// The periodic reader blocks the calling thread until its task on the
+/// runtime has exported, so the shutdown runs off the runtime threads.
+pub async fn shutdown_meter(meter_provider: SdkMeterProvider) -> Result<()> {
+    tokio::task::spawn_blocking(move || meter_provider.shutdown())
+        .await
+        .context("expected meter provider shutdown to finish")?
+        .context("expected to flush the meter provider")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use async_trait::async_trait;
+    use opentelemetry::metrics::MeterProvider;
+    use opentelemetry_sdk::metrics::{
+        data::{ResourceMetrics, Temporality},
+        exporter::PushMetricsExporter,
+        reader::TemporalitySelector,
+        InstrumentKind, PeriodicReader,
+    };
+
+    use crate::configuration::test_settings;
+
+    use super::*;
+
+    /// Keeps the names of the exported metrics, even after its shutdown.
+    #[derive(Clone, Default)]
+    struct Exported(Arc<Mutex<Vec<String>>>);
+
+    impl TemporalitySelector for Exported {
+        fn temporality(&self, _kind: InstrumentKind) -> Temporality {
+            Temporality::Cumulative
+        }
+    }
+
+    #[async_trait]
+    impl PushMetricsExporter for Exported {
+        async fn export(
+            &self,
+            metrics: &mut ResourceMetrics,
+        ) -> opentelemetry::metrics::Result<()> {
+            let names = metrics
+                .scope_metrics
+                .iter()
+                .flat_map(|scope| &scope.metrics)
+                .map(|metric| metric.name.to_string());
+            self.0.lock().unwrap().extend(names);
+            Ok(())
+        }
+
+        async fn force_flush(&self) -> opentelemetry::metrics::Result<()> {
+            Ok(())
+        }
+
+        fn shutdown(&self) -> opentelemetry::metrics::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn shutdown_flushes_pending_metrics_without_hanging() {
+        let exporter = Exported::default();
+        // Far longer than the test, only the shutdown can export.
+        let reader = PeriodicReader::builder(exporter.clone(), runtime::Tokio)
+            .with_interval(Duration::from_secs(3600))
+            .build();
+        let meter_provider = SdkMeterProvider::builder().with_reader(reader).build();
+
+        let counter = meter_provider.meter("test").u64_counter("fires").init();
+        counter.add(3, &[]);
+
+        tokio::time::timeout(Duration::from_secs(5), shutdown_meter(meter_provider))
+            .await
+            .expect("expected shutdown not to hang")
+            .unwrap();
+
+        assert_eq!(*exporter.0.lock().unwrap(), vec!["fires".to_owned()]);
+    }
+
+    #[tokio::test]
+    async fn disabled_export_shuts_down_without_hanging() {
+        let mut settings = test_settings();
+        settings.otel.enable = false;
+        let meter_provider = init_meter(&settings).unwrap();
+        meter_provider
+            .meter("test")
+            .u64_counter("fires")
+            .init()
+            .add(1, &[]);
+
+        tokio::time::timeout(Duration::from_secs(5), shutdown_meter(meter_provider))
+            .await
+            .expect("expected shutdown not to hang")
+            .unwrap();
+    }
+}
@@ -1,22 +1,133 @@
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{atomic::AtomicU64, Arc},
+    time::Duration,
+};
 
+use chrono::TimeDelta;
 use prometheus_client::{
     encoding::EncodeLabelSet,
-    metrics::{counter::Counter, family::Family, histogram::Histogram},
+    metrics::{
+        counter::Counter,
+        family::{Family, MetricConstructor},
+        gauge::Gauge,
+        histogram::Histogram,
+    },
     registry::Registry,
 };
 
-use crate::configuration::Settings;
+use crate::{
+    configuration::{ReloadCounter, Settings},
+    domain::{
+        cleanup::CleanupCounter,
+        delivery::SendFailureCounter,
+        id::GuildId,
+        job::{JobOutcome, JobRecorder},
+        kickoff::KickoffHistogram,
+        participation::ParticipationGauge,
+        reminder::PendingAgeGauge,
+        replay::ReplayCounter,
+        retention::PruneCounter,
+        scheduler::FireCounter,
+        standup::FeedRecorder,
+    },
+    drivers::{
+        database::{
+            breaker::{BreakerGauge, BreakerState},
+            RetryCounter,
+        },
+        discord::{command::BusyCounter, gateway::ShardGauge},
+    },
+    observability::export::ExportRecorder,
+};
+
+/// Buckets of the request latencies, in seconds.
+pub const LATENCY_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+/// Buckets of the delay to the first standup of a sprint, in hours.
+const FIRST_STANDUP_HOURS: [f64; 10] = [1.0, 3.0, 6.0, 12.0, 24.0, 48.0, 72.0, 120.0, 168.0, 336.0];
+/// Buckets of the background job runs, in seconds.
+const JOB_DURATION_BUCKETS: [f64; 10] = [0.1, 0.5, 1.0, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 600.0];
+
+/// The histograms whose buckets `prometheus.buckets` can override, by
+/// registered name.
+pub const HISTOGRAMS: [&str; 4] = [
+    "latency_error",
+    "latency_success",
+    "sprint_time_to_first_standup_seconds",
+    "job_duration_seconds",
+];
+
+/// Histogram buckets by metric name, see `prometheus.buckets`.
+#[derive(Debug, Clone, Default)]
+pub struct BucketOverrides(HashMap<String, Vec<f64>>);
+
+impl BucketOverrides {
+    pub fn new(buckets: HashMap<String, Vec<f64>>) -> Self {
+        Self(buckets)
+    }
+
+    /// The buckets configured for `name`, `default` otherwise.
+    pub fn get(&self, name: &str, default: &[f64]) -> HistogramBuckets {
+        let buckets = self.0.get(name).map_or(default, Vec::as_slice);
+
+        HistogramBuckets(buckets.into())
+    }
+
+    /// A histogram with the buckets of `name`, `default` unless configured.
+    pub fn histogram(&self, name: &str, default: &[f64]) -> Histogram {
+        self.get(name, default).new_metric()
+    }
+
+    /// A histogram family with the buckets of `name`, `default` unless
+    /// configured.
+    pub fn family<S>(&self, name: &str, default: &[f64]) -> Family<S, Histogram, HistogramBuckets>
+    where
+        S: Clone + Hash + Eq,
+    {
+        Family::new_with_constructor(self.get(name, default))
+    }
+}
+
+/// Creates the histograms of a family, all with the same buckets.
+#[derive(Debug, Clone)]
+pub struct HistogramBuckets(Arc<[f64]>);
+
+impl MetricConstructor<Histogram> for HistogramBuckets {
+    fn new_metric(&self) -> Histogram {
+        Histogram::new(self.0.iter().copied())
+    }
+}
+
+/// Register each `name => metric, help;` on the registry, cloning the metric.
+macro_rules! register {
+    ($registry:expr, { $($name:literal => $metric:expr, $help:literal;)+ }) => {
+        $($registry.register($name, $help, $metric.clone());)+
+    };
+}
 
 pub struct Metrics {
     pub http: Arc<HttpMetrics>,
+    pub standup: Arc<StandupMetrics>,
+    pub discord: Arc<DiscordMetrics>,
+    pub database: Arc<DatabaseMetrics>,
+    pub scheduler: Arc<SchedulerMetrics>,
+    pub retention: Arc<RetentionMetrics>,
+    pub config: Arc<ConfigMetrics>,
+    pub jobs: Arc<JobMetrics>,
+    pub export: Arc<ExportMetrics>,
 }
 
 #[derive(Clone, Debug)]
 pub struct HttpMetrics {
     pub total_requests: Family<HttpRequestLabels, Counter>,
-    pub latency_error: Family<HttpRequestLabels, Histogram>,
-    pub latency_success: Family<HttpRequestLabels, Histogram>,
+    pub latency_error: Family<HttpRequestLabels, Histogram, HistogramBuckets>,
+    pub latency_success: Family<HttpRequestLabels, Histogram, HistogramBuckets>,
+    /// Requests that matched no route, unlabeled to keep scanners from
+    /// blowing up the cardinality.
+    pub not_found: Counter,
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
@@ -34,49 +145,764 @@ impl Default for HttpMetrics {
 
 impl HttpMetrics {
     pub fn new() -> Self {
+        Self::with_buckets(&BucketOverrides::default())
+    }
+
+    pub fn with_buckets(buckets: &BucketOverrides) -> Self {
         Self {
             total_requests: Family::default(),
-            latency_error: Family::<HttpRequestLabels, Histogram>::new_with_constructor(|| {
-                let custom_buckets = [
-                    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
-                ];
-                Histogram::new(custom_buckets.into_iter())
-            }),
-            latency_success: Family::<HttpRequestLabels, Histogram>::new_with_constructor(|| {
-                let custom_buckets = [
-                    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
-                ];
-                Histogram::new(custom_buckets.into_iter())
-            }),
+            latency_error: buckets.family("latency_error", &LATENCY_BUCKETS),
+            latency_success: buckets.family("latency_success", &LATENCY_BUCKETS),
+            not_found: Counter::default(),
         }
     }
 
     pub fn register(&self, registry: &mut Registry) {
-        registry.register(
-            "total_request",
-            "Total amount of requests",
-            self.total_requests.clone(),
-        );
+        register!(registry, {
+            "total_request" => self.total_requests,
+                "Total amount of requests";
+            "latency_error" => self.latency_error,
+                "Latency error";
+            "latency_success" => self.latency_success,
+                "Latency success";
+            "http_not_found" => self.not_found,
+                "Requests that matched no route";
+        });
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct GuildLabels {
+    pub guild_id: String,
+}
 
-        registry.register("latency_error", "Latency error", self.latency_error.clone());
+#[derive(Clone, Debug)]
+pub struct StandupMetrics {
+    /// Share of the roster that answered the standup of the current guild day.
+    pub participation: Family<GuildLabels, Gauge<f64, AtomicU64>>,
+    /// Delay from the start of a sprint to its first standup.
+    pub time_to_first_standup: Histogram,
+    /// Entries the live standup listeners skipped for falling behind.
+    pub feed_lagged: Counter,
+    /// Live standup listeners.
+    pub feed_listeners: Gauge,
+}
 
-        registry.register(
-            "latency_success",
-            "Latency success",
-            self.latency_success.clone(),
-        );
+impl Default for StandupMetrics {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
+impl StandupMetrics {
+    pub fn new() -> Self {
+        Self::with_buckets(&BucketOverrides::default())
+    }
+
+    pub fn with_buckets(buckets: &BucketOverrides) -> Self {
+        let hour = 60.0 * 60.0;
+        let default = FIRST_STANDUP_HOURS.map(|hours| hours * hour);
+
+        Self {
+            participation: Family::default(),
+            time_to_first_standup: buckets
+                .histogram("sprint_time_to_first_standup_seconds", &default),
+            feed_lagged: Counter::default(),
+            feed_listeners: Gauge::default(),
+        }
+    }
+
+    pub fn register(&self, registry: &mut Registry) {
+        register!(registry, {
+            "standup_participation_ratio" => self.participation,
+                "Share of the guild roster that answered today's standup";
+            "sprint_time_to_first_standup_seconds" => self.time_to_first_standup,
+                "Delay from the start of a sprint to its first standup";
+            "broadcast_lagged" => self.feed_lagged,
+                "Standup entries skipped by live listeners that fell behind";
+            "broadcast_receiver_count" => self.feed_listeners,
+                "Live listeners of the standup feed";
+        });
+    }
+}
+
+impl ParticipationGauge for StandupMetrics {
+    fn record(&self, guild_id: GuildId, ratio: f64) {
+        let labels = GuildLabels {
+            guild_id: guild_id.to_string(),
+        };
+
+        self.participation.get_or_create(&labels).set(ratio);
+    }
+}
+
+impl KickoffHistogram for StandupMetrics {
+    fn observe(&self, delay: TimeDelta) {
+        self.time_to_first_standup
+            .observe(delay.num_milliseconds() as f64 / 1000.0);
+    }
+}
+
+impl FeedRecorder for StandupMetrics {
+    fn lagged(&self, skipped: u64) {
+        self.feed_lagged.inc_by(skipped);
+    }
+
+    fn listener_opened(&self) {
+        self.feed_listeners.inc();
+    }
+
+    fn listener_closed(&self) {
+        self.feed_listeners.dec();
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct ShardLabels {
+    pub shard_id: u32,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DiscordMetrics {
+    /// Messages Discord refused, retries included.
+    pub send_failures: Family<ShardLabels, Counter>,
+    /// 1 while the gateway shard is connected, 0 otherwise.
+    pub shard_connected: Family<ShardLabels, Gauge>,
+    /// Interactions turned away by the concurrency limit.
+    pub interactions_rejected: Counter,
+    /// Old messages of the bot deleted from the admin API.
+    pub messages_deleted: Counter,
+    /// Standup prompts posted again from the admin API.
+    pub prompts_replayed: Counter,
+}
+
+impl DiscordMetrics {
+    pub fn register(&self, registry: &mut Registry) {
+        register!(registry, {
+            "discord_send_failures" => self.send_failures,
+                "Messages that failed to be posted to Discord";
+            "discord_shard_connected" => self.shard_connected,
+                "Whether the gateway shard is connected";
+            "discord_interactions_rejected" => self.interactions_rejected,
+                "Interactions answered busy because too many were being handled";
+            "discord_messages_deleted" => self.messages_deleted,
+                "Old messages of the bot deleted by a channel cleanup";
+            "discord_prompts_replayed" => self.prompts_replayed,
+                "Standup prompts posted again on demand";
+        });
+    }
+
+    /// The metrics of the messages sent by `shard_id`.
+    pub fn shard(&self, shard_id: u32) -> ShardMetrics {
+        ShardMetrics {
+            labels: ShardLabels { shard_id },
+            metrics: self.clone(),
+        }
+    }
+}
+
+impl BusyCounter for DiscordMetrics {
+    fn rejected(&self) {
+        self.interactions_rejected.inc();
+    }
+}
+
+impl CleanupCounter for DiscordMetrics {
+    fn deleted(&self, count: u64) {
+        self.messages_deleted.inc_by(count);
+    }
+}
+
+impl ReplayCounter for DiscordMetrics {
+    fn replayed(&self) {
+        self.prompts_replayed.inc();
+    }
+}
+
+impl ShardGauge for DiscordMetrics {
+    fn set_connected(&self, shard_id: u32, connected: bool) {
+        self.shard_connected
+            .get_or_create(&ShardLabels { shard_id })
+            .set(connected.into());
+    }
+
+    fn remove(&self, shard_id: u32) {
+        self.shard_connected.remove(&ShardLabels { shard_id });
+    }
+}
+
+/// [`DiscordMetrics`] of a single shard.
+#[derive(Clone, Debug)]
+pub struct ShardMetrics {
+    labels: ShardLabels,
+    metrics: DiscordMetrics,
+}
+
+impl SendFailureCounter for ShardMetrics {
+    fn increment(&self) {
+        self.metrics.send_failures.get_or_create(&self.labels).inc();
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DatabaseMetrics {
+    /// 0 when closed, 1 when half-open and 2 when open.
+    pub breaker_state: Gauge,
+    /// Writes tried again after a transient error.
+    pub write_retries: Counter,
+}
+
+impl DatabaseMetrics {
+    pub fn register(&self, registry: &mut Registry) {
+        register!(registry, {
+            "database_circuit_breaker_state" => self.breaker_state,
+                "State of the database circuit breaker, 0 closed, 1 half-open and 2 open";
+            "database_write_retries" => self.write_retries,
+                "Writes tried again after a transient database error";
+        });
+    }
+}
+
+impl RetryCounter for DatabaseMetrics {
+    fn retried(&self) {
+        self.write_retries.inc();
+    }
+}
+
+impl BreakerGauge for DatabaseMetrics {
+    fn record(&self, state: BreakerState) {
+        self.breaker_state.set(state.as_i64());
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct FireLabels {
+    /// `dry_run` or `live`.
+    pub mode: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SchedulerMetrics {
+    /// Scheduled messages fired, whether they were sent or only logged.
+    pub fires: Family<FireLabels, Counter>,
+    /// Age of the oldest due reminder at the last tick, near zero when healthy.
+    pub oldest_pending: Gauge<f64, AtomicU64>,
+}
+
+impl SchedulerMetrics {
+    pub fn register(&self, registry: &mut Registry) {
+        register!(registry, {
+            "scheduler_fires" => self.fires,
+                "Scheduled messages fired, by dry run or live mode";
+            "scheduler_oldest_pending_seconds" => self.oldest_pending,
+                "Age of the oldest due reminder at the last scheduler tick";
+        });
+    }
+}
+
+impl PendingAgeGauge for SchedulerMetrics {
+    fn oldest_pending(&self, age: Duration) {
+        self.oldest_pending.set(age.as_secs_f64());
+    }
+}
+
+impl FireCounter for SchedulerMetrics {
+    fn fired(&self, dry_run: bool) {
+        let mode = if dry_run { "dry_run" } else { "live" };
+
+        self.fires
+            .get_or_create(&FireLabels { mode: mode.into() })
+            .inc();
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct RetentionMetrics {
+    /// Records deleted by the retention policy.
+    pub pruned: Counter,
+}
+
+impl RetentionMetrics {
+    pub fn register(&self, registry: &mut Registry) {
+        register!(registry, {
+            "data_pruned" => self.pruned,
+                "Records deleted by the data retention policy";
+        });
+    }
+}
+
+impl PruneCounter for RetentionMetrics {
+    fn pruned(&self, count: u64) {
+        self.pruned.inc_by(count);
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct ReloadLabels {
+    /// `applied` or `rejected`.
+    pub outcome: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ConfigMetrics {
+    /// Configuration reloads, from SIGHUP or the admin API.
+    pub reloads: Family<ReloadLabels, Counter>,
+}
+
+impl ConfigMetrics {
+    pub fn register(&self, registry: &mut Registry) {
+        register!(registry, {
+            "config_reloads" => self.reloads,
+                "Configuration reloads, by applied or rejected outcome";
+        });
+    }
+}
+
+impl ReloadCounter for ConfigMetrics {
+    fn reloaded(&self, applied: bool) {
+        let outcome = if applied { "applied" } else { "rejected" };
+
+        self.reloads
+            .get_or_create(&ReloadLabels {
+                outcome: outcome.into(),
+            })
+            .inc();
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct SignalLabels {
+    /// `traces`, `metrics`, `logs` or `other`.
+    pub signal: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ExportMetrics {
+    /// Batches handed to the OTLP exporters.
+    pub exports: Family<SignalLabels, Counter>,
+    /// Errors reported by the opentelemetry SDK.
+    pub export_errors: Family<SignalLabels, Counter>,
+}
+
+impl ExportMetrics {
+    pub fn register(&self, registry: &mut Registry) {
+        register!(registry, {
+            "otel_exports" => self.exports,
+                "Telemetry batches handed to the OTLP exporters, by signal";
+            "otel_export_errors" => self.export_errors,
+                "Telemetry export errors reported by the opentelemetry SDK, by signal";
+        });
+    }
+}
+
+impl ExportRecorder for ExportMetrics {
+    fn exported(&self, signal: &'static str) {
+        self.exports
+            .get_or_create(&SignalLabels {
+                signal: signal.into(),
+            })
+            .inc();
+    }
+
+    fn failed(&self, signal: &'static str) {
+        self.export_errors
+            .get_or_create(&SignalLabels {
+                signal: signal.into(),
+            })
+            .inc();
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct JobLabels {
+    pub job: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct JobMetrics {
+    pub runs: Family<JobLabels, Counter>,
+    /// Runs that returned an error, timed out or panicked.
+    pub failures: Family<JobLabels, Counter>,
+    pub duration: Family<JobLabels, Histogram, HistogramBuckets>,
+}
+
+impl Default for JobMetrics {
+    fn default() -> Self {
+        Self::with_buckets(&BucketOverrides::default())
+    }
+}
+
+impl JobMetrics {
+    pub fn with_buckets(buckets: &BucketOverrides) -> Self {
+        Self {
+            runs: Family::default(),
+            failures: Family::default(),
+            duration: buckets.family("job_duration_seconds", &JOB_DURATION_BUCKETS),
+        }
+    }
+
+    pub fn register(&self, registry: &mut Registry) {
+        register!(registry, {
+            "job_runs" => self.runs,
+                "Runs of the background jobs";
+            "job_failures" => self.failures,
+                "Runs of the background jobs that failed, timed out or panicked";
+            "job_duration_seconds" => self.duration,
+                "Duration of the background job runs";
+        });
+    }
+}
+
+impl JobRecorder for JobMetrics {
+    fn finished(&self, job: &str, outcome: JobOutcome, duration: Duration) {
+        let labels = JobLabels { job: job.into() };
+
+        self.runs.get_or_create(&labels).inc();
+        if outcome.is_failure() {
+            self.failures.get_or_create(&labels).inc();
+        }
+        self.duration
+            .get_or_create(&labels)
+            .observe(duration.as_secs_f64());
+    }
+}
+
+/// Turn `name` into a valid Prometheus metric prefix, lowercase `[a-z0-9_]`
+/// not starting with a digit.
+pub fn sanitize_namespace(name: &str) -> String {
+    let mut namespace: String = name
+        .trim()
+        .chars()
+        .map(|c| match c.to_ascii_lowercase() {
+            c @ ('a'..='z' | '0'..='9') => c,
+            _ => '_',
+        })
+        .collect();
+
+    if namespace.starts_with(|c: char| c.is_ascii_digit()) {
+        namespace.insert(0, '_');
+    }
+    namespace
+}
+
+/// The prefix of every metric, `prometheus.namespace` or the application name.
+pub fn metric_namespace(settings: &Settings) -> String {
+    let namespace = settings
+        .prometheus
+        .namespace
+        .as_deref()
+        .filter(|namespace| !namespace.trim().is_empty())
+        .unwrap_or(&settings.application.name);
+
+    sanitize_namespace(namespace)
+}
+
 pub fn init_metrics(settings: &Settings) -> (Arc<Metrics>, Registry) {
-    let mut registry = Registry::with_prefix(&settings.application.name);
+    let mut registry = Registry::with_prefix(metric_namespace(settings));
+    let buckets = BucketOverrides::new(settings.prometheus.buckets.clone());
 
-    let http_metrics = HttpMetrics::default();
+    let http_metrics = HttpMetrics::with_buckets(&buckets);
     http_metrics.register(&mut registry);
 
+    let standup_metrics = StandupMetrics::with_buckets(&buckets);
+    standup_metrics.register(&mut registry);
+
+    let discord_metrics = DiscordMetrics::default();
+    discord_metrics.register(&mut registry);
+
+    let database_metrics = DatabaseMetrics::default();
+    database_metrics.register(&mut registry);
+
+    let scheduler_metrics = SchedulerMetrics::default();
+    scheduler_metrics.register(&mut registry);
+
+    let retention_metrics = RetentionMetrics::default();
+    retention_metrics.register(&mut registry);
+
+    let config_metrics = ConfigMetrics::default();
+    config_metrics.register(&mut registry);
+
+    let job_metrics = JobMetrics::with_buckets(&buckets);
+    job_metrics.register(&mut registry);
+
+    let export_metrics = ExportMetrics::default();
+    export_metrics.register(&mut registry);
+
     let metrics = Metrics {
         http: http_metrics.into(),
+        standup: standup_metrics.into(),
+        discord: discord_metrics.into(),
+        database: database_metrics.into(),
+        scheduler: scheduler_metrics.into(),
+        retention: retention_metrics.into(),
+        config: config_metrics.into(),
+        jobs: job_metrics.into(),
+        export: export_metrics.into(),
     };
 
     (Arc::new(metrics), registry)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use prometheus_client::encoding::text::encode;
+
+    use crate::{
+        configuration::{test_settings, ConfigReloader},
+        domain::feature::FeatureFlags,
+    };
+
+    use super::*;
+
+    #[test]
+    fn every_metric_is_registered_under_its_name() {
+        let mut settings = test_settings();
+        settings.application.name = "bot".into();
+        let (_, registry) = init_metrics(&settings);
+
+        let mut encoded = String::new();
+        encode(&mut encoded, &registry).unwrap();
+
+        let names = [
+            ("total_request", "counter", "Total amount of requests"),
+            ("latency_error", "histogram", "Latency error"),
+            ("latency_success", "histogram", "Latency success"),
+            (
+                "http_not_found",
+                "counter",
+                "Requests that matched no route",
+            ),
+            (
+                "standup_participation_ratio",
+                "gauge",
+                "Share of the guild roster that answered today's standup",
+            ),
+            (
+                "sprint_time_to_first_standup_seconds",
+                "histogram",
+                "Delay from the start of a sprint to its first standup",
+            ),
+            (
+                "broadcast_lagged",
+                "counter",
+                "Standup entries skipped by live listeners that fell behind",
+            ),
+            (
+                "broadcast_receiver_count",
+                "gauge",
+                "Live listeners of the standup feed",
+            ),
+            (
+                "discord_send_failures",
+                "counter",
+                "Messages that failed to be posted to Discord",
+            ),
+            (
+                "discord_shard_connected",
+                "gauge",
+                "Whether the gateway shard is connected",
+            ),
+            (
+                "discord_interactions_rejected",
+                "counter",
+                "Interactions answered busy because too many were being handled",
+            ),
+            (
+                "discord_messages_deleted",
+                "counter",
+                "Old messages of the bot deleted by a channel cleanup",
+            ),
+            (
+                "discord_prompts_replayed",
+                "counter",
+                "Standup prompts posted again on demand",
+            ),
+            (
+                "database_circuit_breaker_state",
+                "gauge",
+                "State of the database circuit breaker, 0 closed, 1 half-open and 2 open",
+            ),
+            (
+                "database_write_retries",
+                "counter",
+                "Writes tried again after a transient database error",
+            ),
+            (
+                "scheduler_fires",
+                "counter",
+                "Scheduled messages fired, by dry run or live mode",
+            ),
+            (
+                "scheduler_oldest_pending_seconds",
+                "gauge",
+                "Age of the oldest due reminder at the last scheduler tick",
+            ),
+            (
+                "data_pruned",
+                "counter",
+                "Records deleted by the data retention policy",
+            ),
+            (
+                "config_reloads",
+                "counter",
+                "Configuration reloads, by applied or rejected outcome",
+            ),
+            (
+                "otel_exports",
+                "counter",
+                "Telemetry batches handed to the OTLP exporters, by signal",
+            ),
+            (
+                "otel_export_errors",
+                "counter",
+                "Telemetry export errors reported by the opentelemetry SDK, by signal",
+            ),
+            ("job_runs", "counter", "Runs of the background jobs"),
+            (
+                "job_failures",
+                "counter",
+                "Runs of the background jobs that failed, timed out or panicked",
+            ),
+            (
+                "job_duration_seconds",
+                "histogram",
+                "Duration of the background job runs",
+            ),
+        ];
+        for (name, kind, help) in names {
+            assert!(
+                encoded.contains(&format!(
+                    "# HELP bot_{name} {help}.\n# TYPE bot_{name} {kind}\n"
+                )),
+                "{name} is missing from\n{encoded}"
+            );
+        }
+    }
+
+    #[test]
+    fn namespace_follows_the_prometheus_naming_rules() {
+        assert_eq!(sanitize_namespace("Scrum Bot-dev"), "scrum_bot_dev");
+        assert_eq!(sanitize_namespace("bot.v2"), "bot_v2");
+        assert_eq!(sanitize_namespace("2fast"), "_2fast");
+        assert_eq!(sanitize_namespace("scrum_bot"), "scrum_bot");
+    }
+
+    #[test]
+    fn namespace_overrides_the_application_name() {
+        let mut settings = test_settings();
+        settings.application.name = "Scrum Discord-Bot".into();
+        assert_eq!(metric_namespace(&settings), "scrum_discord_bot");
+
+        settings.prometheus.namespace = Some("standups".into());
+        assert_eq!(metric_namespace(&settings), "standups");
+
+        settings.prometheus.namespace = Some(" ".into());
+        assert_eq!(metric_namespace(&settings), "scrum_discord_bot");
+    }
+
+    #[test]
+    fn histograms_get_their_configured_buckets() {
+        let mut settings = test_settings();
+        settings.application.name = "bot".into();
+        settings.prometheus.buckets = HashMap::from([
+            ("latency_error".to_owned(), vec![0.1, 1.0]),
+            (
+                "sprint_time_to_first_standup_seconds".to_owned(),
+                vec![3600.0],
+            ),
+        ]);
+        let (metrics, registry) = init_metrics(&settings);
+        let labels = HttpRequestLabels {
+            method: "GET".into(),
+            path: "/standups/:id".into(),
+            status_code: 500,
+        };
+        metrics
+            .http
+            .latency_error
+            .get_or_create(&labels)
+            .observe(0.2);
+        metrics
+            .http
+            .latency_success
+            .get_or_create(&labels)
+            .observe(0.2);
+
+        let mut output = String::new();
+        encode(&mut output, &registry).unwrap();
+
+        let bounds = |name: &str| {
+            output
+                .lines()
+                .filter(|line| line.starts_with(&format!("bot_{}_bucket", name)))
+                .filter_map(|line| line.split("le=\"").nth(1)?.split('"').next())
+                .map(str::to_owned)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(bounds("latency_error"), vec!["0.1", "1.0", "+Inf"]);
+        assert_eq!(bounds("latency_success").len(), LATENCY_BUCKETS.len() + 1);
+        assert_eq!(
+            bounds("sprint_time_to_first_standup_seconds"),
+            vec!["3600.0", "+Inf"]
+        );
+    }
+
+    #[test]
+    fn reloads_are_counted_by_outcome() {
+        let mut settings = test_settings();
+        settings.application.name = "bot".into();
+        let (metrics, registry) = init_metrics(&settings);
+        let valid = AtomicBool::new(true);
+        let reloader = ConfigReloader::with_loader(FeatureFlags::default(), move || {
+            let mut settings = test_settings();
+            if !valid.swap(false, Ordering::SeqCst) {
+                settings.application.default_timezone = "Mars/Olympus".into();
+            }
+            Ok(settings)
+        })
+        .with_counter(metrics.config.clone());
+
+        reloader.reload().unwrap();
+        reloader.reload().unwrap_err();
+        reloader.reload().unwrap_err();
+
+        let mut output = String::new();
+        encode(&mut output, &registry).unwrap();
+        assert!(output.contains("bot_config_reloads_total{outcome=\"applied\"} 1\n"));
+        assert!(output.contains("bot_config_reloads_total{outcome=\"rejected\"} 2\n"));
+    }
+
+    #[test]
+    fn job_runs_and_failures_are_counted_by_job() {
+        let mut settings = test_settings();
+        settings.application.name = "bot".into();
+        let (metrics, registry) = init_metrics(&settings);
+
+        metrics
+            .jobs
+            .finished("prune", JobOutcome::Succeeded, Duration::from_secs(2));
+        metrics
+            .jobs
+            .finished("prune", JobOutcome::Panicked, Duration::from_millis(5));
+
+        let mut output = String::new();
+        encode(&mut output, &registry).unwrap();
+        assert!(output.contains("bot_job_runs_total{job=\"prune\"} 2\n"));
+        assert!(output.contains("bot_job_failures_total{job=\"prune\"} 1\n"));
+        assert!(output.contains("bot_job_duration_seconds_count{job=\"prune\"} 2\n"));
+    }
+
+    #[test]
+    fn registered_metrics_use_the_namespace() {
+        let mut settings = test_settings();
+        settings.application.name = "Scrum Bot".into();
+        let (_, registry) = init_metrics(&settings);
+
+        let mut output = String::new();
+        encode(&mut output, &registry).unwrap();
+
+        assert!(output.contains("# TYPE scrum_bot_scheduler_fires counter"));
+    }
+}
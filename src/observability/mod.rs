@@ -1,14 +1,35 @@
+pub mod collector;
+pub mod export;
+pub mod fields;
+pub mod file;
 pub mod log;
+pub mod meter;
 pub mod metrics;
+pub mod sink;
 pub mod trace;
 
+use std::collections::HashMap;
+
 use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
 use opentelemetry_sdk::logs::LoggerProvider;
+use serde_json::Value;
 use tracing::dispatcher::set_global_default;
 use tracing::Subscriber;
 use tracing_bunyan_formatter::{BunyanFormattingLayer, JsonStorageLayer};
 use tracing_log::LogTracer;
-use tracing_subscriber::{fmt::MakeWriter, layer::SubscriberExt, EnvFilter, Registry};
+use tracing_subscriber::{
+    fmt::{writer::MakeWriterExt, MakeWriter},
+    layer::SubscriberExt,
+    EnvFilter, Registry,
+};
+
+use crate::configuration::ApplicationSettings;
+
+use self::{
+    fields::{FieldMapping, MakeMappedWriter},
+    file::LogFile,
+    sink::MakeQuietWriter,
+};
 
 /// Compose multiple layers into a `tracing`'s subscriber.
 ///
@@ -17,9 +38,11 @@ use tracing_subscriber::{fmt::MakeWriter, layer::SubscriberExt, EnvFilter, Regis
 /// We are using `impl Subscriber` as return type to avoid having to spell out the actual
 /// type of the returned subscriber, which is indeed quite complex.
 pub fn get_subscriber<Sink>(
-    name: String,
+    application: &ApplicationSettings,
     env_filter: String,
     sink: Sink,
+    file: LogFile,
+    fields: FieldMapping,
     tracer: opentelemetry_sdk::trace::Tracer,
     logger_provider: LoggerProvider,
 ) -> impl Subscriber + Sync + Send
@@ -29,7 +52,18 @@ where
     let env_filter =
         EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(env_filter));
 
-    let formatting_layer = BunyanFormattingLayer::new(name, sink);
+    // Piping the logs to a reader that exits early must not take the bot
+    // down, nor stop the file logs.
+    // Every record names the deployed commit, to tell deploys apart.
+    let default_fields = application
+        .commit_sha()
+        .map(|sha| HashMap::from([("commit_sha".to_owned(), Value::from(sha))]))
+        .unwrap_or_default();
+    let formatting_layer = BunyanFormattingLayer::with_default_fields(
+        application.name.clone(),
+        MakeMappedWriter::new(MakeQuietWriter::new(sink).and(file), fields),
+        default_fields,
+    );
 
     let otel_logger = OpenTelemetryTracingBridge::new(&logger_provider);
 
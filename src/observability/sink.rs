@@ -0,0 +1,148 @@
+//! Keep logging from taking the bot down when nobody reads the logs anymore.
+
+use std::{
+    io::{self, ErrorKind, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Wraps the sink of the formatting layer so a closed reader, `head` exiting
+/// while the logs are piped to it, reads as a successful write.
+///
+/// Once the pipe is broken the records are dropped without trying to write
+/// them, the other errors are returned as they are.
+#[derive(Clone)]
+pub struct MakeQuietWriter<M> {
+    inner: M,
+    broken: Arc<AtomicBool>,
+}
+
+impl<M> MakeQuietWriter<M> {
+    pub fn new(inner: M) -> Self {
+        Self {
+            inner,
+            broken: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for MakeQuietWriter<M> {
+    type Writer = QuietWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        QuietWriter {
+            inner: self.inner.make_writer(),
+            broken: self.broken.clone(),
+        }
+    }
+}
+
+pub struct QuietWriter<W> {
+    inner: W,
+    broken: Arc<AtomicBool>,
+}
+
+impl<W> QuietWriter<W> {
+    fn swallow_broken_pipe<T>(&self, result: io::Result<T>, written: T) -> io::Result<T> {
+        match result {
+            Err(error) if error.kind() == ErrorKind::BrokenPipe => {
+                if !self.broken.swap(true, Ordering::Relaxed) {
+                    // Nowhere left to log it to.
+                    eprintln!("log sink closed, dropping the log records from now on");
+                }
+                Ok(written)
+            }
+            result => result,
+        }
+    }
+}
+
+impl<W: Write> Write for QuietWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.broken.load(Ordering::Relaxed) {
+            return Ok(buf.len());
+        }
+
+        let result = self.inner.write(buf);
+        self.swallow_broken_pipe(result, buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.broken.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let result = self.inner.flush();
+        self.swallow_broken_pipe(result, ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+
+    use tracing_bunyan_formatter::{BunyanFormattingLayer, JsonStorageLayer};
+    use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+    use super::*;
+
+    /// Fails every write with `kind`, counting the attempts.
+    #[derive(Clone)]
+    struct Failing {
+        kind: ErrorKind,
+        attempts: Arc<AtomicUsize>,
+    }
+
+    impl Failing {
+        fn new(kind: ErrorKind) -> Self {
+            Self {
+                kind,
+                attempts: Arc::default(),
+            }
+        }
+    }
+
+    impl Write for Failing {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            self.attempts.fetch_add(1, Ordering::Relaxed);
+            Err(self.kind.into())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Err(self.kind.into())
+        }
+    }
+
+    #[test]
+    fn broken_pipe_is_swallowed_and_stops_the_writes() {
+        let failing = Failing::new(ErrorKind::BrokenPipe);
+        let writer = failing.clone();
+        let sink = MakeQuietWriter::new(move || writer.clone());
+        let subscriber = Registry::default()
+            .with(JsonStorageLayer)
+            .with(BunyanFormattingLayer::new("test".into(), sink.clone()));
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("reminder sent");
+            tracing::info!("reminder sent again");
+        });
+
+        assert_eq!(failing.attempts.load(Ordering::Relaxed), 1);
+        assert_eq!(sink.make_writer().write(b"record\n").unwrap(), 7);
+        assert!(sink.make_writer().flush().is_ok());
+    }
+
+    #[test]
+    fn other_errors_are_returned() {
+        let failing = Failing::new(ErrorKind::PermissionDenied);
+        let sink = MakeQuietWriter::new(move || failing.clone());
+
+        let error = sink.make_writer().write(b"record\n").unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::PermissionDenied);
+    }
+}
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use anyhow::{Context, Result};
 use opentelemetry::global;
 use opentelemetry_otlp::WithExportConfig;
@@ -6,28 +8,41 @@ use opentelemetry_sdk::{
     runtime,
     trace::{self, RandomIdGenerator, Sampler, TracerProvider},
 };
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::configuration::{OtlpSignal, Settings};
 
-use crate::configuration::Settings;
+use super::export::{CountingSpanExporter, ExportRecorder};
 
-pub fn init_trace(settings: &Settings) -> Result<TracerProvider> {
+/// Build the tracer provider, counting the exported batches on `recorder`.
+pub fn init_trace(
+    settings: &Settings,
+    recorder: Arc<dyn ExportRecorder>,
+) -> Result<TracerProvider> {
     global::set_text_map_propagator(TraceContextPropagator::new());
 
     let trace_provider = match settings.otel.enable {
-        true => opentelemetry_otlp::new_pipeline()
-            .tracing()
-            .with_exporter(
-                opentelemetry_otlp::new_exporter()
-                    .tonic()
-                    .with_endpoint(&settings.otel.endpoint),
-            )
-            .with_trace_config(
-                trace::Config::default()
-                    .with_sampler(Sampler::AlwaysOn)
-                    .with_id_generator(RandomIdGenerator::default())
-                    .with_resource(settings.get_resource()),
-            )
-            .install_batch(runtime::Tokio)
-            .context("expected to genereate otlp provider")?,
+        true => {
+            let exporter = opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(settings.otel.endpoint(OtlpSignal::Traces)?)
+                .build_span_exporter()
+                .context("expected to genereate otlp span exporter")?;
+
+            TracerProvider::builder()
+                .with_batch_exporter(
+                    CountingSpanExporter::new(exporter, recorder),
+                    runtime::Tokio,
+                )
+                .with_config(
+                    trace::Config::default()
+                        .with_sampler(sampler(settings.otel.sample_ratio))
+                        .with_id_generator(RandomIdGenerator::default())
+                        .with_resource(settings.get_resource()),
+                )
+                .build()
+        }
         false => TracerProvider::builder()
             .with_simple_exporter(opentelemetry_stdout::SpanExporter::default())
             .build(),
@@ -35,3 +50,89 @@ pub fn init_trace(settings: &Settings) -> Result<TracerProvider> {
 
     Ok(trace_provider)
 }
+
+/// Exports the spans buffered by the tracer provider on demand.
+pub trait TraceFlusher: Send + Sync {
+    fn force_flush(&self) -> Result<()>;
+}
+
+impl TraceFlusher for TracerProvider {
+    fn force_flush(&self) -> Result<()> {
+        TracerProvider::force_flush(self)
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .context("expected to flush the span processors")?;
+
+        Ok(())
+    }
+}
+
+/// Sample `ratio` of the new traces and follow the decision of the caller for
+/// the others, which is how `x-force-trace` gets a trace sampled.
+pub fn sampler(ratio: f64) -> Sampler {
+    Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(ratio)))
+}
+
+/// Export `span` in the trace of the current span, for work handed to another
+/// task that outlives it.
+///
+/// `span` should have no tracing parent (`parent: None`), a child would keep
+/// the request span open until the task ends.
+pub fn follow_current(span: Span) -> Span {
+    span.set_parent(Span::current().context());
+    span
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry::{
+        trace::{
+            Span as _, SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState,
+            Tracer, TracerProvider as _,
+        },
+        Context,
+    };
+    use opentelemetry_sdk::testing::trace::InMemorySpanExporter;
+
+    use super::*;
+
+    fn tracer(ratio: f64) -> opentelemetry_sdk::trace::Tracer {
+        TracerProvider::builder()
+            .with_simple_exporter(InMemorySpanExporter::default())
+            .with_config(trace::Config::default().with_sampler(sampler(ratio)))
+            .build()
+            .tracer("test")
+    }
+
+    fn remote_parent(flags: TraceFlags) -> Context {
+        Context::new().with_remote_span_context(SpanContext::new(
+            TraceId::from_u128(1),
+            SpanId::from_u64(2),
+            flags,
+            true,
+            TraceState::default(),
+        ))
+    }
+
+    #[test]
+    fn children_follow_the_decision_of_their_parent() {
+        let tracer = tracer(0.0);
+
+        let child = tracer.start_with_context("child", &remote_parent(TraceFlags::SAMPLED));
+        assert!(child.span_context().is_sampled());
+
+        // The decision travels with the span context, not computed again.
+        let parent = Context::new().with_span(child);
+        let grandchild = tracer.start_with_context("grandchild", &parent);
+        assert!(grandchild.span_context().is_sampled());
+
+        let dropped = tracer.start_with_context("child", &remote_parent(TraceFlags::default()));
+        assert!(!dropped.span_context().is_sampled());
+    }
+
+    #[test]
+    fn root_spans_follow_the_ratio() {
+        assert!(!tracer(0.0).start("root").span_context().is_sampled());
+        assert!(tracer(1.0).start("root").span_context().is_sampled());
+    }
+}